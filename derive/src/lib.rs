@@ -0,0 +1,753 @@
+//! Derive macros for structured logging with `log`.
+//!
+//! This crate isn't intended to be used directly. Enable the `kv_derive` feature of
+//! `log` and use the derives it re-exports from its `kv` module instead.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Delimiter, Spacing, Span, TokenStream as TokenStream2, TokenTree};
+use quote::quote;
+use syn::{
+    parse_macro_input, spanned::Spanned, Attribute, Data, DeriveInput, Error, Expr, ExprLit,
+    Fields, FnArg, Ident, ItemFn, Lit, LitStr, Pat,
+};
+
+/// Derive `log::kv::ToValue` for a struct or fieldless enum.
+///
+/// See the `log::kv` module for details.
+#[proc_macro_derive(ToValue, attributes(kv))]
+pub fn derive_to_value(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    to_value(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Derive `log::kv::Source` for a struct.
+///
+/// See the `log::kv` module for details.
+#[proc_macro_derive(Source, attributes(kv))]
+pub fn derive_source(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    source(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Log entry and exit of a function.
+///
+/// See the `log` crate for details.
+#[proc_macro_attribute]
+pub fn instrument(args: TokenStream, input: TokenStream) -> TokenStream {
+    let mut instrument = Instrument::default();
+    let parser = syn::meta::parser(|meta| instrument.parse(meta));
+    parse_macro_input!(args with parser);
+
+    let input = parse_macro_input!(input as ItemFn);
+
+    instrument
+        .expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Capture the implicit named arguments in a format string as key-values.
+///
+/// This macro is used by the `log!` macros when the `kv_format_args` feature of `log`
+/// is enabled. Its input is the path to `log`, a bracketed list of key-values, and then
+/// the format arguments. Its output is the key-values with an extra pair for each `{name}`
+/// or `{name:?}` in the format string, or `()` if there aren't any key-values. Holes that
+/// are already an explicit key or named argument, or that use other format specs like
+/// `{name:x}`, aren't captured.
+#[doc(hidden)]
+#[proc_macro]
+pub fn __format_kvs(input: TokenStream) -> TokenStream {
+    format_kvs(input.into())
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn format_kvs(input: TokenStream2) -> Result<TokenStream2, Error> {
+    let mut tokens = input.into_iter();
+
+    let krate = match tokens.next() {
+        Some(krate) => krate,
+        None => return Err(Error::new(Span::call_site(), "expected a path to `log`")),
+    };
+
+    let kvs = match tokens.next() {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Bracket => group.stream(),
+        _ => return Err(Error::new(Span::call_site(), "expected a list of key-values")),
+    };
+
+    let mut captured = Vec::new();
+    if let Some(TokenTree::Literal(lit)) = tokens.next() {
+        // Format strings can also be macros like `concat!`, which we can't look inside
+        if let Ok(lit) = syn::parse2::<LitStr>(TokenTree::Literal(lit).into()) {
+            let mut explicit = explicit_named_args(tokens);
+            explicit.extend(explicit_keys(kvs.clone()));
+
+            for (name, debug) in implicit_named_args(&lit.value()) {
+                if explicit.contains(&name) || captured.iter().any(|(n, _)| *n == name) {
+                    continue;
+                }
+
+                captured.push((name, debug));
+            }
+
+            let captured = captured.iter().filter_map(|(name, debug)| {
+                let ident = syn::parse_str::<Ident>(name).ok()?;
+                let ident = Ident::new(&ident.to_string(), lit.span());
+
+                Some(if *debug {
+                    quote!((#name, #krate::__private_api::capture_debug(&&#ident)))
+                } else {
+                    quote!((#name, #krate::__private_api::capture_display(&&#ident)))
+                })
+            });
+
+            let captured = captured.collect::<Vec<_>>();
+            if kvs.is_empty() && captured.is_empty() {
+                return Ok(quote!(()));
+            }
+
+            let sep = if kvs.is_empty() || captured.is_empty() {
+                None
+            } else {
+                Some(quote!(,))
+            };
+
+            return Ok(quote!(&[#kvs #sep #(#captured),*][..]));
+        }
+    }
+
+    if kvs.is_empty() {
+        Ok(quote!(()))
+    } else {
+        Ok(quote!(&[#kvs][..]))
+    }
+}
+
+/// Get the keys of explicit key-values, like `name` in `name = value; "{name}"`.
+///
+/// Each key-value is a `(__log_key!(key), value)` pair, where the key is an identifier,
+/// possibly with a `?` or `%` before it, or a string literal.
+fn explicit_keys(kvs: TokenStream2) -> Vec<String> {
+    let mut keys = Vec::new();
+
+    for kv in kvs {
+        let kv = match kv {
+            TokenTree::Group(group) if group.delimiter() == Delimiter::Parenthesis => group,
+            _ => continue,
+        };
+
+        let mut tokens = kv.stream().into_iter();
+        while let Some(token) = tokens.next() {
+            if !matches!(&token, TokenTree::Ident(ident) if ident == "__log_key") {
+                continue;
+            }
+
+            if let (Some(TokenTree::Punct(_)), Some(TokenTree::Group(key))) =
+                (tokens.next(), tokens.next())
+            {
+                match key.stream().into_iter().last() {
+                    Some(TokenTree::Ident(ident)) => keys.push(ident.to_string()),
+                    Some(TokenTree::Literal(lit)) => {
+                        if let Ok(lit) = syn::parse2::<LitStr>(TokenTree::Literal(lit).into()) {
+                            keys.push(lit.value());
+                        }
+                    }
+                    _ => (),
+                }
+            }
+
+            break;
+        }
+    }
+
+    keys
+}
+
+/// Get the names of explicit named arguments, like `name` in `"{name}", name = value`.
+fn explicit_named_args(tokens: impl Iterator<Item = TokenTree>) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut tokens = tokens.peekable();
+
+    while let Some(token) = tokens.next() {
+        // Each argument starts after a top-level comma
+        if !matches!(&token, TokenTree::Punct(punct) if punct.as_char() == ',') {
+            continue;
+        }
+
+        if let Some(TokenTree::Ident(ident)) = tokens.next() {
+            if let Some(TokenTree::Punct(punct)) = tokens.peek() {
+                if punct.as_char() == '=' && punct.spacing() == Spacing::Alone {
+                    names.push(ident.to_string());
+                }
+            }
+        }
+    }
+
+    names
+}
+
+/// Get the names of implicit named arguments in a format string, like `name` in `"{name}"`,
+/// along with whether they're formatted using `Debug`.
+///
+/// Only holes formatted using `Display`, like `{name}` or `{name:>6}`, or using `Debug`, like
+/// `{name:?}` or `{name:#?}`, are returned. Holes with other format traits, like `{name:x}`
+/// or `{name:p}`, are skipped, since their values may not implement either.
+fn implicit_named_args(fmt: &str) -> Vec<(String, bool)> {
+    let mut names = Vec::new();
+    let mut chars = fmt.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+            }
+            '{' => {
+                let mut hole = String::new();
+                for c in chars.by_ref() {
+                    if c == '}' {
+                        break;
+                    }
+                    hole.push(c);
+                }
+
+                let (name, spec) = match hole.find(':') {
+                    Some(i) => (&hole[..i], &hole[i + 1..]),
+                    None => (&hole[..], ""),
+                };
+                let name = name.trim();
+
+                let is_ident = name
+                    .chars()
+                    .next()
+                    .map_or(false, |c| c.is_alphabetic() || c == '_')
+                    && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+                    && name != "_";
+
+                // The format trait is the last part of the spec, after any fill, width or precision
+                let debug = match spec.chars().last() {
+                    Some('?') => true,
+                    Some(c) if c.is_alphabetic() => continue,
+                    _ => false,
+                };
+
+                if is_ident {
+                    names.push((name.to_owned(), debug));
+                }
+            }
+            _ => (),
+        }
+    }
+
+    names
+}
+
+fn source(input: &DeriveInput) -> Result<TokenStream2, Error> {
+    let container = Container::from_attrs(&input.attrs)?;
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(Error::new(
+                Span::call_site(),
+                "`Source` can only be derived for structs",
+            ))
+        }
+    };
+
+    let krate = container.krate();
+    let visit = visit_fields(&container, fields)?;
+
+    let mut count = 0usize;
+    for field in fields {
+        if !Field::from_attrs(&field.attrs)?.skip {
+            count += 1;
+        }
+    }
+
+    Ok(quote! {
+        impl #impl_generics #krate::kv::Source for #ident #ty_generics #where_clause {
+            fn visit<'kvs>(
+                &'kvs self,
+                visitor: &mut dyn #krate::kv::VisitSource<'kvs>,
+            ) -> ::core::result::Result<(), #krate::kv::Error> {
+                #visit
+            }
+
+            fn count(&self) -> usize {
+                #count
+            }
+        }
+    })
+}
+
+fn to_value(input: &DeriveInput) -> Result<TokenStream2, Error> {
+    let container = Container::from_attrs(&input.attrs)?;
+    let krate = container.krate();
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let name = container
+        .rename
+        .clone()
+        .unwrap_or_else(|| LitStr::new(&ident.to_string(), ident.span()));
+
+    match &input.data {
+        Data::Struct(data) => {
+            // Newtype structs are transparent
+            if let Fields::Unnamed(fields) = &data.fields {
+                if fields.unnamed.len() == 1 {
+                    let field = &fields.unnamed[0];
+                    let capture = Field::from_attrs(&field.attrs)?.capture(&krate, quote!(&self.0));
+
+                    return Ok(quote! {
+                        impl #impl_generics #krate::kv::ToValue for #ident #ty_generics #where_clause {
+                            fn to_value(&self) -> #krate::kv::Value<'_> {
+                                #capture
+                            }
+                        }
+                    });
+                }
+            }
+
+            if let Fields::Unit = &data.fields {
+                return Ok(quote! {
+                    impl #impl_generics #krate::kv::ToValue for #ident #ty_generics #where_clause {
+                        fn to_value(&self) -> #krate::kv::Value<'_> {
+                            #krate::kv::Value::from(#name)
+                        }
+                    }
+                });
+            }
+
+            let visit = visit_fields(&container, &data.fields)?;
+
+            Ok(quote! {
+                impl #impl_generics #krate::kv::ToValue for #ident #ty_generics #where_clause {
+                    fn to_value(&self) -> #krate::kv::Value<'_> {
+                        #krate::__private_api::capture_fields(self)
+                    }
+                }
+
+                impl #impl_generics #krate::__private_api::Fields for #ident #ty_generics #where_clause {
+                    const NAME: &'static str = #name;
+
+                    fn visit_fields<'kvs>(
+                        &'kvs self,
+                        visitor: &mut dyn #krate::kv::VisitSource<'kvs>,
+                    ) -> ::core::result::Result<(), #krate::kv::Error> {
+                        #visit
+                    }
+                }
+            })
+        }
+        Data::Enum(data) => {
+            let mut arms = Vec::new();
+            for variant in &data.variants {
+                if !matches!(variant.fields, Fields::Unit) {
+                    return Err(Error::new(
+                        variant.span(),
+                        "`ToValue` can only be derived for enums without fields",
+                    ));
+                }
+
+                let field = Field::from_attrs(&variant.attrs)?;
+                let variant_ident = &variant.ident;
+                let variant_name = field.rename.unwrap_or_else(|| {
+                    container.rename_all(&variant_ident.to_string(), variant_ident.span())
+                });
+
+                arms.push(quote!(#ident::#variant_ident => #krate::kv::Value::from(#variant_name)));
+            }
+
+            Ok(quote! {
+                impl #impl_generics #krate::kv::ToValue for #ident #ty_generics #where_clause {
+                    fn to_value(&self) -> #krate::kv::Value<'_> {
+                        match self {
+                            #(#arms,)*
+                        }
+                    }
+                }
+            })
+        }
+        Data::Union(_) => Err(Error::new(
+            Span::call_site(),
+            "`ToValue` can't be derived for unions",
+        )),
+    }
+}
+
+/// Generate the body of a function that visits each field as a key-value.
+fn visit_fields(container: &Container, fields: &Fields) -> Result<TokenStream2, Error> {
+    let krate = container.krate();
+    let mut visits = Vec::new();
+
+    for (index, field) in fields.iter().enumerate() {
+        let attrs = Field::from_attrs(&field.attrs)?;
+        if attrs.skip {
+            continue;
+        }
+
+        let (member, key) = match &field.ident {
+            Some(ident) => {
+                let name = ident.to_string();
+                let name = name.strip_prefix("r#").unwrap_or(&name);
+
+                (quote!(#ident), container.rename_all(name, ident.span()))
+            }
+            None => {
+                let index = syn::Index::from(index);
+                let key = LitStr::new(&index.index.to_string(), field.span());
+
+                (quote!(#index), key)
+            }
+        };
+
+        let key = attrs.rename.clone().unwrap_or(key);
+        let value = attrs.capture(&krate, quote!(&self.#member));
+
+        visits.push(quote! {
+            visitor.visit_pair(#krate::kv::Key::from_str(#key), #value)?;
+        });
+    }
+
+    Ok(quote! {
+        #(#visits)*
+        ::core::result::Result::Ok(())
+    })
+}
+
+/// Arguments to the `instrument` attribute.
+#[derive(Default)]
+struct Instrument {
+    level: Option<InstrumentLevel>,
+    target: Option<Expr>,
+    name: Option<LitStr>,
+    skip: Vec<Ident>,
+    krate: Option<syn::Path>,
+}
+
+/// The level given to the `instrument` attribute.
+enum InstrumentLevel {
+    /// A level given as a string, like `level = "debug"`.
+    Named(Ident),
+    /// A level given as an expression, like `level = log::Level::Debug`.
+    Expr(Expr),
+}
+
+impl Instrument {
+    fn parse(&mut self, meta: syn::meta::ParseNestedMeta) -> Result<(), Error> {
+        if meta.path.is_ident("level") {
+            let level: Expr = meta.value()?.parse()?;
+            self.level = Some(match &level {
+                // Levels can be given as strings, like `level = "debug"`
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(lit), ..
+                }) => {
+                    let variant = match &*lit.value().to_lowercase() {
+                        "error" => "Error",
+                        "warn" => "Warn",
+                        "info" => "Info",
+                        "debug" => "Debug",
+                        "trace" => "Trace",
+                        _ => return Err(Error::new(lit.span(), "unsupported level")),
+                    };
+
+                    InstrumentLevel::Named(Ident::new(variant, lit.span()))
+                }
+                _ => InstrumentLevel::Expr(level),
+            });
+            Ok(())
+        } else if meta.path.is_ident("target") {
+            self.target = Some(meta.value()?.parse()?);
+            Ok(())
+        } else if meta.path.is_ident("name") {
+            self.name = Some(meta.value()?.parse()?);
+            Ok(())
+        } else if meta.path.is_ident("skip") {
+            meta.parse_nested_meta(|meta| {
+                self.skip.push(meta.path.require_ident()?.clone());
+                Ok(())
+            })
+        } else if meta.path.is_ident("crate") {
+            self.krate = Some(parse_crate(&meta)?);
+            Ok(())
+        } else {
+            Err(meta.error("unsupported instrument attribute"))
+        }
+    }
+
+    fn expand(&self, mut input: ItemFn) -> Result<TokenStream2, Error> {
+        let ident = &input.sig.ident;
+
+        let name = self
+            .name
+            .clone()
+            .unwrap_or_else(|| LitStr::new(&ident.to_string(), ident.span()));
+        let enter = LitStr::new(&format!("enter {}", name.value()), name.span());
+        let exit = LitStr::new(&format!("exit {}", name.value()), name.span());
+
+        let krate = krate(self.krate.as_ref());
+
+        let level = match &self.level {
+            Some(InstrumentLevel::Named(variant)) => quote!(#krate::Level::#variant),
+            Some(InstrumentLevel::Expr(level)) => quote!(#level),
+            None => quote!(#krate::Level::Info),
+        };
+        let target = match &self.target {
+            Some(target) => quote!(#target),
+            None => quote!(#krate::__private_api::module_path!()),
+        };
+
+        for skip in &self.skip {
+            if !input.sig.inputs.iter().any(|arg| arg_ident(arg) == Some(skip)) {
+                return Err(Error::new(skip.span(), "no argument with this name"));
+            }
+        }
+
+        // Arguments are captured using `Debug`. Receivers and destructured arguments aren't
+        let kvs = input
+            .sig
+            .inputs
+            .iter()
+            .filter_map(arg_ident)
+            .filter(|ident| !self.skip.contains(ident))
+            .map(|ident| quote!(#ident:?))
+            .collect::<Vec<_>>();
+        let kvs = if kvs.is_empty() {
+            None
+        } else {
+            Some(quote!(#(#kvs),*;))
+        };
+
+        let block = &input.block;
+        input.block = syn::parse_quote!({
+            let __log_instrument_level: #krate::Level = #level;
+            let __log_instrument_target: &str = #target;
+
+            #krate::log!(
+                target: __log_instrument_target,
+                __log_instrument_level,
+                #kvs
+                #enter
+            );
+            let __log_instrument_timer = #krate::log_time!(
+                target: __log_instrument_target,
+                __log_instrument_level,
+                #exit
+            );
+
+            #block
+        });
+
+        Ok(quote!(#input))
+    }
+}
+
+/// Parse the path to `log` given by a `crate = "path"` attribute.
+fn parse_crate(meta: &syn::meta::ParseNestedMeta) -> Result<syn::Path, Error> {
+    let path: LitStr = meta.value()?.parse()?;
+    path.parse()
+}
+
+/// Get the path to `log`, which is `::log` unless another was given.
+fn krate(path: Option<&syn::Path>) -> TokenStream2 {
+    match path {
+        Some(path) => quote!(#path),
+        None => quote!(::log),
+    }
+}
+
+/// Get the name of a function argument that's a plain binding, like `a` in `a: u32`.
+fn arg_ident(arg: &FnArg) -> Option<&Ident> {
+    match arg {
+        FnArg::Typed(arg) => match &*arg.pat {
+            Pat::Ident(pat) if pat.subpat.is_none() => Some(&pat.ident),
+            _ => None,
+        },
+        FnArg::Receiver(_) => None,
+    }
+}
+
+/// Attributes on a container.
+#[derive(Default)]
+struct Container {
+    rename: Option<LitStr>,
+    rename_all: Option<RenameAll>,
+    krate: Option<syn::Path>,
+}
+
+impl Container {
+    fn from_attrs(attrs: &[Attribute]) -> Result<Self, Error> {
+        let mut container = Container::default();
+
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("kv")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    container.rename = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("rename_all") {
+                    let value: LitStr = meta.value()?.parse()?;
+                    container.rename_all = Some(RenameAll::parse(&value)?);
+                    Ok(())
+                } else if meta.path.is_ident("crate") {
+                    container.krate = Some(parse_crate(&meta)?);
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported container attribute"))
+                }
+            })?;
+        }
+
+        Ok(container)
+    }
+
+    /// The path to `log`.
+    fn krate(&self) -> TokenStream2 {
+        krate(self.krate.as_ref())
+    }
+
+    fn rename_all(&self, name: &str, span: Span) -> LitStr {
+        match self.rename_all {
+            Some(rename_all) => LitStr::new(&rename_all.apply(name), span),
+            None => LitStr::new(name, span),
+        }
+    }
+}
+
+/// Attributes on a field or variant.
+#[derive(Default)]
+struct Field {
+    rename: Option<LitStr>,
+    skip: bool,
+    capture: Option<Ident>,
+}
+
+impl Field {
+    fn from_attrs(attrs: &[Attribute]) -> Result<Self, Error> {
+        let mut field = Field::default();
+
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("kv")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    field.rename = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("skip") {
+                    field.skip = true;
+                    Ok(())
+                } else if meta.path.is_ident("debug") || meta.path.is_ident("display") {
+                    field.capture = meta.path.get_ident().cloned();
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported field attribute"))
+                }
+            })?;
+        }
+
+        Ok(field)
+    }
+
+    /// Capture a reference to a field as a `Value`.
+    fn capture(&self, krate: &TokenStream2, value: TokenStream2) -> TokenStream2 {
+        match &self.capture {
+            Some(capture) if capture == "debug" => quote!(#krate::kv::Value::from_debug(#value)),
+            Some(capture) if capture == "display" => {
+                quote!(#krate::kv::Value::from_display(#value))
+            }
+            _ => quote!(#krate::kv::ToValue::to_value(#value)),
+        }
+    }
+}
+
+/// A case convention to rename fields or variants with.
+#[derive(Clone, Copy)]
+enum RenameAll {
+    Lower,
+    Upper,
+    Camel,
+    Pascal,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+}
+
+impl RenameAll {
+    fn parse(value: &LitStr) -> Result<Self, Error> {
+        match &*value.value() {
+            "lowercase" => Ok(RenameAll::Lower),
+            "UPPERCASE" => Ok(RenameAll::Upper),
+            "camelCase" => Ok(RenameAll::Camel),
+            "PascalCase" => Ok(RenameAll::Pascal),
+            "snake_case" => Ok(RenameAll::Snake),
+            "SCREAMING_SNAKE_CASE" => Ok(RenameAll::ScreamingSnake),
+            "kebab-case" => Ok(RenameAll::Kebab),
+            _ => Err(Error::new(value.span(), "unsupported case convention")),
+        }
+    }
+
+    fn apply(self, name: &str) -> String {
+        let words = words(name);
+
+        match self {
+            RenameAll::Lower => words.concat().to_lowercase(),
+            RenameAll::Upper => words.concat().to_uppercase(),
+            RenameAll::Snake => words.join("_").to_lowercase(),
+            RenameAll::ScreamingSnake => words.join("_").to_uppercase(),
+            RenameAll::Kebab => words.join("-").to_lowercase(),
+            RenameAll::Pascal => words.iter().map(|word| capitalize(word)).collect(),
+            RenameAll::Camel => {
+                let mut renamed = String::new();
+                for (i, word) in words.iter().enumerate() {
+                    if i == 0 {
+                        renamed.push_str(&word.to_lowercase());
+                    } else {
+                        renamed.push_str(&capitalize(word));
+                    }
+                }
+                renamed
+            }
+        }
+    }
+}
+
+/// Split an identifier in either `snake_case` or `PascalCase` into its words.
+fn words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+
+    for c in name.chars() {
+        if c == '_' {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+        } else {
+            if c.is_uppercase() && !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            word.push(c);
+        }
+    }
+
+    if !word.is_empty() {
+        words.push(word);
+    }
+
+    words
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars.flat_map(char::to_lowercase)).collect(),
+        None => String::new(),
+    }
+}
@@ -1,10 +1,14 @@
 //! WARNING: this is not part of the crate's public API and is subject to change at any time
 
-use self::sealed::KVs;
-use crate::{Level, Metadata, Record};
+use self::sealed::{KVs, Target};
+use crate::{AtomicUsize, Level, LevelFilter, Log, Metadata, Ordering, Record, RecordBuilder};
 use std::fmt::Arguments;
 use std::panic::Location;
-pub use std::{format_args, module_path, stringify};
+pub use std::result::Result::{Err, Ok};
+
+#[cfg(feature = "kv_format_args")]
+pub use log_derive::__format_kvs as format_kvs;
+pub use std::{cfg, concat, format_args, module_path, option_env, panic, stringify};
 
 #[cfg(not(feature = "kv"))]
 pub type Value<'a> = &'a str;
@@ -14,6 +18,16 @@ mod sealed {
     pub trait KVs<'a> {
         fn into_kvs(self) -> Option<&'a [(&'a str, super::Value<'a>)]>;
     }
+
+    /// Types for the `target` argument.
+    pub trait Target {
+        fn apply<'a>(
+            &'a self,
+            builder: &mut super::RecordBuilder<'a>,
+            module_path: &'static str,
+            loc: Option<&'static super::Location>,
+        );
+    }
 }
 
 // Types for the `kv` argument.
@@ -32,13 +46,190 @@ impl<'a> KVs<'a> for () {
     }
 }
 
+// Types for the `target` argument.
+
+impl<T: AsRef<str> + ?Sized> Target for &T {
+    #[inline]
+    fn apply<'a>(
+        &'a self,
+        builder: &mut RecordBuilder<'a>,
+        module_path: &'static str,
+        loc: Option<&'static Location>,
+    ) {
+        builder.target((**self).as_ref());
+        apply_location(builder, module_path, loc);
+    }
+}
+
+fn apply_location(
+    builder: &mut RecordBuilder,
+    module_path: &'static str,
+    loc: Option<&'static Location>,
+) {
+    // The location is only missing if it's been stripped, along with the module path
+    if let Some(loc) = loc {
+        builder
+            .module_path_static(Some(module_path))
+            .file_static(Some(loc.file()))
+            .line(Some(loc.line()));
+    }
+}
+
+/// A target along with overrides for the module path, file, and line of a record,
+/// its static key-values, and its verbosity, for `log!(file: .., line: .., static: [..], ..)`
+/// and `vlog!`.
+#[derive(Debug)]
+pub struct Origin<'a> {
+    default_target: &'a str,
+    target: Option<&'a str>,
+    module_path: Option<&'a str>,
+    file: Option<&'a str>,
+    line: Option<u32>,
+    verbosity: Option<u32>,
+    #[cfg(feature = "kv")]
+    static_key_values: &'a [(&'a str, &'a str)],
+}
+
+impl<'a> Origin<'a> {
+    pub fn new(default_target: &'a str) -> Self {
+        Origin {
+            default_target,
+            target: None,
+            module_path: None,
+            file: None,
+            line: None,
+            verbosity: None,
+            #[cfg(feature = "kv")]
+            static_key_values: &[],
+        }
+    }
+
+    pub fn target(mut self, target: &'a str) -> Self {
+        self.target = Some(target);
+        self
+    }
+
+    pub fn module_path(mut self, module_path: &'a str) -> Self {
+        self.module_path = Some(module_path);
+        self
+    }
+
+    pub fn file(mut self, file: &'a str) -> Self {
+        self.file = Some(file);
+        self
+    }
+
+    pub fn line(mut self, line: u32) -> Self {
+        self.line = Some(line);
+        self
+    }
+
+    pub fn verbosity(mut self, verbosity: u32) -> Self {
+        self.verbosity = Some(verbosity);
+        self
+    }
+
+    #[cfg(feature = "kv")]
+    pub fn static_key_values(mut self, static_key_values: &'a [(&'a str, &'a str)]) -> Self {
+        self.static_key_values = static_key_values;
+        self
+    }
+}
+
+/// A target along with the name and version of the crate that logged the record.
+#[cfg(feature = "crate_info")]
+#[derive(Clone, Copy, Debug)]
+pub struct Crate<T> {
+    target: T,
+    name: Option<&'static str>,
+    version: Option<&'static str>,
+}
+
+#[cfg(feature = "crate_info")]
+impl<T> Crate<T> {
+    pub fn new(target: T, name: Option<&'static str>, version: Option<&'static str>) -> Self {
+        Crate {
+            target,
+            name,
+            version,
+        }
+    }
+}
+
+#[cfg(feature = "crate_info")]
+impl<T: Target> Target for Crate<T> {
+    #[inline]
+    fn apply<'a>(
+        &'a self,
+        builder: &mut RecordBuilder<'a>,
+        module_path: &'static str,
+        loc: Option<&'static Location>,
+    ) {
+        self.target.apply(builder, module_path, loc);
+
+        builder.crate_name(self.name).crate_version(self.version);
+    }
+}
+
+/// A target, along with the crate it was logged from with `crate_info`, for `log_time!` and
+/// `log_writer!`, which hold on to it until their record is logged.
+#[cfg(feature = "crate_info")]
+pub type CrateTarget<'a> = Crate<&'a str>;
+#[cfg(not(feature = "crate_info"))]
+pub type CrateTarget<'a> = &'a str;
+
+#[cfg(all(feature = "std", feature = "crate_info"))]
+fn crate_target<'a>(target: &CrateTarget<'a>) -> &'a str {
+    target.target
+}
+
+#[cfg(all(feature = "std", not(feature = "crate_info")))]
+fn crate_target<'a>(target: &CrateTarget<'a>) -> &'a str {
+    target
+}
+
+impl<'o> Target for Origin<'o> {
+    fn apply<'a>(
+        &'a self,
+        builder: &mut RecordBuilder<'a>,
+        module_path: &'static str,
+        loc: Option<&'static Location>,
+    ) {
+        apply_location(builder, module_path, loc);
+
+        // The target defaults to the overridden module path, if there is one
+        builder.target(
+            self.target
+                .or(self.module_path)
+                .unwrap_or(self.default_target),
+        );
+
+        if let Some(module_path) = self.module_path {
+            builder.module_path(Some(module_path));
+        }
+        if let Some(file) = self.file {
+            builder.file(Some(file));
+        }
+        if let Some(line) = self.line {
+            builder.line(Some(line));
+        }
+        if let Some(verbosity) = self.verbosity {
+            builder.verbosity(verbosity);
+        }
+
+        #[cfg(feature = "kv")]
+        builder.static_key_values(self.static_key_values);
+    }
+}
+
 // Log implementation.
 
-fn log_impl(
+pub(crate) fn log_impl(
     args: Arguments,
     level: Level,
-    &(target, module_path, loc): &(&str, &'static str, &'static Location),
+    (target, module_path, loc): (&dyn Target, &'static str, Option<&'static Location>),
     kvs: Option<&[(&str, Value)]>,
+    template: Option<&str>,
 ) {
     #[cfg(not(feature = "kv"))]
     if kvs.is_some() {
@@ -47,38 +238,577 @@ fn log_impl(
 
     let mut builder = Record::builder();
 
+    builder.args(args).level(level);
+    target.apply(&mut builder, module_path, loc);
+
+    // Records with explicit targets are only known to be stripped at runtime
+    // This is checked before anything else is captured for the record
+    let record_target = builder.record.target();
+    if is_target_stripped(record_target) || is_target_disabled(level, record_target) {
+        return;
+    }
+
+    #[cfg(all(feature = "timestamp", feature = "std"))]
+    builder.timestamp(Some(std::time::SystemTime::now()));
+
+    #[cfg(feature = "seq")]
+    builder.seq(Some(next_seq()));
+
+    #[cfg(feature = "thread")]
+    let thread = std::thread::current();
+    #[cfg(feature = "thread")]
     builder
-        .args(args)
-        .level(level)
-        .target(target)
-        .module_path_static(Some(module_path))
-        .file_static(Some(loc.file()))
-        .line(Some(loc.line()));
+        .thread_id(Some(thread.id()))
+        .thread_name(thread.name());
+
+    #[cfg(all(feature = "kv", feature = "std"))]
+    let (global, mdc, scope) = (
+        crate::kv::global::current(),
+        crate::mdc::current(),
+        crate::kv::scope::current(),
+    );
+    #[cfg(all(feature = "kv", feature = "std"))]
+    let kvs = crate::kv::Source::chain(
+        &global,
+        crate::kv::Source::chain(&mdc, crate::kv::Source::chain(&scope, &kvs)),
+    );
 
     #[cfg(feature = "kv")]
-    builder.key_values(&kvs);
+    builder.key_values(&kvs).template(template);
+    #[cfg(not(feature = "kv"))]
+    let _ = template;
 
-    crate::logger().log(&builder.build());
+    let record = builder.build();
+
+    with_logger(|logger| logger.log(&record));
+}
+
+// Caching interest.
+
+#[cfg(feature = "callsite_cache")]
+pub struct Callsite {
+    // The epoch, level, and interest the callsite last saw, or 0 if it hasn't been checked yet
+    cached: AtomicUsize,
+}
+
+#[cfg(feature = "callsite_cache")]
+impl Default for Callsite {
+    fn default() -> Self {
+        Callsite::new()
+    }
+}
+
+#[cfg(feature = "callsite_cache")]
+impl std::fmt::Debug for Callsite {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Callsite").finish_non_exhaustive()
+    }
 }
 
-pub fn log<'a, K>(
+#[cfg(feature = "callsite_cache")]
+impl Callsite {
+    const LEVEL_BITS: u32 = 3;
+    const INTEREST_BITS: u32 = 2;
+
+    pub const fn new() -> Self {
+        Callsite {
+            cached: AtomicUsize::new(0),
+        }
+    }
+
+    /// Whether records from this callsite should be logged, using the global logger's
+    /// cached interest in it.
+    pub fn is_enabled(&self, level: Level, target: &str) -> bool {
+        // Scoped loggers can have a different interest in any callsite
+        #[cfg(feature = "std")]
+        if crate::scoped::any_active() {
+            return true;
+        }
+
+        let key =
+            (crate::interest_epoch() << Self::LEVEL_BITS | level as usize) << Self::INTEREST_BITS;
+
+        let cached = self.cached.load(Ordering::Relaxed);
+        let interest = if cached >> Self::INTEREST_BITS == key >> Self::INTEREST_BITS {
+            cached & ((1 << Self::INTEREST_BITS) - 1)
+        } else {
+            let interest = match crate::logger()
+                .interest(&Metadata::builder().level(level).target(target).build())
+            {
+                crate::Interest::Never => 1,
+                crate::Interest::Sometimes => 2,
+                crate::Interest::Always => 3,
+            };
+            self.cached.store(key | interest, Ordering::Relaxed);
+
+            interest
+        };
+
+        interest != 1
+    }
+}
+
+// Records can be filtered by their target's max level
+fn is_target_disabled(level: Level, target: &str) -> bool {
+    #[cfg(feature = "std")]
+    {
+        crate::targets::is_target_disabled(level, target)
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        let _ = (level, target);
+        false
+    }
+}
+
+// Records go to the thread's scoped logger, if there is one
+fn with_logger<R>(f: impl FnOnce(&dyn Log) -> R) -> R {
+    #[cfg(feature = "std")]
+    {
+        crate::scoped::with_current(f)
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        f(crate::logger())
+    }
+}
+
+pub fn log<'a, T, K>(
     args: Arguments,
     level: Level,
-    target_module_path_and_loc: &(&str, &'static str, &'static Location),
+    (target, module_path, loc): &(T, &'static str, Option<&'static Location>),
     kvs: K,
 ) where
+    T: Target,
     K: KVs<'a>,
 {
-    log_impl(args, level, target_module_path_and_loc, kvs.into_kvs())
+    log_impl(
+        args,
+        level,
+        (target, *module_path, *loc),
+        kvs.into_kvs(),
+        None,
+    )
+}
+
+#[cfg(feature = "kv")]
+pub fn log_template<T: Target>(
+    template: &str,
+    level: Level,
+    (target, module_path, loc): &(T, &'static str, Option<&'static Location>),
+    kvs: &[(&str, Value)],
+) {
+    log_impl(
+        format_args!("{}", Template { template, kvs }),
+        level,
+        (target, *module_path, *loc),
+        Some(kvs),
+        Some(template),
+    )
+}
+
+/// A message template rendered by filling its `{key}` holes with the record's key-values.
+///
+/// Holes can be formatted using `Debug` with `{key:?}`. `{{` and `}}` are escapes for
+/// `{` and `}`. Holes that don't match a key are written as-is.
+#[cfg(feature = "kv")]
+struct Template<'a> {
+    template: &'a str,
+    kvs: &'a [(&'a str, Value<'a>)],
+}
+
+#[cfg(feature = "kv")]
+impl<'a> std::fmt::Display for Template<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let mut rest = self.template;
+
+        while let Some(i) = rest.find(['{', '}']) {
+            f.write_str(&rest[..i])?;
+
+            let escape = &rest[i..i + 1];
+            rest = &rest[i + 1..];
+
+            if let Some(escaped) = rest.strip_prefix(escape) {
+                f.write_str(escape)?;
+                rest = escaped;
+                continue;
+            }
+
+            let hole = match (escape, rest.find('}')) {
+                ("{", Some(end)) => &rest[..end],
+                _ => {
+                    f.write_str(escape)?;
+                    continue;
+                }
+            };
+
+            let (key, spec) = match hole.find(':') {
+                Some(i) => (&hole[..i], &hole[i + 1..]),
+                None => (hole, ""),
+            };
+
+            match self.kvs.iter().rev().find(|(k, _)| *k == key) {
+                Some((_, value)) if spec.contains('?') => std::fmt::Debug::fmt(value, f)?,
+                Some((_, value)) => std::fmt::Display::fmt(value, f)?,
+                None => write!(f, "{{{}}}", hole)?,
+            }
+
+            rest = &rest[hole.len() + 1..];
+        }
+
+        f.write_str(rest)
+    }
+}
+
+/// The static max level for a module, using the `LOG_STATIC_MAX_LEVEL` variable its crate
+/// was compiled with, if there was one.
+///
+/// The crate's level can only lower the global `STATIC_MAX_LEVEL`, not raise it. Modules
+/// stripped by `LOG_STRIP_TARGETS` are always `Off`.
+pub const fn static_max_level(crate_max_level: Option<&str>, module_path: &str) -> LevelFilter {
+    if is_target_stripped(module_path) {
+        return LevelFilter::Off;
+    }
+
+    let crate_max_level = match crate_max_level {
+        Some(level) if !level.is_empty() => level.as_bytes(),
+        _ => return crate::STATIC_MAX_LEVEL,
+    };
+
+    const LEVELS: [(&str, LevelFilter); 6] = [
+        ("off", LevelFilter::Off),
+        ("error", LevelFilter::Error),
+        ("warn", LevelFilter::Warn),
+        ("info", LevelFilter::Info),
+        ("debug", LevelFilter::Debug),
+        ("trace", LevelFilter::Trace),
+    ];
+
+    let mut i = 0;
+    while i < LEVELS.len() {
+        let (name, level) = LEVELS[i];
+
+        if eq_ignore_ascii_case(name.as_bytes(), crate_max_level) {
+            return if (level as usize) < (crate::STATIC_MAX_LEVEL as usize) {
+                level
+            } else {
+                crate::STATIC_MAX_LEVEL
+            };
+        }
+
+        i += 1;
+    }
+
+    panic!(
+        "`LOG_STATIC_MAX_LEVEL` must be one of `off`, `error`, `warn`, `info`, `debug`, or `trace`"
+    )
+}
+
+const fn eq_ignore_ascii_case(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let mut i = 0;
+    while i < a.len() {
+        if !a[i].eq_ignore_ascii_case(&b[i]) {
+            return false;
+        }
+
+        i += 1;
+    }
+
+    true
+}
+
+/// The target prefixes to strip, from the `LOG_STRIP_TARGETS` variable `log` was compiled with.
+const STRIP_TARGETS: Option<&str> = option_env!("LOG_STRIP_TARGETS");
+
+/// Whether records for a target are stripped by `LOG_STRIP_TARGETS`.
+pub const fn is_target_stripped(target: &str) -> bool {
+    is_stripped_by(STRIP_TARGETS, target)
+}
+
+/// Whether a target matches any of a comma-separated list of prefixes.
+///
+/// A prefix matches the target with the same name and any targets nested under it,
+/// so `a::b` matches `a::b` and `a::b::c`, but not `a::bc`.
+pub const fn is_stripped_by(prefixes: Option<&str>, target: &str) -> bool {
+    let prefixes = match prefixes {
+        Some(prefixes) => prefixes.as_bytes(),
+        None => return false,
+    };
+    let target = target.as_bytes();
+
+    let mut start = 0;
+    while start < prefixes.len() {
+        let mut end = start;
+        while end < prefixes.len() && prefixes[end] != b',' {
+            end += 1;
+        }
+
+        if matches_prefix(prefixes, start, end, target) {
+            return true;
+        }
+
+        start = end + 1;
+    }
+
+    false
+}
+
+const fn matches_prefix(prefixes: &[u8], mut start: usize, mut end: usize, target: &[u8]) -> bool {
+    while start < end && prefixes[start].is_ascii_whitespace() {
+        start += 1;
+    }
+    while end > start && prefixes[end - 1].is_ascii_whitespace() {
+        end -= 1;
+    }
+
+    let len = end - start;
+    if len == 0 || target.len() < len {
+        return false;
+    }
+
+    let mut i = 0;
+    while i < len {
+        if prefixes[start + i] != target[i] {
+            return false;
+        }
+
+        i += 1;
+    }
+
+    target.len() == len
+        || (target.len() >= len + 2 && target[len] == b':' && target[len + 1] == b':')
 }
 
 pub fn enabled(level: Level, target: &str) -> bool {
-    crate::logger().enabled(&Metadata::builder().level(level).target(target).build())
+    if is_target_stripped(target) || is_target_disabled(level, target) {
+        return false;
+    }
+
+    with_logger(|logger| logger.enabled(&Metadata::builder().level(level).target(target).build()))
+}
+
+#[cfg(feature = "kv")]
+pub fn enabled_keys(level: Level, target: &str, keys: &[&str]) -> bool {
+    enabled_static(level, target, keys, &[])
+}
+
+#[cfg(feature = "kv")]
+pub fn enabled_static(
+    level: Level,
+    target: &str,
+    keys: &[&str],
+    static_key_values: &[(&str, &str)],
+) -> bool {
+    if is_target_stripped(target) || is_target_disabled(level, target) {
+        return false;
+    }
+
+    with_logger(|logger| {
+        logger.enabled(
+            &Metadata::builder()
+                .level(level)
+                .target(target)
+                .keys(keys)
+                .static_key_values(static_key_values)
+                .build(),
+        )
+    })
 }
 
+#[cfg(not(all(feature = "release_strip_location", not(debug_assertions))))]
 #[track_caller]
-pub fn loc() -> &'static Location<'static> {
-    Location::caller()
+pub fn loc() -> Option<&'static Location<'static>> {
+    Some(Location::caller())
+}
+
+#[cfg(all(feature = "release_strip_location", not(debug_assertions)))]
+pub fn loc() -> Option<&'static Location<'static>> {
+    None
+}
+
+// The sequence number for the next record logged by the macros
+// Targets without 64-bit atomics use a pointer-sized counter, which may wrap
+#[cfg(all(feature = "seq", target_has_atomic = "64"))]
+static SEQ: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+#[cfg(all(feature = "seq", not(target_has_atomic = "64")))]
+static SEQ: AtomicUsize = AtomicUsize::new(0);
+
+#[cfg(all(feature = "seq", target_has_atomic = "64"))]
+fn next_seq() -> u64 {
+    SEQ.fetch_add(1, Ordering::Relaxed)
+}
+
+#[cfg(all(feature = "seq", not(target_has_atomic = "64")))]
+fn next_seq() -> u64 {
+    SEQ.fetch_add(1, Ordering::Relaxed) as u64
+}
+
+// Rate limiting.
+
+/// The state for a rate-limited callsite, like `log_once!` or `log_every_n!`.
+pub struct RateLimit(AtomicUsize);
+
+impl RateLimit {
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> Self {
+        RateLimit(AtomicUsize::new(0))
+    }
+
+    pub fn once(&self) -> bool {
+        self.0
+            .compare_exchange(0, 1, Ordering::Relaxed, Ordering::Relaxed)
+            .is_ok()
+    }
+
+    pub fn every_n(&self, n: usize) -> bool {
+        self.0.fetch_add(1, Ordering::Relaxed) % n.max(1) == 0
+    }
+
+    #[cfg(feature = "std")]
+    pub fn every_secs(&self, secs: u64) -> bool {
+        use std::convert::TryFrom;
+        use std::time::{SystemTime, UNIX_EPOCH};
+
+        // The state holds the second the callsite last logged at, plus one,
+        // so that `0` can mean it hasn't logged yet
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|now| usize::try_from(now.as_secs()).unwrap_or(usize::MAX))
+            .unwrap_or(0)
+            .saturating_add(1);
+        let secs = usize::try_from(secs).unwrap_or(usize::MAX);
+
+        let last = self.0.load(Ordering::Relaxed);
+
+        // If the clock has gone backwards then log anyway
+        if last != 0 && now >= last && now - last < secs {
+            return false;
+        }
+
+        self.0
+            .compare_exchange(last, now, Ordering::Relaxed, Ordering::Relaxed)
+            .is_ok()
+    }
+}
+
+impl std::fmt::Debug for RateLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("RateLimit").finish_non_exhaustive()
+    }
+}
+
+// Timing.
+
+/// A guard that logs the time since it was created when it's dropped, for `log_time!`.
+#[cfg(all(feature = "kv", feature = "std"))]
+#[must_use = "the elapsed time is logged when the guard is dropped"]
+pub struct Timer<'a> {
+    label: &'a str,
+    level: Level,
+    static_max_level: LevelFilter,
+    target_module_path_and_loc: (
+        CrateTarget<'a>,
+        &'static str,
+        Option<&'static Location<'static>>,
+    ),
+    start: std::time::Instant,
+}
+
+#[cfg(all(feature = "kv", feature = "std"))]
+impl<'a> Timer<'a> {
+    pub fn new(
+        label: &'a str,
+        level: Level,
+        static_max_level: LevelFilter,
+        target_module_path_and_loc: (
+            CrateTarget<'a>,
+            &'static str,
+            Option<&'static Location<'static>>,
+        ),
+    ) -> Self {
+        Timer {
+            label,
+            level,
+            static_max_level,
+            target_module_path_and_loc,
+            start: std::time::Instant::now(),
+        }
+    }
+}
+
+#[cfg(all(feature = "kv", feature = "std"))]
+impl<'a> Drop for Timer<'a> {
+    fn drop(&mut self) {
+        let duration_ms = self.start.elapsed().as_millis();
+
+        if self.level <= self.static_max_level && self.level <= crate::max_level() {
+            let (target, module_path, loc) = self.target_module_path_and_loc;
+
+            log_impl(
+                format_args!("{}", self.label),
+                self.level,
+                (&target, module_path, loc),
+                Some(&[("duration_ms", Value::from(duration_ms))]),
+                None,
+            );
+        }
+    }
+}
+
+#[cfg(all(feature = "kv", feature = "std"))]
+impl<'a> std::fmt::Debug for Timer<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Timer")
+            .field("label", &self.label)
+            .field("level", &self.level)
+            .finish_non_exhaustive()
+    }
+}
+
+// Building messages.
+
+#[cfg(feature = "std")]
+pub fn log_writer<'a>(
+    level: Level,
+    static_max_level: LevelFilter,
+    target_module_path_and_loc: (
+        CrateTarget<'a>,
+        &'static str,
+        Option<&'static Location<'static>>,
+    ),
+) -> crate::LogWriter<'a> {
+    let enabled = level <= static_max_level
+        && level <= crate::max_level()
+        && enabled(level, crate_target(&target_module_path_and_loc.0));
+
+    crate::LogWriter::new(enabled, level, target_module_path_and_loc)
+}
+
+// Logging errors.
+
+/// Formats an error followed by its sources, separated by `: `, for `log_err!`.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct ErrorChainDisplay<'a, E: ?Sized>(pub &'a E);
+
+#[cfg(feature = "std")]
+impl<'a, E: std::error::Error + ?Sized> std::fmt::Display for ErrorChainDisplay<'a, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        std::fmt::Display::fmt(self.0, f)?;
+
+        let mut source = self.0.source();
+        while let Some(err) = source {
+            write!(f, ": {}", err)?;
+            source = err.source();
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(feature = "kv")]
@@ -95,19 +825,76 @@ mod kv_support {
         v.to_value()
     }
 
+    pub fn capture_redacted<'a, V: kv::ToValue + ?Sized>(v: &'a &'a V) -> Value<'a> {
+        Value::from_redacted(v)
+    }
+
+    pub fn capture_unit<'a, V: kv::ToValue + ?Sized>(
+        v: &'a &'a V,
+        unit: &'static str,
+    ) -> Value<'a> {
+        v.to_value().with_unit(unit)
+    }
+
+    pub fn capture_hint<'a, V: kv::ToValue + ?Sized>(
+        v: &'a &'a V,
+        hint: kv::FormatHint,
+    ) -> Value<'a> {
+        Value::from_hinted(v, hint)
+    }
+
     pub fn capture_debug<'a, V: core::fmt::Debug + ?Sized>(v: &'a &'a V) -> Value<'a> {
         Value::from_debug(v)
     }
 
+    pub fn capture_debug_pretty<'a, V: core::fmt::Debug + ?Sized>(v: &'a &'a V) -> Value<'a> {
+        Value::from_hinted(PrettyDebug::new(v), kv::FormatHint::PrettyDebug)
+    }
+
     pub fn capture_display<'a, V: core::fmt::Display + ?Sized>(v: &'a &'a V) -> Value<'a> {
         Value::from_display(v)
     }
 
+    /// A wrapper that captures a type through its alternate `Debug` implementation.
+    ///
+    /// Not every backend passes formatting flags through to captured values,
+    /// so the wrapper always formats using `{:#?}`.
+    #[repr(transparent)]
+    struct PrettyDebug<T>(T);
+
+    impl<T: core::fmt::Debug> PrettyDebug<T> {
+        fn new(v: &T) -> &Self {
+            // SAFETY: `PrettyDebug<T>` is `repr(transparent)` over `T`
+            unsafe { &*(v as *const T as *const PrettyDebug<T>) }
+        }
+    }
+
+    impl<T: core::fmt::Debug> core::fmt::Debug for PrettyDebug<T> {
+        fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+            write!(f, "{:#?}", self.0)
+        }
+    }
+
+    impl<T: core::fmt::Debug> kv::ToValue for PrettyDebug<T> {
+        fn to_value(&self) -> Value {
+            Value::from_debug(self)
+        }
+    }
+
     #[cfg(feature = "kv_std")]
     pub fn capture_error<'a>(v: &'a (dyn std::error::Error + 'static)) -> Value<'a> {
         Value::from_dyn_error(v)
     }
 
+    #[cfg(feature = "kv_std")]
+    pub fn capture_result<'a, T, E>(v: &'a Result<T, E>) -> Value<'a>
+    where
+        T: kv::ToValue,
+        E: std::error::Error + 'static,
+    {
+        Value::from_result(v)
+    }
+
     #[cfg(feature = "kv_sval")]
     pub fn capture_sval<'a, V: sval::Value + ?Sized>(v: &'a &'a V) -> Value<'a> {
         Value::from_sval(v)
@@ -117,6 +904,236 @@ mod kv_support {
     pub fn capture_serde<'a, V: serde::Serialize + ?Sized>(v: &'a &'a V) -> Value<'a> {
         Value::from_serde(v)
     }
+
+    #[cfg(feature = "kv_json")]
+    pub fn capture_json<'a, V: AsRef<str> + ?Sized>(v: &'a &'a V) -> Value<'a> {
+        Value::from_raw_json(AsRef::<str>::as_ref(*v))
+    }
+
+    // Types for the `ToValue` derive.
+
+    /// The fields of a type with a derived `ToValue` implementation.
+    pub trait Fields {
+        const NAME: &'static str;
+
+        fn visit_fields<'kvs>(
+            &'kvs self,
+            visitor: &mut dyn kv::VisitSource<'kvs>,
+        ) -> Result<(), kv::Error>;
+    }
+
+    pub fn capture_fields<T: Fields>(v: &T) -> Value<'_> {
+        #[cfg(feature = "kv_sval")]
+        {
+            Value::from_sval(DerivedFields::new(v))
+        }
+        #[cfg(all(feature = "kv_serde", not(feature = "kv_sval")))]
+        {
+            Value::from_serde(DerivedFields::new(v))
+        }
+        #[cfg(not(any(feature = "kv_sval", feature = "kv_serde")))]
+        {
+            Value::from_debug(DerivedFields::new(v))
+        }
+    }
+
+    /// A wrapper that formats or serializes a type through its `Fields`.
+    #[repr(transparent)]
+    struct DerivedFields<T>(T);
+
+    impl<T: Fields> DerivedFields<T> {
+        fn new(v: &T) -> &Self {
+            // SAFETY: `DerivedFields<T>` is `repr(transparent)` over `T`
+            unsafe { &*(v as *const T as *const DerivedFields<T>) }
+        }
+    }
+
+    impl<T: Fields> core::fmt::Debug for DerivedFields<T> {
+        fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+            struct DebugFields<'a, 'b: 'a>(core::fmt::DebugStruct<'a, 'b>);
+
+            impl<'a, 'b: 'a, 'kvs> kv::VisitSource<'kvs> for DebugFields<'a, 'b> {
+                fn visit_pair(
+                    &mut self,
+                    key: kv::Key<'kvs>,
+                    value: kv::Value<'kvs>,
+                ) -> Result<(), kv::Error> {
+                    self.0.field(key.as_str(), &value);
+                    Ok(())
+                }
+            }
+
+            let mut fields = DebugFields(f.debug_struct(T::NAME));
+            let _ = self.0.visit_fields(&mut fields);
+            fields.0.finish()
+        }
+    }
+
+    #[cfg(feature = "kv_sval")]
+    impl<T: Fields> sval::Value for DerivedFields<T> {
+        fn stream<'sval, S: sval::Stream<'sval> + ?Sized>(
+            &'sval self,
+            stream: &mut S,
+        ) -> sval::Result {
+            struct StreamFields<'a, S: ?Sized>(&'a mut S);
+
+            impl<'a, 'sval, 'kvs, S: sval::Stream<'sval> + ?Sized> kv::VisitSource<'kvs>
+                for StreamFields<'a, S>
+            {
+                fn visit_pair(
+                    &mut self,
+                    key: kv::Key<'kvs>,
+                    value: kv::Value<'kvs>,
+                ) -> Result<(), kv::Error> {
+                    let err = |_| {
+                        kv::Error::new(kv::ErrorKind::Serialization, "failed to stream a field")
+                    };
+
+                    self.0.map_key_begin().map_err(err)?;
+                    self.0.value_computed(key.as_str()).map_err(err)?;
+                    self.0.map_key_end().map_err(err)?;
+                    self.0.map_value_begin().map_err(err)?;
+                    self.0.value_computed(&value).map_err(err)?;
+                    self.0.map_value_end().map_err(err)
+                }
+            }
+
+            stream.map_begin(None)?;
+            self.0
+                .visit_fields(&mut StreamFields(stream))
+                .map_err(|_| sval::Error::new())?;
+            stream.map_end()
+        }
+    }
+
+    #[cfg(feature = "kv_serde")]
+    impl<T: Fields> serde::Serialize for DerivedFields<T> {
+        fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            use serde::ser::SerializeMap;
+
+            struct SerializeFields<M: SerializeMap> {
+                map: M,
+                err: Option<M::Error>,
+            }
+
+            impl<'kvs, M: SerializeMap> kv::VisitSource<'kvs> for SerializeFields<M> {
+                fn visit_pair(
+                    &mut self,
+                    key: kv::Key<'kvs>,
+                    value: kv::Value<'kvs>,
+                ) -> Result<(), kv::Error> {
+                    self.map
+                        .serialize_entry(key.as_str(), &value)
+                        .map_err(|err| {
+                            self.err = Some(err);
+                            kv::Error::new(
+                                kv::ErrorKind::Serialization,
+                                "failed to serialize a field",
+                            )
+                        })
+                }
+            }
+
+            let mut fields = SerializeFields {
+                map: s.serialize_map(None)?,
+                err: None,
+            };
+
+            if self.0.visit_fields(&mut fields).is_err() {
+                if let Some(err) = fields.err {
+                    return Err(err);
+                }
+            }
+
+            fields.map.end()
+        }
+    }
+
+    // Types for the `kv!` macro.
+
+    /// A key-value in a source built by `kv!`, followed by the rest of the source.
+    ///
+    /// Values are stored as references so temporaries in the macro get their
+    /// lifetimes extended, and are only captured when the source is visited.
+    pub struct KvPair<'a, V: ?Sized, R> {
+        pub key: &'a str,
+        pub value: &'a V,
+        pub capture: for<'v> fn(&'v &'v V) -> Value<'v>,
+        pub rest: R,
+    }
+
+    /// The end of a source built by `kv!`.
+    pub struct KvEnd;
+
+    impl<'a, V, R> kv::Source for KvPair<'a, V, R>
+    where
+        V: ?Sized,
+        R: kv::Source,
+    {
+        fn visit<'kvs>(
+            &'kvs self,
+            visitor: &mut dyn kv::VisitSource<'kvs>,
+        ) -> Result<(), kv::Error> {
+            visitor.visit_pair(kv::Key::from_str(self.key), (self.capture)(&self.value))?;
+            self.rest.visit(visitor)
+        }
+
+        fn count(&self) -> usize {
+            1 + self.rest.count()
+        }
+
+        fn is_empty(&self) -> bool {
+            false
+        }
+    }
+
+    impl kv::Source for KvEnd {
+        fn visit<'kvs>(&'kvs self, _: &mut dyn kv::VisitSource<'kvs>) -> Result<(), kv::Error> {
+            Ok(())
+        }
+
+        fn count(&self) -> usize {
+            0
+        }
+
+        fn is_empty(&self) -> bool {
+            true
+        }
+    }
+
+    impl<'a, V, R> core::fmt::Debug for KvPair<'a, V, R>
+    where
+        V: ?Sized,
+        R: kv::Source,
+    {
+        fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+            struct Entries<'a, 'b: 'a>(core::fmt::DebugMap<'a, 'b>);
+
+            impl<'a, 'b: 'a, 'kvs> kv::VisitSource<'kvs> for Entries<'a, 'b> {
+                fn visit_pair(
+                    &mut self,
+                    key: kv::Key<'kvs>,
+                    value: kv::Value<'kvs>,
+                ) -> Result<(), kv::Error> {
+                    self.0.entry(&key.as_str(), &value);
+                    Ok(())
+                }
+            }
+
+            let mut entries = Entries(f.debug_map());
+            let _ = kv::Source::visit(self, &mut entries);
+            entries.0.finish()
+        }
+    }
+
+    impl core::fmt::Debug for KvEnd {
+        fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+            f.debug_map().finish()
+        }
+    }
 }
 
 #[cfg(feature = "kv")]
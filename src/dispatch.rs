@@ -0,0 +1,274 @@
+//! Sending records to multiple loggers.
+
+use std::fmt;
+
+use crate::{FlushError, Interest, LevelFilter, Log, Metadata, Record, SetLoggerError};
+
+/// A logger that sends records to a set of other loggers.
+///
+/// Each logger is given a level filter, and only receives records at or above it.
+/// This lets an application log to, say, stderr and a file and a network backend
+/// at different levels without a third-party multiplexer.
+///
+/// # Examples
+///
+/// ```
+/// use log::{Dispatch, LevelFilter, Log, Metadata, Record};
+///
+/// struct Stderr;
+///
+/// impl Log for Stderr {
+///     fn enabled(&self, _: &Metadata) -> bool {
+///         true
+///     }
+///
+///     fn log(&self, record: &Record) {
+///         eprintln!("{} - {}", record.level(), record.args());
+///     }
+///
+///     fn flush(&self) {}
+/// }
+///
+/// # struct File;
+/// # impl Log for File {
+/// #     fn enabled(&self, _: &Metadata) -> bool { true }
+/// #     fn log(&self, _: &Record) {}
+/// #     fn flush(&self) {}
+/// # }
+/// # fn main() -> Result<(), log::SetLoggerError> {
+/// Dispatch::new()
+///     .chain(LevelFilter::Warn, Stderr)
+///     .chain(LevelFilter::Debug, File)
+///     .apply()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Default)]
+pub struct Dispatch {
+    loggers: Vec<(LevelFilter, Box<dyn Log>)>,
+}
+
+impl Dispatch {
+    /// Create a dispatcher without any loggers.
+    pub fn new() -> Self {
+        Dispatch::default()
+    }
+
+    /// Add a logger that receives records at or above the given level.
+    pub fn chain(mut self, level: LevelFilter, logger: impl Log + 'static) -> Self {
+        self.loggers.push((level, Box::new(logger)));
+        self
+    }
+
+    /// Add a boxed logger that receives records at or above the given level.
+    pub fn chain_boxed(mut self, level: LevelFilter, logger: Box<dyn Log>) -> Self {
+        self.loggers.push((level, logger));
+        self
+    }
+
+    /// The most verbose level any of the loggers receive.
+    ///
+    /// This is the level to pass to [`set_max_level`](crate::set_max_level) when the
+    /// dispatcher is the global logger.
+    pub fn max_level(&self) -> LevelFilter {
+        self.loggers
+            .iter()
+            .map(|(level, _)| *level)
+            .max()
+            .unwrap_or(LevelFilter::Off)
+    }
+
+    /// Set this dispatcher as the global logger, and the max level to [`Dispatch::max_level`].
+    pub fn apply(self) -> Result<(), SetLoggerError> {
+        let max_level = self.max_level();
+
+        crate::set_boxed_logger(Box::new(self)).map(|()| crate::set_max_level(max_level))
+    }
+}
+
+impl Log for Dispatch {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.loggers
+            .iter()
+            .any(|(level, logger)| metadata.level() <= *level && logger.enabled(metadata))
+    }
+
+    fn log(&self, record: &Record) {
+        for (level, logger) in &self.loggers {
+            if record.level() <= *level {
+                logger.log(record);
+            }
+        }
+    }
+
+    fn flush(&self) {
+        for (_, logger) in &self.loggers {
+            logger.flush();
+        }
+    }
+
+    fn try_flush(&self) -> Result<(), FlushError> {
+        // Every logger is flushed, even if an earlier one fails. The first error is returned
+        let mut result = Ok(());
+        for (_, logger) in &self.loggers {
+            let flushed = logger.try_flush();
+            if result.is_ok() {
+                result = flushed;
+            }
+        }
+
+        result
+    }
+
+    fn shutdown(&self) {
+        for (_, logger) in &self.loggers {
+            logger.shutdown();
+        }
+    }
+
+    fn interest(&self, metadata: &Metadata) -> Interest {
+        // The dispatcher is only as certain as the least certain logger that could log the record
+        let mut interest = Interest::Never;
+        for (level, logger) in &self.loggers {
+            if metadata.level() > *level {
+                continue;
+            }
+
+            match logger.interest(metadata) {
+                Interest::Never => (),
+                Interest::Sometimes => return Interest::Sometimes,
+                Interest::Always => interest = Interest::Always,
+            }
+        }
+
+        interest
+    }
+}
+
+impl fmt::Debug for Dispatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Dispatch")
+            .field(
+                "levels",
+                &self
+                    .loggers
+                    .iter()
+                    .map(|(level, _)| level)
+                    .collect::<Vec<_>>(),
+            )
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Level;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct Capture(Arc<Mutex<Vec<Level>>>);
+
+    impl Log for Capture {
+        fn enabled(&self, _: &Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &Record) {
+            self.0.lock().unwrap().push(record.level());
+        }
+
+        fn flush(&self) {}
+    }
+
+    struct Failing;
+
+    impl Log for Failing {
+        fn enabled(&self, _: &Metadata) -> bool {
+            false
+        }
+
+        fn log(&self, _: &Record) {}
+
+        fn flush(&self) {}
+
+        fn try_flush(&self) -> Result<(), FlushError> {
+            Err(FlushError::msg("failed"))
+        }
+    }
+
+    #[test]
+    fn dispatch() {
+        let (warn, debug) = (Capture::default(), Capture::default());
+
+        let dispatch = Dispatch::new()
+            .chain(LevelFilter::Warn, warn.clone())
+            .chain(LevelFilter::Debug, debug.clone());
+        assert_eq!(LevelFilter::Debug, dispatch.max_level());
+
+        for level in [Level::Error, Level::Info, Level::Trace] {
+            dispatch.log(&Record::builder().level(level).build());
+        }
+
+        assert_eq!(vec![Level::Error], *warn.0.lock().unwrap());
+        assert_eq!(vec![Level::Error, Level::Info], *debug.0.lock().unwrap());
+
+        assert!(dispatch.enabled(&Metadata::builder().level(Level::Debug).build()));
+        assert!(!dispatch.enabled(&Metadata::builder().level(Level::Trace).build()));
+    }
+
+    #[test]
+    fn dispatch_interest() {
+        struct Interested(Interest);
+
+        impl Log for Interested {
+            fn enabled(&self, _: &Metadata) -> bool {
+                self.0 != Interest::Never
+            }
+
+            fn log(&self, _: &Record) {}
+
+            fn flush(&self) {}
+
+            fn interest(&self, _: &Metadata) -> Interest {
+                self.0
+            }
+        }
+
+        let interest = |dispatch: &Dispatch, level| {
+            dispatch.interest(&Metadata::builder().level(level).build())
+        };
+
+        let dispatch = Dispatch::new()
+            .chain(LevelFilter::Warn, Interested(Interest::Always))
+            .chain(LevelFilter::Debug, Interested(Interest::Never));
+        assert_eq!(Interest::Always, interest(&dispatch, Level::Error));
+        assert_eq!(Interest::Never, interest(&dispatch, Level::Info));
+
+        let dispatch = dispatch.chain(LevelFilter::Info, Interested(Interest::Sometimes));
+        assert_eq!(Interest::Sometimes, interest(&dispatch, Level::Info));
+        assert_eq!(Interest::Never, interest(&dispatch, Level::Trace));
+    }
+
+    #[test]
+    fn dispatch_empty() {
+        let dispatch = Dispatch::new();
+
+        assert_eq!(LevelFilter::Off, dispatch.max_level());
+        assert!(!dispatch.enabled(&Metadata::builder().level(Level::Error).build()));
+        assert!(dispatch.try_flush().is_ok());
+    }
+
+    #[test]
+    fn dispatch_try_flush() {
+        let dispatch = Dispatch::new()
+            .chain(LevelFilter::Info, Capture::default())
+            .chain_boxed(LevelFilter::Info, Box::new(Failing));
+
+        assert_eq!("failed", dispatch.try_flush().unwrap_err().to_string());
+
+        // Loggers that filter everything out themselves aren't enabled
+        let dispatch = Dispatch::new().chain(LevelFilter::Trace, Failing);
+        assert!(!dispatch.enabled(&Metadata::builder().build()));
+    }
+}
@@ -0,0 +1,183 @@
+//! Buffering records logged before the global logger is set.
+
+use std::mem;
+use std::ptr;
+use std::sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use crate::{LevelFilter, Log, Metadata, OwnedRecord, Record, MAX_LOG_LEVEL_FILTER};
+
+/// The most records that are buffered before the global logger is set.
+///
+/// Records logged once the buffer is full are dropped.
+pub(crate) const CAPACITY: usize = 1024;
+
+static BUFFER: AtomicPtr<Mutex<Vec<OwnedRecord>>> = AtomicPtr::new(ptr::null_mut());
+
+// Set once the buffer has been replayed, so records logged after that aren't held forever
+// It's only set while holding the buffer's lock, so records are never pushed after it's taken
+static CLOSED: AtomicBool = AtomicBool::new(false);
+
+// The level passed to `set_early_buffer_level`, and the max level it replaced, so the max
+// level can be put back once the buffer has been replayed
+static BUFFER_LEVEL: AtomicUsize = AtomicUsize::new(UNSET);
+static PREVIOUS_MAX_LEVEL: AtomicUsize = AtomicUsize::new(0);
+
+const UNSET: usize = usize::MAX;
+
+/// Buffer records up to a level until the global logger is set.
+///
+/// The max level is `Off` until [`set_max_level`](crate::set_max_level) is called, so records
+/// logged before then are normally discarded by the log macros, even with the `early_buffer`
+/// feature. This sets the max level to `level` until a logger is set, so records up to it are
+/// buffered and replayed into the logger once it's set. Up to 1024 records are buffered, and
+/// later ones are dropped.
+///
+/// When the buffer is replayed, the max level is put back to what it was before this was
+/// called, unless it's been changed since. Calling this after a logger has been set does
+/// nothing.
+///
+/// Requires the `early_buffer` feature.
+///
+/// # Examples
+///
+/// ```
+/// use log::{info, LevelFilter};
+///
+/// log::set_early_buffer_level(LevelFilter::Info);
+///
+/// // This record is buffered until a logger is set
+/// info!("loading configuration");
+/// ```
+pub fn set_early_buffer_level(level: LevelFilter) {
+    let buffer = match buffer().lock() {
+        Ok(buffer) => buffer,
+        Err(_) => return,
+    };
+
+    if CLOSED.load(Ordering::Acquire) {
+        return;
+    }
+
+    let previous = MAX_LOG_LEVEL_FILTER.swap(level as usize, Ordering::Relaxed);
+
+    // Only remember the max level from before the first call
+    if BUFFER_LEVEL.swap(level as usize, Ordering::Relaxed) == UNSET {
+        PREVIOUS_MAX_LEVEL.store(previous, Ordering::Relaxed);
+    }
+
+    drop(buffer);
+}
+
+/// The logger used in place of the global one until it's set.
+pub(crate) struct EarlyLogger;
+
+pub(crate) static EARLY: EarlyLogger = EarlyLogger;
+
+/// Whether records are still being buffered.
+pub(crate) fn is_open() -> bool {
+    !CLOSED.load(Ordering::Acquire)
+}
+
+impl Log for EarlyLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        if is_open() {
+            true
+        } else {
+            crate::logger().enabled(metadata)
+        }
+    }
+
+    fn log(&self, record: &Record) {
+        if let Ok(mut buffer) = buffer().lock() {
+            // The buffer may have been replayed since this logger was returned by `logger`,
+            // in which case the global logger has been set
+            if CLOSED.load(Ordering::Acquire) {
+                drop(buffer);
+                crate::logger().log(record);
+
+                return;
+            }
+
+            if buffer.len() < CAPACITY {
+                buffer.push(record.to_owned());
+            }
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Replay the buffered records into the global logger, and stop buffering.
+///
+/// Records are only replayed if the logger is enabled for them. Records logged while the
+/// buffer is being replayed are buffered and replayed too, so they keep their order.
+/// `initialize` is called once the buffer is empty, before it's closed and while records
+/// can't be pushed to it, so records logged after that are passed to the global logger
+/// directly.
+pub(crate) fn replay(logger: &dyn Log, initialize: impl FnOnce()) {
+    loop {
+        let records = match buffer().lock() {
+            Ok(mut buffer) => {
+                if buffer.is_empty() {
+                    // The global logger is set before the buffer is closed, so there's no
+                    // point where `logger` falls back to a no-op logger
+                    initialize();
+                    reset_max_level();
+                    CLOSED.store(true, Ordering::Release);
+
+                    return;
+                }
+
+                mem::take(&mut *buffer)
+            }
+            Err(_) => return initialize(),
+        };
+
+        for record in &records {
+            record.with_record(|record| {
+                if logger.enabled(record.metadata()) {
+                    logger.log(record);
+                }
+            });
+        }
+    }
+}
+
+fn reset_max_level() {
+    let level = BUFFER_LEVEL.swap(UNSET, Ordering::Relaxed);
+
+    if level != UNSET {
+        let _ = MAX_LOG_LEVEL_FILTER.compare_exchange(
+            level,
+            PREVIOUS_MAX_LEVEL.load(Ordering::Relaxed),
+            Ordering::Relaxed,
+            Ordering::Relaxed,
+        );
+    }
+}
+
+fn buffer() -> &'static Mutex<Vec<OwnedRecord>> {
+    let mut current = BUFFER.load(Ordering::Acquire);
+
+    if current.is_null() {
+        let buffer = Box::into_raw(Box::new(Mutex::new(Vec::new())));
+
+        current = match BUFFER.compare_exchange(
+            ptr::null_mut(),
+            buffer,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        ) {
+            Ok(_) => buffer,
+            Err(actual) => {
+                // SAFETY: The buffer was never shared
+                drop(unsafe { Box::from_raw(buffer) });
+                actual
+            }
+        };
+    }
+
+    // SAFETY: Buffers stored in `BUFFER` are never freed
+    unsafe { &*current }
+}
@@ -0,0 +1,233 @@
+//! Filtering records using `RUST_LOG`-style directives.
+//!
+//! A [`Filter`] is parsed from a comma-separated list of directives, like
+//! `info,my_crate=debug,hyper::proto=warn`. Each directive is one of:
+//!
+//! * `level`, which sets the level for targets that don't match any other directive.
+//! * `target=level`, which sets the level for a target and any targets nested under it.
+//! * `target`, which enables all levels for a target and any targets nested under it.
+//!
+//! Levels are one of `off`, `error`, `warn`, `info`, `debug`, or `trace`, ignoring case. A
+//! target matches itself and any targets nested under it, so `hyper` matches `hyper` and
+//! `hyper::proto`, but not `hyperlocal`. When multiple targets match, the most specific one
+//! is used. When the same target appears more than once, the last directive wins. Targets
+//! that don't match any directive are disabled unless a default level is given.
+//!
+//! Loggers can use filters to share the same semantics, instead of each parsing directives
+//! their own way.
+//!
+//! # Examples
+//!
+//! ```
+//! use log::filter::Filter;
+//! use log::{Level, LevelFilter, Metadata};
+//!
+//! # fn main() -> Result<(), log::filter::ParseFilterError> {
+//! let filter: Filter = "info,my_crate=debug,hyper::proto=warn".parse()?;
+//!
+//! assert_eq!(LevelFilter::Debug, filter.max_level());
+//! assert_eq!(LevelFilter::Warn, filter.level_for("hyper::proto::h1"));
+//!
+//! let metadata = Metadata::builder()
+//!     .level(Level::Debug)
+//!     .target("my_crate::db")
+//!     .build();
+//! assert!(filter.enabled(&metadata));
+//! # Ok(())
+//! # }
+//! ```
+
+use std::error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{LevelFilter, Metadata};
+
+/// A set of parsed `RUST_LOG`-style directives.
+///
+/// See the [module documentation](index.html) for the syntax.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Filter {
+    default: LevelFilter,
+    // Sorted with the longest targets first, so the most specific one matches
+    directives: Vec<(Box<str>, LevelFilter)>,
+}
+
+impl Filter {
+    /// Parse a filter from a comma-separated list of directives.
+    ///
+    /// Empty directives are ignored, so an empty string is a filter that disables everything.
+    pub fn parse(directives: &str) -> Result<Self, ParseFilterError> {
+        let mut filter = Filter {
+            default: LevelFilter::Off,
+            directives: Vec::new(),
+        };
+
+        for directive in directives.split(',').map(str::trim) {
+            if directive.is_empty() {
+                continue;
+            }
+
+            let mut parts = directive.splitn(2, '=');
+            let (target, level) = match (parts.next().map(str::trim), parts.next().map(str::trim)) {
+                (Some(target), Some(level)) if !target.is_empty() => {
+                    let level = level
+                        .parse()
+                        .map_err(|_| ParseFilterError::new(directive))?;
+
+                    (target, level)
+                }
+                (Some(level_or_target), None) => match level_or_target.parse() {
+                    Ok(level) => {
+                        filter.default = level;
+                        continue;
+                    }
+                    Err(_) => (level_or_target, LevelFilter::Trace),
+                },
+                _ => return Err(ParseFilterError::new(directive)),
+            };
+
+            filter.insert(target, level);
+        }
+
+        Ok(filter)
+    }
+
+    fn insert(&mut self, target: &str, level: LevelFilter) {
+        match self.directives.iter_mut().find(|(t, _)| **t == *target) {
+            Some((_, existing)) => *existing = level,
+            None => {
+                let index = self
+                    .directives
+                    .partition_point(|(t, _)| t.len() >= target.len());
+                self.directives.insert(index, (target.into(), level));
+            }
+        }
+    }
+
+    /// Whether a record with the given metadata passes the filter.
+    pub fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level_for(metadata.target())
+    }
+
+    /// The level for a target.
+    ///
+    /// This is the level of the most specific directive that matches the target, or the
+    /// default level if there isn't one.
+    pub fn level_for(&self, target: &str) -> LevelFilter {
+        self.directives
+            .iter()
+            .find(|(prefix, _)| crate::targets::matches(prefix, target))
+            .map_or(self.default, |(_, level)| *level)
+    }
+
+    /// The most verbose level any target is enabled for.
+    ///
+    /// This is the level to pass to [`set_max_level`](crate::set_max_level) when a logger
+    /// uses this filter.
+    pub fn max_level(&self) -> LevelFilter {
+        self.directives
+            .iter()
+            .map(|(_, level)| *level)
+            .fold(self.default, Ord::max)
+    }
+}
+
+impl FromStr for Filter {
+    type Err = ParseFilterError;
+
+    fn from_str(directives: &str) -> Result<Self, Self::Err> {
+        Filter::parse(directives)
+    }
+}
+
+/// The type returned by [`Filter::parse`] when a directive is invalid.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseFilterError {
+    directive: Box<str>,
+}
+
+impl ParseFilterError {
+    fn new(directive: &str) -> Self {
+        ParseFilterError {
+            directive: directive.into(),
+        }
+    }
+}
+
+impl fmt::Display for ParseFilterError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            fmt,
+            "invalid log directive `{}`, expected `level`, `target`, or `target=level`",
+            self.directive
+        )
+    }
+}
+
+impl error::Error for ParseFilterError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Level;
+
+    fn enabled(filter: &Filter, level: Level, target: &str) -> bool {
+        filter.enabled(&Metadata::builder().level(level).target(target).build())
+    }
+
+    #[test]
+    fn parse() {
+        let filter = Filter::parse(" info , my_crate=debug,hyper::proto = WARN ,,").unwrap();
+
+        assert_eq!(LevelFilter::Info, filter.level_for("other"));
+        assert_eq!(LevelFilter::Debug, filter.level_for("my_crate"));
+        assert_eq!(LevelFilter::Debug, filter.level_for("my_crate::db"));
+        assert_eq!(LevelFilter::Info, filter.level_for("my_crates"));
+        assert_eq!(LevelFilter::Warn, filter.level_for("hyper::proto::h1"));
+        assert_eq!(LevelFilter::Info, filter.level_for("hyper"));
+        assert_eq!(LevelFilter::Debug, filter.max_level());
+
+        assert!(enabled(&filter, Level::Debug, "my_crate::db"));
+        assert!(!enabled(&filter, Level::Trace, "my_crate::db"));
+        assert!(!enabled(&filter, Level::Info, "hyper::proto"));
+        assert!(enabled(&filter, Level::Info, "hyper::client"));
+    }
+
+    #[test]
+    fn parse_target_only() {
+        let filter: Filter = "my_crate".parse().unwrap();
+
+        assert_eq!(LevelFilter::Trace, filter.level_for("my_crate::db"));
+        assert_eq!(LevelFilter::Off, filter.level_for("other"));
+        assert_eq!(LevelFilter::Trace, filter.max_level());
+    }
+
+    #[test]
+    fn parse_last_wins() {
+        let filter = Filter::parse("warn,a=debug,a=error,info").unwrap();
+
+        assert_eq!(LevelFilter::Info, filter.level_for("b"));
+        assert_eq!(LevelFilter::Error, filter.level_for("a"));
+    }
+
+    #[test]
+    fn parse_empty() {
+        let filter = Filter::parse("").unwrap();
+
+        assert_eq!(LevelFilter::Off, filter.max_level());
+        assert!(!enabled(&filter, Level::Error, "a"));
+    }
+
+    #[test]
+    fn parse_invalid() {
+        for directives in ["a=loud", "=info", "a=info=debug", "info,a=1"] {
+            assert!(Filter::parse(directives).is_err(), "{}", directives);
+        }
+
+        assert_eq!(
+            "invalid log directive `a=loud`, expected `level`, `target`, or `target=level`",
+            Filter::parse("info,a=loud").unwrap_err().to_string()
+        );
+    }
+}
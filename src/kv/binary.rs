@@ -0,0 +1,723 @@
+//! Writing key-values in compact binary formats.
+//!
+//! These encoders don't depend on any serialization framework. They can write
+//! into a fixed-size buffer, so they're also available without `std`.
+
+use std::fmt::{self, Write};
+
+use crate::kv::{Error, ErrorKind, Key, Seq, Source, Value, VisitSeq, VisitSource, VisitValue};
+
+/// Write the key-values in a source as a CBOR map into a buffer.
+///
+/// This function returns the number of bytes written, or an error if the buffer
+/// is too small. The map is written using an indefinite length, so the source is
+/// only visited once. Values are written as:
+///
+/// - `null`, booleans, numbers, and strings as their CBOR equivalents. Integers that
+///   don't fit in 64 bits are written as bignums.
+/// - Byte slices as byte strings.
+/// - Sequences captured using [`Value::from_seq`] as arrays.
+/// - Maps captured using [`Value::from_map`] as maps.
+/// - Anything else as a text string, using its `Display` implementation.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> Result<(), log::kv::Error> {
+/// let mut buf = [0; 16];
+/// let len = log::kv::to_cbor_slice(&mut buf, &[("a", 1)])?;
+///
+/// assert_eq!([0xbf, 0x61, b'a', 0x01, 0xff], buf[..len]);
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "kv_cbor")]
+pub fn to_cbor_slice(buf: &mut [u8], source: &(impl Source + ?Sized)) -> Result<usize, Error> {
+    let mut sink = SliceSink { buf, len: 0 };
+    cbor::write_map(&mut sink, &source)?;
+
+    Ok(sink.len)
+}
+
+/// Write the key-values in a source as a CBOR map to an IO stream.
+///
+/// See [`to_cbor_slice`] for details on how values are written.
+#[cfg(all(feature = "kv_cbor", feature = "std"))]
+pub fn to_cbor(writer: impl std::io::Write, source: &(impl Source + ?Sized)) -> Result<(), Error> {
+    cbor::write_map(&mut std_support::IoSink(writer), &source)
+}
+
+/// Write the key-values in a source as a MessagePack map into a buffer.
+///
+/// This function returns the number of bytes written, or an error if the buffer
+/// is too small. MessagePack maps and arrays need their lengths upfront, so the source
+/// is counted using [`Source::count`] before it's visited, and sequences are visited twice.
+/// Values are written as:
+///
+/// - `null`, booleans, numbers, and strings as their MessagePack equivalents. Integers
+///   that don't fit in 64 bits are written as strings.
+/// - Byte slices as binary.
+/// - Sequences captured using [`Value::from_seq`] as arrays.
+/// - Maps captured using [`Value::from_map`] as maps.
+/// - Anything else as a string, using its `Display` implementation.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> Result<(), log::kv::Error> {
+/// let mut buf = [0; 16];
+/// let len = log::kv::to_msgpack_slice(&mut buf, &[("a", 1)])?;
+///
+/// assert_eq!([0x81, 0xa1, b'a', 0x01], buf[..len]);
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "kv_msgpack")]
+pub fn to_msgpack_slice(buf: &mut [u8], source: &(impl Source + ?Sized)) -> Result<usize, Error> {
+    let mut sink = SliceSink { buf, len: 0 };
+    msgpack::write_map(&mut sink, &source)?;
+
+    Ok(sink.len)
+}
+
+/// Write the key-values in a source as a MessagePack map to an IO stream.
+///
+/// See [`to_msgpack_slice`] for details on how values are written.
+#[cfg(all(feature = "kv_msgpack", feature = "std"))]
+pub fn to_msgpack(
+    writer: impl std::io::Write,
+    source: &(impl Source + ?Sized),
+) -> Result<(), Error> {
+    msgpack::write_map(&mut std_support::IoSink(writer), &source)
+}
+
+/**
+A destination for encoded bytes.
+*/
+trait Sink {
+    fn write_all(&mut self, bytes: &[u8]) -> Result<(), Error>;
+}
+
+struct SliceSink<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+
+impl<'a> Sink for SliceSink<'a> {
+    fn write_all(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        let end = self.len + bytes.len();
+
+        if end > self.buf.len() {
+            return Err(Error::new(ErrorKind::Io, "the buffer is too small"));
+        }
+
+        self.buf[self.len..end].copy_from_slice(bytes);
+        self.len = end;
+
+        Ok(())
+    }
+}
+
+/**
+Adapts a sink into a `fmt::Write`, holding on to any error.
+*/
+struct FmtSink<'a, F> {
+    sink: &'a mut dyn Sink,
+    write: F,
+    err: Option<Error>,
+}
+
+impl<'a, F: FnMut(&mut dyn Sink, &str) -> Result<(), Error>> Write for FmtSink<'a, F> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        (self.write)(self.sink, s).map_err(|err| {
+            self.err = Some(err);
+            fmt::Error
+        })
+    }
+}
+
+/**
+Write a value's `Display` implementation to a sink, calling `write` for each string it produces.
+*/
+fn write_display(
+    sink: &mut dyn Sink,
+    value: &Value,
+    write: impl FnMut(&mut dyn Sink, &str) -> Result<(), Error>,
+) -> Result<(), Error> {
+    let mut fmt = FmtSink {
+        sink,
+        write,
+        err: None,
+    };
+
+    match fmt::write(&mut fmt, format_args!("{}", value)) {
+        Ok(()) => Ok(()),
+        Err(err) => Err(fmt.err.unwrap_or_else(|| err.into())),
+    }
+}
+
+#[cfg(feature = "kv_cbor")]
+mod cbor {
+    use super::*;
+
+    const UINT: u8 = 0;
+    const NEGINT: u8 = 1;
+    const BYTES: u8 = 2;
+    const TEXT: u8 = 3;
+
+    const POS_BIGNUM: u8 = 0xc2;
+    const NEG_BIGNUM: u8 = 0xc3;
+    const FALSE: u8 = 0xf4;
+    const TRUE: u8 = 0xf5;
+    const NULL: u8 = 0xf6;
+    const F64: u8 = 0xfb;
+    const INDEFINITE_TEXT: u8 = 0x7f;
+    const INDEFINITE_ARRAY: u8 = 0x9f;
+    const INDEFINITE_MAP: u8 = 0xbf;
+    const BREAK: u8 = 0xff;
+
+    fn write_header(sink: &mut dyn Sink, major: u8, n: u64) -> Result<(), Error> {
+        let major = major << 5;
+
+        if n < 24 {
+            sink.write_all(&[major | n as u8])
+        } else if n <= u8::MAX as u64 {
+            sink.write_all(&[major | 24, n as u8])
+        } else if n <= u16::MAX as u64 {
+            sink.write_all(&[major | 25])?;
+            sink.write_all(&(n as u16).to_be_bytes())
+        } else if n <= u32::MAX as u64 {
+            sink.write_all(&[major | 26])?;
+            sink.write_all(&(n as u32).to_be_bytes())
+        } else {
+            sink.write_all(&[major | 27])?;
+            sink.write_all(&n.to_be_bytes())
+        }
+    }
+
+    fn write_bignum(sink: &mut dyn Sink, tag: u8, n: u128) -> Result<(), Error> {
+        let bytes = n.to_be_bytes();
+        let bytes = &bytes[(n.leading_zeros() / 8) as usize..];
+
+        sink.write_all(&[tag])?;
+        write_header(sink, BYTES, bytes.len() as u64)?;
+        sink.write_all(bytes)
+    }
+
+    fn write_str(sink: &mut dyn Sink, value: &str) -> Result<(), Error> {
+        write_header(sink, TEXT, value.len() as u64)?;
+        sink.write_all(value.as_bytes())
+    }
+
+    pub(super) fn write_map(sink: &mut dyn Sink, source: &dyn Source) -> Result<(), Error> {
+        struct Map<'a>(&'a mut dyn Sink);
+
+        impl<'a, 'kvs> VisitSource<'kvs> for Map<'a> {
+            fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+                write_str(self.0, key.as_str())?;
+                write_value(self.0, value)
+            }
+        }
+
+        sink.write_all(&[INDEFINITE_MAP])?;
+        source.visit(&mut Map(&mut *sink))?;
+        sink.write_all(&[BREAK])
+    }
+
+    fn write_seq(sink: &mut dyn Sink, seq: &dyn Seq) -> Result<(), Error> {
+        struct Array<'a>(&'a mut dyn Sink);
+
+        impl<'a, 'v> VisitSeq<'v> for Array<'a> {
+            fn visit_value(&mut self, value: Value<'v>) -> Result<(), Error> {
+                write_value(self.0, value)
+            }
+        }
+
+        sink.write_all(&[INDEFINITE_ARRAY])?;
+        seq.visit(&mut Array(&mut *sink))?;
+        sink.write_all(&[BREAK])
+    }
+
+    fn write_value(sink: &mut dyn Sink, value: Value) -> Result<(), Error> {
+        struct CborValue<'a>(&'a mut dyn Sink);
+
+        impl<'a, 'v> VisitValue<'v> for CborValue<'a> {
+            fn visit_any(&mut self, value: Value) -> Result<(), Error> {
+                // The length of a formatted value isn't known upfront,
+                // so each piece of it is written as a chunk of an indefinite string
+                self.0.write_all(&[INDEFINITE_TEXT])?;
+                write_display(self.0, &value, write_str)?;
+                self.0.write_all(&[BREAK])
+            }
+
+            fn visit_null(&mut self) -> Result<(), Error> {
+                self.0.write_all(&[NULL])
+            }
+
+            fn visit_u64(&mut self, value: u64) -> Result<(), Error> {
+                write_header(self.0, UINT, value)
+            }
+
+            fn visit_i64(&mut self, value: i64) -> Result<(), Error> {
+                if value < 0 {
+                    write_header(self.0, NEGINT, !(value as u64))
+                } else {
+                    write_header(self.0, UINT, value as u64)
+                }
+            }
+
+            fn visit_u128(&mut self, value: u128) -> Result<(), Error> {
+                if value <= u64::MAX as u128 {
+                    write_header(self.0, UINT, value as u64)
+                } else {
+                    write_bignum(self.0, POS_BIGNUM, value)
+                }
+            }
+
+            fn visit_i128(&mut self, value: i128) -> Result<(), Error> {
+                if value >= 0 {
+                    return self.visit_u128(value as u128);
+                }
+
+                let n = !(value as u128);
+                if n <= u64::MAX as u128 {
+                    write_header(self.0, NEGINT, n as u64)
+                } else {
+                    write_bignum(self.0, NEG_BIGNUM, n)
+                }
+            }
+
+            fn visit_f64(&mut self, value: f64) -> Result<(), Error> {
+                self.0.write_all(&[F64])?;
+                self.0.write_all(&value.to_be_bytes())
+            }
+
+            fn visit_bool(&mut self, value: bool) -> Result<(), Error> {
+                self.0.write_all(&[if value { TRUE } else { FALSE }])
+            }
+
+            fn visit_str(&mut self, value: &str) -> Result<(), Error> {
+                write_str(self.0, value)
+            }
+
+            fn visit_char(&mut self, value: char) -> Result<(), Error> {
+                write_str(self.0, value.encode_utf8(&mut [0; 4]))
+            }
+
+            fn visit_bytes(&mut self, value: &'v [u8]) -> Result<(), Error> {
+                write_header(self.0, BYTES, value.len() as u64)?;
+                self.0.write_all(value)
+            }
+
+            fn visit_seq(&mut self, seq: &'v dyn Seq) -> Result<(), Error> {
+                write_seq(self.0, seq)
+            }
+
+            fn visit_map(&mut self, map: &'v dyn Source) -> Result<(), Error> {
+                write_map(self.0, map)
+            }
+        }
+
+        value.visit(CborValue(sink))
+    }
+}
+
+#[cfg(feature = "kv_msgpack")]
+mod msgpack {
+    use super::*;
+
+    const NIL: u8 = 0xc0;
+    const FALSE: u8 = 0xc2;
+    const TRUE: u8 = 0xc3;
+    const F64: u8 = 0xcb;
+
+    /**
+    Write a length using a fixed format when it's small enough, or one of the sized formats after it.
+    */
+    fn write_len(
+        sink: &mut dyn Sink,
+        len: usize,
+        fixed: Option<(u8, usize)>,
+        sized: [Option<u8>; 3],
+    ) -> Result<(), Error> {
+        if let Some((marker, max)) = fixed {
+            if len <= max {
+                return sink.write_all(&[marker | len as u8]);
+            }
+        }
+
+        match sized {
+            [Some(marker), _, _] if len <= u8::MAX as usize => sink.write_all(&[marker, len as u8]),
+            [_, Some(marker), _] if len <= u16::MAX as usize => {
+                sink.write_all(&[marker])?;
+                sink.write_all(&(len as u16).to_be_bytes())
+            }
+            [_, _, Some(marker)] if len <= u32::MAX as usize => {
+                sink.write_all(&[marker])?;
+                sink.write_all(&(len as u32).to_be_bytes())
+            }
+            _ => Err(Error::new(
+                ErrorKind::Unsupported,
+                "the length is too large for MessagePack",
+            )),
+        }
+    }
+
+    fn write_str_len(sink: &mut dyn Sink, len: usize) -> Result<(), Error> {
+        write_len(
+            sink,
+            len,
+            Some((0xa0, 31)),
+            [Some(0xd9), Some(0xda), Some(0xdb)],
+        )
+    }
+
+    fn write_str(sink: &mut dyn Sink, value: &str) -> Result<(), Error> {
+        write_str_len(sink, value.len())?;
+        sink.write_all(value.as_bytes())
+    }
+
+    fn write_uint(sink: &mut dyn Sink, value: u64) -> Result<(), Error> {
+        if value <= 0x7f {
+            sink.write_all(&[value as u8])
+        } else if value <= u8::MAX as u64 {
+            sink.write_all(&[0xcc, value as u8])
+        } else if value <= u16::MAX as u64 {
+            sink.write_all(&[0xcd])?;
+            sink.write_all(&(value as u16).to_be_bytes())
+        } else if value <= u32::MAX as u64 {
+            sink.write_all(&[0xce])?;
+            sink.write_all(&(value as u32).to_be_bytes())
+        } else {
+            sink.write_all(&[0xcf])?;
+            sink.write_all(&value.to_be_bytes())
+        }
+    }
+
+    fn write_int(sink: &mut dyn Sink, value: i64) -> Result<(), Error> {
+        if value >= 0 {
+            write_uint(sink, value as u64)
+        } else if value >= -32 {
+            sink.write_all(&[value as u8])
+        } else if value >= i8::MIN as i64 {
+            sink.write_all(&[0xd0, value as u8])
+        } else if value >= i16::MIN as i64 {
+            sink.write_all(&[0xd1])?;
+            sink.write_all(&(value as i16).to_be_bytes())
+        } else if value >= i32::MIN as i64 {
+            sink.write_all(&[0xd2])?;
+            sink.write_all(&(value as i32).to_be_bytes())
+        } else {
+            sink.write_all(&[0xd3])?;
+            sink.write_all(&value.to_be_bytes())
+        }
+    }
+
+    /**
+    Write a value's `Display` implementation as a string.
+
+    The value is formatted twice; once to find its length and once to write it.
+    */
+    fn write_display_str(sink: &mut dyn Sink, value: &Value) -> Result<(), Error> {
+        struct Len(usize);
+
+        impl Write for Len {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                self.0 += s.len();
+                Ok(())
+            }
+        }
+
+        let mut len = Len(0);
+        write!(len, "{}", value)?;
+
+        write_str_len(sink, len.0)?;
+        write_display(sink, value, |sink, s| sink.write_all(s.as_bytes()))
+    }
+
+    pub(super) fn write_map(sink: &mut dyn Sink, source: &dyn Source) -> Result<(), Error> {
+        struct Map<'a>(&'a mut dyn Sink);
+
+        impl<'a, 'kvs> VisitSource<'kvs> for Map<'a> {
+            fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+                write_str(self.0, key.as_str())?;
+                write_value(self.0, value)
+            }
+        }
+
+        write_len(
+            sink,
+            source.count(),
+            Some((0x80, 15)),
+            [None, Some(0xde), Some(0xdf)],
+        )?;
+        source.visit(&mut Map(&mut *sink))
+    }
+
+    fn write_seq(sink: &mut dyn Sink, seq: &dyn Seq) -> Result<(), Error> {
+        struct Count(usize);
+
+        impl<'v> VisitSeq<'v> for Count {
+            fn visit_value(&mut self, _: Value<'v>) -> Result<(), Error> {
+                self.0 += 1;
+                Ok(())
+            }
+        }
+
+        struct Array<'a>(&'a mut dyn Sink);
+
+        impl<'a, 'v> VisitSeq<'v> for Array<'a> {
+            fn visit_value(&mut self, value: Value<'v>) -> Result<(), Error> {
+                write_value(self.0, value)
+            }
+        }
+
+        let mut count = Count(0);
+        seq.visit(&mut count)?;
+
+        write_len(
+            sink,
+            count.0,
+            Some((0x90, 15)),
+            [None, Some(0xdc), Some(0xdd)],
+        )?;
+        seq.visit(&mut Array(&mut *sink))
+    }
+
+    fn write_value(sink: &mut dyn Sink, value: Value) -> Result<(), Error> {
+        struct MsgpackValue<'a>(&'a mut dyn Sink);
+
+        impl<'a, 'v> VisitValue<'v> for MsgpackValue<'a> {
+            fn visit_any(&mut self, value: Value) -> Result<(), Error> {
+                write_display_str(self.0, &value)
+            }
+
+            fn visit_null(&mut self) -> Result<(), Error> {
+                self.0.write_all(&[NIL])
+            }
+
+            fn visit_u64(&mut self, value: u64) -> Result<(), Error> {
+                write_uint(self.0, value)
+            }
+
+            fn visit_i64(&mut self, value: i64) -> Result<(), Error> {
+                write_int(self.0, value)
+            }
+
+            fn visit_u128(&mut self, value: u128) -> Result<(), Error> {
+                match u64::try_from(value) {
+                    Ok(value) => write_uint(self.0, value),
+                    Err(_) => write_display_str(self.0, &Value::from(value)),
+                }
+            }
+
+            fn visit_i128(&mut self, value: i128) -> Result<(), Error> {
+                match i64::try_from(value) {
+                    Ok(value) => write_int(self.0, value),
+                    Err(_) => write_display_str(self.0, &Value::from(value)),
+                }
+            }
+
+            fn visit_f64(&mut self, value: f64) -> Result<(), Error> {
+                self.0.write_all(&[F64])?;
+                self.0.write_all(&value.to_be_bytes())
+            }
+
+            fn visit_bool(&mut self, value: bool) -> Result<(), Error> {
+                self.0.write_all(&[if value { TRUE } else { FALSE }])
+            }
+
+            fn visit_str(&mut self, value: &str) -> Result<(), Error> {
+                write_str(self.0, value)
+            }
+
+            fn visit_char(&mut self, value: char) -> Result<(), Error> {
+                write_str(self.0, value.encode_utf8(&mut [0; 4]))
+            }
+
+            fn visit_bytes(&mut self, value: &'v [u8]) -> Result<(), Error> {
+                write_len(
+                    self.0,
+                    value.len(),
+                    None,
+                    [Some(0xc4), Some(0xc5), Some(0xc6)],
+                )?;
+                self.0.write_all(value)
+            }
+
+            fn visit_seq(&mut self, seq: &'v dyn Seq) -> Result<(), Error> {
+                write_seq(self.0, seq)
+            }
+
+            fn visit_map(&mut self, map: &'v dyn Source) -> Result<(), Error> {
+                write_map(self.0, map)
+            }
+        }
+
+        value.visit(MsgpackValue(sink))
+    }
+}
+
+#[cfg(feature = "std")]
+mod std_support {
+    use super::*;
+
+    use std::io;
+
+    pub(super) struct IoSink<W>(pub(super) W);
+
+    impl<W: io::Write> Sink for IoSink<W> {
+        fn write_all(&mut self, bytes: &[u8]) -> Result<(), Error> {
+            Ok(self.0.write_all(bytes)?)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(
+        to_slice: fn(&mut [u8], &[(&str, Value)]) -> Result<usize, Error>,
+        source: &[(&str, Value)],
+    ) -> Vec<u8> {
+        let mut buf = [0; 128];
+        let len = to_slice(&mut buf, source).unwrap();
+        buf[..len].to_vec()
+    }
+
+    #[test]
+    #[cfg(feature = "kv_cbor")]
+    fn cbor_primitives() {
+        let cbor =
+            |source: &[(&str, Value)]| encode(|buf, source| to_cbor_slice(buf, source), source);
+
+        assert_eq!(vec![0xbf, 0xff], cbor(&[]));
+        assert_eq!(
+            vec![
+                0xbf, 0x61, b'a', 0x18, 0x64, 0x61, b'b', 0x38, 0x63, 0x61, b'c', 0xf5, 0x61, b'd',
+                0xf6, 0x61, b'e', 0x62, b'h', b'i', 0x61, b'f', 0x61, b'c', 0xff,
+            ],
+            cbor(&[
+                ("a", Value::from(100u8)),
+                ("b", Value::from(-100i32)),
+                ("c", Value::from(true)),
+                ("d", Value::null()),
+                ("e", Value::from("hi")),
+                ("f", Value::from('c')),
+            ])
+        );
+        assert_eq!(
+            vec![
+                0xbf, 0x61, b'a', 0xfb, 0x3f, 0xf8, 0, 0, 0, 0, 0, 0, 0x61, b'b', 0x1b, 0xff, 0xff,
+                0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x61, b'c', 0x3b, 0xff, 0xff, 0xff, 0xff, 0xff,
+                0xff, 0xff, 0xff, 0xff,
+            ],
+            cbor(&[
+                ("a", Value::from(1.5f64)),
+                ("b", Value::from(u64::MAX as u128)),
+                ("c", Value::from(-(u64::MAX as i128) - 1)),
+            ])
+        );
+        assert_eq!(
+            vec![0xbf, 0x61, b'a', 0xc2, 0x49, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0xff],
+            cbor(&[("a", Value::from(u64::MAX as u128 + 1))])
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "kv_cbor")]
+    fn cbor_structured() {
+        let cbor =
+            |source: &[(&str, Value)]| encode(|buf, source| to_cbor_slice(buf, source), source);
+
+        assert_eq!(
+            vec![
+                0xbf, 0x61, b'a', 0x9f, 0x01, 0x02, 0xff, 0x61, b'b', 0x42, 0x01, 0x02, 0x61, b'c',
+                0xbf, 0x61, b'd', 0x01, 0xff, 0x61, b'e', 0x7f, 0x63, b'x', b'y', b'z', 0xff, 0xff,
+            ],
+            cbor(&[
+                ("a", Value::from_seq(&[1, 2])),
+                ("b", Value::from_bytes(&[1, 2])),
+                ("c", Value::from_map(&[("d", 1)])),
+                ("e", Value::from_display(&"xyz")),
+            ])
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "kv_msgpack")]
+    fn msgpack_primitives() {
+        let msgpack =
+            |source: &[(&str, Value)]| encode(|buf, source| to_msgpack_slice(buf, source), source);
+
+        assert_eq!(vec![0x80], msgpack(&[]));
+        assert_eq!(
+            vec![
+                0x86, 0xa1, b'a', 0xcc, 0xc8, 0xa1, b'b', 0xd0, 0x9c, 0xa1, b'c', 0xc3, 0xa1, b'd',
+                0xc0, 0xa1, b'e', 0xa2, b'h', b'i', 0xa1, b'f', 0xff,
+            ],
+            msgpack(&[
+                ("a", Value::from(200u8)),
+                ("b", Value::from(-100i32)),
+                ("c", Value::from(true)),
+                ("d", Value::null()),
+                ("e", Value::from("hi")),
+                ("f", Value::from(-1i8)),
+            ])
+        );
+        assert_eq!(
+            vec![
+                0x82, 0xa1, b'a', 0xcb, 0x3f, 0xf8, 0, 0, 0, 0, 0, 0, 0xa1, b'b', 0xd1, 0xfc, 0x18,
+            ],
+            msgpack(&[("a", Value::from(1.5f64)), ("b", Value::from(-1000i64))])
+        );
+
+        let big = u64::MAX as u128 + 1;
+        let mut expected = vec![0x81, 0xa1, b'a', 0xb4];
+        expected.extend_from_slice(big.to_string().as_bytes());
+        assert_eq!(expected, msgpack(&[("a", Value::from(big))]));
+    }
+
+    #[test]
+    #[cfg(feature = "kv_msgpack")]
+    fn msgpack_structured() {
+        let msgpack =
+            |source: &[(&str, Value)]| encode(|buf, source| to_msgpack_slice(buf, source), source);
+
+        assert_eq!(
+            vec![
+                0x84, 0xa1, b'a', 0x92, 0x01, 0x02, 0xa1, b'b', 0xc4, 0x02, 0x01, 0x02, 0xa1, b'c',
+                0x81, 0xa1, b'd', 0x01, 0xa1, b'e', 0xa3, b'x', b'y', b'z',
+            ],
+            msgpack(&[
+                ("a", Value::from_seq(&[1, 2])),
+                ("b", Value::from_bytes(&[1, 2])),
+                ("c", Value::from_map(&[("d", 1)])),
+                ("e", Value::from_display(&"xyz")),
+            ])
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "kv_cbor")]
+    fn buffer_too_small() {
+        let mut buf = [0; 4];
+        assert_eq!(
+            ErrorKind::Io,
+            to_cbor_slice(&mut buf, &[("a", 1)]).unwrap_err().kind()
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "kv_cbor", feature = "kv_msgpack", feature = "std"))]
+    fn io_writer() {
+        let mut cbor = Vec::new();
+        to_cbor(&mut cbor, &[("a", 1)]).unwrap();
+        assert_eq!(vec![0xbf, 0x61, b'a', 0x01, 0xff], cbor);
+
+        let mut msgpack = Vec::new();
+        to_msgpack(&mut msgpack, &[("a", 1)]).unwrap();
+        assert_eq!(vec![0x81, 0xa1, b'a', 0x01], msgpack);
+    }
+}
@@ -3,13 +3,39 @@ use std::fmt;
 /// An error encountered while working with structured data.
 #[derive(Debug)]
 pub struct Error {
+    kind: ErrorKind,
     inner: Inner,
 }
 
+/// The kind of an [`Error`].
+///
+/// Kinds let consumers react to errors programmatically, like skipping a key-value
+/// that can't be serialized instead of giving up on the whole record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// A value or key-value isn't supported by the consumer.
+    Unsupported,
+    /// A key that was expected isn't present.
+    MissingKey,
+    /// A key-value couldn't be serialized or formatted.
+    Serialization,
+    /// An IO error, or a failure writing to some other destination.
+    Io,
+    /// A visitor stopped visiting early, without failing.
+    ///
+    /// See [`Error::stop`].
+    Stop,
+    /// Any other error.
+    Other,
+}
+
 #[derive(Debug)]
 enum Inner {
     #[cfg(feature = "std")]
     Boxed(std_support::BoxedError),
+    #[cfg(feature = "std")]
+    Source(&'static str, std_support::BoxedError),
     Msg(&'static str),
     #[cfg(feature = "value-bag")]
     Value(crate::kv::value::inner::Error),
@@ -18,16 +44,77 @@ enum Inner {
 
 impl Error {
     /// Create an error from a message.
+    ///
+    /// The error has the kind [`ErrorKind::Other`].
     pub fn msg(msg: &'static str) -> Self {
+        Error::new(ErrorKind::Other, msg)
+    }
+
+    /// Create an error of a given kind from a message.
+    pub fn new(kind: ErrorKind, msg: &'static str) -> Self {
         Error {
+            kind,
             inner: Inner::Msg(msg),
         }
     }
 
+    /// Create an error that stops visiting early.
+    ///
+    /// Visitors can return this error to stop a source, sequence, or value from
+    /// visiting any more data once they've seen what they need, like the key they
+    /// were looking for. Sources and adapters pass it through like any other error,
+    /// so whatever started visiting should check [`Error::is_stop`] and treat it
+    /// as success.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use log::kv::{Error, Key, Source, Value, VisitSource};
+    ///
+    /// struct First<'kvs>(Option<Key<'kvs>>);
+    ///
+    /// impl<'kvs> VisitSource<'kvs> for First<'kvs> {
+    ///     fn visit_pair(&mut self, key: Key<'kvs>, _: Value<'kvs>) -> Result<(), Error> {
+    ///         self.0 = Some(key);
+    ///
+    ///         Err(Error::stop())
+    ///     }
+    /// }
+    ///
+    /// let source = [("a", 1), ("b", 2)];
+    ///
+    /// let mut first = First(None);
+    /// if let Err(err) = source.visit(&mut first) {
+    ///     assert!(err.is_stop());
+    /// }
+    ///
+    /// assert_eq!(Some(Key::from("a")), first.0);
+    /// ```
+    pub fn stop() -> Self {
+        Error::new(ErrorKind::Stop, "visiting was stopped early")
+    }
+
+    /// Check whether this error was created using [`Error::stop`].
+    pub fn is_stop(&self) -> bool {
+        self.kind == ErrorKind::Stop
+    }
+
+    /// Change the kind of this error.
+    pub fn with_kind(mut self, kind: ErrorKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Get the kind of this error.
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
     // Not public so we don't leak the `crate::kv::value::inner` API
     #[cfg(feature = "value-bag")]
     pub(super) fn from_value(err: crate::kv::value::inner::Error) -> Self {
         Error {
+            kind: ErrorKind::Other,
             inner: Inner::Value(err),
         }
     }
@@ -51,6 +138,8 @@ impl fmt::Display for Error {
         match &self.inner {
             #[cfg(feature = "std")]
             Boxed(err) => err.fmt(f),
+            #[cfg(feature = "std")]
+            Source(msg, _) => msg.fmt(f),
             #[cfg(feature = "value-bag")]
             Value(err) => err.fmt(f),
             Msg(msg) => msg.fmt(f),
@@ -61,7 +150,10 @@ impl fmt::Display for Error {
 
 impl From<fmt::Error> for Error {
     fn from(_: fmt::Error) -> Self {
-        Error { inner: Inner::Fmt }
+        Error {
+            kind: ErrorKind::Serialization,
+            inner: Inner::Fmt,
+        }
     }
 }
 
@@ -74,21 +166,95 @@ mod std_support {
 
     impl Error {
         /// Create an error from a standard error type.
+        ///
+        /// The error has the kind [`ErrorKind::Other`].
         pub fn boxed<E>(err: E) -> Self
         where
             E: Into<BoxedError>,
         {
             Error {
+                kind: ErrorKind::Other,
                 inner: Inner::Boxed(err.into()),
             }
         }
+
+        /// Create an error of a given kind from a message, caused by another error.
+        ///
+        /// The cause is returned from [`std::error::Error::source`].
+        pub fn with_source<E>(kind: ErrorKind, msg: &'static str, source: E) -> Self
+        where
+            E: Into<BoxedError>,
+        {
+            Error {
+                kind,
+                inner: Inner::Source(msg, source.into()),
+            }
+        }
     }
 
-    impl error::Error for Error {}
+    impl error::Error for Error {
+        fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+            match &self.inner {
+                Inner::Boxed(err) => err.source(),
+                Inner::Source(_, source) => Some(&**source),
+                _ => None,
+            }
+        }
+    }
 
     impl From<io::Error> for Error {
         fn from(err: io::Error) -> Self {
-            Error::boxed(err)
+            Error::boxed(err).with_kind(ErrorKind::Io)
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kind() {
+        assert_eq!(ErrorKind::Other, Error::msg("an error").kind());
+        assert_eq!(
+            ErrorKind::MissingKey,
+            Error::new(ErrorKind::MissingKey, "an error").kind()
+        );
+        assert_eq!(
+            ErrorKind::Unsupported,
+            Error::msg("an error")
+                .with_kind(ErrorKind::Unsupported)
+                .kind()
+        );
+        assert_eq!(ErrorKind::Serialization, Error::from(fmt::Error).kind());
+    }
+
+    #[test]
+    fn stop() {
+        assert!(Error::stop().is_stop());
+        assert_eq!(ErrorKind::Stop, Error::stop().kind());
+        assert!(!Error::msg("an error").is_stop());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn source() {
+        use std::error::Error as _;
+        use std::io;
+
+        let err = Error::with_source(
+            ErrorKind::Serialization,
+            "failed to write a key-value",
+            io::Error::new(io::ErrorKind::Other, "inner"),
+        );
+
+        assert_eq!("failed to write a key-value", err.to_string());
+        assert_eq!("inner", err.source().unwrap().to_string());
+
+        let err = Error::from(io::Error::new(io::ErrorKind::Other, "inner"));
+
+        assert_eq!(ErrorKind::Io, err.kind());
+        assert_eq!("inner", err.to_string());
+        assert!(err.source().is_none());
+    }
+}
@@ -0,0 +1,69 @@
+//! Key-values attached to every record logged in the process.
+
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+use crate::kv::{OwnedSource, Source};
+
+static GLOBAL: AtomicPtr<OwnedSource> = AtomicPtr::new(ptr::null_mut());
+
+/// Attach key-values to every record logged using the macros, on any thread.
+///
+/// This is a place for fields that describe the process itself, like the name of a service,
+/// its version, or the region it's deployed in, so they're included with records emitted by
+/// libraries as well as the application, without configuring each logger to add them.
+///
+/// The key-values are buffered into an [`OwnedSource`], so the source doesn't need to
+/// outlive the call. They're added before any [thread-local](crate::mdc) or
+/// [scoped](crate::kv::push_context) key-values and the record's own, so any of those take
+/// precedence over the same key when using [`Source::get`].
+///
+/// Calling this function again replaces the global key-values. The previous ones are
+/// leaked, because records may still be borrowing them, so global key-values are meant to
+/// be set once, or a handful of times, while the application starts up.
+///
+/// # Examples
+///
+/// ```
+/// use log::info;
+///
+/// log::set_global_kv(&[("service", "checkout"), ("region", "eu-west-1")]);
+///
+/// // This record includes `service = "checkout"` and `region = "eu-west-1"`
+/// info!("starting up");
+/// ```
+pub fn set_global_kv(source: &(impl Source + ?Sized)) {
+    let global = Box::into_raw(Box::new(OwnedSource::from_source(source)));
+
+    GLOBAL.store(global, Ordering::Release);
+}
+
+/// Get the global key-values, if they've been set.
+pub(crate) fn current() -> Option<&'static OwnedSource> {
+    // SAFETY: Sources stored in `GLOBAL` are never freed
+    unsafe { GLOBAL.load(Ordering::Acquire).as_ref() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kv::Key;
+
+    #[test]
+    fn set_global_kv() {
+        let previous = {
+            let value = String::from("checkout");
+            super::set_global_kv(&[("service", value.as_str())]);
+
+            current().unwrap()
+        };
+
+        assert_eq!(Some("checkout"), previous.get_str(Key::from_str("service")));
+
+        super::set_global_kv(&[("service", "payments"), ("region", "eu")]);
+        assert_eq!(2, current().unwrap().count());
+
+        // Sources that were replaced are still available to anything borrowing them
+        assert_eq!(1, previous.count());
+    }
+}
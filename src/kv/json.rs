@@ -0,0 +1,342 @@
+//! Writing key-values as JSON.
+//!
+//! This module doesn't depend on `serde` or `serde_json`, so it's available
+//! whenever the `kv` feature is.
+
+use std::fmt::{self, Write};
+
+use crate::kv::{Error, Key, Seq, Source, Value, VisitSeq, VisitSource, VisitValue};
+
+/// Write the key-values in a source as a JSON object.
+///
+/// This function doesn't need `serde` or `serde_json`. Key-values are written in the
+/// order they're visited, including any duplicate keys. Values are written as:
+///
+/// - `null`, booleans, numbers, and strings as their JSON equivalents. Floats that
+///   aren't finite are written as `null`.
+/// - Byte slices and sequences captured using [`Value::from_seq`] as arrays.
+/// - Maps captured using [`Value::from_map`] as objects.
+/// - Raw JSON captured using `Value::from_raw_json` verbatim (requires the `kv_json` feature).
+/// - Anything else as a string, using its `Display` implementation.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> Result<(), log::kv::Error> {
+/// let source = [("a", log::kv::Value::from(1)), ("b", log::kv::Value::from("a \"string\""))];
+///
+/// let mut json = String::new();
+/// log::kv::to_json_writer(&mut json, &source)?;
+///
+/// assert_eq!(r#"{"a":1,"b":"a \"string\""}"#, json);
+/// # Ok(())
+/// # }
+/// ```
+pub fn to_json_writer(
+    mut writer: impl fmt::Write,
+    source: &(impl Source + ?Sized),
+) -> Result<(), Error> {
+    write_map(&mut writer, &source)
+}
+
+/// Write the key-values in a source as a JSON object to an IO stream.
+///
+/// See [`to_json_writer`] for details on how values are written.
+#[cfg(feature = "std")]
+pub fn to_json_io_writer(
+    writer: impl std::io::Write,
+    source: &(impl Source + ?Sized),
+) -> Result<(), Error> {
+    let mut writer = std_support::IoWriter::new(writer);
+
+    match write_map(&mut writer, &source) {
+        Ok(()) => Ok(()),
+        Err(err) => Err(writer.into_err().map(Error::from).unwrap_or(err)),
+    }
+}
+
+fn write_map(writer: &mut dyn Write, source: &dyn Source) -> Result<(), Error> {
+    struct Map<'a> {
+        writer: &'a mut dyn Write,
+        first: bool,
+    }
+
+    impl<'a, 'kvs> VisitSource<'kvs> for Map<'a> {
+        fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+            if !self.first {
+                self.writer.write_char(',')?;
+            }
+            self.first = false;
+
+            write_str(self.writer, key.as_str())?;
+            self.writer.write_char(':')?;
+            write_value(self.writer, value)
+        }
+    }
+
+    writer.write_char('{')?;
+    source.visit(&mut Map {
+        writer: &mut *writer,
+        first: true,
+    })?;
+    writer.write_char('}')?;
+
+    Ok(())
+}
+
+fn write_seq(writer: &mut dyn Write, seq: &dyn Seq) -> Result<(), Error> {
+    struct Array<'a> {
+        writer: &'a mut dyn Write,
+        first: bool,
+    }
+
+    impl<'a, 'v> VisitSeq<'v> for Array<'a> {
+        fn visit_value(&mut self, value: Value<'v>) -> Result<(), Error> {
+            if !self.first {
+                self.writer.write_char(',')?;
+            }
+            self.first = false;
+
+            write_value(self.writer, value)
+        }
+    }
+
+    writer.write_char('[')?;
+    seq.visit(&mut Array {
+        writer: &mut *writer,
+        first: true,
+    })?;
+    writer.write_char(']')?;
+
+    Ok(())
+}
+
+fn write_value(writer: &mut dyn Write, value: Value) -> Result<(), Error> {
+    struct JsonValue<'a> {
+        writer: &'a mut dyn Write,
+    }
+
+    impl<'a, 'v> VisitValue<'v> for JsonValue<'a> {
+        fn visit_any(&mut self, value: Value) -> Result<(), Error> {
+            #[cfg(feature = "kv_json")]
+            {
+                if let Some(json) = value.to_raw_json() {
+                    return Ok(self.writer.write_str(json)?);
+                }
+            }
+
+            self.writer.write_char('"')?;
+            write!(Escape(&mut *self.writer), "{}", value)?;
+            self.writer.write_char('"')?;
+
+            Ok(())
+        }
+
+        fn visit_null(&mut self) -> Result<(), Error> {
+            Ok(self.writer.write_str("null")?)
+        }
+
+        fn visit_u64(&mut self, value: u64) -> Result<(), Error> {
+            Ok(write!(self.writer, "{}", value)?)
+        }
+
+        fn visit_i64(&mut self, value: i64) -> Result<(), Error> {
+            Ok(write!(self.writer, "{}", value)?)
+        }
+
+        fn visit_u128(&mut self, value: u128) -> Result<(), Error> {
+            Ok(write!(self.writer, "{}", value)?)
+        }
+
+        fn visit_i128(&mut self, value: i128) -> Result<(), Error> {
+            Ok(write!(self.writer, "{}", value)?)
+        }
+
+        fn visit_f64(&mut self, value: f64) -> Result<(), Error> {
+            if value.is_finite() {
+                Ok(write!(self.writer, "{}", value)?)
+            } else {
+                self.visit_null()
+            }
+        }
+
+        fn visit_bool(&mut self, value: bool) -> Result<(), Error> {
+            Ok(write!(self.writer, "{}", value)?)
+        }
+
+        fn visit_str(&mut self, value: &str) -> Result<(), Error> {
+            write_str(self.writer, value)
+        }
+
+        fn visit_char(&mut self, value: char) -> Result<(), Error> {
+            write_str(self.writer, value.encode_utf8(&mut [0; 4]))
+        }
+
+        fn visit_bytes(&mut self, value: &'v [u8]) -> Result<(), Error> {
+            write_seq(self.writer, &value)
+        }
+
+        fn visit_seq(&mut self, seq: &'v dyn Seq) -> Result<(), Error> {
+            write_seq(self.writer, seq)
+        }
+
+        fn visit_map(&mut self, map: &'v dyn Source) -> Result<(), Error> {
+            write_map(self.writer, map)
+        }
+    }
+
+    value.visit(JsonValue { writer })
+}
+
+fn write_str(writer: &mut dyn Write, value: &str) -> Result<(), Error> {
+    writer.write_char('"')?;
+    Escape(&mut *writer).write_str(value)?;
+    writer.write_char('"')?;
+
+    Ok(())
+}
+
+/// A writer that escapes the contents of a JSON string.
+struct Escape<'a>(&'a mut dyn Write);
+
+impl<'a> Write for Escape<'a> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut start = 0;
+
+        for (i, c) in s.char_indices() {
+            let escaped = match c {
+                '"' => "\\\"",
+                '\\' => "\\\\",
+                '\n' => "\\n",
+                '\r' => "\\r",
+                '\t' => "\\t",
+                '\u{08}' => "\\b",
+                '\u{0c}' => "\\f",
+                c if (c as u32) < 0x20 => "",
+                _ => continue,
+            };
+
+            self.0.write_str(&s[start..i])?;
+            if escaped.is_empty() {
+                write!(self.0, "\\u{:04x}", c as u32)?;
+            } else {
+                self.0.write_str(escaped)?;
+            }
+            start = i + c.len_utf8();
+        }
+
+        self.0.write_str(&s[start..])
+    }
+}
+
+#[cfg(feature = "std")]
+mod std_support {
+    use super::*;
+
+    use std::io;
+
+    /// Adapts an IO stream into a `fmt::Write`, holding on to any IO error.
+    pub(super) struct IoWriter<W> {
+        inner: W,
+        err: Option<io::Error>,
+    }
+
+    impl<W: io::Write> IoWriter<W> {
+        pub(super) fn new(inner: W) -> Self {
+            IoWriter { inner, err: None }
+        }
+
+        pub(super) fn into_err(self) -> Option<io::Error> {
+            self.err
+        }
+    }
+
+    impl<W: io::Write> Write for IoWriter<W> {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            self.inner.write_all(s.as_bytes()).map_err(|err| {
+                self.err = Some(err);
+                fmt::Error
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_json(source: &(impl Source + ?Sized)) -> String {
+        let mut json = String::new();
+        to_json_writer(&mut json, source).unwrap();
+        json
+    }
+
+    #[test]
+    fn primitives() {
+        assert_eq!("{}", to_json(&None::<(&str, i32)>));
+        assert_eq!(
+            r#"{"a":1,"b":-1,"c":1.5,"d":true,"e":null,"f":"s","g":"c"}"#,
+            to_json(&[
+                ("a", Value::from(1u8)),
+                ("b", Value::from(-1i32)),
+                ("c", Value::from(1.5f64)),
+                ("d", Value::from(true)),
+                ("e", Value::null()),
+                ("f", Value::from("s")),
+                ("g", Value::from('c')),
+            ])
+        );
+        assert_eq!(
+            r#"{"a":null,"b":null}"#,
+            to_json(&[("a", f64::NAN), ("b", f64::INFINITY)])
+        );
+    }
+
+    #[test]
+    fn escape() {
+        assert_eq!(
+            r#"{"a\"b":"\"\\\n\r\t\b\f\u0001é"}"#,
+            to_json(&("a\"b", "\"\\\n\r\t\u{08}\u{0c}\u{01}é"))
+        );
+        assert_eq!(
+            r#"{"a":"x \"y\""}"#,
+            to_json(&("a", Value::from_display(&"x \"y\"")))
+        );
+    }
+
+    #[test]
+    fn structured() {
+        assert_eq!(
+            r#"{"a":[1,2,[3]],"b":[1,2],"c":{"d":1,"e":"f"}}"#,
+            to_json(&[
+                (
+                    "a",
+                    Value::from_seq(&[Value::from(1), 2.into(), Value::from_seq(&[3])])
+                ),
+                ("b", Value::from_bytes(&[1, 2])),
+                (
+                    "c",
+                    Value::from_map(&[("d", Value::from(1)), ("e", "f".into())])
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "kv_json")]
+    fn raw_json() {
+        assert_eq!(
+            r#"{"a":{"b":[1]}}"#,
+            to_json(&("a", Value::from_raw_json(r#"{"b":[1]}"#)))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn io_writer() {
+        let mut json = Vec::new();
+        to_json_io_writer(&mut json, &[("a", 1), ("b", 2)]).unwrap();
+
+        assert_eq!(r#"{"a":1,"b":2}"#, String::from_utf8(json).unwrap());
+    }
+}
@@ -1,7 +1,9 @@
 //! Structured keys.
 
 use std::borrow::Borrow;
+use std::cmp::Ordering;
 use std::fmt;
+use std::hash::{Hash, Hasher};
 
 /// A type that can be converted into a [`Key`](struct.Key.html).
 pub trait ToKey {
@@ -20,7 +22,7 @@ where
 
 impl<'k> ToKey for Key<'k> {
     fn to_key(&self) -> Key {
-        Key { key: self.key }
+        self.clone()
     }
 }
 
@@ -31,23 +33,99 @@ impl ToKey for str {
 }
 
 /// A key in a key-value.
+///
+/// Keys are compared by their string representation. Well-known keys can be declared
+/// upfront using [`Key::from_static_index`], so comparisons between them don't need
+/// to look at their strings at all.
 // These impls must only be based on the as_str() representation of the key
 // If a new field (such as an optional index) is added to the key they must not affect comparison
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Clone)]
 pub struct Key<'k> {
     key: &'k str,
+    index: Option<usize>,
 }
 
 impl<'k> Key<'k> {
     /// Get a key from a borrowed string.
     pub fn from_str(key: &'k str) -> Self {
-        Key { key }
+        Key { key, index: None }
     }
 
     /// Get a borrowed string from this key.
     pub fn as_str(&self) -> &str {
         self.key
     }
+
+    /// Get the index this key was declared with using [`Key::from_static_index`].
+    pub fn index(&self) -> Option<usize> {
+        self.index
+    }
+}
+
+impl Key<'static> {
+    /// Get a well-known key from a static string and an index that identifies it.
+    ///
+    /// Comparing two keys that both have indexes only compares their indexes, and
+    /// comparing a key with one that has the same static string is a pointer comparison.
+    /// Otherwise, keys are compared by their strings as usual.
+    ///
+    /// Each index must only be used for a single string. Two keys with the same index
+    /// but different strings will compare equal, even though their strings differ.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use log::kv::{Key, Source};
+    ///
+    /// const TRACE_ID: Key<'static> = Key::from_static_index("trace_id", 0);
+    ///
+    /// let source = [("trace_id", 42)];
+    ///
+    /// assert_eq!(TRACE_ID, Key::from_str("trace_id"));
+    /// assert_eq!(Some(42), source.get_i64(TRACE_ID));
+    /// ```
+    pub const fn from_static_index(key: &'static str, index: usize) -> Self {
+        Key {
+            key,
+            index: Some(index),
+        }
+    }
+}
+
+impl<'k> fmt::Debug for Key<'k> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Key").field("key", &self.key).finish()
+    }
+}
+
+impl<'a, 'b> PartialEq<Key<'b>> for Key<'a> {
+    fn eq(&self, other: &Key<'b>) -> bool {
+        if let (Some(a), Some(b)) = (self.index, other.index) {
+            return a == b;
+        }
+
+        std::ptr::eq(self.key, other.key) || self.key == other.key
+    }
+}
+
+impl<'k> Eq for Key<'k> {}
+
+impl<'a, 'b> PartialOrd<Key<'b>> for Key<'a> {
+    fn partial_cmp(&self, other: &Key<'b>) -> Option<Ordering> {
+        self.key.partial_cmp(other.key)
+    }
+}
+
+impl<'k> Ord for Key<'k> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.cmp(other.key)
+    }
+}
+
+impl<'k> Hash for Key<'k> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key.hash(state)
+    }
 }
 
 impl<'k> fmt::Display for Key<'k> {
@@ -91,8 +169,161 @@ mod std_support {
             Key::from_str(self)
         }
     }
+
+    /// An owned key in a key-value.
+    ///
+    /// Owned keys are buffered from a borrowed [`Key`] using [`Key::to_owned`], and
+    /// can be converted back into one using [`OwnedKey::by_ref`]. They're `'static`,
+    /// so they can be stored in maps that outlive the record they came from.
+    ///
+    /// Owned keys keep the index of a key declared using [`Key::from_static_index`],
+    /// and are compared in the same way as borrowed keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    ///
+    /// use log::kv::{Key, OwnedKey};
+    ///
+    /// let mut counts = HashMap::<OwnedKey, usize>::new();
+    ///
+    /// {
+    ///     let key = String::from("a");
+    ///     *counts.entry(Key::from_str(&key).to_owned()).or_default() += 1;
+    /// }
+    ///
+    /// assert_eq!(Some(&1), counts.get("a"));
+    /// ```
+    #[derive(Clone)]
+    pub struct OwnedKey {
+        key: Box<str>,
+        index: Option<usize>,
+    }
+
+    impl<'k> Key<'k> {
+        /// Buffer this key into an [`OwnedKey`].
+        pub fn to_owned(&self) -> OwnedKey {
+            OwnedKey {
+                key: self.key.into(),
+                index: self.index,
+            }
+        }
+    }
+
+    impl OwnedKey {
+        /// Get an owned key from a string.
+        pub fn new(key: impl Into<Box<str>>) -> Self {
+            OwnedKey {
+                key: key.into(),
+                index: None,
+            }
+        }
+
+        /// Get a borrowed key from this owned key.
+        pub fn by_ref(&self) -> Key<'_> {
+            Key {
+                key: &self.key,
+                index: self.index,
+            }
+        }
+
+        /// Get a borrowed string from this key.
+        pub fn as_str(&self) -> &str {
+            &self.key
+        }
+    }
+
+    impl ToKey for OwnedKey {
+        fn to_key(&self) -> Key {
+            self.by_ref()
+        }
+    }
+
+    impl<'k> From<Key<'k>> for OwnedKey {
+        fn from(key: Key<'k>) -> Self {
+            key.to_owned()
+        }
+    }
+
+    impl From<String> for OwnedKey {
+        fn from(key: String) -> Self {
+            OwnedKey::new(key)
+        }
+    }
+
+    impl<'a> From<&'a str> for OwnedKey {
+        fn from(key: &'a str) -> Self {
+            OwnedKey::new(key)
+        }
+    }
+
+    impl fmt::Debug for OwnedKey {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            fmt::Debug::fmt(&self.by_ref(), f)
+        }
+    }
+
+    impl fmt::Display for OwnedKey {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            fmt::Display::fmt(&self.by_ref(), f)
+        }
+    }
+
+    impl PartialEq for OwnedKey {
+        fn eq(&self, other: &Self) -> bool {
+            self.by_ref() == other.by_ref()
+        }
+    }
+
+    impl<'k> PartialEq<Key<'k>> for OwnedKey {
+        fn eq(&self, other: &Key<'k>) -> bool {
+            self.by_ref() == *other
+        }
+    }
+
+    impl<'k> PartialEq<OwnedKey> for Key<'k> {
+        fn eq(&self, other: &OwnedKey) -> bool {
+            *self == other.by_ref()
+        }
+    }
+
+    impl Eq for OwnedKey {}
+
+    impl PartialOrd for OwnedKey {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl Ord for OwnedKey {
+        fn cmp(&self, other: &Self) -> Ordering {
+            self.key.cmp(&other.key)
+        }
+    }
+
+    impl Hash for OwnedKey {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            self.by_ref().hash(state)
+        }
+    }
+
+    impl AsRef<str> for OwnedKey {
+        fn as_ref(&self) -> &str {
+            self.as_str()
+        }
+    }
+
+    impl Borrow<str> for OwnedKey {
+        fn borrow(&self) -> &str {
+            self.as_str()
+        }
+    }
 }
 
+#[cfg(feature = "std")]
+pub use self::std_support::OwnedKey;
+
 #[cfg(feature = "kv_sval")]
 mod sval_support {
     use super::*;
@@ -130,6 +361,15 @@ mod serde_support {
             self.key.serialize(serializer)
         }
     }
+
+    impl Serialize for OwnedKey {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            self.by_ref().serialize(serializer)
+        }
+    }
 }
 
 #[cfg(test)]
@@ -140,4 +380,52 @@ mod tests {
     fn key_from_string() {
         assert_eq!("a key", Key::from_str("a key").as_str());
     }
+
+    #[test]
+    fn key_from_static_index() {
+        const A: Key<'static> = Key::from_static_index("a", 0);
+        const B: Key<'static> = Key::from_static_index("b", 1);
+
+        assert_eq!("a", A.as_str());
+        assert_eq!(Some(0), A.index());
+        assert_eq!(None, Key::from_str("a").index());
+
+        assert_eq!(A, A.clone());
+        assert_ne!(A, B);
+        assert_eq!(A, Key::from_str("a"));
+        assert_eq!(Key::from_str("b"), B);
+        assert_ne!(A, Key::from_str("b"));
+        assert!(A < B);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn owned_key() {
+        const A: Key<'static> = Key::from_static_index("a", 0);
+
+        let owned = {
+            let key = String::from("b");
+            Key::from_str(&key).to_owned()
+        };
+
+        assert_eq!("b", owned.as_str());
+        assert_eq!(None, owned.by_ref().index());
+        assert_eq!(Key::from_str("b"), owned);
+        assert_eq!(OwnedKey::from("b"), owned);
+
+        let owned = A.to_owned();
+        assert_eq!(Some(0), owned.by_ref().index());
+        assert_eq!(A, owned.by_ref());
+        assert!(owned < OwnedKey::from("b"));
+        assert_eq!(r#"Key { key: "a" }"#, format!("{:?}", owned));
+        assert_eq!("a", owned.to_string());
+    }
+
+    #[test]
+    fn key_debug() {
+        assert_eq!(
+            r#"Key { key: "a" }"#,
+            format!("{:?}", Key::from_static_index("a", 0))
+        );
+    }
 }
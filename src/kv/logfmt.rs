@@ -0,0 +1,277 @@
+//! Writing key-values as logfmt.
+//!
+//! Like the JSON writer, this module doesn't depend on any serialization framework.
+
+use std::fmt::{self, Write};
+
+use crate::kv::{Error, Key, Source, Value, VisitSource, VisitValue};
+use crate::Record;
+
+/// Write the key-values in a source as logfmt.
+///
+/// Key-values are written as `key=value` pairs separated by spaces, in the order
+/// they're visited. Values are written using their `Display` implementation, except
+/// for `null`, which is written as `null`. A value is quoted if it's empty or contains
+/// whitespace, `=`, `"`, `\`, or control characters. Within quotes, `"` and `\` are escaped
+/// with a backslash, newlines, carriage returns, and tabs are written as `\n`, `\r`, and `\t`,
+/// and other control characters are written as `\u{..}` escapes.
+///
+/// Keys can't be quoted, so any whitespace, `=`, `"`, or control characters in them are
+/// replaced with `_`. Empty keys are also written as `_`.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> Result<(), log::kv::Error> {
+/// let source = [("a", log::kv::Value::from(1)), ("b", log::kv::Value::from("a \"string\""))];
+///
+/// let mut logfmt = String::new();
+/// log::kv::to_logfmt(&mut logfmt, &source)?;
+///
+/// assert_eq!(r#"a=1 b="a \"string\"""#, logfmt);
+/// # Ok(())
+/// # }
+/// ```
+pub fn to_logfmt(
+    mut writer: impl fmt::Write,
+    source: &(impl Source + ?Sized),
+) -> Result<(), Error> {
+    write_pairs(&mut writer, &source, true)
+}
+
+/// Write a record as logfmt.
+///
+/// The record's level, target, and message are written as `level`, `target`, and `msg`
+/// before its key-values. See [`to_logfmt`] for details on how key-values are written.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> Result<(), log::kv::Error> {
+/// let record = log::Record::builder()
+///     .level(log::Level::Info)
+///     .target("app")
+///     .args(format_args!("hello world"))
+///     .key_values(&[("a", 1)])
+///     .build();
+///
+/// let mut logfmt = String::new();
+/// log::kv::to_logfmt_record(&mut logfmt, &record)?;
+///
+/// assert_eq!(r#"level=INFO target=app msg="hello world" a=1"#, logfmt);
+/// # Ok(())
+/// # }
+/// ```
+pub fn to_logfmt_record(mut writer: impl fmt::Write, record: &Record) -> Result<(), Error> {
+    let writer = &mut writer as &mut dyn Write;
+
+    writer.write_str("level=")?;
+    write_display(writer, record.level())?;
+    writer.write_str(" target=")?;
+    write_display(writer, record.target())?;
+    writer.write_str(" msg=")?;
+    write_display(writer, record.args())?;
+
+    write_pairs(writer, record.key_values(), false)
+}
+
+fn write_pairs(writer: &mut dyn Write, source: &dyn Source, first: bool) -> Result<(), Error> {
+    struct Pairs<'a> {
+        writer: &'a mut dyn Write,
+        first: bool,
+    }
+
+    impl<'a, 'kvs> VisitSource<'kvs> for Pairs<'a> {
+        fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+            if !self.first {
+                self.writer.write_char(' ')?;
+            }
+            self.first = false;
+
+            write_key(self.writer, key.as_str())?;
+            self.writer.write_char('=')?;
+            write_value(self.writer, value)
+        }
+    }
+
+    source.visit(&mut Pairs { writer, first })
+}
+
+fn write_key(writer: &mut dyn Write, key: &str) -> Result<(), Error> {
+    if key.is_empty() {
+        return Ok(writer.write_char('_')?);
+    }
+
+    for c in key.chars() {
+        if c.is_whitespace() || c.is_control() || c == '=' || c == '"' {
+            writer.write_char('_')?;
+        } else {
+            writer.write_char(c)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn write_value(writer: &mut dyn Write, value: Value) -> Result<(), Error> {
+    struct LogfmtValue<'a> {
+        writer: &'a mut dyn Write,
+    }
+
+    impl<'a, 'v> VisitValue<'v> for LogfmtValue<'a> {
+        fn visit_any(&mut self, value: Value) -> Result<(), Error> {
+            write_display(self.writer, value)
+        }
+
+        fn visit_null(&mut self) -> Result<(), Error> {
+            Ok(self.writer.write_str("null")?)
+        }
+
+        fn visit_str(&mut self, value: &str) -> Result<(), Error> {
+            write_display(self.writer, value)
+        }
+    }
+
+    value.visit(LogfmtValue { writer })
+}
+
+/// Write a value using its `Display` implementation, quoting it if needed.
+///
+/// The value is formatted twice; once to check whether it needs quotes and once to write it.
+fn write_display(writer: &mut dyn Write, value: impl fmt::Display) -> Result<(), Error> {
+    let mut check = NeedsQuotes {
+        empty: true,
+        needs_quotes: false,
+    };
+    write!(check, "{}", value)?;
+
+    if check.empty || check.needs_quotes {
+        writer.write_char('"')?;
+        write!(Escape(&mut *writer), "{}", value)?;
+        writer.write_char('"')?;
+    } else {
+        write!(writer, "{}", value)?;
+    }
+
+    Ok(())
+}
+
+struct NeedsQuotes {
+    empty: bool,
+    needs_quotes: bool,
+}
+
+impl Write for NeedsQuotes {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.empty &= s.is_empty();
+        self.needs_quotes |= s
+            .chars()
+            .any(|c| c.is_whitespace() || c.is_control() || c == '=' || c == '"' || c == '\\');
+
+        Ok(())
+    }
+}
+
+/// A writer that escapes the contents of a quoted logfmt value.
+struct Escape<'a>(&'a mut dyn Write);
+
+impl<'a> Write for Escape<'a> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut start = 0;
+
+        for (i, c) in s.char_indices() {
+            let escaped = match c {
+                '"' => "\\\"",
+                '\\' => "\\\\",
+                '\n' => "\\n",
+                '\r' => "\\r",
+                '\t' => "\\t",
+                c if c.is_control() => "",
+                _ => continue,
+            };
+
+            self.0.write_str(&s[start..i])?;
+            if escaped.is_empty() {
+                write!(self.0, "{}", c.escape_unicode())?;
+            } else {
+                self.0.write_str(escaped)?;
+            }
+            start = i + c.len_utf8();
+        }
+
+        self.0.write_str(&s[start..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn logfmt(source: &(impl Source + ?Sized)) -> String {
+        let mut logfmt = String::new();
+        to_logfmt(&mut logfmt, source).unwrap();
+        logfmt
+    }
+
+    #[test]
+    fn primitives() {
+        assert_eq!("", logfmt(&None::<(&str, i32)>));
+        assert_eq!(
+            "a=1 b=-1 c=1.5 d=true e=null f=s g=c",
+            logfmt(&[
+                ("a", Value::from(1u8)),
+                ("b", Value::from(-1i32)),
+                ("c", Value::from(1.5f64)),
+                ("d", Value::from(true)),
+                ("e", Value::null()),
+                ("f", Value::from("s")),
+                ("g", Value::from('c')),
+            ])
+        );
+    }
+
+    #[test]
+    fn quoting() {
+        assert_eq!(
+            r#"a="" b="x y" c="x=y" d="\"\\" e="\n\r\t\u{1}" f=é"#,
+            logfmt(&[
+                ("a", ""),
+                ("b", "x y"),
+                ("c", "x=y"),
+                ("d", "\"\\"),
+                ("e", "\n\r\t\u{01}"),
+                ("f", "é"),
+            ])
+        );
+        assert_eq!(
+            r#"a="x \"y\"""#,
+            logfmt(&("a", Value::from_display(&"x \"y\"")))
+        );
+    }
+
+    #[test]
+    fn keys() {
+        assert_eq!("a_b_c_d=1", logfmt(&("a b=c\"d", 1)));
+        assert_eq!("_=1 a=2", logfmt(&[("", 1), ("a", 2)]));
+    }
+
+    #[test]
+    fn record() {
+        let mut logfmt = String::new();
+        to_logfmt_record(
+            &mut logfmt,
+            &Record::builder()
+                .level(crate::Level::Warn)
+                .target("app::db")
+                .args(format_args!("a \"message\""))
+                .key_values(&[("a", 1), ("b", 2)])
+                .build(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            r#"level=WARN target=app::db msg="a \"message\"" a=1 b=2"#,
+            logfmt
+        );
+    }
+}
@@ -38,6 +38,28 @@
 //! info!(a; "Something of interest");
 //! ```
 //!
+//! The message can be left out for event-style records that only carry key-values.
+//! These records have an empty message:
+//!
+//! ```
+//! # use log::info;
+//! # let key = "a";
+//! info!(event = "cache_miss", key;);
+//! ```
+//!
+//! Messages can also be given as a template, where each `{key}` hole is filled with the
+//! key-value with that key when the message is formatted. The raw template is kept on the
+//! record, and can be retrieved using [`Record::template`](crate::Record::template):
+//!
+//! ```
+//! # use log::info;
+//! # let name = "Nori";
+//! info!(user = name; template: "User {user} logged in");
+//! ```
+//!
+//! Holes can be formatted using `Debug` with `{key:?}`. Any other formatting options
+//! are ignored, and holes that don't match a key are written as-is.
+//!
 //! Values are capturing using the [`ToValue`] trait by default. To capture a value
 //! using a different trait implementation, use a modifier after its key. Here's how
 //! the same example can capture `a` using its `Debug` implementation instead:
@@ -50,12 +72,167 @@
 //! The following capturing modifiers are supported:
 //!
 //! - `:?` will capture the value using `Debug`.
+//! - `:#?` will capture the value using `Debug`, and format it using the alternate,
+//!   pretty-printed `Debug` format. See [`FormatHint::PrettyDebug`] for details.
 //! - `:debug` will capture the value using `Debug`.
 //! - `:%` will capture the value using `Display`.
 //! - `:display` will capture the value using `Display`.
 //! - `:err` will capture the value using `std::error::Error` (requires the `kv_std` feature).
+//!   The error and its sources can be walked using [`Value::to_error_chain`].
+//! - `:result` will capture a `Result` as its `Ok` value using `ToValue`, or its `Err`
+//!   value using `std::error::Error` (requires the `kv_std` feature).
 //! - `:sval` will capture the value using `sval::Value` (requires the `kv_sval` feature).
 //! - `:serde` will capture the value using `serde::Serialize` (requires the `kv_serde` feature).
+//! - `:json` will capture a string that's already valid JSON, so it's embedded verbatim when
+//!   serialized instead of being encoded as a string (requires the `kv_json` feature).
+//! - `:redact` will capture the value using `ToValue`, but format and serialize it as
+//!   `"[REDACTED]"`. See [`Redacted`] for details.
+//! - `:lazy` will capture a closure that's only called if the value is formatted or
+//!   serialized, like `dump:lazy = || expensive_dump()`. See [`Lazy`] for details.
+//! - `:seq` will capture a reference to a collection, or an iterator over references, as a
+//!   sequence of values using `ToValue`, like `ids:seq = &batch`. See [`SeqIter`] for details.
+//! - `:ns`, `:us`, `:ms`, `:s`, and `:bytes` will capture a number using `ToValue` and attach
+//!   a unit of measurement to it. See [`Value::to_unit`] for details.
+//! - `:x`, `:X`, `:o`, `:b`, `:e`, `:E`, and `:{.N}` will capture the value using `ToValue`,
+//!   but format it as hex, octal, binary, scientific notation, or with `N` decimal places.
+//!   See [`FormatHint`] for details.
+//!
+//! Shorthand identifiers can also be prefixed with `?` or `%` to capture them using
+//! `Debug` or `Display`, so `?a` is the same as `a:?` and `%a` is the same as `a:%`:
+//!
+//! ```
+//! # use log::info;
+//! let (user_id, path) = (1, "/");
+//!
+//! info!(?user_id, %path; "Handled request");
+//! ```
+//!
+//! The same syntax can be used to build a [`Source`] outside of a log statement with
+//! the [`kv!`](../macro.kv.html) macro.
+//!
+//! ## Scoped key-values
+//!
+//! With the `std` feature, key-values can be attached to every record logged on the
+//! current thread for the duration of a block using the [`kv_scope!`](../macro.kv_scope.html)
+//! macro, or until a guard is dropped using [`push_context`]:
+//!
+//! ```
+//! # #[cfg(feature = "std")]
+//! # {
+//! # use log::info;
+//! # let id = 42;
+//! log::kv_scope!(request_id = id => {
+//!     // This record includes `request_id = 42`
+//!     info!("Handling a request");
+//! });
+//! # }
+//! ```
+//!
+//! Key-values that apply to the whole process, like the name of the service, can be attached
+//! to every record logged on any thread using [`set_global_kv`](../fn.set_global_kv.html):
+//!
+//! ```
+//! # #[cfg(feature = "std")]
+//! log::set_global_kv(&[("service", "checkout")]);
+//! ```
+//!
+//! Key-values can also be inserted into, and removed from, a map for the current thread
+//! individually using the [`mdc`](../mdc/index.html) module. Both are tied to the thread they're
+//! added on, so a [`Context`](../struct.Context.html) can be captured to carry them across
+//! threads or tasks.
+//!
+//! ## Capturing format arguments
+//!
+//! Add the `kv_format_args` feature to also capture the implicit named arguments in a
+//! message's format string as key-values. Arguments are captured using `Display`, or
+//! `Debug` if they're formatted using `?`:
+//!
+//! ```
+//! # #[cfg(feature = "kv_format_args")]
+//! # {
+//! # use log::info;
+//! let (name, roles) = ("Nori", ["admin"]);
+//!
+//! // This record includes `name = "Nori"` and `roles = ["admin"]`
+//! info!("User {name} logged in with {roles:?}");
+//! # }
+//! ```
+//!
+//! Only arguments that refer to a variable in scope, like `{name}`, are captured.
+//! Positional arguments and explicit named arguments, like `{name}` in
+//! `info!("{name}", name = user.name())`, are left out, so their expressions aren't
+//! evaluated twice. Arguments with the same name as a key given before the `;` are left
+//! out too, and so are arguments formatted with other traits, like `{addr:p}` or `{id:x}`.
+//! The captured key-values come after any given before the `;`.
+//!
+//! ## Deriving `ToValue`
+//!
+//! Add the `kv_derive` feature to implement [`ToValue`] for your own structs and
+//! fieldless enums. Structs are captured with their fields as a map of key-values,
+//! using `sval` or `serde` if they're available, so they don't need to implement
+//! those traits themselves:
+//!
+//! ```
+//! # #[cfg(feature = "kv_derive")]
+//! # {
+//! # use log::info;
+//! use log::kv::ToValue;
+//!
+//! #[derive(ToValue)]
+//! #[kv(rename_all = "camelCase")]
+//! struct Request {
+//!     request_id: u64,
+//!     #[kv(rename = "route")]
+//!     path: &'static str,
+//!     #[kv(skip)]
+//!     token: &'static str,
+//!     #[kv(debug)]
+//!     tags: Vec<&'static str>,
+//! }
+//!
+//! let request = Request { request_id: 1, path: "/", token: "secret", tags: vec!["a"] };
+//!
+//! info!(request; "Something of interest");
+//! # }
+//! ```
+//!
+//! The following attributes are supported:
+//!
+//! - `#[kv(rename = "name")]` on a container renames it, or on a field or variant renames its key.
+//! - `#[kv(rename_all = "case")]` on a container renames all its fields or variants.
+//!   The case can be one of `lowercase`, `UPPERCASE`, `camelCase`, `PascalCase`,
+//!   `snake_case`, `SCREAMING_SNAKE_CASE`, or `kebab-case`.
+//! - `#[kv(skip)]` on a field leaves it out.
+//! - `#[kv(debug)]` or `#[kv(display)]` on a field captures it using `Debug` or `Display`
+//!   instead of `ToValue`.
+//! - `#[kv(crate = "path")]` on a container sets the path to `log` used by the generated
+//!   code, for crates that rename or re-export it. The default is `::log`.
+//!
+//! Structs can also derive [`Source`], which turns each of their fields into a
+//! key-value of their own. The same attributes are supported:
+//!
+//! ```
+//! # #[cfg(feature = "kv_derive")]
+//! # {
+//! use log::kv::{Key, Source};
+//!
+//! #[derive(Source)]
+//! struct Context {
+//!     request_id: u64,
+//!     #[kv(rename = "user")]
+//!     user_name: &'static str,
+//! }
+//!
+//! let ctx = Context { request_id: 1, user_name: "Nori" };
+//!
+//! let record = log::Record::builder()
+//!     .args(format_args!("Something of interest"))
+//!     .key_values(&ctx)
+//!     .build();
+//!
+//! assert_eq!(Some("Nori"), record.key_values().get_str(Key::from("user")));
+//! # }
+//! ```
 //!
 //! ## Working with key-values on log records
 //!
@@ -187,6 +364,36 @@
 //! # }
 //! ```
 //!
+//! Sequences and maps captured using [`Value::from_seq`] and [`Value::from_map`], like
+//! arrays, `Vec`s, and `BTreeMap`s, can be walked using [`Value::as_seq`] and [`Value::as_map`]
+//! without needing a serialization framework:
+//!
+//! ```
+//! # fn main() -> Result<(), log::kv::Error> {
+//! use log::kv::{self, Seq, Value, VisitSeq};
+//!
+//! struct Sum(i64);
+//!
+//! impl<'kvs> VisitSeq<'kvs> for Sum {
+//!     fn visit_value(&mut self, value: kv::Value) -> Result<(), kv::Error> {
+//!         self.0 += value.to_i64().unwrap_or(0);
+//!         Ok(())
+//!     }
+//! }
+//!
+//! let a = Value::from_seq(&[1, 2, 3]);
+//!
+//! let mut visitor = Sum(0);
+//!
+//! if let Some(seq) = a.as_seq() {
+//!     seq.visit(&mut visitor)?;
+//! }
+//!
+//! assert_eq!(6, visitor.0);
+//! # Ok(())
+//! # }
+//! ```
+//!
 //! To serialize a value to a format like JSON, you can also use either `serde` or `sval`:
 //!
 //! ```
@@ -214,12 +421,39 @@
 //! # }
 //! ```
 //!
+//! A whole source can also be written as a JSON object using [`to_json_writer`], which
+//! doesn't need `serde` or `sval` at all:
+//!
+//! ```
+//! # fn main() -> Result<(), log::kv::Error> {
+//! # let source = [("a", 1)];
+//! # let record = log::Record::builder().key_values(&source).build();
+//! let mut json = String::new();
+//! log::kv::to_json_writer(&mut json, record.key_values())?;
+//!
+//! assert_eq!("{\"a\":1}", json);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Sources and records can be written as logfmt (`key=value key="quoted value"`)
+//! using [`to_logfmt`] and [`to_logfmt_record`].
+//!
+//! For shipping logs over constrained links, the `kv_cbor` and `kv_msgpack` features add
+//! compact binary encoders for sources. See `to_cbor_slice` and `to_msgpack_slice` for details.
+//!
 //! The choice of serialization framework depends on the needs of the consumer.
 //! If you're in a no-std environment, you can use `sval`. In other cases, you can use `serde`.
 //! Log producers and log consumers don't need to agree on the serialization framework.
 //! A value can be captured using its `serde::Serialize` implementation and still be serialized
 //! through `sval` without losing any structure or data.
 //!
+//! The `kv_sval` feature targets `sval` 2.x. The `sval` 1.0 releases never left alpha and
+//! aren't supported, so values from libraries still on them should be captured through
+//! `serde`, `Debug`, or `Display` instead. When `kv_sval` moves to a new major version
+//! of `sval`, the bridge between them will live in `value-bag`, so producers and consumers
+//! won't need to upgrade together.
+//!
 //! Values can also always be formatted using the standard `Debug` and `Display`
 //! traits:
 //!
@@ -242,19 +476,71 @@
 //!
 //! assert_eq!("Data { a: 1, b: true, c: \"Some data\" }", format!("{a:?}"));
 //! ```
+//!
+//! # Stability
+//!
+//! Everything exported from this module without an `*_unstable` feature is covered by
+//! `log`'s semver guarantees. That includes the core of the API: [`Key`], [`Value`] and
+//! its conversions from primitives and strings, and [`Source`] with its `get` and `visit`
+//! methods. Libraries can depend on the `kv` feature to attach key-values to their records
+//! without tracking changes in `log`.
+//!
+//! The `kv_std`, `kv_sval`, `kv_serde`, and `kv_json` features are stable in the same way,
+//! but tie their APIs to the major versions of the libraries they integrate with.
+//!
+//! Features starting with `kv_unstable`, like `kv_unstable_uuid`, may change or be removed
+//! in minor releases. With the `kv_unstable` feature, the `source` and `value` modules are
+//! public, exposing their deprecated APIs.
 
+#[cfg(any(feature = "kv_cbor", feature = "kv_msgpack"))]
+mod binary;
 mod error;
+#[cfg(feature = "std")]
+pub(crate) mod global;
+mod json;
 mod key;
+mod logfmt;
+#[cfg(feature = "std")]
+pub(crate) mod scope;
 
 #[cfg(not(feature = "kv_unstable"))]
 mod source;
 #[cfg(not(feature = "kv_unstable"))]
 mod value;
 
-pub use self::error::Error;
+pub use self::error::{Error, ErrorKind};
+pub use self::json::to_json_writer;
 pub use self::key::{Key, ToKey};
-pub use self::source::{Source, VisitSource};
-pub use self::value::{ToValue, Value, VisitValue};
+pub use self::logfmt::{to_logfmt, to_logfmt_record};
+pub use self::source::{Chained, Dedup, Iter, Merged, Source, Truncate, VisitSource};
+pub use self::value::{
+    FormatHint, Lazy, Redacted, Seq, SeqIter, ToValue, Value, ValueKind, VisitSeq, VisitValue,
+};
+
+#[cfg(all(feature = "kv_cbor", feature = "std"))]
+pub use self::binary::to_cbor;
+#[cfg(feature = "kv_cbor")]
+pub use self::binary::to_cbor_slice;
+#[cfg(all(feature = "kv_msgpack", feature = "std"))]
+pub use self::binary::to_msgpack;
+#[cfg(feature = "kv_msgpack")]
+pub use self::binary::to_msgpack_slice;
+
+#[cfg(feature = "kv_derive")]
+pub use log_derive::{Source, ToValue};
+
+#[cfg(feature = "std")]
+pub use self::json::to_json_io_writer;
+#[cfg(feature = "std")]
+pub use self::key::OwnedKey;
+#[cfg(feature = "std")]
+pub use self::scope::{push_context, ContextGuard};
+#[cfg(feature = "std")]
+pub use self::source::{Flatten, OwnedSource, Prefixed, Sorted};
+#[cfg(feature = "kv_std")]
+pub use self::value::ErrorChain;
+#[cfg(feature = "std")]
+pub use self::value::OwnedValue;
 
 #[cfg(feature = "kv_unstable")]
 pub mod source;
@@ -0,0 +1,452 @@
+//! Key-values attached to every record logged on the current thread.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::marker::PhantomData;
+use std::rc::Rc;
+use std::sync::Arc;
+
+#[cfg(feature = "task_local")]
+use std::future::Future;
+#[cfg(feature = "task_local")]
+use std::pin::Pin;
+#[cfg(feature = "task_local")]
+use std::task::{Context as TaskContext, Poll};
+
+use crate::kv::{Error, Key, OwnedSource, Source, Value, VisitSource};
+use crate::mdc;
+
+thread_local! {
+    static CURRENT: RefCell<Option<Arc<Frame>>> = const { RefCell::new(None) };
+}
+
+/**
+A set of key-values pushed onto the current thread, along with the ones that were
+already there.
+
+Frames are immutable once pushed, so they can be shared with the records that are
+logged while they're current without holding a borrow on the thread-local.
+*/
+#[derive(Debug)]
+struct Frame {
+    pairs: OwnedSource,
+    parent: Option<Arc<Frame>>,
+}
+
+/// Attach key-values to every record logged on the current thread until the returned
+/// guard is dropped.
+///
+/// The key-values are buffered into an [`OwnedSource`], so the source doesn't need to
+/// outlive the call. Contexts can be nested. When records are logged using the macros,
+/// the key-values of each active context are added before the record's own, from the
+/// outermost context to the innermost. That means a key on the record itself takes
+/// precedence over the same key in a context, and inner contexts take precedence over
+/// outer ones when using [`Source::get`].
+///
+/// Guards should be dropped in the reverse order they were created in. Dropping a guard
+/// restores the context that was current when it was created.
+///
+/// The [`kv_scope!`](../macro.kv_scope.html) macro pushes a context for the duration
+/// of a block.
+///
+/// # Examples
+///
+/// ```
+/// use log::info;
+///
+/// let _guard = log::kv::push_context(&[("request_id", 42)]);
+///
+/// // This record includes `request_id = 42`
+/// info!("handling a request");
+/// ```
+pub fn push_context(source: &(impl Source + ?Sized)) -> ContextGuard {
+    let pairs = OwnedSource::from_source(source);
+
+    let prev = CURRENT
+        .try_with(|current| {
+            let mut current = current.borrow_mut();
+            let prev = current.take();
+
+            *current = Some(Arc::new(Frame {
+                pairs,
+                parent: prev.clone(),
+            }));
+
+            prev
+        })
+        .ok()
+        .flatten();
+
+    ContextGuard {
+        prev,
+        prev_mdc: None,
+        _not_send: PhantomData,
+    }
+}
+
+/// A guard returned by [`push_context`] and [`Context::enter`].
+///
+/// Dropping the guard removes its key-values from the current thread.
+#[must_use = "the context is removed as soon as the guard is dropped"]
+#[derive(Debug)]
+pub struct ContextGuard {
+    prev: Option<Arc<Frame>>,
+    // Only set when a `Context` is entered, because that replaces the MDC too
+    prev_mdc: Option<Option<Rc<mdc::Entries>>>,
+    // Contexts belong to the thread they were pushed on
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for ContextGuard {
+    fn drop(&mut self) {
+        let prev = self.prev.take();
+
+        let _ = CURRENT.try_with(|current| *current.borrow_mut() = prev);
+
+        if let Some(prev_mdc) = self.prev_mdc.take() {
+            mdc::replace(prev_mdc);
+        }
+    }
+}
+
+/// A captured set of thread-local key-values that can be moved to another thread or task
+/// and re-entered there.
+///
+/// Key-values pushed using [`push_context`] and inserted into the [`mdc`](crate::mdc) belong
+/// to the thread they were added on. That breaks down when work hops between threads, like
+/// a future on a work-stealing executor, or a job handed to a thread pool. A context is an
+/// explicit handle to those key-values instead: capture it with [`Context::current`], move
+/// it to where the work runs, and [enter](Context::enter) it there.
+///
+/// Contexts are cheap to clone, and immutable. [`Context::with`] returns a new context
+/// with more key-values added.
+///
+/// # Examples
+///
+/// ```
+/// use log::{info, Context};
+///
+/// let _guard = log::kv::push_context(&[("request_id", 42)]);
+///
+/// let context = Context::current();
+///
+/// std::thread::spawn(move || {
+///     context.scope(|| {
+///         // This record includes `request_id = 42`
+///         info!("handling a request in the background");
+///     });
+/// })
+/// .join()
+/// .unwrap();
+/// ```
+#[derive(Clone, Default)]
+pub struct Context {
+    scope: Option<Arc<Frame>>,
+    mdc: Option<Arc<mdc::Entries>>,
+}
+
+impl Context {
+    /// Create a context without any key-values.
+    pub fn new() -> Self {
+        Context::default()
+    }
+
+    /// Capture the key-values pushed onto the current thread, and its MDC.
+    pub fn current() -> Self {
+        Context {
+            scope: current().map(|scope| scope.0),
+            mdc: mdc::capture(),
+        }
+    }
+
+    /// Create a new context with the given key-values added to this one.
+    ///
+    /// The key-values are buffered into an [`OwnedSource`], so the source doesn't need
+    /// to outlive the call.
+    pub fn with(&self, source: &(impl Source + ?Sized)) -> Self {
+        Context {
+            scope: Some(Arc::new(Frame {
+                pairs: OwnedSource::from_source(source),
+                parent: self.scope.clone(),
+            })),
+            mdc: self.mdc.clone(),
+        }
+    }
+
+    /// Make this context current on this thread until the returned guard is dropped.
+    ///
+    /// Entering a context replaces the key-values that were pushed onto the thread and its
+    /// MDC, rather than adding to them. Dropping the guard restores them.
+    pub fn enter(&self) -> ContextGuard {
+        let scope = self.scope.clone();
+        let prev = CURRENT
+            .try_with(|current| std::mem::replace(&mut *current.borrow_mut(), scope))
+            .ok()
+            .flatten();
+
+        let prev_mdc = mdc::replace(self.mdc.as_ref().map(|mdc| Rc::new((**mdc).clone())));
+
+        ContextGuard {
+            prev,
+            prev_mdc: Some(prev_mdc),
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Run `f` with this context entered on the current thread.
+    pub fn scope<R>(&self, f: impl FnOnce() -> R) -> R {
+        let _guard = self.enter();
+
+        f()
+    }
+
+    /// Enter this context each time `future` is polled.
+    ///
+    /// This keeps the context with the future across `.await` points, even if it's moved
+    /// between threads, without leaking it into other tasks that run on the same thread.
+    #[cfg(feature = "task_local")]
+    pub fn attach<F: Future>(self, future: F) -> WithContext<F> {
+        WithContext {
+            context: self,
+            future,
+        }
+    }
+}
+
+impl Source for Context {
+    fn visit<'kvs>(&'kvs self, visitor: &mut dyn VisitSource<'kvs>) -> Result<(), Error> {
+        if let Some(mdc) = &self.mdc {
+            for (key, value) in &**mdc {
+                visitor.visit_pair(Key::from_str(key), value.by_ref())?;
+            }
+        }
+
+        match &self.scope {
+            Some(scope) => scope.visit(visitor),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Debug for Context {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        struct Entries<'a, 'b: 'a>(fmt::DebugMap<'a, 'b>);
+
+        impl<'a, 'b: 'a, 'kvs> VisitSource<'kvs> for Entries<'a, 'b> {
+            fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+                self.0.entry(&key, &value);
+
+                Ok(())
+            }
+        }
+
+        let mut entries = Entries(f.debug_map());
+        let _ = self.visit(&mut entries);
+
+        entries.0.finish()
+    }
+}
+
+/// A future returned by [`Context::attach`].
+#[cfg(feature = "task_local")]
+#[must_use = "futures do nothing unless polled"]
+#[derive(Debug)]
+pub struct WithContext<F> {
+    context: Context,
+    future: F,
+}
+
+#[cfg(feature = "task_local")]
+impl<F: Future> Future for WithContext<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut TaskContext) -> Poll<Self::Output> {
+        // SAFETY: The future is never moved out of `self`, and the context isn't pinned
+        let this = unsafe { self.get_unchecked_mut() };
+        let future = unsafe { Pin::new_unchecked(&mut this.future) };
+
+        this.context.scope(|| future.poll(cx))
+    }
+}
+
+/**
+The key-values pushed onto the current thread, as a source.
+*/
+#[derive(Clone)]
+pub(crate) struct Scope(Arc<Frame>);
+
+/**
+Get the key-values pushed onto the current thread, if there are any.
+*/
+pub(crate) fn current() -> Option<Scope> {
+    CURRENT
+        .try_with(|current| current.borrow().clone().map(Scope))
+        .ok()
+        .flatten()
+}
+
+impl Frame {
+    // Visit the outermost frame's key-values first
+    fn visit<'kvs>(&'kvs self, visitor: &mut dyn VisitSource<'kvs>) -> Result<(), Error> {
+        if let Some(parent) = &self.parent {
+            parent.visit(visitor)?;
+        }
+
+        self.pairs.visit(visitor)
+    }
+}
+
+impl Source for Scope {
+    fn visit<'kvs>(&'kvs self, visitor: &mut dyn VisitSource<'kvs>) -> Result<(), Error> {
+        self.0.visit(visitor)
+    }
+
+    fn get(&self, key: Key) -> Option<Value<'_>> {
+        let mut frame = Some(&*self.0);
+        while let Some(current) = frame {
+            if let Some(value) = current.pairs.get(key.clone()) {
+                return Some(value);
+            }
+
+            frame = current.parent.as_deref();
+        }
+
+        None
+    }
+
+    fn count(&self) -> usize {
+        let mut count = 0;
+
+        let mut frame = Some(&*self.0);
+        while let Some(current) = frame {
+            count += current.pairs.count();
+            frame = current.parent.as_deref();
+        }
+
+        count
+    }
+}
+
+impl fmt::Debug for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        struct Entries<'a, 'b: 'a>(fmt::DebugMap<'a, 'b>);
+
+        impl<'a, 'b: 'a, 'kvs> VisitSource<'kvs> for Entries<'a, 'b> {
+            fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+                self.0.entry(&key, &value);
+
+                Ok(())
+            }
+        }
+
+        let mut entries = Entries(f.debug_map());
+        let _ = self.visit(&mut entries);
+
+        entries.0.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn current_string() -> Option<String> {
+        current().map(|scope| {
+            let mut logfmt = String::new();
+            crate::kv::to_logfmt(&mut logfmt, &scope).unwrap();
+            logfmt
+        })
+    }
+
+    #[test]
+    fn push_pop() {
+        assert!(current().is_none());
+
+        {
+            let _outer = push_context(&[("a", 1), ("b", 2)]);
+            assert_eq!(Some("a=1 b=2"), current_string().as_deref());
+
+            {
+                let _inner = push_context(&("a", 3));
+                assert_eq!(Some("a=1 b=2 a=3"), current_string().as_deref());
+
+                let scope = current().unwrap();
+                assert_eq!(3, scope.count());
+                assert_eq!(Some(3), scope.get_i64(Key::from_str("a")));
+                assert_eq!(Some(2), scope.get_i64(Key::from_str("b")));
+                assert!(scope.get(Key::from_str("c")).is_none());
+            }
+
+            assert_eq!(Some("a=1 b=2"), current_string().as_deref());
+        }
+
+        assert!(current().is_none());
+    }
+
+    #[test]
+    fn scope_outlives_guard() {
+        let scope = {
+            let _guard = push_context(&("a", 1));
+            current().unwrap()
+        };
+
+        assert!(current().is_none());
+        assert_eq!(Some(1), scope.get_i64(Key::from_str("a")));
+    }
+
+    #[test]
+    fn context() {
+        fn assert_send_sync<T: Send + Sync + 'static>(_: &T) {}
+
+        let context = {
+            let _guard = push_context(&("a", 1));
+            crate::mdc::insert("b", 2);
+
+            let context = Context::current();
+            crate::mdc::clear();
+
+            context
+        };
+        assert_send_sync(&context);
+        assert!(current().is_none());
+
+        let context = context.with(&("c", 3));
+
+        std::thread::spawn(move || {
+            let _outer = push_context(&("d", 4));
+
+            context.scope(|| {
+                assert_eq!(Some("a=1 c=3"), current_string().as_deref());
+                assert_eq!(
+                    Some(2),
+                    crate::mdc::get("b").and_then(|value| value.by_ref().to_i64())
+                );
+            });
+
+            assert_eq!(Some("d=4"), current_string().as_deref());
+            assert!(crate::mdc::get("b").is_none());
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[test]
+    fn context_source() {
+        let mut logfmt = String::new();
+        crate::kv::to_logfmt(&mut logfmt, &Context::new().with(&[("a", 1), ("b", 2)])).unwrap();
+        assert_eq!("a=1 b=2", logfmt);
+
+        assert_eq!(0, Context::new().count());
+    }
+
+    #[test]
+    fn per_thread() {
+        let _guard = push_context(&("a", 1));
+
+        std::thread::spawn(|| assert!(current().is_none()))
+            .join()
+            .unwrap();
+
+        assert!(current().is_some());
+    }
+}
@@ -6,6 +6,9 @@
 use crate::kv::{Error, Key, ToKey, ToValue, Value};
 use std::fmt;
 
+#[cfg(feature = "std")]
+pub use self::std_support::{Flatten, OwnedSource, Prefixed, Sorted};
+
 /// A source of key-values.
 ///
 /// The source may be a single pair, a set of pairs, or a filter over a set of pairs.
@@ -15,6 +18,12 @@ use std::fmt;
 /// A source is like an iterator over its key-values, except with a push-based API
 /// instead of a pull-based one.
 ///
+/// Arrays and slices of key-value tuples are sources. With the `std` feature, so are
+/// `Vec`s of them, and `HashMap`s and `BTreeMap`s with string keys, so key-values built
+/// at runtime can be attached to a record directly. The `kv_unstable_smallvec` and
+/// `kv_unstable_arrayvec` features do the same for `SmallVec` and `ArrayVec`, which can
+/// collect key-values without allocating.
+///
 /// # Examples
 ///
 /// Enumerating the key-values in a source:
@@ -53,7 +62,8 @@ pub trait Source {
     ///
     /// A source doesn't have to guarantee any ordering or uniqueness of key-values.
     /// If the given visitor returns an error then the source may early-return with it,
-    /// even if there are more key-values.
+    /// even if there are more key-values. Visitors that only need to see some key-values
+    /// can stop visiting by returning [`Error::stop`].
     ///
     /// # Implementation notes
     ///
@@ -85,6 +95,140 @@ pub trait Source {
     fn count(&self) -> usize {
         count_default(self)
     }
+
+    /// Check whether the source has no key-values to visit.
+    ///
+    /// # Implementation notes
+    ///
+    /// The default implementation stops visiting at the first key-value.
+    /// A source that knows whether it's empty upfront may provide a more
+    /// efficient implementation.
+    ///
+    /// This method should return `true` only if `count` returns `0`.
+    fn is_empty(&self) -> bool {
+        is_empty_default(self)
+    }
+
+    /// Iterate over the key-values in this source.
+    ///
+    /// The iterator doesn't allocate, so each key-value it yields requires another
+    /// pass over the source. This makes iterating quadratic in the number of key-values,
+    /// which works well for the small sources typically found on records. Prefer
+    /// [`visit`](Source::visit) when all key-values are needed from a large source.
+    ///
+    /// Sources behind a `&dyn Source`, like [`Record::key_values`](crate::Record::key_values),
+    /// can be iterated using [`Iter::new`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use log::kv::Source;
+    ///
+    /// let source = [("a", 1), ("b", 2)];
+    ///
+    /// for (key, value) in source.pairs() {
+    ///     println!("{key}: {value}");
+    /// }
+    /// ```
+    fn pairs(&self) -> Iter<'_, Self>
+    where
+        Self: Sized,
+    {
+        Iter::new(self)
+    }
+
+    /// Check whether a given key is in this source.
+    ///
+    /// Unlike [`get`](Source::get), this method stops visiting at the first
+    /// key-value with the given key.
+    fn contains_key(&self, key: Key) -> bool {
+        contains_key_default(self, key)
+    }
+
+    /// Get the value for a given key as a borrowed string.
+    ///
+    /// This method returns `None` if the key isn't present, or its value
+    /// isn't a borrowed string.
+    fn get_str(&self, key: Key) -> Option<&str> {
+        self.get(key).and_then(|value| value.to_borrowed_str())
+    }
+
+    /// Get the value for a given key as an `i64`.
+    ///
+    /// This method returns `None` if the key isn't present, or its value
+    /// can't be converted into an `i64`.
+    fn get_i64(&self, key: Key) -> Option<i64> {
+        self.get(key).and_then(|value| value.to_i64())
+    }
+
+    /// Get the value for a given key as a `u64`.
+    ///
+    /// This method returns `None` if the key isn't present, or its value
+    /// can't be converted into a `u64`.
+    fn get_u64(&self, key: Key) -> Option<u64> {
+        self.get(key).and_then(|value| value.to_u64())
+    }
+
+    /// Get the value for a given key as an `f64`.
+    ///
+    /// This method returns `None` if the key isn't present, or its value
+    /// can't be converted into an `f64`.
+    fn get_f64(&self, key: Key) -> Option<f64> {
+        self.get(key).and_then(|value| value.to_f64())
+    }
+
+    /// Get the value for a given key as a `bool`.
+    ///
+    /// This method returns `None` if the key isn't present, or its value
+    /// isn't a `bool`.
+    fn get_bool(&self, key: Key) -> Option<bool> {
+        self.get(key).and_then(|value| value.to_bool())
+    }
+
+    /// Get the value at a dot-separated path, like `http.request.method`.
+    ///
+    /// The first segment of the path is looked up as a key in this source. The
+    /// remaining segments descend into the structure of its value, matching
+    /// either the keys of maps and structs, or the indexes of sequences and tuples.
+    /// Nested structure is only visible in values captured using `sval` or `serde`.
+    ///
+    /// This method returns `None` if any segment of the path isn't present.
+    #[cfg(all(feature = "kv_sval", feature = "std"))]
+    fn get_path(&self, path: &str) -> Option<crate::kv::OwnedValue> {
+        path_support::get_path(self, path)
+    }
+
+    /// Chain this source with another, visiting all key-values in both.
+    ///
+    /// Key-values in this source are visited before key-values in `other`.
+    /// No attempt is made to remove duplicates. If a key appears in both sources,
+    /// [`get`](Source::get) returns the value from `other`, the same as a visitor
+    /// that keeps the last value it sees for each key would.
+    fn chain<S>(self, other: S) -> Chained<Self, S>
+    where
+        Self: Sized,
+        S: Source,
+    {
+        Chained {
+            first: self,
+            second: other,
+        }
+    }
+
+    /// Merge this source with another, where key-values in `other` take precedence.
+    ///
+    /// Key-values in this source are visited first, skipping any whose key also
+    /// appears in `other`, followed by all key-values in `other`.
+    fn merge<S>(self, other: S) -> Merged<Self, S>
+    where
+        Self: Sized,
+        S: Source,
+    {
+        Merged {
+            base: self,
+            overrides: other,
+        }
+    }
 }
 
 /// The default implementation of `Source::get`
@@ -110,6 +254,48 @@ fn get_default<'v>(source: &'v (impl Source + ?Sized), key: Key) -> Option<Value
     get.found
 }
 
+/// The default implementation of `Source::contains_key`.
+fn contains_key_default(source: &(impl Source + ?Sized), key: Key) -> bool {
+    struct ContainsKey<'k> {
+        key: Key<'k>,
+        found: bool,
+    }
+
+    impl<'k, 'kvs> VisitSource<'kvs> for ContainsKey<'k> {
+        fn visit_pair(&mut self, key: Key<'kvs>, _: Value<'kvs>) -> Result<(), Error> {
+            if self.key == key {
+                self.found = true;
+
+                return Err(Error::stop());
+            }
+
+            Ok(())
+        }
+    }
+
+    let mut contains = ContainsKey { key, found: false };
+
+    let _ = source.visit(&mut contains);
+    contains.found
+}
+
+/// The default implementation of `Source::is_empty`.
+fn is_empty_default(source: impl Source) -> bool {
+    struct IsEmpty(bool);
+
+    impl<'kvs> VisitSource<'kvs> for IsEmpty {
+        fn visit_pair(&mut self, _: Key<'kvs>, _: Value<'kvs>) -> Result<(), Error> {
+            self.0 = false;
+
+            Err(Error::stop())
+        }
+    }
+
+    let mut is_empty = IsEmpty(true);
+    let _ = source.visit(&mut is_empty);
+    is_empty.0
+}
+
 /// The default implementation of `Source::count`.
 fn count_default(source: impl Source) -> usize {
     struct Count(usize);
@@ -142,6 +328,14 @@ where
     fn count(&self) -> usize {
         Source::count(&**self)
     }
+
+    fn is_empty(&self) -> bool {
+        Source::is_empty(&**self)
+    }
+
+    fn contains_key(&self, key: Key) -> bool {
+        Source::contains_key(&**self, key)
+    }
 }
 
 impl<K, V> Source for (K, V)
@@ -164,6 +358,10 @@ where
     fn count(&self) -> usize {
         1
     }
+
+    fn is_empty(&self) -> bool {
+        false
+    }
 }
 
 impl<S> Source for [S]
@@ -191,6 +389,10 @@ where
     fn count(&self) -> usize {
         self.iter().map(Source::count).sum()
     }
+
+    fn is_empty(&self) -> bool {
+        self.iter().all(Source::is_empty)
+    }
 }
 
 impl<const N: usize, S> Source for [S; N]
@@ -208,6 +410,10 @@ where
     fn count(&self) -> usize {
         Source::count(self as &[_])
     }
+
+    fn is_empty(&self) -> bool {
+        Source::is_empty(self as &[_])
+    }
 }
 
 impl<S> Source for Option<S>
@@ -229,186 +435,1115 @@ where
     fn count(&self) -> usize {
         self.as_ref().map_or(0, Source::count)
     }
+
+    fn is_empty(&self) -> bool {
+        self.as_ref().map_or(true, Source::is_empty)
+    }
 }
 
-/// A visitor for the key-value pairs in a [`Source`](trait.Source.html).
-pub trait VisitSource<'kvs> {
-    /// Visit a key-value pair.
-    fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error>;
+/// Two sources chained together.
+///
+/// This type is returned by [`Source::chain`].
+#[derive(Debug, Clone)]
+pub struct Chained<A, B> {
+    first: A,
+    second: B,
 }
 
-impl<'a, 'kvs, T> VisitSource<'kvs> for &'a mut T
+impl<A, B> Source for Chained<A, B>
 where
-    T: VisitSource<'kvs> + ?Sized,
+    A: Source,
+    B: Source,
 {
-    fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
-        (**self).visit_pair(key, value)
+    fn visit<'kvs>(&'kvs self, visitor: &mut dyn VisitSource<'kvs>) -> Result<(), Error> {
+        self.first.visit(visitor)?;
+        self.second.visit(visitor)
     }
-}
 
-impl<'a, 'b: 'a, 'kvs> VisitSource<'kvs> for fmt::DebugMap<'a, 'b> {
-    fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
-        self.entry(&key, &value);
-        Ok(())
+    fn get(&self, key: Key) -> Option<Value<'_>> {
+        self.second.get(key.clone()).or_else(|| self.first.get(key))
     }
-}
 
-impl<'a, 'b: 'a, 'kvs> VisitSource<'kvs> for fmt::DebugList<'a, 'b> {
-    fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
-        self.entry(&(key, value));
-        Ok(())
+    fn count(&self) -> usize {
+        self.first.count() + self.second.count()
     }
-}
 
-impl<'a, 'b: 'a, 'kvs> VisitSource<'kvs> for fmt::DebugSet<'a, 'b> {
-    fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
-        self.entry(&(key, value));
-        Ok(())
+    fn is_empty(&self) -> bool {
+        self.first.is_empty() && self.second.is_empty()
     }
-}
 
-impl<'a, 'b: 'a, 'kvs> VisitSource<'kvs> for fmt::DebugTuple<'a, 'b> {
-    fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
-        self.field(&key);
-        self.field(&value);
-        Ok(())
+    fn contains_key(&self, key: Key) -> bool {
+        self.first.contains_key(key.clone()) || self.second.contains_key(key)
     }
 }
 
-#[cfg(feature = "std")]
-mod std_support {
-    use super::*;
-    use std::borrow::Borrow;
-    use std::collections::{BTreeMap, HashMap};
-    use std::hash::{BuildHasher, Hash};
-    use std::rc::Rc;
-    use std::sync::Arc;
+/// A source with another source merged over it.
+///
+/// This type is returned by [`Source::merge`].
+#[derive(Debug, Clone)]
+pub struct Merged<A, B> {
+    base: A,
+    overrides: B,
+}
 
-    impl<S> Source for Box<S>
-    where
-        S: Source + ?Sized,
-    {
-        fn visit<'kvs>(&'kvs self, visitor: &mut dyn VisitSource<'kvs>) -> Result<(), Error> {
-            Source::visit(&**self, visitor)
+impl<A, B> Source for Merged<A, B>
+where
+    A: Source,
+    B: Source,
+{
+    fn visit<'kvs>(&'kvs self, visitor: &mut dyn VisitSource<'kvs>) -> Result<(), Error> {
+        struct Base<'a, 'kvs, B: ?Sized> {
+            overrides: &'a B,
+            visitor: &'a mut dyn VisitSource<'kvs>,
         }
 
-        fn get(&self, key: Key) -> Option<Value<'_>> {
-            Source::get(&**self, key)
-        }
+        impl<'a, 'kvs, B: Source + ?Sized> VisitSource<'kvs> for Base<'a, 'kvs, B> {
+            fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+                if self.overrides.get(key.clone()).is_some() {
+                    return Ok(());
+                }
 
-        fn count(&self) -> usize {
-            Source::count(&**self)
+                self.visitor.visit_pair(key, value)
+            }
         }
+
+        self.base.visit(&mut Base {
+            overrides: &self.overrides,
+            visitor,
+        })?;
+        self.overrides.visit(visitor)
     }
 
-    impl<S> Source for Arc<S>
-    where
-        S: Source + ?Sized,
-    {
-        fn visit<'kvs>(&'kvs self, visitor: &mut dyn VisitSource<'kvs>) -> Result<(), Error> {
-            Source::visit(&**self, visitor)
-        }
+    fn get(&self, key: Key) -> Option<Value<'_>> {
+        self.overrides
+            .get(key.clone())
+            .or_else(|| self.base.get(key))
+    }
 
-        fn get(&self, key: Key) -> Option<Value<'_>> {
-            Source::get(&**self, key)
-        }
+    fn is_empty(&self) -> bool {
+        self.base.is_empty() && self.overrides.is_empty()
+    }
 
-        fn count(&self) -> usize {
-            Source::count(&**self)
-        }
+    fn contains_key(&self, key: Key) -> bool {
+        self.base.contains_key(key.clone()) || self.overrides.contains_key(key)
     }
+}
 
-    impl<S> Source for Rc<S>
-    where
-        S: Source + ?Sized,
-    {
-        fn visit<'kvs>(&'kvs self, visitor: &mut dyn VisitSource<'kvs>) -> Result<(), Error> {
-            Source::visit(&**self, visitor)
-        }
+/// A source that removes duplicate keys.
+///
+/// By default, the last value for each key wins, which is how consumers of a source
+/// should treat duplicates they don't remove themselves. Use [`Dedup::first_wins`]
+/// to keep the first value instead. Key-values are otherwise visited in their
+/// original order.
+///
+/// Removing duplicates doesn't allocate, so each key-value visited requires another
+/// pass over the wrapped source. This makes visiting quadratic in the number of
+/// key-values, which works well for the small sources typically found on records.
+///
+/// # Examples
+///
+/// ```
+/// use log::kv::{Dedup, Key, Source};
+///
+/// let source = Dedup::new([("a", 1), ("b", 2), ("a", 3)]);
+///
+/// assert_eq!(2, source.count());
+/// assert_eq!(Some(3), source.get_i64(Key::from("a")));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Dedup<S> {
+    source: S,
+    keep: Keep,
+}
 
-        fn get(&self, key: Key) -> Option<Value<'_>> {
-            Source::get(&**self, key)
-        }
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Keep {
+    First,
+    Last,
+}
 
-        fn count(&self) -> usize {
-            Source::count(&**self)
-        }
+impl<S> Dedup<S> {
+    /// Remove duplicate keys from a source, keeping the last value for each.
+    pub fn new(source: S) -> Self {
+        Dedup::last_wins(source)
     }
 
-    impl<S> Source for Vec<S>
-    where
-        S: Source,
-    {
-        fn visit<'kvs>(&'kvs self, visitor: &mut dyn VisitSource<'kvs>) -> Result<(), Error> {
-            Source::visit(&**self, visitor)
-        }
-
-        fn get(&self, key: Key) -> Option<Value<'_>> {
-            Source::get(&**self, key)
+    /// Remove duplicate keys from a source, keeping the last value for each.
+    pub fn last_wins(source: S) -> Self {
+        Dedup {
+            source,
+            keep: Keep::Last,
         }
+    }
 
-        fn count(&self) -> usize {
-            Source::count(&**self)
+    /// Remove duplicate keys from a source, keeping the first value for each.
+    pub fn first_wins(source: S) -> Self {
+        Dedup {
+            source,
+            keep: Keep::First,
         }
     }
 
-    impl<'kvs, V> VisitSource<'kvs> for Box<V>
-    where
-        V: VisitSource<'kvs> + ?Sized,
-    {
-        fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
-            (**self).visit_pair(key, value)
-        }
+    /// Get the wrapped source.
+    pub fn into_inner(self) -> S {
+        self.source
     }
+}
 
-    impl<K, V, S> Source for HashMap<K, V, S>
-    where
-        K: ToKey + Borrow<str> + Eq + Hash,
-        V: ToValue,
-        S: BuildHasher,
-    {
-        fn visit<'kvs>(&'kvs self, visitor: &mut dyn VisitSource<'kvs>) -> Result<(), Error> {
-            for (key, value) in self {
-                visitor.visit_pair(key.to_key(), value.to_value())?;
-            }
-            Ok(())
+impl<S> Source for Dedup<S>
+where
+    S: Source,
+{
+    fn visit<'kvs>(&'kvs self, visitor: &mut dyn VisitSource<'kvs>) -> Result<(), Error> {
+        struct Filter<'a, 'kvs, S> {
+            source: &'kvs S,
+            keep: Keep,
+            index: usize,
+            visitor: &'a mut dyn VisitSource<'kvs>,
         }
 
-        fn get(&self, key: Key) -> Option<Value<'_>> {
-            HashMap::get(self, key.as_str()).map(|v| v.to_value())
-        }
+        impl<'a, 'kvs, S: Source> VisitSource<'kvs> for Filter<'a, 'kvs, S> {
+            fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+                let index = self.index;
+                self.index += 1;
 
-        fn count(&self) -> usize {
-            self.len()
-        }
-    }
+                if is_shadowed(self.source, &key, index, self.keep) {
+                    return Ok(());
+                }
 
-    impl<K, V> Source for BTreeMap<K, V>
-    where
-        K: ToKey + Borrow<str> + Ord,
-        V: ToValue,
-    {
-        fn visit<'kvs>(&'kvs self, visitor: &mut dyn VisitSource<'kvs>) -> Result<(), Error> {
-            for (key, value) in self {
-                visitor.visit_pair(key.to_key(), value.to_value())?;
+                self.visitor.visit_pair(key, value)
             }
-            Ok(())
         }
 
-        fn get(&self, key: Key) -> Option<Value<'_>> {
-            BTreeMap::get(self, key.as_str()).map(|v| v.to_value())
-        }
+        self.source.visit(&mut Filter {
+            source: &self.source,
+            keep: self.keep,
+            index: 0,
+            visitor,
+        })
+    }
 
-        fn count(&self) -> usize {
-            self.len()
+    fn get(&self, key: Key) -> Option<Value<'_>> {
+        match self.keep {
+            Keep::Last => get_default(&self.source, key),
+            Keep::First => {
+                struct GetFirst<'k, 'v> {
+                    key: Key<'k>,
+                    found: Option<Value<'v>>,
+                }
+
+                impl<'k, 'kvs> VisitSource<'kvs> for GetFirst<'k, 'kvs> {
+                    fn visit_pair(
+                        &mut self,
+                        key: Key<'kvs>,
+                        value: Value<'kvs>,
+                    ) -> Result<(), Error> {
+                        if self.key == key {
+                            self.found = Some(value);
+
+                            return Err(Error::stop());
+                        }
+
+                        Ok(())
+                    }
+                }
+
+                let mut get = GetFirst { key, found: None };
+
+                let _ = self.source.visit(&mut get);
+                get.found
+            }
         }
     }
+}
 
-    #[cfg(test)]
-    mod tests {
-        use crate::kv::value;
+/// Whether the key-value at `index` has the same key as another that takes precedence over it.
+fn is_shadowed(source: &impl Source, key: &Key, index: usize, keep: Keep) -> bool {
+    struct Find<'a, 'k> {
+        key: &'a Key<'k>,
+        index: usize,
+        keep: Keep,
+        current: usize,
+        found: bool,
+    }
 
-        use super::*;
+    impl<'a, 'k, 'kvs> VisitSource<'kvs> for Find<'a, 'k> {
+        fn visit_pair(&mut self, key: Key<'kvs>, _: Value<'kvs>) -> Result<(), Error> {
+            let current = self.current;
+            self.current += 1;
+
+            // When the first value wins, there's no need to look past the current one
+            if self.keep == Keep::First && current >= self.index {
+                return Err(Error::stop());
+            }
+
+            let shadows = match self.keep {
+                Keep::First => current < self.index,
+                Keep::Last => current > self.index,
+            };
+
+            if shadows && self.key.as_str() == key.as_str() {
+                self.found = true;
+
+                return Err(Error::stop());
+            }
+
+            Ok(())
+        }
+    }
+
+    let mut find = Find {
+        key,
+        index,
+        keep,
+        current: 0,
+        found: false,
+    };
+
+    let _ = source.visit(&mut find);
+    find.found
+}
+
+/// A source that visits at most a given number of key-values.
+///
+/// If the wrapped source has more key-values than the limit, the ones after it are
+/// skipped and a `truncated = true` key-value is visited in their place. This keeps
+/// the size of sources with many key-values bounded.
+///
+/// # Examples
+///
+/// ```
+/// use log::kv::{Key, Source, Truncate};
+///
+/// let source = Truncate::new([("a", 1), ("b", 2), ("c", 3)], 2);
+///
+/// assert_eq!(3, source.count());
+/// assert_eq!(Some(true), source.get(Key::from("truncated")).and_then(|v| v.to_bool()));
+/// assert!(source.get(Key::from("c")).is_none());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Truncate<S> {
+    source: S,
+    max_pairs: usize,
+}
+
+impl<S> Truncate<S> {
+    /// Limit the number of key-values visited in a source.
+    pub fn new(source: S, max_pairs: usize) -> Self {
+        Truncate { source, max_pairs }
+    }
+
+    /// Get the wrapped source.
+    pub fn into_inner(self) -> S {
+        self.source
+    }
+}
+
+impl<S> Source for Truncate<S>
+where
+    S: Source,
+{
+    fn visit<'kvs>(&'kvs self, visitor: &mut dyn VisitSource<'kvs>) -> Result<(), Error> {
+        struct Limit<'a, 'kvs> {
+            remaining: usize,
+            truncated: bool,
+            visitor: &'a mut dyn VisitSource<'kvs>,
+        }
+
+        impl<'a, 'kvs> VisitSource<'kvs> for Limit<'a, 'kvs> {
+            fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+                if self.remaining == 0 {
+                    self.visitor
+                        .visit_pair(Key::from_str("truncated"), Value::from(true))?;
+                    self.truncated = true;
+
+                    return Err(Error::stop());
+                }
+
+                self.remaining -= 1;
+                self.visitor.visit_pair(key, value)
+            }
+        }
+
+        let mut limit = Limit {
+            remaining: self.max_pairs,
+            truncated: false,
+            visitor,
+        };
+
+        match self.source.visit(&mut limit) {
+            Err(err) if err.is_stop() && limit.truncated => Ok(()),
+            r => r,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.source.is_empty()
+    }
+}
+
+/// An iterator over the key-values in a source.
+///
+/// This type is returned by [`Source::pairs`].
+pub struct Iter<'kvs, S: ?Sized> {
+    source: &'kvs S,
+    index: usize,
+    len: usize,
+}
+
+impl<'kvs, S: Source + ?Sized> Iter<'kvs, S> {
+    /// Iterate over the key-values in a source.
+    ///
+    /// Unlike [`Source::pairs`], this method also accepts unsized sources, like `dyn Source`.
+    pub fn new(source: &'kvs S) -> Self {
+        Iter {
+            source,
+            index: 0,
+            len: source.count(),
+        }
+    }
+}
+
+impl<'kvs, S: Source + ?Sized> Iterator for Iter<'kvs, S> {
+    type Item = (Key<'kvs>, Value<'kvs>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        struct Nth<'kvs> {
+            skip: usize,
+            found: Option<(Key<'kvs>, Value<'kvs>)>,
+        }
+
+        impl<'kvs> VisitSource<'kvs> for Nth<'kvs> {
+            fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+                if self.skip > 0 {
+                    self.skip -= 1;
+
+                    return Ok(());
+                }
+
+                self.found = Some((key, value));
+
+                Err(Error::stop())
+            }
+        }
+
+        if self.index >= self.len {
+            return None;
+        }
+
+        let mut nth = Nth {
+            skip: self.index,
+            found: None,
+        };
+        let _ = self.source.visit(&mut nth);
+
+        self.index += 1;
+        nth.found
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.len - self.index;
+
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'kvs, S: Source + ?Sized> ExactSizeIterator for Iter<'kvs, S> {}
+
+impl<'kvs, S: ?Sized> Clone for Iter<'kvs, S> {
+    fn clone(&self) -> Self {
+        Iter {
+            source: self.source,
+            index: self.index,
+            len: self.len,
+        }
+    }
+}
+
+impl<'kvs, S: ?Sized> fmt::Debug for Iter<'kvs, S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Iter")
+            .field("index", &self.index)
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+/// A visitor for the key-value pairs in a [`Source`](trait.Source.html).
+pub trait VisitSource<'kvs> {
+    /// Visit a key-value pair.
+    fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error>;
+}
+
+impl<'a, 'kvs, T> VisitSource<'kvs> for &'a mut T
+where
+    T: VisitSource<'kvs> + ?Sized,
+{
+    fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+        (**self).visit_pair(key, value)
+    }
+}
+
+impl<'a, 'b: 'a, 'kvs> VisitSource<'kvs> for fmt::DebugMap<'a, 'b> {
+    fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+        self.entry(&key, &value);
+        Ok(())
+    }
+}
+
+impl<'a, 'b: 'a, 'kvs> VisitSource<'kvs> for fmt::DebugList<'a, 'b> {
+    fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+        self.entry(&(key, value));
+        Ok(())
+    }
+}
+
+impl<'a, 'b: 'a, 'kvs> VisitSource<'kvs> for fmt::DebugSet<'a, 'b> {
+    fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+        self.entry(&(key, value));
+        Ok(())
+    }
+}
+
+impl<'a, 'b: 'a, 'kvs> VisitSource<'kvs> for fmt::DebugTuple<'a, 'b> {
+    fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+        self.field(&key);
+        self.field(&value);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+mod std_support {
+    use super::*;
+    use crate::kv::value::OwnedValue;
+    use std::borrow::Borrow;
+    use std::collections::{BTreeMap, HashMap};
+    use std::hash::{BuildHasher, Hash};
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    impl<S> Source for Box<S>
+    where
+        S: Source + ?Sized,
+    {
+        fn visit<'kvs>(&'kvs self, visitor: &mut dyn VisitSource<'kvs>) -> Result<(), Error> {
+            Source::visit(&**self, visitor)
+        }
+
+        fn get(&self, key: Key) -> Option<Value<'_>> {
+            Source::get(&**self, key)
+        }
+
+        fn count(&self) -> usize {
+            Source::count(&**self)
+        }
+
+        fn is_empty(&self) -> bool {
+            Source::is_empty(&**self)
+        }
+
+        fn contains_key(&self, key: Key) -> bool {
+            Source::contains_key(&**self, key)
+        }
+    }
+
+    impl<S> Source for Arc<S>
+    where
+        S: Source + ?Sized,
+    {
+        fn visit<'kvs>(&'kvs self, visitor: &mut dyn VisitSource<'kvs>) -> Result<(), Error> {
+            Source::visit(&**self, visitor)
+        }
+
+        fn get(&self, key: Key) -> Option<Value<'_>> {
+            Source::get(&**self, key)
+        }
+
+        fn count(&self) -> usize {
+            Source::count(&**self)
+        }
+
+        fn is_empty(&self) -> bool {
+            Source::is_empty(&**self)
+        }
+
+        fn contains_key(&self, key: Key) -> bool {
+            Source::contains_key(&**self, key)
+        }
+    }
+
+    impl<S> Source for Rc<S>
+    where
+        S: Source + ?Sized,
+    {
+        fn visit<'kvs>(&'kvs self, visitor: &mut dyn VisitSource<'kvs>) -> Result<(), Error> {
+            Source::visit(&**self, visitor)
+        }
+
+        fn get(&self, key: Key) -> Option<Value<'_>> {
+            Source::get(&**self, key)
+        }
+
+        fn count(&self) -> usize {
+            Source::count(&**self)
+        }
+
+        fn is_empty(&self) -> bool {
+            Source::is_empty(&**self)
+        }
+
+        fn contains_key(&self, key: Key) -> bool {
+            Source::contains_key(&**self, key)
+        }
+    }
+
+    impl<S> Source for Vec<S>
+    where
+        S: Source,
+    {
+        fn visit<'kvs>(&'kvs self, visitor: &mut dyn VisitSource<'kvs>) -> Result<(), Error> {
+            Source::visit(&**self, visitor)
+        }
+
+        fn get(&self, key: Key) -> Option<Value<'_>> {
+            Source::get(&**self, key)
+        }
+
+        fn count(&self) -> usize {
+            Source::count(&**self)
+        }
+
+        fn is_empty(&self) -> bool {
+            Source::is_empty(&**self)
+        }
+
+        fn contains_key(&self, key: Key) -> bool {
+            Source::contains_key(&**self, key)
+        }
+    }
+
+    impl<'kvs, V> VisitSource<'kvs> for Box<V>
+    where
+        V: VisitSource<'kvs> + ?Sized,
+    {
+        fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+            (**self).visit_pair(key, value)
+        }
+    }
+
+    impl<K, V, S> Source for HashMap<K, V, S>
+    where
+        K: ToKey + Borrow<str> + Eq + Hash,
+        V: ToValue,
+        S: BuildHasher,
+    {
+        fn visit<'kvs>(&'kvs self, visitor: &mut dyn VisitSource<'kvs>) -> Result<(), Error> {
+            for (key, value) in self {
+                visitor.visit_pair(key.to_key(), value.to_value())?;
+            }
+            Ok(())
+        }
+
+        fn get(&self, key: Key) -> Option<Value<'_>> {
+            HashMap::get(self, key.as_str()).map(|v| v.to_value())
+        }
+
+        fn count(&self) -> usize {
+            self.len()
+        }
+
+        fn is_empty(&self) -> bool {
+            self.is_empty()
+        }
+    }
+
+    impl<K, V> Source for BTreeMap<K, V>
+    where
+        K: ToKey + Borrow<str> + Ord,
+        V: ToValue,
+    {
+        fn visit<'kvs>(&'kvs self, visitor: &mut dyn VisitSource<'kvs>) -> Result<(), Error> {
+            for (key, value) in self {
+                visitor.visit_pair(key.to_key(), value.to_value())?;
+            }
+            Ok(())
+        }
+
+        fn get(&self, key: Key) -> Option<Value<'_>> {
+            BTreeMap::get(self, key.as_str()).map(|v| v.to_value())
+        }
+
+        fn count(&self) -> usize {
+            self.len()
+        }
+
+        fn is_empty(&self) -> bool {
+            self.is_empty()
+        }
+    }
+
+    /// A source that visits its key-values in key order.
+    ///
+    /// Key-values are buffered and sorted each time the source is visited. Sorting is
+    /// stable, so key-values with the same key are visited in their original order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use log::kv::{Sorted, Source};
+    ///
+    /// let source = Sorted::new([("c", 3), ("a", 1), ("b", 2)]);
+    ///
+    /// assert_eq!(r#"{"a": 1, "b": 2, "c": 3}"#, format!("{:?}", source));
+    /// ```
+    #[derive(Clone)]
+    pub struct Sorted<S> {
+        source: S,
+    }
+
+    impl<S> Sorted<S> {
+        /// Visit the key-values in a source in key order.
+        pub fn new(source: S) -> Self {
+            Sorted { source }
+        }
+
+        /// Get the wrapped source.
+        pub fn into_inner(self) -> S {
+            self.source
+        }
+    }
+
+    impl<S> Source for Sorted<S>
+    where
+        S: Source,
+    {
+        fn visit<'kvs>(&'kvs self, visitor: &mut dyn VisitSource<'kvs>) -> Result<(), Error> {
+            struct Collect<'kvs>(Vec<(Key<'kvs>, Value<'kvs>)>);
+
+            impl<'kvs> VisitSource<'kvs> for Collect<'kvs> {
+                fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+                    self.0.push((key, value));
+
+                    Ok(())
+                }
+            }
+
+            let mut collect = Collect(Vec::with_capacity(self.source.count()));
+            self.source.visit(&mut collect)?;
+
+            let mut pairs = collect.0;
+            pairs.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+            for (key, value) in pairs {
+                visitor.visit_pair(key, value)?;
+            }
+            Ok(())
+        }
+
+        fn get(&self, key: Key) -> Option<Value<'_>> {
+            self.source.get(key)
+        }
+
+        fn count(&self) -> usize {
+            self.source.count()
+        }
+
+        fn is_empty(&self) -> bool {
+            self.source.is_empty()
+        }
+    }
+
+    impl<S: Source> fmt::Debug for Sorted<S> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            struct Entries<'a, 'b: 'a>(fmt::DebugMap<'a, 'b>);
+
+            impl<'a, 'b: 'a, 'kvs> VisitSource<'kvs> for Entries<'a, 'b> {
+                fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+                    self.0.entry(&key.as_str(), &value);
+                    Ok(())
+                }
+            }
+
+            let mut entries = Entries(f.debug_map());
+            let _ = self.visit(&mut entries);
+            entries.0.finish()
+        }
+    }
+
+    /// A source that visits the key-values in nested maps as dotted keys.
+    ///
+    /// Any value captured using [`Value::from_map`] is replaced by its key-values, with
+    /// their keys prefixed by the key of the map and a `.`. Maps nested in those maps are
+    /// flattened the same way. Empty maps are visited as-is.
+    ///
+    /// The dotted keys are built once when the source is flattened, so the wrapped source
+    /// must visit the same key-values each time, as documented on [`Source::count`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use log::kv::{Flatten, Source, Value};
+    ///
+    /// let http = [("method", Value::from("GET")), ("status", Value::from(200))];
+    /// let source = Flatten::new([("http", Value::from_map(&http)), ("ok", Value::from(true))]);
+    ///
+    /// assert_eq!(
+    ///     r#"{"http.method": "GET", "http.status": 200, "ok": true}"#,
+    ///     format!("{:?}", source)
+    /// );
+    /// ```
+    #[derive(Clone)]
+    pub struct Flatten<S> {
+        source: S,
+        keys: Vec<Box<str>>,
+    }
+
+    impl<S: Source> Flatten<S> {
+        /// Flatten the nested maps in a source.
+        pub fn new(source: S) -> Self {
+            struct Keys<'a> {
+                prefix: Option<&'a str>,
+                keys: &'a mut Vec<Box<str>>,
+            }
+
+            impl<'a, 'kvs> VisitSource<'kvs> for Keys<'a> {
+                fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+                    let key = match self.prefix {
+                        Some(prefix) => format!("{}.{}", prefix, key.as_str()),
+                        None => key.as_str().to_owned(),
+                    };
+
+                    match value.as_map() {
+                        Some(map) if !map.is_empty() => map.visit(&mut Keys {
+                            prefix: Some(&key),
+                            keys: &mut *self.keys,
+                        }),
+                        _ => {
+                            if self.prefix.is_some() {
+                                self.keys.push(key.into());
+                            }
+
+                            Ok(())
+                        }
+                    }
+                }
+            }
+
+            let mut keys = Vec::new();
+            let _ = source.visit(&mut Keys {
+                prefix: None,
+                keys: &mut keys,
+            });
+
+            Flatten { source, keys }
+        }
+    }
+
+    impl<S> Flatten<S> {
+        /// Get the wrapped source.
+        pub fn into_inner(self) -> S {
+            self.source
+        }
+    }
+
+    impl<S> Source for Flatten<S>
+    where
+        S: Source,
+    {
+        fn visit<'kvs>(&'kvs self, visitor: &mut dyn VisitSource<'kvs>) -> Result<(), Error> {
+            struct Flat<'a, 'kvs> {
+                keys: std::slice::Iter<'kvs, Box<str>>,
+                visitor: &'a mut dyn VisitSource<'kvs>,
+            }
+
+            impl<'a, 'kvs> Flat<'a, 'kvs> {
+                fn visit(
+                    &mut self,
+                    key: Key<'kvs>,
+                    value: Value<'kvs>,
+                    nested: bool,
+                ) -> Result<(), Error> {
+                    if let Some(map) = value.as_map() {
+                        if !map.is_empty() {
+                            return map.visit(&mut Nested(self));
+                        }
+                    }
+
+                    if nested {
+                        let key = self.keys.next().ok_or_else(|| {
+                            Error::msg("the source changed after it was flattened")
+                        })?;
+
+                        self.visitor.visit_pair(Key::from_str(key), value)
+                    } else {
+                        self.visitor.visit_pair(key, value)
+                    }
+                }
+            }
+
+            impl<'a, 'kvs> VisitSource<'kvs> for Flat<'a, 'kvs> {
+                fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+                    self.visit(key, value, false)
+                }
+            }
+
+            struct Nested<'b, 'a, 'kvs>(&'b mut Flat<'a, 'kvs>);
+
+            impl<'b, 'a, 'kvs> VisitSource<'kvs> for Nested<'b, 'a, 'kvs> {
+                fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+                    self.0.visit(key, value, true)
+                }
+            }
+
+            self.source.visit(&mut Flat {
+                keys: self.keys.iter(),
+                visitor,
+            })
+        }
+    }
+
+    impl<S: Source> fmt::Debug for Flatten<S> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            struct Entries<'a, 'b: 'a>(fmt::DebugMap<'a, 'b>);
+
+            impl<'a, 'b: 'a, 'kvs> VisitSource<'kvs> for Entries<'a, 'b> {
+                fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+                    self.0.entry(&key.as_str(), &value);
+                    Ok(())
+                }
+            }
+
+            let mut entries = Entries(f.debug_map());
+            let _ = self.visit(&mut entries);
+            entries.0.finish()
+        }
+    }
+
+    /// A source that visits its keys with a prefix.
+    ///
+    /// Each key is visited as the prefix, a `.`, and the original key. This lets libraries
+    /// attach their key-values to a caller's record without clashing with the caller's keys.
+    ///
+    /// The prefixed keys are built once when the source is created, so the wrapped source
+    /// must visit the same key-values each time, as documented on [`Source::count`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use log::kv::{Key, Prefixed, Source};
+    ///
+    /// let source = Prefixed::new("db", [("query", "select 1"), ("rows", "1")]);
+    ///
+    /// assert_eq!(
+    ///     r#"{"db.query": "select 1", "db.rows": "1"}"#,
+    ///     format!("{:?}", source)
+    /// );
+    /// assert!(source.get(Key::from("db.query")).is_some());
+    /// ```
+    #[derive(Clone)]
+    pub struct Prefixed<S> {
+        source: S,
+        prefix: Box<str>,
+        keys: Vec<Box<str>>,
+    }
+
+    impl<S: Source> Prefixed<S> {
+        /// Prefix the keys in a source.
+        pub fn new(prefix: &str, source: S) -> Self {
+            struct Keys<'a> {
+                prefix: &'a str,
+                keys: &'a mut Vec<Box<str>>,
+            }
+
+            impl<'a, 'kvs> VisitSource<'kvs> for Keys<'a> {
+                fn visit_pair(&mut self, key: Key<'kvs>, _: Value<'kvs>) -> Result<(), Error> {
+                    self.keys
+                        .push(format!("{}.{}", self.prefix, key.as_str()).into());
+                    Ok(())
+                }
+            }
+
+            let mut keys = Vec::new();
+            let _ = source.visit(&mut Keys {
+                prefix,
+                keys: &mut keys,
+            });
+
+            Prefixed {
+                source,
+                prefix: prefix.into(),
+                keys,
+            }
+        }
+    }
+
+    impl<S> Prefixed<S> {
+        /// Get the prefix.
+        pub fn prefix(&self) -> &str {
+            &self.prefix
+        }
+
+        /// Get the wrapped source.
+        pub fn into_inner(self) -> S {
+            self.source
+        }
+    }
+
+    impl<S> Source for Prefixed<S>
+    where
+        S: Source,
+    {
+        fn visit<'kvs>(&'kvs self, visitor: &mut dyn VisitSource<'kvs>) -> Result<(), Error> {
+            struct Prefix<'a, 'kvs> {
+                keys: std::slice::Iter<'kvs, Box<str>>,
+                visitor: &'a mut dyn VisitSource<'kvs>,
+            }
+
+            impl<'a, 'kvs> VisitSource<'kvs> for Prefix<'a, 'kvs> {
+                fn visit_pair(&mut self, _: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+                    let key = self
+                        .keys
+                        .next()
+                        .ok_or_else(|| Error::msg("the source changed after it was prefixed"))?;
+
+                    self.visitor.visit_pair(Key::from_str(key), value)
+                }
+            }
+
+            self.source.visit(&mut Prefix {
+                keys: self.keys.iter(),
+                visitor,
+            })
+        }
+
+        fn get(&self, key: Key) -> Option<Value<'_>> {
+            let key = key
+                .as_str()
+                .strip_prefix(&*self.prefix)?
+                .strip_prefix('.')?;
+
+            self.source.get(Key::from_str(key))
+        }
+
+        fn count(&self) -> usize {
+            self.source.count()
+        }
+
+        fn is_empty(&self) -> bool {
+            self.source.is_empty()
+        }
+    }
+
+    impl<S: Source> fmt::Debug for Prefixed<S> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            struct Entries<'a, 'b: 'a>(fmt::DebugMap<'a, 'b>);
+
+            impl<'a, 'b: 'a, 'kvs> VisitSource<'kvs> for Entries<'a, 'b> {
+                fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+                    self.0.entry(&key.as_str(), &value);
+                    Ok(())
+                }
+            }
+
+            let mut entries = Entries(f.debug_map());
+            let _ = self.visit(&mut entries);
+            entries.0.finish()
+        }
+    }
+
+    /// A source that owns its key-values.
+    ///
+    /// An owned source is buffered from any other [`Source`] by copying its keys
+    /// and values. It's `'static`, so it can be stored or sent to another thread
+    /// after the record it came from is gone.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use log::kv::{OwnedSource, Source, Key};
+    ///
+    /// let owned = {
+    ///     let value = String::from("borrowed");
+    ///     let source = [("a", value.as_str())];
+    ///
+    ///     OwnedSource::from_source(&source)
+    /// };
+    ///
+    /// std::thread::spawn(move || {
+    ///     assert_eq!(Some("borrowed"), owned.get_str(Key::from_str("a")));
+    /// })
+    /// .join()
+    /// .unwrap();
+    /// ```
+    #[derive(Clone, Default)]
+    pub struct OwnedSource {
+        pairs: Box<[(Box<str>, OwnedValue)]>,
+    }
+
+    impl OwnedSource {
+        /// Buffer the key-values in a source into an owned source.
+        pub fn from_source(source: &(impl Source + ?Sized)) -> Self {
+            struct Buffer(Vec<(Box<str>, OwnedValue)>);
+
+            impl<'kvs> VisitSource<'kvs> for Buffer {
+                fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+                    self.0.push((key.as_str().into(), value.to_owned()));
+
+                    Ok(())
+                }
+            }
+
+            let mut buffer = Buffer(Vec::with_capacity(source.count()));
+            let _ = source.visit(&mut buffer);
+
+            OwnedSource {
+                pairs: buffer.0.into_boxed_slice(),
+            }
+        }
+    }
+
+    impl Source for OwnedSource {
+        fn visit<'kvs>(&'kvs self, visitor: &mut dyn VisitSource<'kvs>) -> Result<(), Error> {
+            for (key, value) in &*self.pairs {
+                visitor.visit_pair(Key::from_str(key), value.by_ref())?;
+            }
+            Ok(())
+        }
+
+        fn get(&self, key: Key) -> Option<Value<'_>> {
+            // Match the default `get`, where the last matching key wins
+            self.pairs
+                .iter()
+                .rev()
+                .find(|(k, _)| **k == *key.as_str())
+                .map(|(_, v)| v.by_ref())
+        }
+
+        fn count(&self) -> usize {
+            self.pairs.len()
+        }
+
+        fn is_empty(&self) -> bool {
+            self.pairs.is_empty()
+        }
+    }
+
+    impl fmt::Debug for OwnedSource {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            let mut map = f.debug_map();
+            for (key, value) in &*self.pairs {
+                map.entry(key, value);
+            }
+            map.finish()
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use crate::kv::value;
+
+        use super::*;
 
         #[test]
         fn count() {
@@ -417,46 +1552,664 @@ mod std_support {
         }
 
         #[test]
-        fn get() {
-            let source = vec![("a", 1), ("b", 2), ("a", 1)];
+        fn get() {
+            let source = vec![("a", 1), ("b", 2), ("a", 1)];
+            assert_eq!(
+                value::inner::Token::I64(1),
+                Source::get(&source, Key::from_str("a")).unwrap().to_token()
+            );
+
+            let source = Box::new(None::<(&str, i32)>);
+            assert!(Source::get(&source, Key::from_str("a")).is_none());
+        }
+
+        #[test]
+        fn collections_in_record() {
+            let mut map = HashMap::new();
+            map.insert(String::from("a"), String::from("1"));
+
+            let mut tree = BTreeMap::new();
+            tree.insert(String::from("b"), 2);
+
+            let pairs = vec![(String::from("c"), std::borrow::Cow::Borrowed("3"))];
+
+            let record = crate::Record::builder().key_values(&map).build();
+            assert_eq!(Some("1"), record.key_values().get_str(Key::from("a")));
+
+            let record = crate::Record::builder().key_values(&tree).build();
+            assert_eq!(Some(2), record.key_values().get_i64(Key::from("b")));
+
+            let record = crate::Record::builder().key_values(&pairs).build();
+            assert_eq!(Some("3"), record.key_values().get_str(Key::from("c")));
+        }
+
+        #[test]
+        fn hash_map() {
+            let mut map = HashMap::new();
+            map.insert("a", 1);
+            map.insert("b", 2);
+
+            assert_eq!(2, Source::count(&map));
+            assert_eq!(
+                value::inner::Token::I64(1),
+                Source::get(&map, Key::from_str("a")).unwrap().to_token()
+            );
+        }
+
+        #[test]
+        fn owned_source() {
+            let owned = {
+                let a = String::from("a");
+                let source = vec![
+                    ("a", a.to_value()),
+                    ("b", 1.to_value()),
+                    ("a", 2.to_value()),
+                ];
+
+                OwnedSource::from_source(&source)
+            };
+
+            assert_eq!(3, Source::count(&owned));
+            assert_eq!(Some(2), owned.get_i64(Key::from_str("a")));
+            assert_eq!(Some(1), owned.get_i64(Key::from_str("b")));
+
+            assert_eq!(r#"{"a": "a", "b": 1, "a": 2}"#, format!("{:?}", owned));
+        }
+
+        #[test]
+        fn sorted() {
+            let source = Sorted::new([("c", 3), ("a", 1), ("b", 2), ("a", 4)]);
+
+            assert_eq!(4, Source::count(&source));
+            assert_eq!(
+                r#"{"a": 1, "a": 4, "b": 2, "c": 3}"#,
+                format!("{:?}", source)
+            );
+        }
+
+        #[test]
+        fn flatten() {
+            let status = [("code", Value::from(200)), ("ok", Value::from(true))];
+            let http = [
+                ("method", Value::from("GET")),
+                ("status", Value::from_map(&status)),
+                ("headers", Value::from_map(&None::<(&str, i32)>)),
+            ];
+            let source = Flatten::new([("http", Value::from_map(&http)), ("a", Value::from(1))]);
+
+            assert_eq!(5, Source::count(&source));
+            assert_eq!(
+                r#"{"http.method": "GET", "http.status.code": 200, "http.status.ok": true, "http.headers": {}, "a": 1}"#,
+                format!("{:?}", source)
+            );
+            assert_eq!(
+                Some(200),
+                source.get(Key::from("http.status.code")).unwrap().to_i64()
+            );
+            assert_eq!(
+                vec![
+                    "http.method",
+                    "http.status.code",
+                    "http.status.ok",
+                    "http.headers",
+                    "a"
+                ],
+                source
+                    .pairs()
+                    .map(|(k, _)| k.as_str().to_owned())
+                    .collect::<Vec<_>>()
+            );
+
+            let flat = Flatten::new([("a", 1), ("b", 2)]);
+            assert_eq!(r#"{"a": 1, "b": 2}"#, format!("{:?}", flat));
+        }
+
+        #[test]
+        fn prefixed() {
+            let source = Prefixed::new("db", [("query", 1), ("rows", 2)]);
+
+            assert_eq!("db", source.prefix());
+            assert_eq!(2, Source::count(&source));
+            assert_eq!(r#"{"db.query": 1, "db.rows": 2}"#, format!("{:?}", source));
+            assert_eq!(Some(2), source.get_i64(Key::from("db.rows")));
+            assert!(source.get(Key::from("rows")).is_none());
+            assert!(source.get(Key::from("dbrows")).is_none());
+
+            let record = [("query", 0)].chain(Prefixed::new("db", [("query", 1)]));
+            assert_eq!(
+                vec!["query=0", "db.query=1"],
+                record
+                    .pairs()
+                    .map(|(k, v)| format!("{}={}", k, v))
+                    .collect::<Vec<_>>()
+            );
+        }
+
+        #[test]
+        fn btree_map() {
+            let mut map = BTreeMap::new();
+            map.insert("a", 1);
+            map.insert("b", 2);
+
+            assert_eq!(2, Source::count(&map));
+            assert_eq!(
+                value::inner::Token::I64(1),
+                Source::get(&map, Key::from_str("a")).unwrap().to_token()
+            );
+        }
+    }
+}
+
+#[cfg(all(feature = "kv_sval", feature = "std"))]
+mod path_support {
+    use super::*;
+    use crate::kv::value::OwnedValue;
+    use crate::kv::VisitValue;
+
+    use sval_buffer::ValueBuf;
+
+    /// The default implementation of `Source::get_path`
+    pub(super) fn get_path(source: &(impl Source + ?Sized), path: &str) -> Option<OwnedValue> {
+        let mut segments = path.split('.');
+
+        let value = source.get(Key::from_str(segments.next()?))?;
+        let segments = segments.collect::<Vec<_>>();
+
+        if segments.is_empty() {
+            return Some(value.to_owned());
+        }
+
+        let mut lookup = Lookup {
+            segments: &segments,
+            matched: 0,
+            depth: 0,
+            key: None,
+            key_matched: false,
+            index: 0,
+            capture: None,
+            found: None,
+        };
+
+        // The lookup returns an error to stop streaming early, whether or not it found a value
+        let _ = sval::stream_computed(&mut lookup, &value);
+
+        lookup.found
+    }
+
+    /**
+    A stream that looks for a value at a path of keys and indexes.
+
+    The stream tracks how deeply nested it is, and how many segments of the path
+    it's matched. Keys and indexes are only compared in the container directly
+    holding the next segment. Once all segments are matched the value is buffered.
+    */
+    struct Lookup<'a, 'sval> {
+        segments: &'a [&'a str],
+        matched: usize,
+        depth: usize,
+        key: Option<String>,
+        key_matched: bool,
+        index: usize,
+        capture: Option<(usize, ValueBuf<'sval>)>,
+        found: Option<OwnedValue>,
+    }
+
+    impl<'a, 'sval> Lookup<'a, 'sval> {
+        fn is_searching(&self) -> bool {
+            self.capture.is_none() && self.depth == self.matched + 1
+        }
+
+        fn capture(
+            &mut self,
+            f: impl FnOnce(&mut ValueBuf<'sval>) -> sval::Result,
+        ) -> Option<sval::Result> {
+            self.capture.as_mut().map(|(_, buf)| f(buf))
+        }
+
+        fn begin(&mut self) {
+            self.depth += 1;
+
+            if self.is_searching() {
+                self.index = 0;
+            }
+        }
+
+        fn end(&mut self) {
+            self.depth -= 1;
+        }
+
+        fn value_begin(&mut self, is_match: bool) {
+            if is_match {
+                self.matched += 1;
+
+                if self.matched == self.segments.len() {
+                    self.capture = Some((self.depth, ValueBuf::new()));
+                }
+            }
+        }
+
+        fn value_end(&mut self) -> sval::Result {
+            match self.capture.take() {
+                // The matched value is complete
+                Some((depth, buf)) if depth == self.depth => {
+                    self.found = Some(to_owned(Value::from_sval(&buf)));
+
+                    Err(sval::Error::new())
+                }
+                capture => {
+                    self.capture = capture;
+
+                    // The last matched value ended without containing the next segment
+                    if self.capture.is_none() && self.depth == self.matched && self.matched > 0 {
+                        Err(sval::Error::new())
+                    } else {
+                        Ok(())
+                    }
+                }
+            }
+        }
+    }
+
+    /**
+    Buffer a found value, unwrapping primitives so they can be converted.
+    */
+    fn to_owned(value: Value) -> OwnedValue {
+        struct Primitive(Option<OwnedValue>);
+
+        // Primitives are visited through their own methods, which forward to `visit_any`
+        // with a value that's no longer wrapped in `sval`
+        impl<'v> VisitValue<'v> for Primitive {
+            fn visit_any(&mut self, value: Value) -> Result<(), Error> {
+                self.0 = Some(value.to_owned());
+                Ok(())
+            }
+        }
+
+        let mut primitive = Primitive(None);
+        let _ = value.visit(&mut primitive);
+
+        primitive.0.unwrap_or_else(|| value.to_owned())
+    }
+
+    impl<'a, 'sval> sval::Stream<'sval> for Lookup<'a, 'sval> {
+        fn null(&mut self) -> sval::Result {
+            self.capture(|buf| buf.null()).unwrap_or(Ok(()))
+        }
+
+        fn bool(&mut self, value: bool) -> sval::Result {
+            self.capture(|buf| buf.bool(value)).unwrap_or(Ok(()))
+        }
+
+        fn u64(&mut self, value: u64) -> sval::Result {
+            if let Some(key) = self.key.as_mut() {
+                key.push_str(&value.to_string());
+            }
+
+            self.capture(|buf| buf.u64(value)).unwrap_or(Ok(()))
+        }
+
+        fn i64(&mut self, value: i64) -> sval::Result {
+            if let Some(key) = self.key.as_mut() {
+                key.push_str(&value.to_string());
+            }
+
+            self.capture(|buf| buf.i64(value)).unwrap_or(Ok(()))
+        }
+
+        fn u128(&mut self, value: u128) -> sval::Result {
+            self.capture(|buf| buf.u128(value)).unwrap_or(Ok(()))
+        }
+
+        fn i128(&mut self, value: i128) -> sval::Result {
+            self.capture(|buf| buf.i128(value)).unwrap_or(Ok(()))
+        }
+
+        fn f64(&mut self, value: f64) -> sval::Result {
+            self.capture(|buf| buf.f64(value)).unwrap_or(Ok(()))
+        }
+
+        fn text_begin(&mut self, num_bytes: Option<usize>) -> sval::Result {
+            self.capture(|buf| buf.text_begin(num_bytes))
+                .unwrap_or(Ok(()))
+        }
+
+        fn text_fragment(&mut self, fragment: &'sval str) -> sval::Result {
+            if let Some(key) = self.key.as_mut() {
+                key.push_str(fragment);
+            }
+
+            self.capture(|buf| buf.text_fragment(fragment))
+                .unwrap_or(Ok(()))
+        }
+
+        fn text_fragment_computed(&mut self, fragment: &str) -> sval::Result {
+            if let Some(key) = self.key.as_mut() {
+                key.push_str(fragment);
+            }
+
+            self.capture(|buf| buf.text_fragment_computed(fragment))
+                .unwrap_or(Ok(()))
+        }
+
+        fn text_end(&mut self) -> sval::Result {
+            self.capture(|buf| buf.text_end()).unwrap_or(Ok(()))
+        }
+
+        fn map_begin(&mut self, num_entries: Option<usize>) -> sval::Result {
+            self.begin();
+            self.capture(|buf| buf.map_begin(num_entries))
+                .unwrap_or(Ok(()))
+        }
+
+        fn map_key_begin(&mut self) -> sval::Result {
+            if self.is_searching() {
+                self.key = Some(String::new());
+            } else {
+                // Keys are only compared when they're not nested in other keys
+                self.key = None;
+            }
+
+            self.capture(|buf| buf.map_key_begin()).unwrap_or(Ok(()))
+        }
+
+        fn map_key_end(&mut self) -> sval::Result {
+            if self.is_searching() {
+                self.key_matched = self.key.take().as_deref() == Some(self.segments[self.matched]);
+            }
+
+            self.capture(|buf| buf.map_key_end()).unwrap_or(Ok(()))
+        }
+
+        fn map_value_begin(&mut self) -> sval::Result {
+            let r = self.capture(|buf| buf.map_value_begin());
+
+            if self.is_searching() {
+                let is_match = self.key_matched;
+                self.value_begin(is_match);
+            }
+
+            r.unwrap_or(Ok(()))
+        }
+
+        fn map_value_end(&mut self) -> sval::Result {
+            self.value_end()?;
+            self.capture(|buf| buf.map_value_end()).unwrap_or(Ok(()))
+        }
+
+        fn map_end(&mut self) -> sval::Result {
+            let r = self.capture(|buf| buf.map_end());
+            self.end();
+
+            r.unwrap_or(Ok(()))
+        }
+
+        fn seq_begin(&mut self, num_entries: Option<usize>) -> sval::Result {
+            self.begin();
+            self.capture(|buf| buf.seq_begin(num_entries))
+                .unwrap_or(Ok(()))
+        }
+
+        fn seq_value_begin(&mut self) -> sval::Result {
+            let r = self.capture(|buf| buf.seq_value_begin());
+
+            if self.is_searching() {
+                let is_match = self.segments[self.matched].parse() == Ok(self.index);
+                self.index += 1;
+
+                self.value_begin(is_match);
+            }
+
+            r.unwrap_or(Ok(()))
+        }
+
+        fn seq_value_end(&mut self) -> sval::Result {
+            self.value_end()?;
+            self.capture(|buf| buf.seq_value_end()).unwrap_or(Ok(()))
+        }
+
+        fn seq_end(&mut self) -> sval::Result {
+            let r = self.capture(|buf| buf.seq_end());
+            self.end();
+
+            r.unwrap_or(Ok(()))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[derive(sval_derive::Value)]
+        struct Request {
+            method: &'static str,
+            headers: Headers,
+            ids: [u32; 3],
+        }
+
+        #[derive(sval_derive::Value)]
+        struct Headers {
+            host: &'static str,
+            port: u16,
+        }
+
+        fn request() -> Request {
+            Request {
+                method: "GET",
+                headers: Headers {
+                    host: "example.com",
+                    port: 8080,
+                },
+                ids: [1, 2, 3],
+            }
+        }
+
+        #[test]
+        fn get_path() {
+            let request = request();
+            let source = [
+                ("http", Value::from_sval(&request)),
+                ("status", Value::from(200)),
+            ];
+
             assert_eq!(
-                value::inner::Token::I64(1),
-                Source::get(&source, Key::from_str("a")).unwrap().to_token()
+                Some("GET".into()),
+                source.get_path("http.method").map(|v| v.to_string())
+            );
+            assert_eq!(
+                Some(8080),
+                source
+                    .get_path("http.headers.port")
+                    .and_then(|v| v.by_ref().to_u64())
+            );
+            assert_eq!(
+                Some(2),
+                source
+                    .get_path("http.ids.1")
+                    .and_then(|v| v.by_ref().to_u64())
+            );
+            assert_eq!(
+                Some(200),
+                source.get_path("status").and_then(|v| v.by_ref().to_u64())
             );
 
-            let source = Box::new(None::<(&str, i32)>);
-            assert!(Source::get(&source, Key::from_str("a")).is_none());
+            let headers = source.get_path("http.headers").unwrap();
+            assert_eq!(
+                Some("example.com".into()),
+                [("headers", headers.by_ref())]
+                    .get_path("headers.host")
+                    .map(|v| v.to_string())
+            );
         }
 
         #[test]
-        fn hash_map() {
-            let mut map = HashMap::new();
-            map.insert("a", 1);
-            map.insert("b", 2);
+        fn get_path_missing() {
+            let request = request();
+            let source = [("http", Value::from_sval(&request))];
+
+            assert!(source.get_path("http.path").is_none());
+            assert!(source.get_path("http.method.len").is_none());
+            assert!(source.get_path("http.headers.host.0").is_none());
+            assert!(source.get_path("http.ids.3").is_none());
+            assert!(source.get_path("https.method").is_none());
+            assert!(source.get_path("http.host").is_none());
+        }
+
+        #[test]
+        #[cfg(feature = "kv_serde")]
+        fn get_path_serde() {
+            #[derive(serde::Serialize)]
+            struct Request {
+                method: &'static str,
+            }
+
+            let request = Request { method: "GET" };
+            let source = [("http", Value::from_serde(&request))];
 
-            assert_eq!(2, Source::count(&map));
             assert_eq!(
-                value::inner::Token::I64(1),
-                Source::get(&map, Key::from_str("a")).unwrap().to_token()
+                Some("GET".into()),
+                source.get_path("http.method").map(|v| v.to_string())
             );
         }
+    }
+}
+
+#[cfg(feature = "kv_unstable_smallvec")]
+mod smallvec_support {
+    use super::*;
+
+    use smallvec::{Array, SmallVec};
+
+    impl<A: Array> Source for SmallVec<A>
+    where
+        A::Item: Source,
+    {
+        fn visit<'kvs>(&'kvs self, visitor: &mut dyn VisitSource<'kvs>) -> Result<(), Error> {
+            Source::visit(&**self, visitor)
+        }
+
+        fn get(&self, key: Key) -> Option<Value<'_>> {
+            Source::get(&**self, key)
+        }
+
+        fn count(&self) -> usize {
+            Source::count(&**self)
+        }
+
+        fn is_empty(&self) -> bool {
+            Source::is_empty(&**self)
+        }
+
+        fn contains_key(&self, key: Key) -> bool {
+            Source::contains_key(&**self, key)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
 
         #[test]
-        fn btree_map() {
-            let mut map = BTreeMap::new();
-            map.insert("a", 1);
-            map.insert("b", 2);
+        fn smallvec() {
+            let mut source = SmallVec::<[(&str, i32); 4]>::new();
+            assert!(source.is_empty());
+
+            source.push(("a", 1));
+            source.push(("b", 2));
+
+            assert_eq!(2, Source::count(&source));
+            assert_eq!(Some(2), source.get_i64(Key::from("b")));
+
+            let record = crate::Record::builder().key_values(&source).build();
+            assert_eq!(Some(1), record.key_values().get_i64(Key::from("a")));
+        }
+    }
+}
+
+#[cfg(feature = "kv_unstable_arrayvec")]
+mod arrayvec_support {
+    use super::*;
+
+    use arrayvec::ArrayVec;
+
+    impl<S, const CAP: usize> Source for ArrayVec<S, CAP>
+    where
+        S: Source,
+    {
+        fn visit<'kvs>(&'kvs self, visitor: &mut dyn VisitSource<'kvs>) -> Result<(), Error> {
+            Source::visit(&**self, visitor)
+        }
+
+        fn get(&self, key: Key) -> Option<Value<'_>> {
+            Source::get(&**self, key)
+        }
+
+        fn count(&self) -> usize {
+            Source::count(&**self)
+        }
+
+        fn is_empty(&self) -> bool {
+            Source::is_empty(&**self)
+        }
+
+        fn contains_key(&self, key: Key) -> bool {
+            Source::contains_key(&**self, key)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn arrayvec() {
+            let mut source = ArrayVec::<(&str, i32), 4>::new();
+            assert!(source.is_empty());
+
+            source.push(("a", 1));
+            source.push(("b", 2));
+
+            assert_eq!(2, Source::count(&source));
+            assert_eq!(Some(2), source.get_i64(Key::from("b")));
+
+            let record = crate::Record::builder().key_values(&source).build();
+            assert_eq!(Some(1), record.key_values().get_i64(Key::from("a")));
+        }
+    }
+}
+
+// NOTE: Deprecated; but aliases can't carry this attribute
+#[cfg(feature = "kv_serde")]
+mod serde_support {
+    use super::*;
+
+    use serde::{Serialize, Serializer};
+
+    /// Sources are serialized as maps of their key-values.
+    impl<'a> Serialize for dyn Source + 'a {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            Value::from_dyn_map(self).serialize(serializer)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn serialize() {
+            let source = [("a", Value::from(1)), ("b", Value::from("b"))];
 
-            assert_eq!(2, Source::count(&map));
             assert_eq!(
-                value::inner::Token::I64(1),
-                Source::get(&map, Key::from_str("a")).unwrap().to_token()
+                r#"{"a":1,"b":"b"}"#,
+                serde_json::to_string(&source as &dyn Source).unwrap()
             );
         }
     }
 }
 
-// NOTE: Deprecated; but aliases can't carry this attribute
 #[cfg(feature = "kv_unstable")]
 pub use VisitSource as Visitor;
 
@@ -495,6 +2248,71 @@ mod tests {
         assert_eq!(1, Source::count(&OnePair { key: "a", value: 1 }));
     }
 
+    #[test]
+    fn is_empty() {
+        struct OnePair;
+
+        impl Source for OnePair {
+            fn visit<'kvs>(&'kvs self, visitor: &mut dyn VisitSource<'kvs>) -> Result<(), Error> {
+                visitor.visit_pair("a".to_key(), 1.to_value())
+            }
+        }
+
+        assert!(!Source::is_empty(&("a", 1)));
+        assert!(Source::is_empty(&[] as &[(&str, i32)]));
+        assert!(Source::is_empty(&[None::<(&str, i32)>, None]));
+        assert!(!Source::is_empty(&[None, Some(("a", 1))]));
+        assert!(Source::is_empty(&None::<(&str, i32)>));
+        assert!(!Source::is_empty(&OnePair));
+        assert!(Source::is_empty(
+            &None::<(&str, i32)>.chain(None::<(&str, i32)>)
+        ));
+        assert!(!Source::is_empty(&Dedup::new([("a", 1), ("a", 2)])));
+    }
+
+    #[test]
+    fn count_adapters() {
+        let source = [("a", 1), ("b", 2), ("a", 3)];
+
+        assert_eq!(4, source.chain(("c", 4)).count());
+        assert_eq!(2, source.merge(("a", 4)).count());
+        assert_eq!(2, Dedup::new(source).count());
+        assert_eq!(2, Dedup::first_wins(source).count());
+    }
+
+    #[test]
+    fn pairs() {
+        let source = [("a", 1), ("b", 2), ("c", 3)];
+
+        let mut iter = source.pairs();
+        assert_eq!(3, iter.len());
+
+        let (key, value) = iter.next().unwrap();
+        assert_eq!("a", key.as_str());
+        assert_eq!(Some(1), value.to_i64());
+        assert_eq!(2, iter.len());
+
+        let rest: Vec<_> = iter.map(|(key, _)| key.as_str().to_owned()).collect();
+        assert_eq!(vec!["b".to_owned(), "c".to_owned()], rest);
+
+        // Slices of pairs still use their own `iter`
+        let values: Vec<_> = source.iter().map(|&(_, v)| v).collect();
+        assert_eq!(vec![1, 2, 3], values);
+    }
+
+    #[test]
+    fn pairs_dyn() {
+        let source = ("a", 1).chain([("b", 2)]);
+        let source: &dyn Source = &source;
+
+        let keys: Vec<_> = Iter::new(source)
+            .map(|(key, _)| key.as_str().to_owned())
+            .collect();
+        assert_eq!(vec!["a".to_owned(), "b".to_owned()], keys);
+
+        assert_eq!(0, Iter::new(&None::<(&str, i32)> as &dyn Source).count());
+    }
+
     #[test]
     fn get() {
         let source = &[("a", 1), ("b", 2), ("a", 1)] as &[_];
@@ -511,4 +2329,136 @@ mod tests {
         let source = None::<(&str, i32)>;
         assert!(Source::get(&source, Key::from_str("a")).is_none());
     }
+
+    #[test]
+    fn get_typed() {
+        let source = &[
+            ("str", Value::from("a")),
+            ("i64", Value::from(-1i64)),
+            ("u64", Value::from(1u64)),
+            ("f64", Value::from(1.5f64)),
+            ("bool", Value::from(true)),
+        ] as &[_];
+
+        assert_eq!(Some("a"), source.get_str(Key::from_str("str")));
+        assert_eq!(Some(-1), source.get_i64(Key::from_str("i64")));
+        assert_eq!(Some(1), source.get_u64(Key::from_str("u64")));
+        assert_eq!(Some(1.5), source.get_f64(Key::from_str("f64")));
+        assert_eq!(Some(true), source.get_bool(Key::from_str("bool")));
+
+        assert!(source.get_str(Key::from_str("i64")).is_none());
+        assert!(source.get_bool(Key::from_str("str")).is_none());
+        assert!(source.get_i64(Key::from_str("missing")).is_none());
+    }
+
+    #[test]
+    fn chain() {
+        let source = [("a", 1), ("b", 2)].chain([("b", 3), ("c", 4)]);
+
+        assert_eq!(4, source.count());
+        assert_eq!(Some(1), source.get_i64(Key::from_str("a")));
+        assert_eq!(Some(3), source.get_i64(Key::from_str("b")));
+        assert_eq!(Some(4), source.get_i64(Key::from_str("c")));
+    }
+
+    #[test]
+    fn merge() {
+        let source = [("a", 1), ("b", 2)].merge([("b", 3), ("c", 4)]);
+
+        assert_eq!(3, source.count());
+        assert_eq!(Some(1), source.get_i64(Key::from_str("a")));
+        assert_eq!(Some(3), source.get_i64(Key::from_str("b")));
+        assert_eq!(Some(4), source.get_i64(Key::from_str("c")));
+    }
+
+    fn collect_i64(source: &impl Source) -> Vec<(String, i64)> {
+        struct Collect(Vec<(String, i64)>);
+
+        impl<'kvs> VisitSource<'kvs> for Collect {
+            fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+                self.0.push((key.as_str().into(), value.to_i64().unwrap()));
+                Ok(())
+            }
+        }
+
+        let mut collect = Collect(Vec::new());
+        source.visit(&mut collect).unwrap();
+        collect.0
+    }
+
+    #[test]
+    fn dedup_last_wins() {
+        let source = Dedup::new([("a", 1), ("b", 2), ("a", 3), ("c", 4), ("b", 5)]);
+
+        assert_eq!(3, source.count());
+        assert_eq!(Some(3), source.get_i64(Key::from_str("a")));
+        assert_eq!(Some(5), source.get_i64(Key::from_str("b")));
+        assert_eq!(
+            vec![("a".into(), 3), ("c".into(), 4), ("b".into(), 5)],
+            collect_i64(&source)
+        );
+    }
+
+    #[test]
+    fn contains_key() {
+        struct Counted<'a>(&'a [(&'a str, i32)], std::cell::Cell<usize>);
+
+        impl<'a> Source for Counted<'a> {
+            fn visit<'kvs>(&'kvs self, visitor: &mut dyn VisitSource<'kvs>) -> Result<(), Error> {
+                for (k, v) in self.0 {
+                    self.1.set(self.1.get() + 1);
+                    visitor.visit_pair(Key::from_str(k), Value::from(*v))?;
+                }
+
+                Ok(())
+            }
+        }
+
+        let source = Counted(&[("a", 1), ("b", 2), ("c", 3)], Default::default());
+
+        assert!(source.contains_key(Key::from_str("a")));
+        assert_eq!(1, source.1.get());
+
+        assert!(!source.contains_key(Key::from_str("d")));
+        assert!([("a", 1)]
+            .chain([("b", 2)])
+            .contains_key(Key::from_str("b")));
+    }
+
+    #[test]
+    fn truncate() {
+        let source = Truncate::new([("a", 1), ("b", 2), ("c", 3)], 2);
+
+        assert_eq!(3, source.count());
+        assert_eq!(Some(2), source.get_i64(Key::from_str("b")));
+        assert_eq!(None, source.get_i64(Key::from_str("c")));
+        assert_eq!(
+            Some(true),
+            source.get(Key::from_str("truncated")).unwrap().to_bool()
+        );
+
+        let source = Truncate::new([("a", 1), ("b", 2)], 2);
+
+        assert_eq!(vec![("a".into(), 1), ("b".into(), 2)], collect_i64(&source));
+
+        let source = Truncate::new([("a", 1)], 0);
+
+        assert_eq!(1, source.count());
+        assert!(source.get(Key::from_str("a")).is_none());
+        assert!(!source.is_empty());
+        assert!(Truncate::new(None::<(&str, i32)>, 0).is_empty());
+    }
+
+    #[test]
+    fn dedup_first_wins() {
+        let source = Dedup::first_wins([("a", 1), ("b", 2), ("a", 3), ("c", 4), ("b", 5)]);
+
+        assert_eq!(3, source.count());
+        assert_eq!(Some(1), source.get_i64(Key::from_str("a")));
+        assert_eq!(Some(2), source.get_i64(Key::from_str("b")));
+        assert_eq!(
+            vec![("a".into(), 1), ("b".into(), 2), ("c".into(), 4)],
+            collect_i64(&source)
+        );
+    }
 }
@@ -4,9 +4,12 @@
 //! capturing and serializing them.
 
 use std::fmt;
+use std::marker::PhantomData;
 
 pub use crate::kv::Error;
 
+use crate::kv::Source;
+
 /// A type that can be converted into a [`Value`](struct.Value.html).
 pub trait ToValue {
     /// Perform the conversion.
@@ -22,6 +25,15 @@ where
     }
 }
 
+impl<'a, T> ToValue for &'a mut T
+where
+    T: ToValue + ?Sized,
+{
+    fn to_value(&self) -> Value {
+        (**self).to_value()
+    }
+}
+
 impl<'v> ToValue for Value<'v> {
     fn to_value(&self) -> Value {
         Value {
@@ -89,8 +101,9 @@ impl<'v> ToValue for Value<'v> {
 ///   but its value was empty (`Some(Value::null())`) and a key-value
 ///   that was never logged at all (`None`).
 /// - **Strings:** `str`, `char`.
-/// - **Booleans:** `bool`.
-/// - **Integers:** `u8`-`u128`, `i8`-`i128`, `NonZero*`.
+/// - **Booleans:** `bool`, `AtomicBool`.
+/// - **Integers:** `u8`-`u128`, `i8`-`i128`, `NonZero*`, `Atomic*`.
+///   Atomics are captured by loading their current value with `Ordering::Relaxed`.
 /// - **Floating point numbers:** `f32`-`f64`.
 /// - **Errors:** `dyn (Error + 'static)`.
 /// - **`serde`:** Any type in `serde`'s data model.
@@ -128,6 +141,83 @@ impl<'v> Value<'v> {
         value.to_value()
     }
 
+    /// Get a value from a concrete type implementing `ToValue`.
+    ///
+    /// The value behaves the same as one returned by [`ToValue::to_value`], but
+    /// remembers its type so it can be retrieved later using [`Value::downcast_ref`].
+    pub fn from_concrete<T>(value: &'v T) -> Self
+    where
+        T: ToValue + 'static,
+    {
+        Value {
+            inner: inner::Inner::from_concrete(value),
+        }
+    }
+
+    /// Get a redacted value from a type implementing `ToValue`.
+    ///
+    /// The value is formatted and serialized as `"[REDACTED]"`. Consumers that opt in
+    /// can get the original value back using [`Value::to_unredacted`].
+    pub fn from_redacted<T>(value: &'v T) -> Self
+    where
+        T: ToValue,
+    {
+        Value {
+            inner: inner::Inner::from_redacted(value),
+        }
+    }
+
+    /// Get a value from an unsigned integer with a unit of measurement, like `"bytes"`.
+    ///
+    /// The value behaves the same as one returned by `Value::from(value)`. Consumers that
+    /// understand units can get it using [`Value::to_unit`].
+    pub fn from_u64_with_unit(value: u64, unit: &'static str) -> Self {
+        Value {
+            inner: inner::Inner::from_unit(unit_support::Measured::new(
+                unit_support::Number::U64(value),
+                unit,
+            )),
+        }
+    }
+
+    /// Get a value from a signed integer with a unit of measurement, like `"bytes"`.
+    ///
+    /// See [`Value::from_u64_with_unit`] for details.
+    pub fn from_i64_with_unit(value: i64, unit: &'static str) -> Self {
+        Value {
+            inner: inner::Inner::from_unit(unit_support::Measured::new(
+                unit_support::Number::I64(value),
+                unit,
+            )),
+        }
+    }
+
+    /// Get a value from a float with a unit of measurement, like `"ms"`.
+    ///
+    /// See [`Value::from_u64_with_unit`] for details.
+    pub fn from_f64_with_unit(value: f64, unit: &'static str) -> Self {
+        Value {
+            inner: inner::Inner::from_unit(unit_support::Measured::new(
+                unit_support::Number::F64(value),
+                unit,
+            )),
+        }
+    }
+
+    /// Get a value from a type implementing `ToValue`, with a hint for how to format it.
+    ///
+    /// The hint is used when the value is formatted using `Debug` or `Display`. The value
+    /// is otherwise the same as one returned by [`ToValue::to_value`], so numbers are still
+    /// serialized as numbers.
+    pub fn from_hinted<T>(value: &'v T, hint: FormatHint) -> Self
+    where
+        T: ToValue,
+    {
+        Value {
+            inner: inner::Inner::from_hinted(value, hint),
+        }
+    }
+
     /// Get a value from a type implementing `std::fmt::Debug`.
     pub fn from_debug<T>(value: &'v T) -> Self
     where
@@ -192,7 +282,103 @@ impl<'v> Value<'v> {
         }
     }
 
+    /// Get a value from a `Result`.
+    ///
+    /// An `Ok` is captured using its `ToValue` implementation, and an `Err` is captured using
+    /// [`Value::from_dyn_error`], so consumers can tell them apart using [`Value::to_borrowed_error`].
+    #[cfg(feature = "kv_std")]
+    pub fn from_result<T, E>(value: &'v Result<T, E>) -> Self
+    where
+        T: ToValue,
+        E: std::error::Error + 'static,
+    {
+        match value {
+            Ok(value) => value.to_value(),
+            Err(err) => Value::from_dyn_error(err),
+        }
+    }
+
+    /// Get a value from a byte slice.
+    ///
+    /// Bytes are formatted as a list of numbers, and serialized as bytes using
+    /// `serde`'s `serialize_bytes` or `sval`'s binary type.
+    pub fn from_bytes(value: &'v [u8]) -> Self {
+        Value {
+            inner: inner::Inner::from_bytes(value),
+        }
+    }
+
+    /// Get a value from a sequence of values.
+    ///
+    /// The values in the sequence can be walked using [`Value::as_seq`], without
+    /// needing `serde` or `sval`.
+    pub fn from_seq<T>(value: &'v T) -> Self
+    where
+        T: Seq,
+    {
+        Value::from_dyn_seq(value)
+    }
+
+    /// Get a value from a dynamic sequence of values.
+    pub fn from_dyn_seq(value: &'v dyn Seq) -> Self {
+        Value {
+            inner: inner::Inner::from_seq(value),
+        }
+    }
+
+    /// Get a value from a map of key-values.
+    ///
+    /// Any [`Source`](crate::kv::Source) can be captured as a map. Its key-values
+    /// can be walked using [`Value::as_map`], without needing `serde` or `sval`.
+    pub fn from_map<T>(value: &'v T) -> Self
+    where
+        T: Source,
+    {
+        Value::from_dyn_map(value)
+    }
+
+    /// Get a value from a dynamic map of key-values.
+    pub fn from_dyn_map(value: &'v dyn Source) -> Self {
+        Value {
+            inner: inner::Inner::from_map(value),
+        }
+    }
+
     /// Get a `null` value.
+    ///
+    /// Null values represent absent or unknown data, distinct from empty strings.
+    /// They're visited using [`VisitValue::visit_null`], and serialized as `null`
+    /// through `serde` (as a unit) and `sval`. `None` is also captured as null.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), log::kv::Error> {
+    /// use log::kv::{Error, Value, VisitValue};
+    ///
+    /// struct IsNull(bool);
+    ///
+    /// impl<'v> VisitValue<'v> for IsNull {
+    ///     fn visit_any(&mut self, _: Value) -> Result<(), Error> {
+    ///         Ok(())
+    ///     }
+    ///
+    ///     fn visit_null(&mut self) -> Result<(), Error> {
+    ///         self.0 = true;
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// let mut visitor = IsNull(false);
+    /// Value::null().visit(&mut visitor)?;
+    /// assert!(visitor.0);
+    ///
+    /// let mut visitor = IsNull(false);
+    /// Value::from("").visit(&mut visitor)?;
+    /// assert!(!visitor.0);
+    /// # Ok(())
+    /// # }
+    /// ```
     pub fn null() -> Self {
         Value {
             inner: inner::Inner::empty(),
@@ -230,6 +416,30 @@ impl<'v> fmt::Display for Value<'v> {
     }
 }
 
+/// Values are compared structurally when they're primitives, strings, or bytes.
+///
+/// Integers are equal when they have the same numeric value, regardless of their
+/// original type, so `Value::from(1u8) == Value::from(1i64)`. Other kinds of values,
+/// like those captured using `Debug` or `serde`, are never equal to anything.
+impl<'a, 'b> PartialEq<Value<'b>> for Value<'a> {
+    fn eq(&self, other: &Value<'b>) -> bool {
+        match (
+            cmp_support::Primitive::of(self),
+            cmp_support::Primitive::of(other),
+        ) {
+            (Some(a), Some(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+/// Values are hashed consistently with their [`PartialEq`] implementation.
+impl<'v> std::hash::Hash for Value<'v> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        cmp_support::Primitive::of(self).hash(state)
+    }
+}
+
 #[cfg(feature = "kv_serde")]
 impl<'v> serde::Serialize for Value<'v> {
     fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
@@ -272,6 +482,8 @@ impl ToValue for () {
     }
 }
 
+/// `None` is captured as null, so it's visited using [`VisitValue::visit_null`],
+/// rather than needing to be skipped.
 impl<T> ToValue for Option<T>
 where
     T: ToValue,
@@ -332,6 +544,19 @@ macro_rules! impl_to_value_nonzero_primitive {
     };
 }
 
+macro_rules! impl_to_value_atomic {
+    ($($width:literal: $into_ty:ident,)*) => {
+        $(
+            #[cfg(target_has_atomic = $width)]
+            impl ToValue for std::sync::atomic::$into_ty {
+                fn to_value(&self) -> Value {
+                    Value::from(self.load(std::sync::atomic::Ordering::Relaxed))
+                }
+            }
+        )*
+    };
+}
+
 macro_rules! impl_value_to_primitive {
     ($(#[doc = $doc:tt] $into_name:ident -> $into_ty:ty,)*) => {
         impl<'v> Value<'v> {
@@ -355,6 +580,13 @@ impl_to_value_nonzero_primitive![
     NonZeroIsize, NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI128,
 ];
 
+#[rustfmt::skip]
+impl_to_value_atomic![
+    "8": AtomicBool,
+    "ptr": AtomicUsize, "8": AtomicU8, "16": AtomicU16, "32": AtomicU32, "64": AtomicU64,
+    "ptr": AtomicIsize, "8": AtomicI8, "16": AtomicI16, "32": AtomicI32, "64": AtomicI64,
+];
+
 impl_value_to_primitive![
     #[doc = "Try convert this value into a `u64`."]
     to_u64 -> u64,
@@ -373,1022 +605,5068 @@ impl_value_to_primitive![
 ];
 
 impl<'v> Value<'v> {
-    /// Try convert this value into an error.
-    #[cfg(feature = "kv_std")]
-    pub fn to_borrowed_error(&self) -> Option<&(dyn std::error::Error + 'static)> {
-        self.inner.to_borrowed_error()
+    /// Get the shape of this value.
+    ///
+    /// This method is cheap for values captured from primitives, but may call
+    /// arbitrary serialization implementations for complex ones.
+    pub fn kind(&self) -> ValueKind {
+        self.inner.kind()
     }
 
-    /// Try convert this value into a borrowed string.
-    pub fn to_borrowed_str(&self) -> Option<&str> {
-        self.inner.to_borrowed_str()
+    /// Check whether this value is `null`.
+    pub fn is_null(&self) -> bool {
+        self.kind() == ValueKind::Null
     }
-}
 
-#[cfg(feature = "kv_std")]
-mod std_support {
-    use std::borrow::Cow;
-    use std::rc::Rc;
-    use std::sync::Arc;
-
-    use super::*;
+    /// Check whether this value is a boolean.
+    pub fn is_bool(&self) -> bool {
+        self.kind() == ValueKind::Bool
+    }
 
-    impl<T> ToValue for Box<T>
-    where
-        T: ToValue + ?Sized,
-    {
-        fn to_value(&self) -> Value {
-            (**self).to_value()
-        }
+    /// Check whether this value is a borrowed string.
+    pub fn is_str(&self) -> bool {
+        self.kind() == ValueKind::Str
     }
 
-    impl<T> ToValue for Arc<T>
-    where
-        T: ToValue + ?Sized,
-    {
-        fn to_value(&self) -> Value {
-            (**self).to_value()
-        }
+    /// Check whether this value is an integer or floating point number.
+    pub fn is_number(&self) -> bool {
+        matches!(self.kind(), ValueKind::Int | ValueKind::Float)
     }
 
-    impl<T> ToValue for Rc<T>
-    where
-        T: ToValue + ?Sized,
-    {
-        fn to_value(&self) -> Value {
-            (**self).to_value()
-        }
+    /// Check whether this value is a sequence of values.
+    pub fn is_seq(&self) -> bool {
+        self.kind() == ValueKind::Seq
     }
 
-    impl ToValue for String {
-        fn to_value(&self) -> Value {
-            Value::from(&**self)
-        }
+    /// Check whether this value is a map of key-values.
+    pub fn is_map(&self) -> bool {
+        self.kind() == ValueKind::Map
     }
 
-    impl<'v> ToValue for Cow<'v, str> {
-        fn to_value(&self) -> Value {
-            Value::from(&**self)
-        }
+    /// Try convert this value into an error.
+    #[cfg(feature = "kv_std")]
+    pub fn to_borrowed_error(&self) -> Option<&'v (dyn std::error::Error + 'static)> {
+        self.inner.to_borrowed_error()
     }
 
-    impl<'v> Value<'v> {
-        /// Try convert this value into a string.
-        pub fn to_cow_str(&self) -> Option<Cow<'v, str>> {
-            self.inner.to_str()
-        }
+    /// Try get the chain of errors, starting from this value and following its sources.
+    ///
+    /// This method only returns `Some` for values captured as errors,
+    /// such as with the `:err` capturing modifier.
+    #[cfg(feature = "kv_std")]
+    pub fn to_error_chain(&self) -> Option<ErrorChain<'v>> {
+        self.to_borrowed_error().map(ErrorChain::new)
     }
 
-    impl<'v> From<&'v String> for Value<'v> {
-        fn from(v: &'v String) -> Self {
-            Value::from(&**v)
-        }
+    /// Try convert this value into a borrowed string.
+    pub fn to_borrowed_str(&self) -> Option<&'v str> {
+        self.inner.to_borrowed_str()
     }
-}
 
-/// A visitor for a [`Value`].
-///
-/// Also see [`Value`'s documentation on seralization]. Value visitors are a simple alternative
-/// to a more fully-featured serialization framework like `serde` or `sval`. A value visitor
-/// can differentiate primitive types through methods like [`VisitValue::visit_bool`] and
-/// [`VisitValue::visit_str`], but more complex types like maps and sequences
-/// will fallthrough to [`VisitValue::visit_any`].
-///
-/// If you're trying to serialize a value to a format like JSON, you can use either `serde`
-/// or `sval` directly with the value. You don't need a visitor.
-///
-/// [`Value`'s documentation on seralization]: Value#serialization
-pub trait VisitValue<'v> {
-    /// Visit a `Value`.
+    /// Try convert this value into a string, borrowing it if possible.
     ///
-    /// This is the only required method on `VisitValue` and acts as a fallback for any
-    /// more specific methods that aren't overridden.
-    /// The `Value` may be formatted using its `fmt::Debug` or `fmt::Display` implementation,
-    /// or serialized using its `sval::Value` or `serde::Serialize` implementation.
-    fn visit_any(&mut self, value: Value) -> Result<(), Error>;
-
-    /// Visit an empty value.
-    fn visit_null(&mut self) -> Result<(), Error> {
-        self.visit_any(Value::null())
+    /// This method returns a borrowed string for values that are strings, like
+    /// [`Value::to_borrowed_str`] does, and only allocates for values that are
+    /// trivially convertible into strings, like chars, IP addresses, and timestamps.
+    /// It doesn't format arbitrary values; use `Display` for those.
+    #[cfg(feature = "std")]
+    pub fn to_cow_str(&self) -> Option<std::borrow::Cow<'v, str>> {
+        self.inner.to_str()
     }
 
-    /// Visit an unsigned integer.
-    fn visit_u64(&mut self, value: u64) -> Result<(), Error> {
-        self.visit_any(value.into())
+    /// Try convert this value into a borrowed byte slice.
+    pub fn to_borrowed_bytes(&self) -> Option<&'v [u8]> {
+        self.inner.to_borrowed_bytes()
     }
 
-    /// Visit a signed integer.
-    fn visit_i64(&mut self, value: i64) -> Result<(), Error> {
-        self.visit_any(value.into())
+    /// Try get the sequence this value was captured from.
+    ///
+    /// This method only returns `Some` for values captured using [`Value::from_seq`],
+    /// or a [`ToValue`] implementation that uses it, like the one on arrays.
+    pub fn as_seq(&self) -> Option<&'v dyn Seq> {
+        self.inner.as_seq()
     }
 
-    /// Visit a big unsigned integer.
-    fn visit_u128(&mut self, value: u128) -> Result<(), Error> {
-        self.visit_any((value).into())
+    /// Try get the map this value was captured from.
+    ///
+    /// This method only returns `Some` for values captured using [`Value::from_map`],
+    /// or a [`ToValue`] implementation that uses it, like the one on `BTreeMap`.
+    pub fn as_map(&self) -> Option<&'v dyn Source> {
+        self.inner.as_map()
     }
 
-    /// Visit a big signed integer.
-    fn visit_i128(&mut self, value: i128) -> Result<(), Error> {
-        self.visit_any((value).into())
+    /// Check whether this value can be downcast to `T`.
+    pub fn is<T: 'static>(&self) -> bool {
+        self.downcast_ref::<T>().is_some()
     }
 
-    /// Visit a floating point.
-    fn visit_f64(&mut self, value: f64) -> Result<(), Error> {
-        self.visit_any(value.into())
+    /// Try downcast this value to `T`.
+    ///
+    /// This method only returns `Some` for values captured using [`Value::from_concrete`],
+    /// or one of the deprecated `capture_*` methods.
+    pub fn downcast_ref<T: 'static>(&self) -> Option<&T> {
+        self.inner.downcast_ref()
     }
 
-    /// Visit a boolean.
-    fn visit_bool(&mut self, value: bool) -> Result<(), Error> {
-        self.visit_any(value.into())
+    /// Check whether this value is redacted.
+    pub fn is_redacted(&self) -> bool {
+        self.inner.to_unredacted().is_some()
     }
 
-    /// Visit a string.
-    fn visit_str(&mut self, value: &str) -> Result<(), Error> {
-        self.visit_any(value.into())
+    /// Attach a unit of measurement to this value, like `"ms"` or `"bytes"`.
+    ///
+    /// Only numeric values can have units. Any other value is returned unchanged.
+    pub fn with_unit(self, unit: &'static str) -> Self {
+        if let Some(value) = self.to_u64() {
+            Value::from_u64_with_unit(value, unit)
+        } else if let Some(value) = self.to_i64() {
+            Value::from_i64_with_unit(value, unit)
+        } else if let Some(value) = self.to_f64() {
+            Value::from_f64_with_unit(value, unit)
+        } else {
+            self
+        }
     }
 
-    /// Visit a string.
-    fn visit_borrowed_str(&mut self, value: &'v str) -> Result<(), Error> {
-        self.visit_str(value)
+    /// Try get the unit of measurement attached to this value.
+    ///
+    /// This method only returns `Some` for values captured using [`Value::with_unit`], one of
+    /// the `from_*_with_unit` methods, or a unit capture modifier like `:ms`.
+    pub fn to_unit(&self) -> Option<&'static str> {
+        self.inner.to_unit()
     }
 
-    /// Visit a Unicode character.
-    fn visit_char(&mut self, value: char) -> Result<(), Error> {
-        let mut b = [0; 4];
-        self.visit_str(&*value.encode_utf8(&mut b))
+    /// Try get the hint for how to format this value.
+    ///
+    /// This method only returns `Some` for values captured using [`Value::from_hinted`],
+    /// or a formatting capture modifier like `:x`.
+    pub fn to_format_hint(&self) -> Option<FormatHint> {
+        self.inner.to_format_hint()
     }
 
-    /// Visit an error.
-    #[cfg(feature = "kv_std")]
-    fn visit_error(&mut self, err: &(dyn std::error::Error + 'static)) -> Result<(), Error> {
-        self.visit_any(Value::from_dyn_error(err))
+    /// Try get the original value behind a redacted one.
+    ///
+    /// This method only returns `Some` for values captured using [`Value::from_redacted`],
+    /// the `:redact` capture modifier, or a [`Redacted`] value.
+    pub fn to_unredacted(&self) -> Option<Value<'v>> {
+        self.inner.to_unredacted()
     }
+}
 
-    /// Visit an error.
-    #[cfg(feature = "kv_std")]
-    fn visit_borrowed_error(
-        &mut self,
-        err: &'v (dyn std::error::Error + 'static),
-    ) -> Result<(), Error> {
-        self.visit_any(Value::from_dyn_error(err))
-    }
+/// A sequence of values.
+///
+/// Sequences can be captured in a [`Value`] using [`Value::from_seq`].
+/// Use the [`VisitSeq`] trait to inspect the values in a sequence.
+pub trait Seq {
+    /// Visit the values in the sequence, in order.
+    ///
+    /// If the given visitor returns an error then the sequence may early-return with it,
+    /// even if there are more values.
+    fn visit<'v>(&'v self, visitor: &mut dyn VisitSeq<'v>) -> Result<(), Error>;
 }
 
-impl<'a, 'v, T: ?Sized> VisitValue<'v> for &'a mut T
+impl<'a, T> Seq for &'a T
 where
-    T: VisitValue<'v>,
+    T: Seq + ?Sized,
 {
-    fn visit_any(&mut self, value: Value) -> Result<(), Error> {
-        (**self).visit_any(value)
+    fn visit<'v>(&'v self, visitor: &mut dyn VisitSeq<'v>) -> Result<(), Error> {
+        Seq::visit(&**self, visitor)
     }
+}
 
-    fn visit_null(&mut self) -> Result<(), Error> {
-        (**self).visit_null()
-    }
+impl<T> Seq for [T]
+where
+    T: ToValue,
+{
+    fn visit<'v>(&'v self, visitor: &mut dyn VisitSeq<'v>) -> Result<(), Error> {
+        for value in self {
+            visitor.visit_value(value.to_value())?;
+        }
 
-    fn visit_u64(&mut self, value: u64) -> Result<(), Error> {
-        (**self).visit_u64(value)
+        Ok(())
     }
+}
 
-    fn visit_i64(&mut self, value: i64) -> Result<(), Error> {
-        (**self).visit_i64(value)
+impl<const N: usize, T> Seq for [T; N]
+where
+    T: ToValue,
+{
+    fn visit<'v>(&'v self, visitor: &mut dyn VisitSeq<'v>) -> Result<(), Error> {
+        Seq::visit(self as &[_], visitor)
     }
+}
 
-    fn visit_u128(&mut self, value: u128) -> Result<(), Error> {
-        (**self).visit_u128(value)
+impl<const N: usize, T> ToValue for [T; N]
+where
+    T: ToValue,
+{
+    fn to_value(&self) -> Value {
+        Value::from_seq(self)
     }
+}
 
-    fn visit_i128(&mut self, value: i128) -> Result<(), Error> {
-        (**self).visit_i128(value)
+#[cfg(feature = "std")]
+impl<T> Seq for Vec<T>
+where
+    T: ToValue,
+{
+    fn visit<'v>(&'v self, visitor: &mut dyn VisitSeq<'v>) -> Result<(), Error> {
+        Seq::visit(&**self, visitor)
     }
+}
 
-    fn visit_f64(&mut self, value: f64) -> Result<(), Error> {
-        (**self).visit_f64(value)
-    }
+/// A sequence of values borrowed from a collection or iterator.
+///
+/// The iterator is cloned each time the sequence is visited, so the values are
+/// walked lazily from the original collection instead of being collected up front.
+/// Values can also be captured as sequences in the log macros using the `:seq`
+/// capture modifier, like `ids:seq = &batch`.
+///
+/// # Examples
+///
+/// ```
+/// use log::kv::{SeqIter, ToValue};
+///
+/// let batch = vec![1, 2, 3];
+/// let ids = SeqIter::new(batch.iter().skip(1));
+///
+/// assert_eq!("[2, 3]", ids.to_value().to_string());
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct SeqIter<'a, I> {
+    iter: I,
+    // The lifetime of the values makes sure they outlive any borrow of the sequence
+    _marker: PhantomData<&'a ()>,
+}
 
-    fn visit_bool(&mut self, value: bool) -> Result<(), Error> {
-        (**self).visit_bool(value)
+impl<'a, I> SeqIter<'a, I> {
+    /// Wrap an iterator, or a reference to a collection, of values.
+    pub fn new<T>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = &'a T> + Clone,
+        T: ToValue + ?Sized + 'a,
+    {
+        SeqIter {
+            iter,
+            _marker: PhantomData,
+        }
     }
 
-    fn visit_str(&mut self, value: &str) -> Result<(), Error> {
-        (**self).visit_str(value)
+    /// Get the wrapped iterator.
+    pub fn into_inner(self) -> I {
+        self.iter
     }
+}
 
-    fn visit_borrowed_str(&mut self, value: &'v str) -> Result<(), Error> {
-        (**self).visit_borrowed_str(value)
+impl<'a, I, T> Seq for SeqIter<'a, I>
+where
+    I: IntoIterator<Item = &'a T> + Clone,
+    T: ToValue + ?Sized + 'a,
+{
+    fn visit<'v>(&'v self, visitor: &mut dyn VisitSeq<'v>) -> Result<(), Error> {
+        for value in self.iter.clone() {
+            visitor.visit_value(value.to_value())?;
+        }
+
+        Ok(())
     }
+}
 
-    fn visit_char(&mut self, value: char) -> Result<(), Error> {
-        (**self).visit_char(value)
+impl<'a, I, T> ToValue for SeqIter<'a, I>
+where
+    I: IntoIterator<Item = &'a T> + Clone,
+    T: ToValue + ?Sized + 'a,
+{
+    fn to_value(&self) -> Value {
+        Value::from_seq(self)
     }
+}
 
-    #[cfg(feature = "kv_std")]
-    fn visit_error(&mut self, err: &(dyn std::error::Error + 'static)) -> Result<(), Error> {
-        (**self).visit_error(err)
+/// An iterator over an error and its sources.
+///
+/// The chain is also a [`Seq`], so it can be captured in a [`Value`] using [`Value::from_seq`].
+/// Each error in the sequence is captured using [`Value::from_dyn_error`].
+#[cfg(feature = "kv_std")]
+#[derive(Clone, Debug)]
+pub struct ErrorChain<'v> {
+    next: Option<&'v (dyn std::error::Error + 'static)>,
+}
+
+#[cfg(feature = "kv_std")]
+impl<'v> ErrorChain<'v> {
+    /// Get the chain of errors starting from `err`.
+    pub fn new(err: &'v (dyn std::error::Error + 'static)) -> Self {
+        ErrorChain { next: Some(err) }
     }
+}
 
-    #[cfg(feature = "kv_std")]
-    fn visit_borrowed_error(
-        &mut self,
-        err: &'v (dyn std::error::Error + 'static),
-    ) -> Result<(), Error> {
-        (**self).visit_borrowed_error(err)
+#[cfg(feature = "kv_std")]
+impl<'v> Iterator for ErrorChain<'v> {
+    type Item = &'v (dyn std::error::Error + 'static);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let err = self.next?;
+        self.next = err.source();
+
+        Some(err)
     }
 }
 
-#[cfg(feature = "value-bag")]
-pub(in crate::kv) mod inner {
-    /**
-    An implementation of `Value` based on a library called `value_bag`.
+#[cfg(feature = "kv_std")]
+impl<'a> Seq for ErrorChain<'a> {
+    fn visit<'v>(&'v self, visitor: &mut dyn VisitSeq<'v>) -> Result<(), Error> {
+        for err in self.clone() {
+            visitor.visit_value(Value::from_dyn_error(err))?;
+        }
 
-    `value_bag` was written specifically for use in `log`'s value, but was split out when it outgrew
-    the codebase here. It's a general-purpose type-erasure library that handles mapping between
-    more fully-featured serialization frameworks.
-    */
-    use super::*;
+        Ok(())
+    }
+}
 
-    pub use value_bag::ValueBag as Inner;
+#[cfg(feature = "kv_std")]
+impl<'a> ToValue for ErrorChain<'a> {
+    fn to_value(&self) -> Value {
+        Value::from_seq(self)
+    }
+}
 
-    pub use value_bag::Error;
+/// A wrapper for sensitive values that shouldn't appear in logs.
+///
+/// A redacted value is formatted and serialized as `"[REDACTED]"`, but still carries the
+/// value it wraps so consumers that opt in can get it back using [`Value::to_unredacted`].
+/// Values can also be redacted in the log macros using the `:redact` capture modifier.
+///
+/// Converting a redacted value into an [`OwnedValue`] keeps the `"[REDACTED]"` string
+/// instead of the original value.
+///
+/// # Examples
+///
+/// ```
+/// use log::kv::{Redacted, ToValue};
+///
+/// let password = Redacted::new("hunter2");
+/// let value = password.to_value();
+///
+/// assert_eq!("[REDACTED]", value.to_string());
+/// assert_eq!("hunter2", value.to_unredacted().unwrap().to_string());
+/// ```
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Redacted<T>(T);
 
-    #[cfg(test)]
-    pub use value_bag::test::TestToken as Token;
+impl<T> Redacted<T> {
+    /// Redact a value.
+    pub const fn new(value: T) -> Self {
+        Redacted(value)
+    }
 
-    pub fn visit<'v>(
-        inner: &Inner<'v>,
-        visitor: impl VisitValue<'v>,
-    ) -> Result<(), crate::kv::Error> {
-        struct InnerVisitValue<V>(V);
+    /// Get a reference to the redacted value.
+    pub fn get_ref(&self) -> &T {
+        &self.0
+    }
 
-        impl<'v, V> value_bag::visit::Visit<'v> for InnerVisitValue<V>
-        where
-            V: VisitValue<'v>,
-        {
-            fn visit_any(&mut self, value: value_bag::ValueBag) -> Result<(), Error> {
-                self.0
-                    .visit_any(Value { inner: value })
-                    .map_err(crate::kv::Error::into_value)
-            }
+    /// Get the redacted value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
 
-            fn visit_empty(&mut self) -> Result<(), Error> {
-                self.0.visit_null().map_err(crate::kv::Error::into_value)
-            }
+impl<T> fmt::Debug for Redacted<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(redacted_support::REDACTED)
+    }
+}
 
-            fn visit_u64(&mut self, value: u64) -> Result<(), Error> {
-                self.0
-                    .visit_u64(value)
-                    .map_err(crate::kv::Error::into_value)
-            }
+impl<T> fmt::Display for Redacted<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(redacted_support::REDACTED)
+    }
+}
 
-            fn visit_i64(&mut self, value: i64) -> Result<(), Error> {
-                self.0
-                    .visit_i64(value)
-                    .map_err(crate::kv::Error::into_value)
-            }
+impl<T: ToValue> ToValue for Redacted<T> {
+    fn to_value(&self) -> Value {
+        Value::from_redacted(&self.0)
+    }
+}
 
-            fn visit_u128(&mut self, value: u128) -> Result<(), Error> {
-                self.0
-                    .visit_u128(value)
-                    .map_err(crate::kv::Error::into_value)
-            }
+/// A value that's only computed when it's formatted or serialized.
+///
+/// A lazy value wraps a closure that's called each time the value is formatted,
+/// so expensive values aren't computed for records that are never written.
+/// Values can also be captured lazily in the log macros using the `:lazy` capture
+/// modifier.
+///
+/// The result of the closure is temporary, so a lazy value is captured using its
+/// `Display` implementation. This means structured results, like numbers or maps,
+/// are serialized as strings.
+///
+/// # Examples
+///
+/// ```
+/// use log::kv::{Lazy, ToValue};
+///
+/// let dump = Lazy::new(|| 42);
+/// let value = dump.to_value();
+///
+/// assert_eq!("42", value.to_string());
+/// ```
+#[derive(Clone, Copy)]
+pub struct Lazy<F>(F);
 
-            fn visit_i128(&mut self, value: i128) -> Result<(), Error> {
-                self.0
-                    .visit_i128(value)
-                    .map_err(crate::kv::Error::into_value)
-            }
+impl<F> Lazy<F> {
+    /// Wrap a closure that computes a value.
+    pub const fn new(f: F) -> Self {
+        Lazy(f)
+    }
 
-            fn visit_f64(&mut self, value: f64) -> Result<(), Error> {
-                self.0
-                    .visit_f64(value)
-                    .map_err(crate::kv::Error::into_value)
-            }
+    /// Get the closure that computes the value.
+    pub fn into_inner(self) -> F {
+        self.0
+    }
+}
 
-            fn visit_bool(&mut self, value: bool) -> Result<(), Error> {
-                self.0
-                    .visit_bool(value)
-                    .map_err(crate::kv::Error::into_value)
-            }
+impl<F, T> fmt::Debug for Lazy<F>
+where
+    F: Fn() -> T,
+    T: ToValue,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&(self.0)().to_value(), f)
+    }
+}
 
-            fn visit_str(&mut self, value: &str) -> Result<(), Error> {
-                self.0
-                    .visit_str(value)
-                    .map_err(crate::kv::Error::into_value)
-            }
+impl<F, T> fmt::Display for Lazy<F>
+where
+    F: Fn() -> T,
+    T: ToValue,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&(self.0)().to_value(), f)
+    }
+}
 
-            fn visit_borrowed_str(&mut self, value: &'v str) -> Result<(), Error> {
-                self.0
-                    .visit_borrowed_str(value)
-                    .map_err(crate::kv::Error::into_value)
-            }
+impl<F, T> ToValue for Lazy<F>
+where
+    F: Fn() -> T,
+    T: ToValue,
+{
+    fn to_value(&self) -> Value {
+        Value::from_display(self)
+    }
+}
 
-            fn visit_char(&mut self, value: char) -> Result<(), Error> {
-                self.0
-                    .visit_char(value)
-                    .map_err(crate::kv::Error::into_value)
-            }
+/// A hint for how to format a value.
+///
+/// Hints can be attached to a value using [`Value::from_hinted`], or in the log macros
+/// using formatting capture modifiers:
+///
+/// - `:x` for [`FormatHint::LowerHex`].
+/// - `:X` for [`FormatHint::UpperHex`].
+/// - `:o` for [`FormatHint::Octal`].
+/// - `:b` for [`FormatHint::Binary`].
+/// - `:e` for [`FormatHint::LowerExp`].
+/// - `:E` for [`FormatHint::UpperExp`].
+/// - `:{.N}`, where `N` is an integer, for [`FormatHint::Precision`].
+/// - `:#?` for [`FormatHint::PrettyDebug`], which also captures the value using `Debug`.
+///
+/// Hints only apply when a value is formatted using `Debug` or `Display`. If a hint
+/// doesn't apply to a value, like `LowerHex` for a string, then it's ignored.
+///
+/// # Examples
+///
+/// ```
+/// use log::kv::{FormatHint, Value};
+///
+/// let addr = 0xdead_beefu32;
+/// let value = Value::from_hinted(&addr, FormatHint::LowerHex);
+///
+/// assert_eq!("deadbeef", value.to_string());
+/// assert_eq!(Some(0xdead_beef), value.to_u64());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum FormatHint {
+    /// Format integers as lowercase hexadecimal, like `{:x}`.
+    LowerHex,
+    /// Format integers as uppercase hexadecimal, like `{:X}`.
+    UpperHex,
+    /// Format integers as octal, like `{:o}`.
+    Octal,
+    /// Format integers as binary, like `{:b}`.
+    Binary,
+    /// Format numbers in lowercase scientific notation, like `{:e}`.
+    LowerExp,
+    /// Format numbers in uppercase scientific notation, like `{:E}`.
+    UpperExp,
+    /// Format numbers with the given number of decimal places, like `{:.3}`.
+    Precision(usize),
+    /// Format values using their alternate, pretty-printed `Debug` implementation, like `{:#?}`.
+    ///
+    /// Backends can check for this hint to tell a value that was intentionally captured
+    /// as multi-line, pretty-printed `Debug` from one that was captured as compact `Debug`.
+    PrettyDebug,
+}
 
-            #[cfg(feature = "kv_std")]
-            fn visit_error(
-                &mut self,
-                err: &(dyn std::error::Error + 'static),
-            ) -> Result<(), Error> {
-                self.0
-                    .visit_error(err)
-                    .map_err(crate::kv::Error::into_value)
-            }
+/// The shape of a [`Value`].
+///
+/// A value's kind can be checked using [`Value::kind`] without needing
+/// to visit it.
+///
+/// # Examples
+///
+/// ```
+/// use log::kv::{Value, ValueKind};
+///
+/// assert_eq!(ValueKind::Int, Value::from(42).kind());
+/// assert_eq!(ValueKind::Str, Value::from("a string").kind());
+/// assert_eq!(ValueKind::Other, Value::from_debug(&42).kind());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ValueKind {
+    /// The value is `null`.
+    Null,
+    /// The value is a boolean.
+    Bool,
+    /// The value is a character.
+    Char,
+    /// The value is a borrowed string.
+    Str,
+    /// The value is a signed or unsigned integer.
+    Int,
+    /// The value is a floating point number.
+    Float,
+    /// The value is a byte slice.
+    Bytes,
+    /// The value is a sequence of values.
+    Seq,
+    /// The value is a map of key-values.
+    Map,
+    /// The value is an error.
+    Error,
+    /// The value is anything else, like a value captured using its `Debug` or
+    /// `Display` implementation.
+    Other,
+}
 
-            #[cfg(feature = "kv_std")]
-            fn visit_borrowed_error(
-                &mut self,
-                err: &'v (dyn std::error::Error + 'static),
-            ) -> Result<(), Error> {
-                self.0
-                    .visit_borrowed_error(err)
-                    .map_err(crate::kv::Error::into_value)
-            }
-        }
+/// A visitor for the values in a [`Seq`].
+pub trait VisitSeq<'v> {
+    /// Visit a value.
+    fn visit_value(&mut self, value: Value<'v>) -> Result<(), Error>;
+}
 
-        inner
-            .visit(&mut InnerVisitValue(visitor))
-            .map_err(crate::kv::Error::from_value)
+impl<'a, 'v, T> VisitSeq<'v> for &'a mut T
+where
+    T: VisitSeq<'v> + ?Sized,
+{
+    fn visit_value(&mut self, value: Value<'v>) -> Result<(), Error> {
+        (**self).visit_value(value)
     }
 }
 
-#[cfg(not(feature = "value-bag"))]
-pub(in crate::kv) mod inner {
-    /**
-    This is a dependency-free implementation of `Value` when there's no serialization frameworks involved.
-    In these simple cases a more fully featured solution like `value_bag` isn't needed, so we avoid pulling it in.
+/**
+Support for comparing and hashing values.
+*/
+mod cmp_support {
+    use super::*;
 
-    There are a few things here that need to remain consistent with the `value_bag`-based implementation:
+    use std::hash::{Hash, Hasher};
 
-    1. Conversions should always produce the same results. If a conversion here returns `Some`, then
-       the same `value_bag`-based conversion must also. Of particular note here are floats to ints; they're
-       based on the standard library's `TryInto` conversions, which need to be convert to `i32` or `u32`,
-       and then to `f64`.
-    2. VisitValues should always be called in the same way. If a particular type of value calls `visit_i64`,
-       then the same `value_bag`-based visitor must also.
+    /**
+    The primitive a value was captured from, normalized so values of different
+    types that are semantically the same compare equal.
     */
-    use super::*;
-
-    #[derive(Clone)]
-    pub enum Inner<'v> {
-        None,
+    #[derive(Debug)]
+    pub(super) enum Primitive<'v> {
+        Null,
         Bool(bool),
-        Str(&'v str),
         Char(char),
-        I64(i64),
-        U64(u64),
-        F64(f64),
+        // Integers are stored as `i128` if they fit, so `u128` is only used for larger values
         I128(i128),
         U128(u128),
-        Debug(&'v dyn fmt::Debug),
-        Display(&'v dyn fmt::Display),
+        F64(f64),
+        Str(&'v str),
+        #[cfg(feature = "std")]
+        String(String),
+        Bytes(&'v [u8]),
+        #[cfg(feature = "kv_std")]
+        Duration(std::time::Duration),
+        #[cfg(feature = "kv_std")]
+        SystemTime(std::time::SystemTime),
     }
 
-    impl<'v> From<()> for Inner<'v> {
-        fn from(_: ()) -> Self {
-            Inner::None
+    impl<'v> Primitive<'v> {
+        pub(super) fn of(value: &Value<'v>) -> Option<Self> {
+            struct Visit<'v>(Option<Primitive<'v>>);
+
+            impl<'v> VisitValue<'v> for Visit<'v> {
+                fn visit_any(&mut self, _: Value) -> Result<(), Error> {
+                    Ok(())
+                }
+
+                fn visit_null(&mut self) -> Result<(), Error> {
+                    self.0 = Some(Primitive::Null);
+                    Ok(())
+                }
+
+                fn visit_u64(&mut self, value: u64) -> Result<(), Error> {
+                    self.visit_u128(value as u128)
+                }
+
+                fn visit_i64(&mut self, value: i64) -> Result<(), Error> {
+                    self.visit_i128(value as i128)
+                }
+
+                fn visit_u128(&mut self, value: u128) -> Result<(), Error> {
+                    self.0 = Some(match i128::try_from(value) {
+                        Ok(value) => Primitive::I128(value),
+                        Err(_) => Primitive::U128(value),
+                    });
+                    Ok(())
+                }
+
+                fn visit_i128(&mut self, value: i128) -> Result<(), Error> {
+                    self.0 = Some(Primitive::I128(value));
+                    Ok(())
+                }
+
+                fn visit_f64(&mut self, value: f64) -> Result<(), Error> {
+                    self.0 = Some(Primitive::F64(value));
+                    Ok(())
+                }
+
+                fn visit_bool(&mut self, value: bool) -> Result<(), Error> {
+                    self.0 = Some(Primitive::Bool(value));
+                    Ok(())
+                }
+
+                fn visit_str(&mut self, value: &str) -> Result<(), Error> {
+                    #[cfg(feature = "std")]
+                    {
+                        self.0 = Some(Primitive::String(value.into()));
+                    }
+                    #[cfg(not(feature = "std"))]
+                    {
+                        let _ = value;
+                    }
+
+                    Ok(())
+                }
+
+                fn visit_borrowed_str(&mut self, value: &'v str) -> Result<(), Error> {
+                    self.0 = Some(Primitive::Str(value));
+                    Ok(())
+                }
+
+                fn visit_char(&mut self, value: char) -> Result<(), Error> {
+                    self.0 = Some(Primitive::Char(value));
+                    Ok(())
+                }
+
+                fn visit_bytes(&mut self, value: &'v [u8]) -> Result<(), Error> {
+                    self.0 = Some(Primitive::Bytes(value));
+                    Ok(())
+                }
+
+                #[cfg(feature = "kv_std")]
+                fn visit_duration(&mut self, value: std::time::Duration) -> Result<(), Error> {
+                    self.0 = Some(Primitive::Duration(value));
+                    Ok(())
+                }
+
+                #[cfg(feature = "kv_std")]
+                fn visit_system_time(&mut self, value: std::time::SystemTime) -> Result<(), Error> {
+                    self.0 = Some(Primitive::SystemTime(value));
+                    Ok(())
+                }
+            }
+
+            let mut visit = Visit(None);
+            let _ = value.visit(&mut visit);
+
+            visit.0
         }
-    }
 
-    impl<'v> From<bool> for Inner<'v> {
-        fn from(v: bool) -> Self {
-            Inner::Bool(v)
+        fn as_str(&self) -> Option<&str> {
+            match self {
+                Primitive::Str(v) => Some(v),
+                #[cfg(feature = "std")]
+                Primitive::String(v) => Some(v),
+                _ => None,
+            }
         }
     }
 
-    impl<'v> From<char> for Inner<'v> {
-        fn from(v: char) -> Self {
-            Inner::Char(v)
+    impl<'a, 'b> PartialEq<Primitive<'b>> for Primitive<'a> {
+        fn eq(&self, other: &Primitive<'b>) -> bool {
+            if let (Some(a), Some(b)) = (self.as_str(), other.as_str()) {
+                return a == b;
+            }
+
+            match (self, other) {
+                (Primitive::Null, Primitive::Null) => true,
+                (Primitive::Bool(a), Primitive::Bool(b)) => a == b,
+                (Primitive::Char(a), Primitive::Char(b)) => a == b,
+                (Primitive::I128(a), Primitive::I128(b)) => a == b,
+                (Primitive::U128(a), Primitive::U128(b)) => a == b,
+                (Primitive::F64(a), Primitive::F64(b)) => a == b,
+                (Primitive::Bytes(a), Primitive::Bytes(b)) => a == b,
+                #[cfg(feature = "kv_std")]
+                (Primitive::Duration(a), Primitive::Duration(b)) => a == b,
+                #[cfg(feature = "kv_std")]
+                (Primitive::SystemTime(a), Primitive::SystemTime(b)) => a == b,
+                _ => false,
+            }
         }
     }
 
-    impl<'v> From<f32> for Inner<'v> {
-        fn from(v: f32) -> Self {
-            Inner::F64(v as f64)
+    impl<'v> Hash for Primitive<'v> {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            if let Some(v) = self.as_str() {
+                state.write_u8(0);
+                v.hash(state);
+
+                return;
+            }
+
+            match self {
+                Primitive::Null => state.write_u8(1),
+                Primitive::Bool(v) => {
+                    state.write_u8(2);
+                    v.hash(state)
+                }
+                Primitive::Char(v) => {
+                    state.write_u8(3);
+                    v.hash(state)
+                }
+                Primitive::I128(v) => {
+                    state.write_u8(4);
+                    v.hash(state)
+                }
+                Primitive::U128(v) => {
+                    state.write_u8(5);
+                    v.hash(state)
+                }
+                Primitive::F64(v) => {
+                    state.write_u8(6);
+                    // `0.0` and `-0.0` are equal, so they need to hash the same
+                    if *v == 0.0 {
+                        0.0f64.to_bits().hash(state)
+                    } else {
+                        v.to_bits().hash(state)
+                    }
+                }
+                Primitive::Bytes(v) => {
+                    state.write_u8(7);
+                    v.hash(state)
+                }
+                #[cfg(feature = "kv_std")]
+                Primitive::Duration(v) => {
+                    state.write_u8(8);
+                    v.hash(state)
+                }
+                #[cfg(feature = "kv_std")]
+                Primitive::SystemTime(v) => {
+                    state.write_u8(9);
+                    v.hash(state)
+                }
+                Primitive::Str(_) => unreachable!(),
+                #[cfg(feature = "std")]
+                Primitive::String(_) => unreachable!(),
+            }
         }
     }
+}
 
-    impl<'v> From<f64> for Inner<'v> {
-        fn from(v: f64) -> Self {
-            Inner::F64(v)
-        }
+/**
+Support for values captured from concrete types that's shared by both implementations of `Value`.
+*/
+mod any_support {
+    use super::*;
+
+    use std::any::Any;
+
+    /**
+    A value that can be converted into a `Value` or downcast to its concrete type.
+    */
+    pub trait AnyValue {
+        fn as_value(&self) -> Value<'_>;
+        fn as_any(&self) -> &dyn Any;
     }
 
-    impl<'v> From<i8> for Inner<'v> {
-        fn from(v: i8) -> Self {
-            Inner::I64(v as i64)
+    impl<T: ToValue + 'static> AnyValue for T {
+        fn as_value(&self) -> Value<'_> {
+            self.to_value()
         }
-    }
 
-    impl<'v> From<i16> for Inner<'v> {
-        fn from(v: i16) -> Self {
-            Inner::I64(v as i64)
+        fn as_any(&self) -> &dyn Any {
+            self
         }
     }
 
-    impl<'v> From<i32> for Inner<'v> {
-        fn from(v: i32) -> Self {
-            Inner::I64(v as i64)
-        }
+    pub(super) fn resolve<'v>(value: &'v dyn AnyValue) -> inner::Inner<'v> {
+        value.as_value().inner
     }
+}
 
-    impl<'v> From<i64> for Inner<'v> {
-        fn from(v: i64) -> Self {
-            Inner::I64(v as i64)
-        }
+/**
+Support for redacted values that's shared by both implementations of `Value`.
+*/
+mod redacted_support {
+    use super::*;
+
+    pub(super) const REDACTED: &str = "[REDACTED]";
+
+    /**
+    The value a redacted value is treated as.
+    */
+    pub(super) fn resolve<'v>() -> inner::Inner<'v> {
+        inner::Inner::from(REDACTED)
     }
+}
 
-    impl<'v> From<isize> for Inner<'v> {
-        fn from(v: isize) -> Self {
-            Inner::I64(v as i64)
-        }
+/**
+Support for values with units that's shared by both implementations of `Value`.
+*/
+mod unit_support {
+    use super::*;
+
+    /**
+    A number with a unit of measurement.
+    */
+    #[derive(Clone, Copy)]
+    pub struct Measured {
+        value: Number,
+        unit: &'static str,
     }
 
-    impl<'v> From<u8> for Inner<'v> {
-        fn from(v: u8) -> Self {
-            Inner::U64(v as u64)
-        }
+    #[derive(Clone, Copy)]
+    pub(super) enum Number {
+        U64(u64),
+        I64(i64),
+        F64(f64),
     }
 
-    impl<'v> From<u16> for Inner<'v> {
-        fn from(v: u16) -> Self {
-            Inner::U64(v as u64)
+    impl Measured {
+        pub(super) fn new(value: Number, unit: &'static str) -> Self {
+            Measured { value, unit }
         }
-    }
 
-    impl<'v> From<u32> for Inner<'v> {
-        fn from(v: u32) -> Self {
-            Inner::U64(v as u64)
+        pub(super) fn unit(&self) -> &'static str {
+            self.unit
         }
-    }
 
-    impl<'v> From<u64> for Inner<'v> {
-        fn from(v: u64) -> Self {
-            Inner::U64(v as u64)
+        /**
+        The value a number with a unit is treated as.
+        */
+        pub(super) fn resolve<'v>(&self) -> inner::Inner<'v> {
+            match self.value {
+                Number::U64(v) => inner::Inner::from(v),
+                Number::I64(v) => inner::Inner::from(v),
+                Number::F64(v) => inner::Inner::from(v),
+            }
         }
     }
+}
 
-    impl<'v> From<usize> for Inner<'v> {
-        fn from(v: usize) -> Self {
-            Inner::U64(v as u64)
-        }
+/**
+Support for values with formatting hints that's shared by both implementations of `Value`.
+*/
+mod hint_support {
+    use super::*;
+
+    /**
+    The value a value with a formatting hint is treated as, except when it's formatted.
+    */
+    pub(super) fn resolve<'v>(value: &'v dyn ToValue) -> inner::Inner<'v> {
+        value.to_value().inner
     }
 
-    impl<'v> From<i128> for Inner<'v> {
-        fn from(v: i128) -> Self {
-            Inner::I128(v)
+    pub(super) fn fmt(
+        value: &dyn ToValue,
+        hint: FormatHint,
+        f: &mut fmt::Formatter,
+    ) -> fmt::Result {
+        let value = value.to_value();
+
+        macro_rules! fmt_int {
+            ($fmt:path) => {
+                if let Some(v) = value.to_u64() {
+                    $fmt(&v, f)
+                } else if let Some(v) = value.to_i64() {
+                    $fmt(&v, f)
+                } else if let Some(v) = value.to_u128() {
+                    $fmt(&v, f)
+                } else if let Some(v) = value.to_i128() {
+                    $fmt(&v, f)
+                } else {
+                    fmt::Display::fmt(&value, f)
+                }
+            };
         }
-    }
 
-    impl<'v> From<u128> for Inner<'v> {
-        fn from(v: u128) -> Self {
-            Inner::U128(v)
+        macro_rules! fmt_float {
+            ($fmt:path) => {
+                match value.to_f64() {
+                    Some(v) => $fmt(&v, f),
+                    None => fmt::Display::fmt(&value, f),
+                }
+            };
         }
-    }
 
-    impl<'v> From<&'v str> for Inner<'v> {
-        fn from(v: &'v str) -> Self {
-            Inner::Str(v)
+        match hint {
+            FormatHint::LowerHex => fmt_int!(fmt::LowerHex::fmt),
+            FormatHint::UpperHex => fmt_int!(fmt::UpperHex::fmt),
+            FormatHint::Octal => fmt_int!(fmt::Octal::fmt),
+            FormatHint::Binary => fmt_int!(fmt::Binary::fmt),
+            FormatHint::LowerExp => fmt_float!(fmt::LowerExp::fmt),
+            FormatHint::UpperExp => fmt_float!(fmt::UpperExp::fmt),
+            FormatHint::Precision(precision) => match value.to_f64() {
+                Some(v) => write!(f, "{:.*}", precision, v),
+                None => fmt::Display::fmt(&value, f),
+            },
+            FormatHint::PrettyDebug => write!(f, "{:#?}", value),
         }
     }
+}
 
-    impl<'v> fmt::Debug for Inner<'v> {
-        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-            match self {
-                Inner::None => fmt::Debug::fmt(&None::<()>, f),
-                Inner::Bool(v) => fmt::Debug::fmt(v, f),
-                Inner::Str(v) => fmt::Debug::fmt(v, f),
-                Inner::Char(v) => fmt::Debug::fmt(v, f),
-                Inner::I64(v) => fmt::Debug::fmt(v, f),
-                Inner::U64(v) => fmt::Debug::fmt(v, f),
-                Inner::F64(v) => fmt::Debug::fmt(v, f),
-                Inner::I128(v) => fmt::Debug::fmt(v, f),
-                Inner::U128(v) => fmt::Debug::fmt(v, f),
-                Inner::Debug(v) => fmt::Debug::fmt(v, f),
-                Inner::Display(v) => fmt::Display::fmt(v, f),
+/**
+Support for sequences and maps that's shared by both implementations of `Value`.
+*/
+mod structured {
+    use super::*;
+
+    use crate::kv::{Key, VisitSource};
+
+    pub(super) fn fmt_seq(seq: &dyn Seq, f: &mut fmt::Formatter) -> fmt::Result {
+        struct Fmt<'a, 'b>(fmt::DebugList<'a, 'b>);
+
+        impl<'a, 'b, 'v> VisitSeq<'v> for Fmt<'a, 'b> {
+            fn visit_value(&mut self, value: Value<'v>) -> Result<(), Error> {
+                self.0.entry(&value);
+
+                Ok(())
             }
         }
+
+        let mut list = Fmt(f.debug_list());
+        seq.visit(&mut list).map_err(|_| fmt::Error)?;
+        list.0.finish()
     }
 
-    impl<'v> fmt::Display for Inner<'v> {
-        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-            match self {
-                Inner::None => fmt::Debug::fmt(&None::<()>, f),
-                Inner::Bool(v) => fmt::Display::fmt(v, f),
-                Inner::Str(v) => fmt::Display::fmt(v, f),
-                Inner::Char(v) => fmt::Display::fmt(v, f),
-                Inner::I64(v) => fmt::Display::fmt(v, f),
-                Inner::U64(v) => fmt::Display::fmt(v, f),
-                Inner::F64(v) => fmt::Display::fmt(v, f),
-                Inner::I128(v) => fmt::Display::fmt(v, f),
-                Inner::U128(v) => fmt::Display::fmt(v, f),
-                Inner::Debug(v) => fmt::Debug::fmt(v, f),
-                Inner::Display(v) => fmt::Display::fmt(v, f),
+    pub(super) fn fmt_map(map: &dyn Source, f: &mut fmt::Formatter) -> fmt::Result {
+        struct Fmt<'a, 'b>(fmt::DebugMap<'a, 'b>);
+
+        impl<'a, 'b, 'kvs> VisitSource<'kvs> for Fmt<'a, 'b> {
+            fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+                self.0.entry(&key.as_str(), &value);
+
+                Ok(())
             }
         }
+
+        let mut map_fmt = Fmt(f.debug_map());
+        map.visit(&mut map_fmt).map_err(|_| fmt::Error)?;
+        map_fmt.0.finish()
     }
 
-    impl<'v> Inner<'v> {
-        pub fn from_debug<T: fmt::Debug>(value: &'v T) -> Self {
-            Inner::Debug(value)
+    #[cfg(feature = "kv_serde")]
+    pub(super) fn serialize_seq<S: serde::Serializer>(
+        seq: &dyn Seq,
+        s: S,
+    ) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeSeq;
+
+        struct Serialize<S: SerializeSeq>(S, Option<S::Error>);
+
+        impl<'v, S: SerializeSeq> VisitSeq<'v> for Serialize<S> {
+            fn visit_value(&mut self, value: Value<'v>) -> Result<(), Error> {
+                self.0.serialize_element(&value).map_err(|e| {
+                    self.1 = Some(e);
+                    Error::new(
+                        crate::kv::ErrorKind::Serialization,
+                        "failed to serialize a value",
+                    )
+                })
+            }
         }
 
-        pub fn from_display<T: fmt::Display>(value: &'v T) -> Self {
-            Inner::Display(value)
-        }
+        let mut serialize = Serialize(s.serialize_seq(None)?, None);
 
-        pub fn from_dyn_debug(value: &'v dyn fmt::Debug) -> Self {
-            Inner::Debug(value)
+        if seq.visit(&mut serialize).is_err() {
+            return Err(serialize
+                .1
+                .unwrap_or_else(|| serde::ser::Error::custom("failed to visit a sequence")));
         }
 
-        pub fn from_dyn_display(value: &'v dyn fmt::Display) -> Self {
-            Inner::Display(value)
+        serialize.0.end()
+    }
+
+    #[cfg(feature = "kv_serde")]
+    pub(super) fn serialize_map<S: serde::Serializer>(
+        map: &dyn Source,
+        s: S,
+    ) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+
+        struct Serialize<S: SerializeMap>(S, Option<S::Error>);
+
+        impl<'kvs, S: SerializeMap> VisitSource<'kvs> for Serialize<S> {
+            fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+                self.0.serialize_entry(key.as_str(), &value).map_err(|e| {
+                    self.1 = Some(e);
+                    Error::new(
+                        crate::kv::ErrorKind::Serialization,
+                        "failed to serialize a key-value",
+                    )
+                })
+            }
         }
 
-        pub fn empty() -> Self {
-            Inner::None
+        let mut serialize = Serialize(s.serialize_map(Some(map.count()))?, None);
+
+        if map.visit(&mut serialize).is_err() {
+            return Err(serialize
+                .1
+                .unwrap_or_else(|| serde::ser::Error::custom("failed to visit a map")));
         }
 
-        pub fn to_bool(&self) -> Option<bool> {
-            match self {
-                Inner::Bool(v) => Some(*v),
-                _ => None,
+        serialize.0.end()
+    }
+
+    #[cfg(feature = "kv_sval")]
+    pub(super) fn stream_seq<'sval, S: sval::Stream<'sval> + ?Sized>(
+        seq: &dyn Seq,
+        stream: &mut S,
+    ) -> sval::Result {
+        struct Stream<'a, S: ?Sized>(&'a mut S);
+
+        impl<'a, 'sval, 'v, S: sval::Stream<'sval> + ?Sized> VisitSeq<'v> for Stream<'a, S> {
+            fn visit_value(&mut self, value: Value<'v>) -> Result<(), Error> {
+                let mut stream = || {
+                    self.0.seq_value_begin()?;
+                    self.0.value_computed(&value)?;
+                    self.0.seq_value_end()
+                };
+
+                stream().map_err(|_| {
+                    Error::new(
+                        crate::kv::ErrorKind::Serialization,
+                        "failed to stream a value",
+                    )
+                })
             }
         }
 
-        pub fn to_char(&self) -> Option<char> {
-            match self {
-                Inner::Char(v) => Some(*v),
-                _ => None,
+        stream.seq_begin(None)?;
+        seq.visit(&mut Stream(stream))
+            .map_err(|_| sval::Error::new())?;
+        stream.seq_end()
+    }
+
+    #[cfg(feature = "kv_sval")]
+    pub(super) fn stream_map<'sval, S: sval::Stream<'sval> + ?Sized>(
+        map: &dyn Source,
+        stream: &mut S,
+    ) -> sval::Result {
+        struct Stream<'a, S: ?Sized>(&'a mut S);
+
+        impl<'a, 'sval, 'kvs, S: sval::Stream<'sval> + ?Sized> VisitSource<'kvs> for Stream<'a, S> {
+            fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+                let mut stream = || {
+                    self.0.map_key_begin()?;
+                    self.0.value_computed(key.as_str())?;
+                    self.0.map_key_end()?;
+                    self.0.map_value_begin()?;
+                    self.0.value_computed(&value)?;
+                    self.0.map_value_end()
+                };
+
+                stream().map_err(|_| {
+                    Error::new(
+                        crate::kv::ErrorKind::Serialization,
+                        "failed to stream a key-value",
+                    )
+                })
             }
         }
 
-        pub fn to_f64(&self) -> Option<f64> {
-            match self {
-                Inner::F64(v) => Some(*v),
-                Inner::I64(v) => {
-                    let v: i32 = (*v).try_into().ok()?;
-                    v.try_into().ok()
-                }
-                Inner::U64(v) => {
-                    let v: u32 = (*v).try_into().ok()?;
-                    v.try_into().ok()
-                }
-                Inner::I128(v) => {
-                    let v: i32 = (*v).try_into().ok()?;
-                    v.try_into().ok()
-                }
-                Inner::U128(v) => {
-                    let v: u32 = (*v).try_into().ok()?;
-                    v.try_into().ok()
-                }
-                _ => None,
+        stream.map_begin(Some(map.count()))?;
+        map.visit(&mut Stream(stream))
+            .map_err(|_| sval::Error::new())?;
+        stream.map_end()
+    }
+
+    #[cfg(feature = "std")]
+    pub(super) fn to_owned_seq(seq: &dyn Seq) -> Vec<OwnedValue> {
+        struct Buffer(Vec<OwnedValue>);
+
+        impl<'v> VisitSeq<'v> for Buffer {
+            fn visit_value(&mut self, value: Value<'v>) -> Result<(), Error> {
+                self.0.push(value.to_owned());
+
+                Ok(())
             }
         }
 
-        pub fn to_i64(&self) -> Option<i64> {
-            match self {
-                Inner::I64(v) => Some(*v),
-                Inner::U64(v) => (*v).try_into().ok(),
-                Inner::I128(v) => (*v).try_into().ok(),
-                Inner::U128(v) => (*v).try_into().ok(),
-                _ => None,
-            }
-        }
+        let mut buffer = Buffer(Vec::new());
+        let _ = seq.visit(&mut buffer);
 
-        pub fn to_u64(&self) -> Option<u64> {
-            match self {
-                Inner::U64(v) => Some(*v),
-                Inner::I64(v) => (*v).try_into().ok(),
-                Inner::I128(v) => (*v).try_into().ok(),
-                Inner::U128(v) => (*v).try_into().ok(),
-                _ => None,
-            }
-        }
+        buffer.0
+    }
+}
 
-        pub fn to_u128(&self) -> Option<u128> {
-            match self {
-                Inner::U128(v) => Some(*v),
-                Inner::I64(v) => (*v).try_into().ok(),
-                Inner::U64(v) => (*v).try_into().ok(),
-                Inner::I128(v) => (*v).try_into().ok(),
-                _ => None,
-            }
-        }
+/// A value that owns its data.
+///
+/// Owned values are buffered from a borrowed [`Value`] using [`Value::to_owned`],
+/// and can be inspected by converting back into one using [`OwnedValue::by_ref`].
+/// They're `'static`, `Send`, and `Sync`, so they can outlive the log call they
+/// were captured in.
+///
+/// Primitives and strings are stored as-is. Values captured using `serde` or
+/// `sval` keep their structure. Any other value is buffered into a string using
+/// its `Debug` or `Display` implementation.
+///
+/// With the `kv_serde` feature, owned values can also be deserialized from any
+/// self-describing format, like JSON.
+///
+/// # Examples
+///
+/// ```
+/// use log::kv::{OwnedValue, Value};
+///
+/// let owned: OwnedValue = {
+///     let data = vec![1, 2, 3];
+///
+///     Value::from_debug(&data).to_owned()
+/// };
+///
+/// assert_eq!("[1, 2, 3]", owned.by_ref().to_string());
+/// ```
+#[cfg(feature = "std")]
+#[derive(Clone)]
+pub struct OwnedValue {
+    inner: inner::OwnedInner,
+}
 
-        pub fn to_i128(&self) -> Option<i128> {
-            match self {
-                Inner::I128(v) => Some(*v),
-                Inner::I64(v) => (*v).try_into().ok(),
-                Inner::U64(v) => (*v).try_into().ok(),
-                Inner::U128(v) => (*v).try_into().ok(),
-                _ => None,
-            }
+#[cfg(feature = "std")]
+impl<'v> Value<'v> {
+    /// Buffer this value into an [`OwnedValue`].
+    pub fn to_owned(&self) -> OwnedValue {
+        OwnedValue {
+            inner: self.inner.to_owned(),
         }
+    }
+}
 
-        pub fn to_borrowed_str(&self) -> Option<&'v str> {
-            match self {
-                Inner::Str(v) => Some(v),
-                _ => None,
-            }
+#[cfg(feature = "std")]
+impl OwnedValue {
+    /// Get a borrowed [`Value`] from this owned value.
+    pub fn by_ref(&self) -> Value<'_> {
+        Value {
+            inner: self.inner.by_ref(),
         }
+    }
+}
 
-        #[cfg(test)]
-        pub fn to_test_token(&self) -> Token {
-            match self {
-                Inner::None => Token::None,
-                Inner::Bool(v) => Token::Bool(*v),
-                Inner::Str(v) => Token::Str(*v),
-                Inner::Char(v) => Token::Char(*v),
-                Inner::I64(v) => Token::I64(*v),
-                Inner::U64(v) => Token::U64(*v),
-                Inner::F64(v) => Token::F64(*v),
-                Inner::I128(_) => unimplemented!(),
-                Inner::U128(_) => unimplemented!(),
-                Inner::Debug(_) => unimplemented!(),
-                Inner::Display(_) => unimplemented!(),
-            }
-        }
+#[cfg(feature = "std")]
+impl ToValue for OwnedValue {
+    fn to_value(&self) -> Value {
+        self.by_ref()
     }
+}
 
-    #[cfg(test)]
-    #[derive(Debug, PartialEq)]
-    pub enum Token<'v> {
-        None,
-        Bool(bool),
-        Char(char),
-        Str(&'v str),
-        F64(f64),
-        I64(i64),
-        U64(u64),
+#[cfg(feature = "std")]
+impl<'v> From<Value<'v>> for OwnedValue {
+    fn from(value: Value<'v>) -> Self {
+        value.to_owned()
     }
+}
 
-    pub fn visit<'v>(
-        inner: &Inner<'v>,
-        mut visitor: impl VisitValue<'v>,
-    ) -> Result<(), crate::kv::Error> {
-        match inner {
-            Inner::None => visitor.visit_null(),
-            Inner::Bool(v) => visitor.visit_bool(*v),
-            Inner::Str(v) => visitor.visit_borrowed_str(*v),
-            Inner::Char(v) => visitor.visit_char(*v),
-            Inner::I64(v) => visitor.visit_i64(*v),
-            Inner::U64(v) => visitor.visit_u64(*v),
-            Inner::F64(v) => visitor.visit_f64(*v),
-            Inner::I128(v) => visitor.visit_i128(*v),
-            Inner::U128(v) => visitor.visit_u128(*v),
-            Inner::Debug(v) => visitor.visit_any(Value::from_dyn_debug(*v)),
-            Inner::Display(v) => visitor.visit_any(Value::from_dyn_display(*v)),
-        }
+#[cfg(feature = "std")]
+impl fmt::Debug for OwnedValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self.by_ref(), f)
     }
 }
 
-impl<'v> Value<'v> {
-    /// Get a value from a type implementing `std::fmt::Debug`.
-    #[cfg(feature = "kv_unstable")]
-    #[deprecated(note = "use `from_debug` instead")]
-    pub fn capture_debug<T>(value: &'v T) -> Self
+#[cfg(feature = "std")]
+impl fmt::Display for OwnedValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.by_ref(), f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl PartialEq for OwnedValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.by_ref() == other.by_ref()
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::hash::Hash for OwnedValue {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.by_ref().hash(state)
+    }
+}
+
+#[cfg(feature = "kv_serde")]
+impl serde::Serialize for OwnedValue {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
     where
-        T: fmt::Debug + 'static,
+        S: serde::Serializer,
     {
-        Value::from_debug(value)
+        serde::Serialize::serialize(&self.by_ref(), s)
+    }
+}
+
+#[cfg(all(feature = "kv_sval", feature = "std"))]
+impl sval::Value for OwnedValue {
+    fn stream<'sval, S: sval::Stream<'sval> + ?Sized>(&'sval self, stream: &mut S) -> sval::Result {
+        sval_ref::ValueRef::stream_ref(&self.by_ref(), stream)
     }
+}
 
-    /// Get a value from a type implementing `std::fmt::Display`.
-    #[cfg(feature = "kv_unstable")]
-    #[deprecated(note = "use `from_display` instead")]
-    pub fn capture_display<T>(value: &'v T) -> Self
+/**
+Conversions for strings and collections that only need an allocator.
+*/
+#[cfg(feature = "std")]
+mod alloc_support {
+    use crate::kv::ToKey;
+    use std::borrow::{Borrow, Cow};
+    use std::collections::{BTreeMap, HashMap};
+    use std::hash::{BuildHasher, Hash};
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    use super::*;
+
+    impl<T> ToValue for Box<T>
     where
-        T: fmt::Display + 'static,
+        T: ToValue + ?Sized,
     {
-        Value::from_display(value)
+        fn to_value(&self) -> Value {
+            (**self).to_value()
+        }
     }
 
-    /// Get a value from an error.
-    #[cfg(feature = "kv_unstable_std")]
-    #[deprecated(note = "use `from_dyn_error` instead")]
-    pub fn capture_error<T>(err: &'v T) -> Self
+    impl<T> ToValue for Arc<T>
     where
-        T: std::error::Error + 'static,
+        T: ToValue + ?Sized,
     {
-        Value::from_dyn_error(err)
+        fn to_value(&self) -> Value {
+            (**self).to_value()
+        }
     }
 
-    /// Get a value from a type implementing `serde::Serialize`.
-    #[cfg(feature = "kv_unstable_serde")]
-    #[deprecated(note = "use `from_serde` instead")]
-    pub fn capture_serde<T>(value: &'v T) -> Self
+    impl<T> ToValue for Rc<T>
     where
-        T: serde::Serialize + 'static,
+        T: ToValue + ?Sized,
     {
-        Value::from_serde(value)
+        fn to_value(&self) -> Value {
+            (**self).to_value()
+        }
     }
 
-    /// Get a value from a type implementing `sval::Value`.
-    #[cfg(feature = "kv_unstable_sval")]
-    #[deprecated(note = "use `from_sval` instead")]
-    pub fn capture_sval<T>(value: &'v T) -> Self
+    impl ToValue for String {
+        fn to_value(&self) -> Value {
+            Value::from(&**self)
+        }
+    }
+
+    impl<'v, T> ToValue for Cow<'v, T>
     where
-        T: sval::Value + 'static,
+        T: ToValue + ToOwned + ?Sized,
     {
-        Value::from_sval(value)
+        fn to_value(&self) -> Value {
+            (**self).to_value()
+        }
     }
 
-    /// Check whether this value can be downcast to `T`.
-    #[cfg(feature = "kv_unstable")]
-    #[deprecated(
-        note = "downcasting has been removed; log an issue at https://github.com/rust-lang/log/issues if this is something you rely on"
-    )]
-    pub fn is<T: 'static>(&self) -> bool {
-        false
+    impl<T> ToValue for Vec<T>
+    where
+        T: ToValue,
+    {
+        fn to_value(&self) -> Value {
+            Value::from_seq(self)
+        }
     }
 
-    /// Try downcast this value to `T`.
-    #[cfg(feature = "kv_unstable")]
-    #[deprecated(
-        note = "downcasting has been removed; log an issue at https://github.com/rust-lang/log/issues if this is something you rely on"
-    )]
-    pub fn downcast_ref<T: 'static>(&self) -> Option<&T> {
-        None
+    impl<K, V, S> ToValue for HashMap<K, V, S>
+    where
+        K: ToKey + Borrow<str> + Eq + Hash,
+        V: ToValue,
+        S: BuildHasher,
+    {
+        fn to_value(&self) -> Value {
+            Value::from_map(self)
+        }
     }
-}
 
-// NOTE: Deprecated; but aliases can't carry this attribute
-#[cfg(feature = "kv_unstable")]
-pub use VisitValue as Visit;
+    impl<K, V> ToValue for BTreeMap<K, V>
+    where
+        K: ToKey + Borrow<str> + Ord,
+        V: ToValue,
+    {
+        fn to_value(&self) -> Value {
+            Value::from_map(self)
+        }
+    }
 
-/// Get a value from a type implementing `std::fmt::Debug`.
-#[cfg(feature = "kv_unstable")]
-#[deprecated(note = "use the `key:? = value` macro syntax instead")]
-#[macro_export]
-macro_rules! as_debug {
-    ($capture:expr) => {
-        $crate::kv::Value::from_debug(&$capture)
-    };
+    impl<'v> From<&'v String> for Value<'v> {
+        fn from(v: &'v String) -> Self {
+            Value::from(&**v)
+        }
+    }
 }
 
-/// Get a value from a type implementing `std::fmt::Display`.
-#[cfg(feature = "kv_unstable")]
-#[deprecated(note = "use the `key:% = value` macro syntax instead")]
-#[macro_export]
-macro_rules! as_display {
-    ($capture:expr) => {
-        $crate::kv::Value::from_display(&$capture)
-    };
+/**
+Conversions for filesystem paths and OS strings.
+
+Paths and OS strings that are valid UTF-8 are captured as strings. On Unix, any others
+are captured as their raw bytes. On other platforms, owned paths and OS strings are
+captured using their `Debug` implementations, which escape the parts that aren't valid
+UTF-8, and borrowed ones are captured as the string `"[non-UTF-8]"`.
+*/
+#[cfg(feature = "std")]
+mod os_support {
+    use std::ffi::{OsStr, OsString};
+    use std::path::{Path, PathBuf};
+
+    use super::*;
+
+    impl ToValue for OsStr {
+        fn to_value(&self) -> Value {
+            Value::from(self)
+        }
+    }
+
+    impl ToValue for OsString {
+        fn to_value(&self) -> Value {
+            #[cfg(not(unix))]
+            {
+                if self.to_str().is_none() {
+                    return Value::from_dyn_debug(self);
+                }
+            }
+
+            Value::from(&**self)
+        }
+    }
+
+    impl ToValue for Path {
+        fn to_value(&self) -> Value {
+            Value::from(self)
+        }
+    }
+
+    impl ToValue for PathBuf {
+        fn to_value(&self) -> Value {
+            self.as_os_str().to_value()
+        }
+    }
+
+    impl<'v> From<&'v OsStr> for Value<'v> {
+        fn from(v: &'v OsStr) -> Self {
+            match v.to_str() {
+                Some(v) => Value::from(v),
+                #[cfg(unix)]
+                None => Value::from_bytes(std::os::unix::ffi::OsStrExt::as_bytes(v)),
+                #[cfg(not(unix))]
+                None => Value::from("[non-UTF-8]"),
+            }
+        }
+    }
+
+    impl<'v> From<&'v Path> for Value<'v> {
+        fn from(v: &'v Path) -> Self {
+            Value::from(v.as_os_str())
+        }
+    }
+
+    impl<'v> Value<'v> {
+        /// Try convert this value into a borrowed path.
+        ///
+        /// This method returns `Some` for any value that's a borrowed string, including
+        /// paths and OS strings that are valid UTF-8.
+        pub fn to_path(&self) -> Option<&'v Path> {
+            self.to_borrowed_str().map(Path::new)
+        }
+    }
 }
 
-/// Get a value from an error.
-#[cfg(feature = "kv_unstable_std")]
-#[deprecated(note = "use the `key:err = value` macro syntax instead")]
-#[macro_export]
-macro_rules! as_error {
-    ($capture:expr) => {
-        $crate::kv::Value::from_dyn_error(&$capture)
-    };
-}
+#[cfg(feature = "kv_std")]
+mod std_support {
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use super::*;
+
+    impl ToValue for Duration {
+        fn to_value(&self) -> Value {
+            Value::from_duration(*self)
+        }
+    }
+
+    impl ToValue for SystemTime {
+        fn to_value(&self) -> Value {
+            Value::from_system_time(*self)
+        }
+    }
+
+    /**
+    An IP address borrowed by a value, so values don't grow to hold one inline.
+    */
+    pub trait IpAddrValue {
+        fn to_ip_addr(&self) -> IpAddr;
+    }
+
+    /**
+    A socket address borrowed by a value, so values don't grow to hold one inline.
+    */
+    pub trait SocketAddrValue {
+        fn to_socket_addr(&self) -> SocketAddr;
+    }
+
+    macro_rules! impl_to_value_addr {
+        ($($ty:ty => $trait:ident::$to:ident -> $addr:ty, $from:ident,)*) => {
+            $(
+                impl $trait for $ty {
+                    fn $to(&self) -> $addr {
+                        (*self).into()
+                    }
+                }
+
+                impl ToValue for $ty {
+                    fn to_value(&self) -> Value {
+                        Value {
+                            inner: inner::Inner::$from(self),
+                        }
+                    }
+                }
+
+                impl<'v> From<&'v $ty> for Value<'v> {
+                    fn from(v: &'v $ty) -> Self {
+                        v.to_value()
+                    }
+                }
+            )*
+        };
+    }
+
+    impl_to_value_addr![
+        IpAddr => IpAddrValue::to_ip_addr -> IpAddr, from_ip_addr,
+        Ipv4Addr => IpAddrValue::to_ip_addr -> IpAddr, from_ip_addr,
+        Ipv6Addr => IpAddrValue::to_ip_addr -> IpAddr, from_ip_addr,
+        SocketAddr => SocketAddrValue::to_socket_addr -> SocketAddr, from_socket_addr,
+        SocketAddrV4 => SocketAddrValue::to_socket_addr -> SocketAddr, from_socket_addr,
+        SocketAddrV6 => SocketAddrValue::to_socket_addr -> SocketAddr, from_socket_addr,
+    ];
+
+    impl<'v> Value<'v> {
+        /// Get a value from an IP address.
+        ///
+        /// Addresses are formatted and serialized as strings in their canonical form,
+        /// like `127.0.0.1` or `::1`.
+        pub fn from_ip_addr(value: &'v IpAddr) -> Self {
+            Value {
+                inner: inner::Inner::from_ip_addr(value),
+            }
+        }
+
+        /// Get a value from a socket address.
+        ///
+        /// Addresses are formatted and serialized as strings in their canonical form,
+        /// like `127.0.0.1:8080` or `[::1]:8080`.
+        pub fn from_socket_addr(value: &'v SocketAddr) -> Self {
+            Value {
+                inner: inner::Inner::from_socket_addr(value),
+            }
+        }
+
+        /// Get a value from a `Duration`.
+        ///
+        /// Durations are formatted like `1.2345s`, and serialized as a struct with
+        /// `secs` and `nanos` fields, the same way `serde` serializes them.
+        pub fn from_duration(value: Duration) -> Self {
+            Value {
+                inner: inner::Inner::from_duration(value),
+            }
+        }
+
+        /// Get a value from a `SystemTime`.
+        ///
+        /// System times are formatted as RFC 3339 timestamps in UTC, and serialized as
+        /// a struct with `secs_since_epoch` and `nanos_since_epoch` fields, the same way
+        /// `serde` serializes them.
+        pub fn from_system_time(value: SystemTime) -> Self {
+            Value {
+                inner: inner::Inner::from_system_time(value),
+            }
+        }
+
+        /// Try convert this value into an IP address.
+        pub fn to_ip_addr(&self) -> Option<IpAddr> {
+            self.inner.to_ip_addr()
+        }
+
+        /// Try convert this value into a socket address.
+        pub fn to_socket_addr(&self) -> Option<SocketAddr> {
+            self.inner.to_socket_addr()
+        }
+
+        /// Try convert this value into a `Duration`.
+        pub fn to_duration(&self) -> Option<Duration> {
+            self.inner.to_duration()
+        }
+
+        /// Try convert this value into a `SystemTime`.
+        pub fn to_system_time(&self) -> Option<SystemTime> {
+            self.inner.to_system_time()
+        }
+    }
+
+    impl<'v> From<Duration> for Value<'v> {
+        fn from(v: Duration) -> Self {
+            Value::from_duration(v)
+        }
+    }
+
+    impl<'v> From<SystemTime> for Value<'v> {
+        fn from(v: SystemTime) -> Self {
+            Value::from_system_time(v)
+        }
+    }
+
+    /**
+    Format a system time as an RFC 3339 timestamp in UTC, like `2024-01-02T03:04:05.5Z`.
+
+    Times before the Unix epoch fall back to their `Debug` implementation.
+    */
+    pub(super) fn fmt_system_time(v: &SystemTime, f: &mut fmt::Formatter) -> fmt::Result {
+        let since_epoch = match v.duration_since(UNIX_EPOCH) {
+            Ok(since_epoch) => since_epoch,
+            Err(_) => return fmt::Debug::fmt(v, f),
+        };
+
+        let secs = since_epoch.as_secs();
+        let nanos = since_epoch.subsec_nanos();
+
+        // Convert days since the epoch into a civil date
+        // See: http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+        let z = (secs / 86_400) as i64 + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+        let secs_of_day = secs % 86_400;
+
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            year,
+            month,
+            day,
+            secs_of_day / 3_600,
+            secs_of_day % 3_600 / 60,
+            secs_of_day % 60,
+        )?;
+
+        if nanos != 0 {
+            let mut nanos = format!("{:09}", nanos);
+            while nanos.ends_with('0') {
+                nanos.pop();
+            }
+
+            write!(f, ".{}", nanos)?;
+        }
+
+        f.write_str("Z")
+    }
+}
+
+#[cfg(feature = "kv_unstable_uuid")]
+mod uuid_support {
+    use super::*;
+
+    impl ToValue for uuid::Uuid {
+        fn to_value(&self) -> Value {
+            Value::from_uuid(*self)
+        }
+    }
+
+    impl<'v> From<uuid::Uuid> for Value<'v> {
+        fn from(v: uuid::Uuid) -> Self {
+            Value::from_uuid(v)
+        }
+    }
+
+    impl<'v> Value<'v> {
+        /// Get a value from a `Uuid`.
+        ///
+        /// UUIDs are formatted and serialized as strings in their lowercase hyphenated
+        /// form, like `67e55044-10b1-426f-9247-bb680e5fe0c8`.
+        pub fn from_uuid(value: uuid::Uuid) -> Self {
+            Value {
+                inner: inner::Inner::from_uuid(value),
+            }
+        }
+
+        /// Try convert this value into a `Uuid`.
+        pub fn to_uuid(&self) -> Option<uuid::Uuid> {
+            self.inner.to_uuid()
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn uuid() {
+            let id = uuid::Uuid::from_u128(0x67e5504410b1426f9247bb680e5fe0c8);
+            let v = id.to_value();
+
+            assert_eq!(Some(id), v.to_uuid());
+            assert!(Value::from("67e55044-10b1-426f-9247-bb680e5fe0c8")
+                .to_uuid()
+                .is_none());
+
+            assert_eq!("67e55044-10b1-426f-9247-bb680e5fe0c8", v.to_string());
+            assert_eq!("67e55044-10b1-426f-9247-bb680e5fe0c8", format!("{:?}", v));
+        }
+    }
+}
+
+/**
+Decimals are captured using their `Display` implementations, so they're formatted and
+serialized as strings without losing any precision.
+*/
+#[cfg(feature = "kv_unstable_decimal")]
+mod decimal_support {
+    use super::*;
+
+    impl ToValue for rust_decimal::Decimal {
+        fn to_value(&self) -> Value {
+            Value::from_display(self)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn decimal() {
+            let v = rust_decimal::Decimal::from_i128_with_scale(12345678901234567890123, 4);
+
+            assert_eq!("1234567890123456789.0123", v.to_value().to_string());
+            assert_eq!(None, v.to_value().to_f64());
+
+            #[cfg(feature = "kv_serde")]
+            assert_eq!(
+                r#""1234567890123456789.0123""#,
+                serde_json::to_string(&v.to_value()).unwrap()
+            );
+        }
+    }
+}
+
+/**
+Big integers are captured using their `Display` implementations, so they're formatted and
+serialized as strings without losing any precision.
+*/
+#[cfg(feature = "kv_unstable_bigint")]
+mod bigint_support {
+    use super::*;
+
+    impl ToValue for num_bigint::BigInt {
+        fn to_value(&self) -> Value {
+            Value::from_display(self)
+        }
+    }
+
+    impl ToValue for num_bigint::BigUint {
+        fn to_value(&self) -> Value {
+            Value::from_display(self)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn bigint() {
+            let v: num_bigint::BigInt = "-123456789012345678901234567890123456789012"
+                .parse()
+                .unwrap();
+
+            assert_eq!(
+                "-123456789012345678901234567890123456789012",
+                v.to_value().to_string()
+            );
+            assert_eq!(None, v.to_value().to_i128());
+
+            let v = num_bigint::BigUint::from(u128::MAX) * 2u32;
+
+            assert_eq!(
+                "680564733841876926926749214863536422910",
+                v.to_value().to_string()
+            );
+        }
+    }
+}
+
+#[cfg(any(feature = "kv_unstable_time", feature = "kv_unstable_chrono"))]
+mod time_support {
+    use super::*;
+
+    /**
+    A timestamp from a date and time library that can be formatted as RFC 3339.
+    */
+    pub trait Timestamp {
+        fn to_rfc3339(&self) -> String;
+    }
+
+    impl Timestamp for Box<str> {
+        fn to_rfc3339(&self) -> String {
+            (**self).into()
+        }
+    }
+
+    #[cfg(feature = "kv_unstable_time")]
+    impl Timestamp for time::OffsetDateTime {
+        fn to_rfc3339(&self) -> String {
+            self.format(&time::format_description::well_known::Rfc3339)
+                .unwrap_or_else(|_| self.to_string())
+        }
+    }
+
+    #[cfg(feature = "kv_unstable_chrono")]
+    impl<Tz: chrono::TimeZone> Timestamp for chrono::DateTime<Tz>
+    where
+        Tz::Offset: fmt::Display,
+    {
+        fn to_rfc3339(&self) -> String {
+            chrono::DateTime::to_rfc3339(self)
+        }
+    }
+
+    #[cfg(feature = "kv_unstable_time")]
+    impl ToValue for time::OffsetDateTime {
+        fn to_value(&self) -> Value {
+            Value {
+                inner: inner::Inner::from_timestamp(self),
+            }
+        }
+    }
+
+    #[cfg(feature = "kv_unstable_time")]
+    impl<'v> From<&'v time::OffsetDateTime> for Value<'v> {
+        fn from(v: &'v time::OffsetDateTime) -> Self {
+            v.to_value()
+        }
+    }
+
+    #[cfg(feature = "kv_unstable_chrono")]
+    impl<Tz: chrono::TimeZone> ToValue for chrono::DateTime<Tz>
+    where
+        Tz::Offset: fmt::Display,
+    {
+        fn to_value(&self) -> Value {
+            Value {
+                inner: inner::Inner::from_timestamp(self),
+            }
+        }
+    }
+
+    #[cfg(feature = "kv_unstable_chrono")]
+    impl<'v, Tz: chrono::TimeZone> From<&'v chrono::DateTime<Tz>> for Value<'v>
+    where
+        Tz::Offset: fmt::Display,
+    {
+        fn from(v: &'v chrono::DateTime<Tz>) -> Self {
+            v.to_value()
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        struct Extract(Option<String>);
+
+        impl<'v> VisitValue<'v> for Extract {
+            fn visit_any(&mut self, value: Value) -> Result<(), Error> {
+                unimplemented!("unexpected value: {value:?}")
+            }
+
+            fn visit_timestamp(&mut self, value: &str) -> Result<(), Error> {
+                self.0 = Some(value.into());
+
+                Ok(())
+            }
+        }
+
+        #[test]
+        #[cfg(feature = "kv_unstable_time")]
+        fn time() {
+            let ts =
+                time::OffsetDateTime::UNIX_EPOCH + time::Duration::new(1_700_000_000, 500_000_000);
+            let v = ts.to_value();
+
+            assert_eq!("2023-11-14T22:13:20.5Z", v.to_string());
+            assert_eq!(
+                Some("2023-11-14T22:13:20.5Z"),
+                v.to_owned().by_ref().to_cow_str().as_deref()
+            );
+
+            let mut extract = Extract(None);
+            v.visit(&mut extract).unwrap();
+
+            assert_eq!(Some("2023-11-14T22:13:20.5Z"), extract.0.as_deref());
+        }
+
+        #[test]
+        #[cfg(feature = "kv_unstable_chrono")]
+        fn chrono() {
+            let ts = chrono::DateTime::from_timestamp(1_700_000_000, 0)
+                .unwrap()
+                .with_timezone(&chrono::FixedOffset::east_opt(3_600).unwrap());
+            let v = ts.to_value();
+
+            assert_eq!("2023-11-14T23:13:20+01:00", v.to_string());
+            assert_eq!("2023-11-14T23:13:20+01:00", v.to_owned().to_string());
+
+            let mut extract = Extract(None);
+            v.visit(&mut extract).unwrap();
+
+            assert_eq!(Some("2023-11-14T23:13:20+01:00"), extract.0.as_deref());
+        }
+    }
+}
+
+#[cfg(feature = "kv_serde")]
+mod serde_support {
+    use super::*;
+
+    use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess};
+    use serde::ser::{Serialize, SerializeMap, SerializeSeq, Serializer};
+
+    impl<'de> Deserialize<'de> for OwnedValue {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_any(OwnedValueVisitor)
+        }
+    }
+
+    struct OwnedValueVisitor;
+
+    impl<'de> de::Visitor<'de> for OwnedValueVisitor {
+        type Value = OwnedValue;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a structured value")
+        }
+
+        fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+            Ok(Value::from(v).to_owned())
+        }
+
+        fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+            Ok(Value::from(v).to_owned())
+        }
+
+        fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+            Ok(Value::from(v).to_owned())
+        }
+
+        fn visit_i128<E: de::Error>(self, v: i128) -> Result<Self::Value, E> {
+            Ok(Value::from(v).to_owned())
+        }
+
+        fn visit_u128<E: de::Error>(self, v: u128) -> Result<Self::Value, E> {
+            Ok(Value::from(v).to_owned())
+        }
+
+        fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+            Ok(Value::from(v).to_owned())
+        }
+
+        fn visit_char<E: de::Error>(self, v: char) -> Result<Self::Value, E> {
+            Ok(Value::from(v).to_owned())
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+            Ok(Value::from(v).to_owned())
+        }
+
+        fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+            Ok(Structured::Bytes(v.into()).into_owned())
+        }
+
+        fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+            Ok(Value::null().to_owned())
+        }
+
+        fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+            Ok(Value::null().to_owned())
+        }
+
+        fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            OwnedValue::deserialize(deserializer)
+        }
+
+        fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            OwnedValue::deserialize(deserializer)
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut elems = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+            while let Some(elem) = seq.next_element()? {
+                elems.push(elem);
+            }
+
+            Ok(Structured::Seq(elems).into_owned())
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+        where
+            A: MapAccess<'de>,
+        {
+            let mut entries = Vec::with_capacity(map.size_hint().unwrap_or(0));
+            while let Some(entry) = map.next_entry()? {
+                entries.push(entry);
+            }
+
+            Ok(Structured::Map(entries).into_owned())
+        }
+    }
+
+    /// A deserialized value that doesn't map to a primitive.
+    ///
+    /// These are captured as `serde` values, so they keep their structure.
+    enum Structured {
+        Bytes(Box<[u8]>),
+        Seq(Vec<OwnedValue>),
+        Map(Vec<(OwnedValue, OwnedValue)>),
+    }
+
+    impl Structured {
+        fn into_owned(self) -> OwnedValue {
+            OwnedValue {
+                inner: inner::OwnedInner::Bag(
+                    value_bag::ValueBag::capture_shared_serde1(self).to_owned(),
+                ),
+            }
+        }
+    }
+
+    impl Serialize for Structured {
+        fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            match self {
+                Structured::Bytes(bytes) => s.serialize_bytes(bytes),
+                Structured::Seq(elems) => {
+                    let mut seq = s.serialize_seq(Some(elems.len()))?;
+                    for elem in elems {
+                        seq.serialize_element(elem)?;
+                    }
+                    seq.end()
+                }
+                Structured::Map(entries) => {
+                    let mut map = s.serialize_map(Some(entries.len()))?;
+                    for (key, value) in entries {
+                        map.serialize_entry(key, value)?;
+                    }
+                    map.end()
+                }
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn deserialize_primitive() {
+            let value: OwnedValue = serde_json::from_str("42").unwrap();
+            assert_eq!(Some(42), value.by_ref().to_u64());
+
+            let value: OwnedValue = serde_json::from_str("-42").unwrap();
+            assert_eq!(Some(-42), value.by_ref().to_i64());
+
+            let value: OwnedValue = serde_json::from_str("true").unwrap();
+            assert_eq!(Some(true), value.by_ref().to_bool());
+
+            let value: OwnedValue = serde_json::from_str("\"a string\"").unwrap();
+            assert_eq!(Some("a string"), value.by_ref().to_borrowed_str());
+
+            let value: OwnedValue = serde_json::from_str("null").unwrap();
+            assert_eq!("None", value.to_string());
+        }
+
+        #[test]
+        fn deserialize_roundtrip() {
+            let json = r#"{"a":1,"b":[true,"c",null],"d":{"e":1.5}}"#;
+
+            let value: OwnedValue = serde_json::from_str(json).unwrap();
+
+            assert_eq!(json, serde_json::to_string(&value).unwrap());
+        }
+    }
+}
+
+#[cfg(feature = "kv_json")]
+mod json_support {
+    use super::*;
+
+    impl ToValue for serde_json::Value {
+        fn to_value(&self) -> Value {
+            Value::from_json(self)
+        }
+    }
+
+    impl<'v> From<&'v serde_json::Value> for Value<'v> {
+        fn from(value: &'v serde_json::Value) -> Self {
+            Value::from_json(value)
+        }
+    }
+
+    impl<'v> Value<'v> {
+        /// Get a value from a JSON value.
+        ///
+        /// Nulls, booleans, numbers, and strings are captured as primitives.
+        /// Arrays and objects are captured using their `serde::Serialize` implementation.
+        pub fn from_json(value: &'v serde_json::Value) -> Self {
+            match value {
+                serde_json::Value::Null => Value::null(),
+                serde_json::Value::Bool(v) => Value::from(*v),
+                serde_json::Value::String(v) => Value::from(&**v),
+                serde_json::Value::Number(v) => {
+                    if let Some(v) = v.as_u64() {
+                        Value::from(v)
+                    } else if let Some(v) = v.as_i64() {
+                        Value::from(v)
+                    } else if let Some(v) = v.as_f64() {
+                        Value::from(v)
+                    } else {
+                        Value::from_serde(value)
+                    }
+                }
+                serde_json::Value::Array(_) | serde_json::Value::Object(_) => {
+                    Value::from_serde(value)
+                }
+            }
+        }
+
+        /// Get a value from a string that's already valid JSON.
+        ///
+        /// The JSON is embedded verbatim when the value is serialized using `serde_json`,
+        /// instead of being encoded as a string. If `json` isn't valid JSON, it's captured
+        /// as a regular string instead.
+        pub fn from_raw_json(json: &'v str) -> Self {
+            match serde_json::from_str::<&'v serde_json::value::RawValue>(json) {
+                Ok(raw) => Value {
+                    inner: inner::Inner::from_raw_json(raw),
+                },
+                Err(_) => Value::from(json),
+            }
+        }
+
+        /// Try get the raw JSON this value was captured from.
+        ///
+        /// This method only returns `Some` for values captured using [`Value::from_raw_json`].
+        pub fn to_raw_json(&self) -> Option<&'v str> {
+            self.inner.to_raw_json()
+        }
+
+        /// Convert this value into a JSON value.
+        ///
+        /// If the value can't be represented as JSON, such as a map with
+        /// non-string keys, then its `Display` implementation is used to
+        /// produce a JSON string instead.
+        pub fn to_json(&self) -> serde_json::Value {
+            serde_json::to_value(self)
+                .unwrap_or_else(|_| serde_json::Value::String(self.to_string()))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        use serde_json::json;
+
+        #[test]
+        fn from_json_primitive() {
+            assert_eq!(
+                inner::Token::U64(42),
+                Value::from_json(&json!(42)).to_token()
+            );
+            assert_eq!(
+                inner::Token::I64(-42),
+                Value::from_json(&json!(-42)).to_token()
+            );
+            assert_eq!(
+                inner::Token::F64(4.2),
+                Value::from_json(&json!(4.2)).to_token()
+            );
+            assert_eq!(
+                inner::Token::Bool(true),
+                Value::from_json(&json!(true)).to_token()
+            );
+            assert_eq!(
+                inner::Token::Str("a string".into()),
+                Value::from_json(&json!("a string")).to_token()
+            );
+            assert_eq!(
+                inner::Token::None,
+                Value::from_json(&json!(null)).to_token()
+            );
+        }
+
+        #[test]
+        fn json_roundtrip() {
+            let json = json!({
+                "a": 1,
+                "b": [true, "c", null],
+                "d": {
+                    "e": 1.5,
+                },
+            });
+
+            assert_eq!(json, Value::from_json(&json).to_json());
+        }
+
+        #[test]
+        fn raw_json() {
+            let v = Value::from_raw_json(r#"{"a":[1,true]}"#);
+
+            assert_eq!(Some(r#"{"a":[1,true]}"#), v.to_raw_json());
+            assert_eq!(r#"{"a":[1,true]}"#, v.to_string());
+            assert_eq!(
+                r#"{"payload":{"a":[1,true]}}"#,
+                serde_json::to_string(&json!({ "payload": v.to_json() })).unwrap()
+            );
+            assert_eq!(
+                r#"[{"a":[1,true]}]"#,
+                serde_json::to_string(&[v.to_value()]).unwrap()
+            );
+            assert_eq!(json!({ "a": [1, true] }), v.to_json());
+
+            let owned = v.to_owned();
+            assert_eq!(Some(r#"{"a":[1,true]}"#), owned.by_ref().to_raw_json());
+        }
+
+        #[test]
+        fn raw_json_invalid() {
+            let v = Value::from_raw_json("not json");
+
+            assert_eq!(None, v.to_raw_json());
+            assert_eq!(Some("not json"), v.to_borrowed_str());
+            assert_eq!(r#""not json""#, serde_json::to_string(&v).unwrap());
+        }
+
+        #[test]
+        #[cfg(feature = "kv_sval")]
+        fn raw_json_sval() {
+            let v = Value::from_raw_json(r#"{"a":[1,true]}"#);
+
+            assert_eq!(
+                r#"{"a":[1,true]}"#,
+                serde_json::to_string(&value_bag::ValueBag::from_sval2(&v)).unwrap()
+            );
+        }
+
+        #[test]
+        fn to_json() {
+            assert_eq!(json!(42), Value::from(42).to_json());
+            assert_eq!(json!("a string"), Value::from("a string").to_json());
+            assert_eq!(json!("[1, 2]"), Value::from_debug(&vec![1, 2]).to_json());
+        }
+    }
+}
+
+/// A visitor for a [`Value`].
+///
+/// Also see [`Value`'s documentation on seralization]. Value visitors are a simple alternative
+/// to a more fully-featured serialization framework like `serde` or `sval`. A value visitor
+/// can differentiate primitive types through methods like [`VisitValue::visit_bool`] and
+/// [`VisitValue::visit_str`]. Sequences and maps captured using [`Value::from_seq`] and
+/// [`Value::from_map`] are visited through [`VisitValue::visit_seq`] and [`VisitValue::visit_map`],
+/// but other complex types, like values captured using `serde` or `sval`, will fallthrough
+/// to [`VisitValue::visit_any`].
+///
+/// If you're trying to serialize a value to a format like JSON, you can use either `serde`
+/// or `sval` directly with the value. You don't need a visitor.
+///
+/// [`Value`'s documentation on seralization]: Value#serialization
+pub trait VisitValue<'v> {
+    /// Visit a `Value`.
+    ///
+    /// This is the only required method on `VisitValue` and acts as a fallback for any
+    /// more specific methods that aren't overridden.
+    /// The `Value` may be formatted using its `fmt::Debug` or `fmt::Display` implementation,
+    /// or serialized using its `sval::Value` or `serde::Serialize` implementation.
+    fn visit_any(&mut self, value: Value) -> Result<(), Error>;
+
+    /// Visit a `null` value, like [`Value::null`] or `None`.
+    fn visit_null(&mut self) -> Result<(), Error> {
+        self.visit_any(Value::null())
+    }
+
+    /// Visit an unsigned integer.
+    fn visit_u64(&mut self, value: u64) -> Result<(), Error> {
+        self.visit_any(value.into())
+    }
+
+    /// Visit a signed integer.
+    fn visit_i64(&mut self, value: i64) -> Result<(), Error> {
+        self.visit_any(value.into())
+    }
+
+    /// Visit a big unsigned integer.
+    fn visit_u128(&mut self, value: u128) -> Result<(), Error> {
+        self.visit_any((value).into())
+    }
+
+    /// Visit a big signed integer.
+    fn visit_i128(&mut self, value: i128) -> Result<(), Error> {
+        self.visit_any((value).into())
+    }
+
+    /// Visit a floating point.
+    fn visit_f64(&mut self, value: f64) -> Result<(), Error> {
+        self.visit_any(value.into())
+    }
+
+    /// Visit a boolean.
+    fn visit_bool(&mut self, value: bool) -> Result<(), Error> {
+        self.visit_any(value.into())
+    }
+
+    /// Visit a string.
+    fn visit_str(&mut self, value: &str) -> Result<(), Error> {
+        self.visit_any(value.into())
+    }
+
+    /// Visit a string.
+    fn visit_borrowed_str(&mut self, value: &'v str) -> Result<(), Error> {
+        self.visit_str(value)
+    }
+
+    /// Visit a Unicode character.
+    fn visit_char(&mut self, value: char) -> Result<(), Error> {
+        let mut b = [0; 4];
+        self.visit_str(&*value.encode_utf8(&mut b))
+    }
+
+    /// Visit a byte slice.
+    fn visit_bytes(&mut self, value: &'v [u8]) -> Result<(), Error> {
+        self.visit_any(Value::from_bytes(value))
+    }
+
+    /// Visit a sequence.
+    ///
+    /// The values in the sequence can be visited using [`Seq::visit`].
+    fn visit_seq(&mut self, seq: &'v dyn Seq) -> Result<(), Error> {
+        self.visit_any(Value::from_dyn_seq(seq))
+    }
+
+    /// Visit a map.
+    ///
+    /// The key-values in the map can be visited using [`Source::visit`].
+    fn visit_map(&mut self, map: &'v dyn Source) -> Result<(), Error> {
+        self.visit_any(Value::from_dyn_map(map))
+    }
+
+    /// Visit a timestamp, formatted as RFC 3339.
+    ///
+    /// Timestamps from date and time libraries like `time` and `chrono` are visited
+    /// through this method, so it can be overridden to emit a native timestamp type.
+    #[cfg(feature = "kv_std")]
+    fn visit_timestamp(&mut self, rfc3339: &str) -> Result<(), Error> {
+        self.visit_str(rfc3339)
+    }
+
+    /// Visit a duration.
+    #[cfg(feature = "kv_std")]
+    fn visit_duration(&mut self, value: std::time::Duration) -> Result<(), Error> {
+        self.visit_any(Value::from_duration(value))
+    }
+
+    /// Visit a system time.
+    #[cfg(feature = "kv_std")]
+    fn visit_system_time(&mut self, value: std::time::SystemTime) -> Result<(), Error> {
+        self.visit_any(Value::from_system_time(value))
+    }
+
+    /// Visit an error.
+    #[cfg(feature = "kv_std")]
+    fn visit_error(&mut self, err: &(dyn std::error::Error + 'static)) -> Result<(), Error> {
+        self.visit_any(Value::from_dyn_error(err))
+    }
+
+    /// Visit an error.
+    #[cfg(feature = "kv_std")]
+    fn visit_borrowed_error(
+        &mut self,
+        err: &'v (dyn std::error::Error + 'static),
+    ) -> Result<(), Error> {
+        self.visit_any(Value::from_dyn_error(err))
+    }
+}
+
+impl<'a, 'v, T: ?Sized> VisitValue<'v> for &'a mut T
+where
+    T: VisitValue<'v>,
+{
+    fn visit_any(&mut self, value: Value) -> Result<(), Error> {
+        (**self).visit_any(value)
+    }
+
+    fn visit_null(&mut self) -> Result<(), Error> {
+        (**self).visit_null()
+    }
+
+    fn visit_u64(&mut self, value: u64) -> Result<(), Error> {
+        (**self).visit_u64(value)
+    }
+
+    fn visit_i64(&mut self, value: i64) -> Result<(), Error> {
+        (**self).visit_i64(value)
+    }
+
+    fn visit_u128(&mut self, value: u128) -> Result<(), Error> {
+        (**self).visit_u128(value)
+    }
+
+    fn visit_i128(&mut self, value: i128) -> Result<(), Error> {
+        (**self).visit_i128(value)
+    }
+
+    fn visit_f64(&mut self, value: f64) -> Result<(), Error> {
+        (**self).visit_f64(value)
+    }
+
+    fn visit_bool(&mut self, value: bool) -> Result<(), Error> {
+        (**self).visit_bool(value)
+    }
+
+    fn visit_str(&mut self, value: &str) -> Result<(), Error> {
+        (**self).visit_str(value)
+    }
+
+    fn visit_borrowed_str(&mut self, value: &'v str) -> Result<(), Error> {
+        (**self).visit_borrowed_str(value)
+    }
+
+    fn visit_char(&mut self, value: char) -> Result<(), Error> {
+        (**self).visit_char(value)
+    }
+
+    fn visit_bytes(&mut self, value: &'v [u8]) -> Result<(), Error> {
+        (**self).visit_bytes(value)
+    }
+
+    fn visit_seq(&mut self, seq: &'v dyn Seq) -> Result<(), Error> {
+        (**self).visit_seq(seq)
+    }
+
+    fn visit_map(&mut self, map: &'v dyn Source) -> Result<(), Error> {
+        (**self).visit_map(map)
+    }
+
+    #[cfg(feature = "kv_std")]
+    fn visit_timestamp(&mut self, rfc3339: &str) -> Result<(), Error> {
+        (**self).visit_timestamp(rfc3339)
+    }
+
+    #[cfg(feature = "kv_std")]
+    fn visit_duration(&mut self, value: std::time::Duration) -> Result<(), Error> {
+        (**self).visit_duration(value)
+    }
+
+    #[cfg(feature = "kv_std")]
+    fn visit_system_time(&mut self, value: std::time::SystemTime) -> Result<(), Error> {
+        (**self).visit_system_time(value)
+    }
+
+    #[cfg(feature = "kv_std")]
+    fn visit_error(&mut self, err: &(dyn std::error::Error + 'static)) -> Result<(), Error> {
+        (**self).visit_error(err)
+    }
+
+    #[cfg(feature = "kv_std")]
+    fn visit_borrowed_error(
+        &mut self,
+        err: &'v (dyn std::error::Error + 'static),
+    ) -> Result<(), Error> {
+        (**self).visit_borrowed_error(err)
+    }
+}
+
+#[cfg(feature = "value-bag")]
+pub(in crate::kv) mod inner {
+    /**
+    An implementation of `Value` based on a library called `value_bag`.
+
+    `value_bag` was written specifically for use in `log`'s value, but was split out when it outgrew
+    the codebase here. It's a general-purpose type-erasure library that handles mapping between
+    more fully-featured serialization frameworks.
+    */
+    use super::*;
+
+    use value_bag::ValueBag;
+
+    pub use value_bag::Error;
+
+    /**
+    Most values are stored in a `ValueBag`. Any kinds of values it doesn't support are stored alongside it.
+    */
+    #[derive(Clone)]
+    pub enum Inner<'v> {
+        Bag(ValueBag<'v>),
+        Bytes(&'v [u8]),
+        Seq(&'v dyn Seq),
+        Map(&'v dyn Source),
+        Any(&'v dyn any_support::AnyValue),
+        Redacted(&'v dyn ToValue),
+        Unit(unit_support::Measured),
+        Hinted(&'v dyn ToValue, FormatHint),
+        #[cfg(feature = "kv_std")]
+        Duration(std::time::Duration),
+        #[cfg(feature = "kv_std")]
+        SystemTime(std::time::SystemTime),
+        #[cfg(feature = "kv_std")]
+        IpAddr(&'v dyn std_support::IpAddrValue),
+        #[cfg(feature = "kv_std")]
+        SocketAddr(&'v dyn std_support::SocketAddrValue),
+        #[cfg(feature = "kv_unstable_uuid")]
+        Uuid(uuid::Uuid),
+        #[cfg(any(feature = "kv_unstable_time", feature = "kv_unstable_chrono"))]
+        Timestamp(&'v dyn time_support::Timestamp),
+        #[cfg(feature = "kv_json")]
+        Json(&'v serde_json::value::RawValue),
+    }
+
+    #[cfg(feature = "std")]
+    #[derive(Clone)]
+    pub enum OwnedInner {
+        Bag(value_bag::OwnedValueBag),
+        Bytes(Box<[u8]>),
+        Seq(Vec<OwnedValue>),
+        Map(crate::kv::OwnedSource),
+        Unit(unit_support::Measured),
+        #[cfg(feature = "kv_std")]
+        Duration(std::time::Duration),
+        #[cfg(feature = "kv_std")]
+        SystemTime(std::time::SystemTime),
+        #[cfg(feature = "kv_std")]
+        IpAddr(std::net::IpAddr),
+        #[cfg(feature = "kv_std")]
+        SocketAddr(std::net::SocketAddr),
+        #[cfg(feature = "kv_unstable_uuid")]
+        Uuid(uuid::Uuid),
+        #[cfg(any(feature = "kv_unstable_time", feature = "kv_unstable_chrono"))]
+        Timestamp(Box<str>),
+        #[cfg(feature = "kv_json")]
+        Json(Box<serde_json::value::RawValue>),
+    }
+
+    macro_rules! impl_from_primitive {
+        ($($ty:ty,)*) => {
+            $(
+                impl<'v> From<$ty> for Inner<'v> {
+                    fn from(v: $ty) -> Self {
+                        Inner::Bag(ValueBag::from(v))
+                    }
+                }
+            )*
+        };
+    }
+
+    impl_from_primitive![
+        (),
+        bool,
+        char,
+        f32,
+        f64,
+        i8,
+        i16,
+        i32,
+        i64,
+        i128,
+        isize,
+        u8,
+        u16,
+        u32,
+        u64,
+        u128,
+        usize,
+        &'v str,
+    ];
+
+    macro_rules! impl_to_primitive {
+        ($($into_name:ident -> $into_ty:ty,)*) => {
+            $(
+                pub fn $into_name(&self) -> Option<$into_ty> {
+                    match self {
+                        Inner::Bag(v) => v.$into_name(),
+                        Inner::Any(v) => any_support::resolve(*v).$into_name(),
+                        Inner::Redacted(_) => redacted_support::resolve().$into_name(),
+                        Inner::Hinted(v, _) => hint_support::resolve(*v).$into_name(),
+                        Inner::Unit(v) => v.resolve().$into_name(),
+                        _ => None,
+                    }
+                }
+            )*
+        };
+    }
+
+    impl<'v> Inner<'v> {
+        pub fn from_concrete<T: ToValue + 'static>(value: &'v T) -> Self {
+            Inner::Any(value)
+        }
+
+        pub fn from_redacted<T: ToValue>(value: &'v T) -> Self {
+            Inner::Redacted(value)
+        }
+
+        pub fn from_unit(value: unit_support::Measured) -> Self {
+            Inner::Unit(value)
+        }
+
+        pub fn from_hinted<T: ToValue>(value: &'v T, hint: FormatHint) -> Self {
+            Inner::Hinted(value, hint)
+        }
+
+        pub fn from_debug<T: fmt::Debug>(value: &'v T) -> Self {
+            Inner::Bag(ValueBag::from_debug(value))
+        }
+
+        #[cfg(feature = "kv_unstable")]
+        pub fn capture_debug<T: fmt::Debug + 'static>(value: &'v T) -> Self {
+            Inner::Bag(ValueBag::capture_debug(value))
+        }
+
+        #[cfg(feature = "kv_unstable")]
+        pub fn capture_display<T: fmt::Display + 'static>(value: &'v T) -> Self {
+            Inner::Bag(ValueBag::capture_display(value))
+        }
+
+        #[cfg(feature = "kv_unstable_std")]
+        pub fn capture_error<T: std::error::Error + 'static>(value: &'v T) -> Self {
+            Inner::Bag(ValueBag::capture_error(value))
+        }
+
+        #[cfg(feature = "kv_unstable_serde")]
+        pub fn capture_serde1<T: serde::Serialize + 'static>(value: &'v T) -> Self {
+            Inner::Bag(ValueBag::capture_serde1(value))
+        }
+
+        #[cfg(feature = "kv_unstable_sval")]
+        pub fn capture_sval2<T: sval::Value + 'static>(value: &'v T) -> Self {
+            Inner::Bag(ValueBag::capture_sval2(value))
+        }
+
+        pub fn from_display<T: fmt::Display>(value: &'v T) -> Self {
+            Inner::Bag(ValueBag::from_display(value))
+        }
+
+        #[cfg(feature = "kv_serde")]
+        pub fn from_serde1<T: serde::Serialize>(value: &'v T) -> Self {
+            Inner::Bag(ValueBag::from_serde1(value))
+        }
+
+        #[cfg(feature = "kv_sval")]
+        pub fn from_sval2<T: sval::Value>(value: &'v T) -> Self {
+            Inner::Bag(ValueBag::from_sval2(value))
+        }
+
+        pub fn from_dyn_debug(value: &'v dyn fmt::Debug) -> Self {
+            Inner::Bag(ValueBag::from_dyn_debug(value))
+        }
+
+        pub fn from_dyn_display(value: &'v dyn fmt::Display) -> Self {
+            Inner::Bag(ValueBag::from_dyn_display(value))
+        }
+
+        #[cfg(feature = "kv_std")]
+        pub fn from_dyn_error(value: &'v (dyn std::error::Error + 'static)) -> Self {
+            Inner::Bag(ValueBag::from_dyn_error(value))
+        }
+
+        pub fn from_bytes(value: &'v [u8]) -> Self {
+            Inner::Bytes(value)
+        }
+
+        pub fn from_seq(value: &'v dyn Seq) -> Self {
+            Inner::Seq(value)
+        }
+
+        pub fn from_map(value: &'v dyn Source) -> Self {
+            Inner::Map(value)
+        }
+
+        pub fn empty() -> Self {
+            Inner::Bag(ValueBag::empty())
+        }
+
+        impl_to_primitive![
+            to_u64 -> u64,
+            to_i64 -> i64,
+            to_u128 -> u128,
+            to_i128 -> i128,
+            to_f64 -> f64,
+            to_char -> char,
+            to_bool -> bool,
+        ];
+
+        pub fn to_borrowed_str(&self) -> Option<&'v str> {
+            match self {
+                Inner::Bag(v) => v.to_borrowed_str(),
+                Inner::Any(v) => any_support::resolve(*v).to_borrowed_str(),
+                Inner::Redacted(_) => redacted_support::resolve().to_borrowed_str(),
+                Inner::Hinted(v, _) => hint_support::resolve(*v).to_borrowed_str(),
+                Inner::Unit(v) => v.resolve().to_borrowed_str(),
+                _ => None,
+            }
+        }
+
+        #[cfg(feature = "kv_std")]
+        pub fn to_borrowed_error(&self) -> Option<&'v (dyn std::error::Error + 'static)> {
+            match self {
+                Inner::Bag(v) => v.to_borrowed_error(),
+                Inner::Any(v) => any_support::resolve(*v).to_borrowed_error(),
+                Inner::Redacted(_) => redacted_support::resolve().to_borrowed_error(),
+                Inner::Hinted(v, _) => hint_support::resolve(*v).to_borrowed_error(),
+                Inner::Unit(v) => v.resolve().to_borrowed_error(),
+                _ => None,
+            }
+        }
+
+        #[cfg(feature = "std")]
+        pub fn to_str(&self) -> Option<std::borrow::Cow<'v, str>> {
+            match self {
+                Inner::Bag(v) => v
+                    .to_str()
+                    .or_else(|| v.to_char().map(|v| v.to_string().into())),
+                #[cfg(feature = "kv_std")]
+                Inner::IpAddr(v) => Some(v.to_ip_addr().to_string().into()),
+                #[cfg(feature = "kv_std")]
+                Inner::SocketAddr(v) => Some(v.to_socket_addr().to_string().into()),
+                #[cfg(feature = "kv_unstable_uuid")]
+                Inner::Uuid(v) => Some(v.to_string().into()),
+                #[cfg(any(feature = "kv_unstable_time", feature = "kv_unstable_chrono"))]
+                Inner::Timestamp(v) => Some(v.to_rfc3339().into()),
+                Inner::Any(v) => any_support::resolve(*v).to_str(),
+                Inner::Redacted(_) => redacted_support::resolve().to_str(),
+                Inner::Hinted(v, _) => hint_support::resolve(*v).to_str(),
+                Inner::Unit(v) => v.resolve().to_str(),
+                _ => None,
+            }
+        }
+
+        pub fn to_borrowed_bytes(&self) -> Option<&'v [u8]> {
+            match self {
+                Inner::Bytes(v) => Some(v),
+                Inner::Any(v) => any_support::resolve(*v).to_borrowed_bytes(),
+                Inner::Redacted(_) => redacted_support::resolve().to_borrowed_bytes(),
+                Inner::Hinted(v, _) => hint_support::resolve(*v).to_borrowed_bytes(),
+                Inner::Unit(v) => v.resolve().to_borrowed_bytes(),
+                _ => None,
+            }
+        }
+
+        pub fn as_seq(&self) -> Option<&'v dyn Seq> {
+            match self {
+                Inner::Seq(v) => Some(*v),
+                Inner::Any(v) => any_support::resolve(*v).as_seq(),
+                Inner::Redacted(_) => redacted_support::resolve().as_seq(),
+                Inner::Hinted(v, _) => hint_support::resolve(*v).as_seq(),
+                Inner::Unit(v) => v.resolve().as_seq(),
+                _ => None,
+            }
+        }
+
+        pub fn as_map(&self) -> Option<&'v dyn Source> {
+            match self {
+                Inner::Map(v) => Some(*v),
+                Inner::Any(v) => any_support::resolve(*v).as_map(),
+                Inner::Redacted(_) => redacted_support::resolve().as_map(),
+                Inner::Hinted(v, _) => hint_support::resolve(*v).as_map(),
+                Inner::Unit(v) => v.resolve().as_map(),
+                _ => None,
+            }
+        }
+
+        pub fn kind(&self) -> ValueKind {
+            match self {
+                Inner::Bag(v) => {
+                    if v.is_empty() {
+                        ValueKind::Null
+                    } else if v.to_bool().is_some() {
+                        ValueKind::Bool
+                    } else if v.to_char().is_some() {
+                        ValueKind::Char
+                    } else if v.to_borrowed_str().is_some() {
+                        ValueKind::Str
+                    } else if v.to_u128().is_some() || v.to_i128().is_some() {
+                        ValueKind::Int
+                    } else if v.to_f64().is_some() {
+                        ValueKind::Float
+                    } else {
+                        #[cfg(feature = "kv_std")]
+                        {
+                            if v.to_borrowed_error().is_some() {
+                                return ValueKind::Error;
+                            }
+                        }
+
+                        ValueKind::Other
+                    }
+                }
+                Inner::Bytes(_) => ValueKind::Bytes,
+                Inner::Seq(_) => ValueKind::Seq,
+                Inner::Map(_) => ValueKind::Map,
+                Inner::Any(v) => any_support::resolve(*v).kind(),
+                Inner::Redacted(_) => redacted_support::resolve().kind(),
+                Inner::Hinted(v, _) => hint_support::resolve(*v).kind(),
+                Inner::Unit(v) => v.resolve().kind(),
+                #[allow(unreachable_patterns)]
+                _ => ValueKind::Other,
+            }
+        }
+
+        #[cfg(feature = "kv_std")]
+        pub fn from_duration(value: std::time::Duration) -> Self {
+            Inner::Duration(value)
+        }
+
+        #[cfg(feature = "kv_std")]
+        pub fn from_system_time(value: std::time::SystemTime) -> Self {
+            Inner::SystemTime(value)
+        }
+
+        #[cfg(feature = "kv_std")]
+        pub fn from_ip_addr(value: &'v dyn std_support::IpAddrValue) -> Self {
+            Inner::IpAddr(value)
+        }
+
+        #[cfg(feature = "kv_std")]
+        pub fn from_socket_addr(value: &'v dyn std_support::SocketAddrValue) -> Self {
+            Inner::SocketAddr(value)
+        }
+
+        #[cfg(feature = "kv_std")]
+        pub fn to_ip_addr(&self) -> Option<std::net::IpAddr> {
+            match self {
+                Inner::IpAddr(v) => Some(v.to_ip_addr()),
+                Inner::Any(v) => any_support::resolve(*v).to_ip_addr(),
+                Inner::Redacted(_) => redacted_support::resolve().to_ip_addr(),
+                Inner::Hinted(v, _) => hint_support::resolve(*v).to_ip_addr(),
+                Inner::Unit(v) => v.resolve().to_ip_addr(),
+                _ => None,
+            }
+        }
+
+        #[cfg(feature = "kv_std")]
+        pub fn to_socket_addr(&self) -> Option<std::net::SocketAddr> {
+            match self {
+                Inner::SocketAddr(v) => Some(v.to_socket_addr()),
+                Inner::Any(v) => any_support::resolve(*v).to_socket_addr(),
+                Inner::Redacted(_) => redacted_support::resolve().to_socket_addr(),
+                Inner::Hinted(v, _) => hint_support::resolve(*v).to_socket_addr(),
+                Inner::Unit(v) => v.resolve().to_socket_addr(),
+                _ => None,
+            }
+        }
+
+        #[cfg(feature = "kv_unstable_uuid")]
+        pub fn from_uuid(value: uuid::Uuid) -> Self {
+            Inner::Uuid(value)
+        }
+
+        #[cfg(any(feature = "kv_unstable_time", feature = "kv_unstable_chrono"))]
+        pub fn from_timestamp(value: &'v dyn time_support::Timestamp) -> Self {
+            Inner::Timestamp(value)
+        }
+
+        #[cfg(feature = "kv_json")]
+        pub fn from_raw_json(value: &'v serde_json::value::RawValue) -> Self {
+            Inner::Json(value)
+        }
+
+        #[cfg(feature = "kv_json")]
+        pub fn to_raw_json(&self) -> Option<&'v str> {
+            match self {
+                Inner::Json(v) => Some(v.get()),
+                Inner::Any(v) => any_support::resolve(*v).to_raw_json(),
+                Inner::Redacted(_) => redacted_support::resolve().to_raw_json(),
+                Inner::Hinted(v, _) => hint_support::resolve(*v).to_raw_json(),
+                Inner::Unit(v) => v.resolve().to_raw_json(),
+                _ => None,
+            }
+        }
+
+        #[cfg(feature = "kv_unstable_uuid")]
+        pub fn to_uuid(&self) -> Option<uuid::Uuid> {
+            match self {
+                Inner::Uuid(v) => Some(*v),
+                Inner::Any(v) => any_support::resolve(*v).to_uuid(),
+                Inner::Redacted(_) => redacted_support::resolve().to_uuid(),
+                Inner::Hinted(v, _) => hint_support::resolve(*v).to_uuid(),
+                Inner::Unit(v) => v.resolve().to_uuid(),
+                _ => None,
+            }
+        }
+
+        #[cfg(feature = "kv_std")]
+        pub fn to_duration(&self) -> Option<std::time::Duration> {
+            match self {
+                Inner::Duration(v) => Some(*v),
+                Inner::Any(v) => any_support::resolve(*v).to_duration(),
+                Inner::Redacted(_) => redacted_support::resolve().to_duration(),
+                Inner::Hinted(v, _) => hint_support::resolve(*v).to_duration(),
+                Inner::Unit(v) => v.resolve().to_duration(),
+                _ => None,
+            }
+        }
+
+        #[cfg(feature = "kv_std")]
+        pub fn to_system_time(&self) -> Option<std::time::SystemTime> {
+            match self {
+                Inner::SystemTime(v) => Some(*v),
+                Inner::Any(v) => any_support::resolve(*v).to_system_time(),
+                Inner::Redacted(_) => redacted_support::resolve().to_system_time(),
+                Inner::Hinted(v, _) => hint_support::resolve(*v).to_system_time(),
+                Inner::Unit(v) => v.resolve().to_system_time(),
+                _ => None,
+            }
+        }
+
+        pub fn downcast_ref<T: 'static>(&self) -> Option<&T> {
+            match self {
+                Inner::Bag(v) => v.downcast_ref(),
+                Inner::Any(v) => v.as_any().downcast_ref(),
+                _ => None,
+            }
+        }
+
+        pub fn to_unredacted(&self) -> Option<Value<'v>> {
+            match self {
+                Inner::Redacted(v) => Some(ToValue::to_value(*v)),
+                Inner::Hinted(v, _) => hint_support::resolve(*v).to_unredacted(),
+                _ => None,
+            }
+        }
+
+        pub fn to_unit(&self) -> Option<&'static str> {
+            match self {
+                Inner::Unit(v) => Some(v.unit()),
+                Inner::Hinted(v, _) => hint_support::resolve(*v).to_unit(),
+                _ => None,
+            }
+        }
+
+        pub fn to_format_hint(&self) -> Option<FormatHint> {
+            match self {
+                Inner::Hinted(_, hint) => Some(*hint),
+                _ => None,
+            }
+        }
+
+        #[cfg(feature = "std")]
+        pub fn to_owned(&self) -> OwnedInner {
+            match self {
+                Inner::Bag(v) => OwnedInner::Bag(v.to_owned()),
+                Inner::Bytes(v) => OwnedInner::Bytes((*v).into()),
+                Inner::Seq(v) => OwnedInner::Seq(structured::to_owned_seq(*v)),
+                Inner::Map(v) => OwnedInner::Map(crate::kv::OwnedSource::from_source(*v)),
+                Inner::Any(v) => any_support::resolve(*v).to_owned(),
+                Inner::Redacted(_) => redacted_support::resolve().to_owned(),
+                Inner::Hinted(v, _) => hint_support::resolve(*v).to_owned(),
+                Inner::Unit(v) => OwnedInner::Unit(*v),
+                #[cfg(feature = "kv_std")]
+                Inner::Duration(v) => OwnedInner::Duration(*v),
+                #[cfg(feature = "kv_std")]
+                Inner::SystemTime(v) => OwnedInner::SystemTime(*v),
+                #[cfg(feature = "kv_std")]
+                Inner::IpAddr(v) => OwnedInner::IpAddr(v.to_ip_addr()),
+                #[cfg(feature = "kv_std")]
+                Inner::SocketAddr(v) => OwnedInner::SocketAddr(v.to_socket_addr()),
+                #[cfg(feature = "kv_unstable_uuid")]
+                Inner::Uuid(v) => OwnedInner::Uuid(*v),
+                #[cfg(any(feature = "kv_unstable_time", feature = "kv_unstable_chrono"))]
+                Inner::Timestamp(v) => OwnedInner::Timestamp(v.to_rfc3339().into()),
+                #[cfg(feature = "kv_json")]
+                Inner::Json(v) => OwnedInner::Json((*v).to_owned()),
+            }
+        }
+
+        #[cfg(test)]
+        pub fn to_test_token(&self) -> Token {
+            match self {
+                Inner::Bag(v) => v.to_test_token(),
+                Inner::Any(v) => any_support::resolve(*v).to_test_token(),
+                Inner::Redacted(_) => redacted_support::resolve().to_test_token(),
+                Inner::Hinted(v, _) => hint_support::resolve(*v).to_test_token(),
+                Inner::Unit(v) => v.resolve().to_test_token(),
+                _ => unimplemented!(),
+            }
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl OwnedInner {
+        pub fn by_ref(&self) -> Inner<'_> {
+            match self {
+                OwnedInner::Bag(v) => Inner::Bag(v.by_ref()),
+                OwnedInner::Bytes(v) => Inner::Bytes(v),
+                OwnedInner::Seq(v) => Inner::Seq(v),
+                OwnedInner::Map(v) => Inner::Map(v),
+                OwnedInner::Unit(v) => Inner::Unit(*v),
+                #[cfg(feature = "kv_std")]
+                OwnedInner::Duration(v) => Inner::Duration(*v),
+                #[cfg(feature = "kv_std")]
+                OwnedInner::SystemTime(v) => Inner::SystemTime(*v),
+                #[cfg(feature = "kv_std")]
+                OwnedInner::IpAddr(v) => Inner::IpAddr(v),
+                #[cfg(feature = "kv_std")]
+                OwnedInner::SocketAddr(v) => Inner::SocketAddr(v),
+                #[cfg(feature = "kv_unstable_uuid")]
+                OwnedInner::Uuid(v) => Inner::Uuid(*v),
+                #[cfg(any(feature = "kv_unstable_time", feature = "kv_unstable_chrono"))]
+                OwnedInner::Timestamp(v) => Inner::Timestamp(v),
+                #[cfg(feature = "kv_json")]
+                OwnedInner::Json(v) => Inner::Json(v),
+            }
+        }
+    }
+
+    impl<'v> fmt::Debug for Inner<'v> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                Inner::Bag(v) => fmt::Debug::fmt(v, f),
+                Inner::Bytes(v) => fmt::Debug::fmt(v, f),
+                Inner::Seq(v) => structured::fmt_seq(*v, f),
+                Inner::Map(v) => structured::fmt_map(*v, f),
+                Inner::Any(v) => fmt::Debug::fmt(&any_support::resolve(*v), f),
+                Inner::Redacted(_) => fmt::Debug::fmt(&redacted_support::resolve(), f),
+                Inner::Hinted(v, hint) => hint_support::fmt(*v, *hint, f),
+                Inner::Unit(v) => fmt::Debug::fmt(&v.resolve(), f),
+                #[cfg(feature = "kv_std")]
+                Inner::Duration(v) => fmt::Debug::fmt(v, f),
+                #[cfg(feature = "kv_std")]
+                Inner::SystemTime(v) => std_support::fmt_system_time(v, f),
+                #[cfg(feature = "kv_std")]
+                Inner::IpAddr(v) => fmt::Display::fmt(&v.to_ip_addr(), f),
+                #[cfg(feature = "kv_std")]
+                Inner::SocketAddr(v) => fmt::Display::fmt(&v.to_socket_addr(), f),
+                #[cfg(feature = "kv_unstable_uuid")]
+                Inner::Uuid(v) => fmt::Display::fmt(v, f),
+                #[cfg(any(feature = "kv_unstable_time", feature = "kv_unstable_chrono"))]
+                Inner::Timestamp(v) => f.write_str(&v.to_rfc3339()),
+                #[cfg(feature = "kv_json")]
+                Inner::Json(v) => f.write_str(v.get()),
+            }
+        }
+    }
+
+    impl<'v> fmt::Display for Inner<'v> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                Inner::Bag(v) => fmt::Display::fmt(v, f),
+                Inner::Bytes(v) => fmt::Debug::fmt(v, f),
+                Inner::Seq(v) => structured::fmt_seq(*v, f),
+                Inner::Map(v) => structured::fmt_map(*v, f),
+                Inner::Any(v) => fmt::Display::fmt(&any_support::resolve(*v), f),
+                Inner::Redacted(_) => fmt::Display::fmt(&redacted_support::resolve(), f),
+                Inner::Hinted(v, hint) => hint_support::fmt(*v, *hint, f),
+                Inner::Unit(v) => fmt::Display::fmt(&v.resolve(), f),
+                #[cfg(feature = "kv_std")]
+                Inner::Duration(v) => fmt::Debug::fmt(v, f),
+                #[cfg(feature = "kv_std")]
+                Inner::SystemTime(v) => std_support::fmt_system_time(v, f),
+                #[cfg(feature = "kv_std")]
+                Inner::IpAddr(v) => fmt::Display::fmt(&v.to_ip_addr(), f),
+                #[cfg(feature = "kv_std")]
+                Inner::SocketAddr(v) => fmt::Display::fmt(&v.to_socket_addr(), f),
+                #[cfg(feature = "kv_unstable_uuid")]
+                Inner::Uuid(v) => fmt::Display::fmt(v, f),
+                #[cfg(any(feature = "kv_unstable_time", feature = "kv_unstable_chrono"))]
+                Inner::Timestamp(v) => f.write_str(&v.to_rfc3339()),
+                #[cfg(feature = "kv_json")]
+                Inner::Json(v) => f.write_str(v.get()),
+            }
+        }
+    }
+
+    #[cfg(feature = "kv_serde")]
+    impl<'v> serde::Serialize for Inner<'v> {
+        fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            match self {
+                Inner::Bag(v) => v.serialize(s),
+                Inner::Bytes(v) => s.serialize_bytes(v),
+                Inner::Seq(v) => structured::serialize_seq(*v, s),
+                Inner::Map(v) => structured::serialize_map(*v, s),
+                Inner::Any(v) => any_support::resolve(*v).serialize(s),
+                Inner::Redacted(_) => redacted_support::resolve().serialize(s),
+                Inner::Hinted(v, _) => hint_support::resolve(*v).serialize(s),
+                Inner::Unit(v) => v.resolve().serialize(s),
+                Inner::Duration(v) => serialize_secs_nanos(
+                    s,
+                    "Duration",
+                    "secs",
+                    v.as_secs(),
+                    "nanos",
+                    v.subsec_nanos(),
+                ),
+                Inner::SystemTime(v) => {
+                    let v = v.duration_since(std::time::UNIX_EPOCH).map_err(|_| {
+                        serde::ser::Error::custom("SystemTime must be later than UNIX_EPOCH")
+                    })?;
+
+                    serialize_secs_nanos(
+                        s,
+                        "SystemTime",
+                        "secs_since_epoch",
+                        v.as_secs(),
+                        "nanos_since_epoch",
+                        v.subsec_nanos(),
+                    )
+                }
+                Inner::IpAddr(v) => s.collect_str(&v.to_ip_addr()),
+                Inner::SocketAddr(v) => s.collect_str(&v.to_socket_addr()),
+                #[cfg(any(feature = "kv_unstable_time", feature = "kv_unstable_chrono"))]
+                Inner::Timestamp(v) => s.serialize_str(&v.to_rfc3339()),
+                #[cfg(feature = "kv_unstable_uuid")]
+                Inner::Uuid(v) => s.serialize_str(
+                    v.hyphenated()
+                        .encode_lower(&mut uuid::Uuid::encode_buffer()),
+                ),
+                #[cfg(feature = "kv_json")]
+                Inner::Json(v) => v.serialize(s),
+            }
+        }
+    }
+
+    /**
+    Serialize a duration the same way `serde` does for `Duration` and `SystemTime`.
+    */
+    #[cfg(feature = "kv_serde")]
+    fn serialize_secs_nanos<S: serde::Serializer>(
+        s: S,
+        name: &'static str,
+        secs_label: &'static str,
+        secs: u64,
+        nanos_label: &'static str,
+        nanos: u32,
+    ) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut s = s.serialize_struct(name, 2)?;
+        s.serialize_field(secs_label, &secs)?;
+        s.serialize_field(nanos_label, &nanos)?;
+        s.end()
+    }
+
+    #[cfg(feature = "kv_sval")]
+    impl<'v> sval::Value for Inner<'v> {
+        fn stream<'sval, S: sval::Stream<'sval> + ?Sized>(
+            &'sval self,
+            stream: &mut S,
+        ) -> sval::Result {
+            match self {
+                Inner::Bag(v) => sval::Value::stream(v, stream),
+                Inner::Bytes(v) => sval::Value::stream(sval::BinarySlice::new(v), stream),
+                Inner::Seq(v) => structured::stream_seq(*v, stream),
+                Inner::Map(v) => structured::stream_map(*v, stream),
+                Inner::Any(v) => stream.value_computed(&any_support::resolve(*v)),
+                Inner::Redacted(_) => stream.value_computed(&redacted_support::resolve()),
+                Inner::Hinted(v, _) => stream.value_computed(&hint_support::resolve(*v)),
+                Inner::Unit(v) => stream.value_computed(&v.resolve()),
+                #[cfg(feature = "kv_std")]
+                Inner::Duration(_) | Inner::SystemTime(_) => stream_time(self, stream),
+                #[cfg(feature = "kv_std")]
+                Inner::IpAddr(_) | Inner::SocketAddr(_) => {
+                    stream.value_computed(&*self.to_str().unwrap_or_default())
+                }
+                #[cfg(any(feature = "kv_unstable_time", feature = "kv_unstable_chrono"))]
+                Inner::Timestamp(v) => stream.value_computed(&*v.to_rfc3339()),
+                #[cfg(feature = "kv_unstable_uuid")]
+                Inner::Uuid(v) => stream.value_computed(
+                    v.hyphenated()
+                        .encode_lower(&mut uuid::Uuid::encode_buffer()),
+                ),
+                #[cfg(feature = "kv_json")]
+                Inner::Json(v) => stream_json(v, stream),
+            }
+        }
+    }
+
+    #[cfg(feature = "kv_sval")]
+    impl<'v> sval_ref::ValueRef<'v> for Inner<'v> {
+        fn stream_ref<S: sval::Stream<'v> + ?Sized>(&self, stream: &mut S) -> sval::Result {
+            match self {
+                Inner::Bag(v) => sval_ref::ValueRef::stream_ref(v, stream),
+                Inner::Bytes(v) => {
+                    stream.binary_begin(Some(v.len()))?;
+                    stream.binary_fragment(v)?;
+                    stream.binary_end()
+                }
+                Inner::Seq(v) => structured::stream_seq(*v, stream),
+                Inner::Map(v) => structured::stream_map(*v, stream),
+                Inner::Any(v) => stream.value_computed(&any_support::resolve(*v)),
+                Inner::Redacted(_) => stream.value_computed(&redacted_support::resolve()),
+                Inner::Hinted(v, _) => stream.value_computed(&hint_support::resolve(*v)),
+                Inner::Unit(v) => stream.value_computed(&v.resolve()),
+                #[cfg(feature = "kv_std")]
+                Inner::Duration(_) | Inner::SystemTime(_) => stream_time(self, stream),
+                #[cfg(feature = "kv_std")]
+                Inner::IpAddr(_) | Inner::SocketAddr(_) => {
+                    stream.value_computed(&*self.to_str().unwrap_or_default())
+                }
+                #[cfg(any(feature = "kv_unstable_time", feature = "kv_unstable_chrono"))]
+                Inner::Timestamp(v) => stream.value_computed(&*v.to_rfc3339()),
+                #[cfg(feature = "kv_unstable_uuid")]
+                Inner::Uuid(v) => stream.value_computed(
+                    v.hyphenated()
+                        .encode_lower(&mut uuid::Uuid::encode_buffer()),
+                ),
+                #[cfg(feature = "kv_json")]
+                Inner::Json(v) => stream_json(v, stream),
+            }
+        }
+    }
+
+    /**
+    Stream a duration or system time using the same shape `serde` uses for them.
+    */
+    #[cfg(all(feature = "kv_sval", feature = "kv_std"))]
+    fn stream_time<'sval, S: sval::Stream<'sval> + ?Sized>(
+        inner: &Inner,
+        stream: &mut S,
+    ) -> sval::Result {
+        let (secs_label, nanos_label, v) = match inner {
+            Inner::Duration(v) => ("secs", "nanos", *v),
+            Inner::SystemTime(v) => (
+                "secs_since_epoch",
+                "nanos_since_epoch",
+                v.duration_since(std::time::UNIX_EPOCH)
+                    .map_err(|_| sval::Error::new())?,
+            ),
+            _ => return Err(sval::Error::new()),
+        };
+
+        stream.map_begin(Some(2))?;
+
+        stream.map_key_begin()?;
+        sval::stream(stream, secs_label)?;
+        stream.map_key_end()?;
+        stream.map_value_begin()?;
+        stream.u64(v.as_secs())?;
+        stream.map_value_end()?;
+
+        stream.map_key_begin()?;
+        sval::stream(stream, nanos_label)?;
+        stream.map_key_end()?;
+        stream.map_value_begin()?;
+        stream.u32(v.subsec_nanos())?;
+        stream.map_value_end()?;
+
+        stream.map_end()
+    }
+
+    /**
+    Stream raw JSON by parsing it, since `sval` has no way to embed it verbatim.
+    */
+    #[cfg(all(feature = "kv_sval", feature = "kv_json"))]
+    fn stream_json<'sval, S: sval::Stream<'sval> + ?Sized>(
+        json: &serde_json::value::RawValue,
+        stream: &mut S,
+    ) -> sval::Result {
+        let json: serde_json::Value =
+            serde_json::from_str(json.get()).map_err(|_| sval::Error::new())?;
+
+        stream.value_computed(&ValueBag::from_serde1(&json))
+    }
+
+    #[cfg(test)]
+    pub use value_bag::test::TestToken as Token;
+
+    pub fn visit<'v>(
+        inner: &Inner<'v>,
+        visitor: impl VisitValue<'v>,
+    ) -> Result<(), crate::kv::Error> {
+        struct InnerVisitValue<V>(V);
+
+        impl<'v, V> value_bag::visit::Visit<'v> for InnerVisitValue<V>
+        where
+            V: VisitValue<'v>,
+        {
+            fn visit_any(&mut self, value: value_bag::ValueBag) -> Result<(), Error> {
+                self.0
+                    .visit_any(Value {
+                        inner: Inner::Bag(value),
+                    })
+                    .map_err(crate::kv::Error::into_value)
+            }
+
+            fn visit_empty(&mut self) -> Result<(), Error> {
+                self.0.visit_null().map_err(crate::kv::Error::into_value)
+            }
+
+            fn visit_u64(&mut self, value: u64) -> Result<(), Error> {
+                self.0
+                    .visit_u64(value)
+                    .map_err(crate::kv::Error::into_value)
+            }
+
+            fn visit_i64(&mut self, value: i64) -> Result<(), Error> {
+                self.0
+                    .visit_i64(value)
+                    .map_err(crate::kv::Error::into_value)
+            }
+
+            fn visit_u128(&mut self, value: u128) -> Result<(), Error> {
+                self.0
+                    .visit_u128(value)
+                    .map_err(crate::kv::Error::into_value)
+            }
+
+            fn visit_i128(&mut self, value: i128) -> Result<(), Error> {
+                self.0
+                    .visit_i128(value)
+                    .map_err(crate::kv::Error::into_value)
+            }
+
+            fn visit_f64(&mut self, value: f64) -> Result<(), Error> {
+                self.0
+                    .visit_f64(value)
+                    .map_err(crate::kv::Error::into_value)
+            }
+
+            fn visit_bool(&mut self, value: bool) -> Result<(), Error> {
+                self.0
+                    .visit_bool(value)
+                    .map_err(crate::kv::Error::into_value)
+            }
+
+            fn visit_str(&mut self, value: &str) -> Result<(), Error> {
+                self.0
+                    .visit_str(value)
+                    .map_err(crate::kv::Error::into_value)
+            }
+
+            fn visit_borrowed_str(&mut self, value: &'v str) -> Result<(), Error> {
+                self.0
+                    .visit_borrowed_str(value)
+                    .map_err(crate::kv::Error::into_value)
+            }
+
+            fn visit_char(&mut self, value: char) -> Result<(), Error> {
+                self.0
+                    .visit_char(value)
+                    .map_err(crate::kv::Error::into_value)
+            }
+
+            #[cfg(feature = "kv_std")]
+            fn visit_error(
+                &mut self,
+                err: &(dyn std::error::Error + 'static),
+            ) -> Result<(), Error> {
+                self.0
+                    .visit_error(err)
+                    .map_err(crate::kv::Error::into_value)
+            }
+
+            #[cfg(feature = "kv_std")]
+            fn visit_borrowed_error(
+                &mut self,
+                err: &'v (dyn std::error::Error + 'static),
+            ) -> Result<(), Error> {
+                self.0
+                    .visit_borrowed_error(err)
+                    .map_err(crate::kv::Error::into_value)
+            }
+        }
+
+        let mut visitor = visitor;
+
+        match inner {
+            Inner::Bag(inner) => inner
+                .visit(&mut InnerVisitValue(visitor))
+                .map_err(crate::kv::Error::from_value),
+            Inner::Bytes(value) => visitor.visit_bytes(value),
+            Inner::Seq(value) => visitor.visit_seq(*value),
+            Inner::Map(value) => visitor.visit_map(*value),
+            Inner::Any(value) => visit(&any_support::resolve(*value), visitor),
+            Inner::Redacted(_) => visit(&redacted_support::resolve(), visitor),
+            Inner::Hinted(v, _) => visit(&hint_support::resolve(*v), visitor),
+            Inner::Unit(v) => visit(&v.resolve(), visitor),
+            #[cfg(feature = "kv_std")]
+            Inner::Duration(value) => visitor.visit_duration(*value),
+            #[cfg(feature = "kv_std")]
+            Inner::SystemTime(value) => visitor.visit_system_time(*value),
+            #[cfg(feature = "kv_std")]
+            Inner::IpAddr(_) | Inner::SocketAddr(_) => visitor.visit_any(Value {
+                inner: inner.clone(),
+            }),
+            #[cfg(feature = "kv_unstable_uuid")]
+            Inner::Uuid(_) => visitor.visit_any(Value {
+                inner: inner.clone(),
+            }),
+            #[cfg(any(feature = "kv_unstable_time", feature = "kv_unstable_chrono"))]
+            Inner::Timestamp(value) => visitor.visit_timestamp(&value.to_rfc3339()),
+            #[cfg(feature = "kv_json")]
+            Inner::Json(_) => visitor.visit_any(Value {
+                inner: inner.clone(),
+            }),
+        }
+    }
+}
+
+#[cfg(not(feature = "value-bag"))]
+pub(in crate::kv) mod inner {
+    /**
+    This is a dependency-free implementation of `Value` when there's no serialization frameworks involved.
+    In these simple cases a more fully featured solution like `value_bag` isn't needed, so we avoid pulling it in.
+
+    There are a few things here that need to remain consistent with the `value_bag`-based implementation:
+
+    1. Conversions should always produce the same results. If a conversion here returns `Some`, then
+       the same `value_bag`-based conversion must also. Of particular note here are floats to ints; they're
+       based on the standard library's `TryInto` conversions, which need to be convert to `i32` or `u32`,
+       and then to `f64`.
+    2. VisitValues should always be called in the same way. If a particular type of value calls `visit_i64`,
+       then the same `value_bag`-based visitor must also.
+    */
+    use super::*;
+
+    #[derive(Clone)]
+    pub enum Inner<'v> {
+        None,
+        Bool(bool),
+        Str(&'v str),
+        Char(char),
+        I64(i64),
+        U64(u64),
+        F64(f64),
+        I128(i128),
+        U128(u128),
+        Bytes(&'v [u8]),
+        Seq(&'v dyn Seq),
+        Map(&'v dyn Source),
+        Any(&'v dyn any_support::AnyValue),
+        Redacted(&'v dyn ToValue),
+        Unit(unit_support::Measured),
+        Hinted(&'v dyn ToValue, FormatHint),
+        Debug(&'v dyn fmt::Debug),
+        Display(&'v dyn fmt::Display),
+    }
+
+    #[cfg(feature = "std")]
+    #[derive(Clone)]
+    pub enum OwnedInner {
+        None,
+        Bool(bool),
+        Str(Box<str>),
+        Char(char),
+        F64(f64),
+        I128(i128),
+        U128(u128),
+        Bytes(Box<[u8]>),
+        Seq(Vec<OwnedValue>),
+        Map(crate::kv::OwnedSource),
+        Unit(unit_support::Measured),
+        Fmt(Box<str>),
+    }
+
+    impl<'v> From<()> for Inner<'v> {
+        fn from(_: ()) -> Self {
+            Inner::None
+        }
+    }
+
+    impl<'v> From<bool> for Inner<'v> {
+        fn from(v: bool) -> Self {
+            Inner::Bool(v)
+        }
+    }
+
+    impl<'v> From<char> for Inner<'v> {
+        fn from(v: char) -> Self {
+            Inner::Char(v)
+        }
+    }
+
+    impl<'v> From<f32> for Inner<'v> {
+        fn from(v: f32) -> Self {
+            Inner::F64(v as f64)
+        }
+    }
+
+    impl<'v> From<f64> for Inner<'v> {
+        fn from(v: f64) -> Self {
+            Inner::F64(v)
+        }
+    }
+
+    impl<'v> From<i8> for Inner<'v> {
+        fn from(v: i8) -> Self {
+            Inner::I64(v as i64)
+        }
+    }
+
+    impl<'v> From<i16> for Inner<'v> {
+        fn from(v: i16) -> Self {
+            Inner::I64(v as i64)
+        }
+    }
+
+    impl<'v> From<i32> for Inner<'v> {
+        fn from(v: i32) -> Self {
+            Inner::I64(v as i64)
+        }
+    }
+
+    impl<'v> From<i64> for Inner<'v> {
+        fn from(v: i64) -> Self {
+            Inner::I64(v as i64)
+        }
+    }
+
+    impl<'v> From<isize> for Inner<'v> {
+        fn from(v: isize) -> Self {
+            Inner::I64(v as i64)
+        }
+    }
+
+    impl<'v> From<u8> for Inner<'v> {
+        fn from(v: u8) -> Self {
+            Inner::U64(v as u64)
+        }
+    }
+
+    impl<'v> From<u16> for Inner<'v> {
+        fn from(v: u16) -> Self {
+            Inner::U64(v as u64)
+        }
+    }
+
+    impl<'v> From<u32> for Inner<'v> {
+        fn from(v: u32) -> Self {
+            Inner::U64(v as u64)
+        }
+    }
+
+    impl<'v> From<u64> for Inner<'v> {
+        fn from(v: u64) -> Self {
+            Inner::U64(v as u64)
+        }
+    }
+
+    impl<'v> From<usize> for Inner<'v> {
+        fn from(v: usize) -> Self {
+            Inner::U64(v as u64)
+        }
+    }
+
+    impl<'v> From<i128> for Inner<'v> {
+        fn from(v: i128) -> Self {
+            Inner::I128(v)
+        }
+    }
+
+    impl<'v> From<u128> for Inner<'v> {
+        fn from(v: u128) -> Self {
+            Inner::U128(v)
+        }
+    }
+
+    impl<'v> From<&'v str> for Inner<'v> {
+        fn from(v: &'v str) -> Self {
+            Inner::Str(v)
+        }
+    }
+
+    impl<'v> fmt::Debug for Inner<'v> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                Inner::None => fmt::Debug::fmt(&None::<()>, f),
+                Inner::Bool(v) => fmt::Debug::fmt(v, f),
+                Inner::Str(v) => fmt::Debug::fmt(v, f),
+                Inner::Char(v) => fmt::Debug::fmt(v, f),
+                Inner::I64(v) => fmt::Debug::fmt(v, f),
+                Inner::U64(v) => fmt::Debug::fmt(v, f),
+                Inner::F64(v) => fmt::Debug::fmt(v, f),
+                Inner::I128(v) => fmt::Debug::fmt(v, f),
+                Inner::U128(v) => fmt::Debug::fmt(v, f),
+                Inner::Bytes(v) => fmt::Debug::fmt(v, f),
+                Inner::Seq(v) => structured::fmt_seq(*v, f),
+                Inner::Map(v) => structured::fmt_map(*v, f),
+                Inner::Any(v) => fmt::Debug::fmt(&any_support::resolve(*v), f),
+                Inner::Redacted(_) => fmt::Debug::fmt(&redacted_support::resolve(), f),
+                Inner::Hinted(v, hint) => hint_support::fmt(*v, *hint, f),
+                Inner::Unit(v) => fmt::Debug::fmt(&v.resolve(), f),
+                Inner::Debug(v) => fmt::Debug::fmt(v, f),
+                Inner::Display(v) => fmt::Display::fmt(v, f),
+            }
+        }
+    }
+
+    impl<'v> fmt::Display for Inner<'v> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                Inner::None => fmt::Debug::fmt(&None::<()>, f),
+                Inner::Bool(v) => fmt::Display::fmt(v, f),
+                Inner::Str(v) => fmt::Display::fmt(v, f),
+                Inner::Char(v) => fmt::Display::fmt(v, f),
+                Inner::I64(v) => fmt::Display::fmt(v, f),
+                Inner::U64(v) => fmt::Display::fmt(v, f),
+                Inner::F64(v) => fmt::Display::fmt(v, f),
+                Inner::I128(v) => fmt::Display::fmt(v, f),
+                Inner::U128(v) => fmt::Display::fmt(v, f),
+                Inner::Bytes(v) => fmt::Debug::fmt(v, f),
+                Inner::Seq(v) => structured::fmt_seq(*v, f),
+                Inner::Map(v) => structured::fmt_map(*v, f),
+                Inner::Any(v) => fmt::Display::fmt(&any_support::resolve(*v), f),
+                Inner::Redacted(_) => fmt::Display::fmt(&redacted_support::resolve(), f),
+                Inner::Hinted(v, hint) => hint_support::fmt(*v, *hint, f),
+                Inner::Unit(v) => fmt::Display::fmt(&v.resolve(), f),
+                Inner::Debug(v) => fmt::Debug::fmt(v, f),
+                Inner::Display(v) => fmt::Display::fmt(v, f),
+            }
+        }
+    }
+
+    impl<'v> Inner<'v> {
+        pub fn from_concrete<T: ToValue + 'static>(value: &'v T) -> Self {
+            Inner::Any(value)
+        }
+
+        pub fn from_redacted<T: ToValue>(value: &'v T) -> Self {
+            Inner::Redacted(value)
+        }
+
+        pub fn from_unit(value: unit_support::Measured) -> Self {
+            Inner::Unit(value)
+        }
+
+        pub fn from_hinted<T: ToValue>(value: &'v T, hint: FormatHint) -> Self {
+            Inner::Hinted(value, hint)
+        }
+
+        pub fn from_debug<T: fmt::Debug>(value: &'v T) -> Self {
+            Inner::Debug(value)
+        }
+
+        pub fn from_display<T: fmt::Display>(value: &'v T) -> Self {
+            Inner::Display(value)
+        }
+
+        pub fn from_dyn_debug(value: &'v dyn fmt::Debug) -> Self {
+            Inner::Debug(value)
+        }
+
+        pub fn from_dyn_display(value: &'v dyn fmt::Display) -> Self {
+            Inner::Display(value)
+        }
+
+        pub fn from_bytes(value: &'v [u8]) -> Self {
+            Inner::Bytes(value)
+        }
+
+        pub fn from_seq(value: &'v dyn Seq) -> Self {
+            Inner::Seq(value)
+        }
+
+        pub fn from_map(value: &'v dyn Source) -> Self {
+            Inner::Map(value)
+        }
+
+        pub fn empty() -> Self {
+            Inner::None
+        }
+
+        pub fn to_bool(&self) -> Option<bool> {
+            match self {
+                Inner::Bool(v) => Some(*v),
+                Inner::Any(v) => any_support::resolve(*v).to_bool(),
+                Inner::Redacted(_) => redacted_support::resolve().to_bool(),
+                Inner::Hinted(v, _) => hint_support::resolve(*v).to_bool(),
+                Inner::Unit(v) => v.resolve().to_bool(),
+                _ => None,
+            }
+        }
+
+        pub fn to_char(&self) -> Option<char> {
+            match self {
+                Inner::Char(v) => Some(*v),
+                Inner::Any(v) => any_support::resolve(*v).to_char(),
+                Inner::Redacted(_) => redacted_support::resolve().to_char(),
+                Inner::Hinted(v, _) => hint_support::resolve(*v).to_char(),
+                Inner::Unit(v) => v.resolve().to_char(),
+                _ => None,
+            }
+        }
+
+        pub fn to_f64(&self) -> Option<f64> {
+            match self {
+                Inner::F64(v) => Some(*v),
+                Inner::I64(v) => {
+                    let v: i32 = (*v).try_into().ok()?;
+                    v.try_into().ok()
+                }
+                Inner::U64(v) => {
+                    let v: u32 = (*v).try_into().ok()?;
+                    v.try_into().ok()
+                }
+                Inner::I128(v) => {
+                    let v: i32 = (*v).try_into().ok()?;
+                    v.try_into().ok()
+                }
+                Inner::U128(v) => {
+                    let v: u32 = (*v).try_into().ok()?;
+                    v.try_into().ok()
+                }
+                Inner::Any(v) => any_support::resolve(*v).to_f64(),
+                Inner::Redacted(_) => redacted_support::resolve().to_f64(),
+                Inner::Hinted(v, _) => hint_support::resolve(*v).to_f64(),
+                Inner::Unit(v) => v.resolve().to_f64(),
+                _ => None,
+            }
+        }
+
+        pub fn to_i64(&self) -> Option<i64> {
+            match self {
+                Inner::I64(v) => Some(*v),
+                Inner::U64(v) => (*v).try_into().ok(),
+                Inner::I128(v) => (*v).try_into().ok(),
+                Inner::U128(v) => (*v).try_into().ok(),
+                Inner::Any(v) => any_support::resolve(*v).to_i64(),
+                Inner::Redacted(_) => redacted_support::resolve().to_i64(),
+                Inner::Hinted(v, _) => hint_support::resolve(*v).to_i64(),
+                Inner::Unit(v) => v.resolve().to_i64(),
+                _ => None,
+            }
+        }
+
+        pub fn to_u64(&self) -> Option<u64> {
+            match self {
+                Inner::U64(v) => Some(*v),
+                Inner::I64(v) => (*v).try_into().ok(),
+                Inner::I128(v) => (*v).try_into().ok(),
+                Inner::U128(v) => (*v).try_into().ok(),
+                Inner::Any(v) => any_support::resolve(*v).to_u64(),
+                Inner::Redacted(_) => redacted_support::resolve().to_u64(),
+                Inner::Hinted(v, _) => hint_support::resolve(*v).to_u64(),
+                Inner::Unit(v) => v.resolve().to_u64(),
+                _ => None,
+            }
+        }
+
+        pub fn to_u128(&self) -> Option<u128> {
+            match self {
+                Inner::U128(v) => Some(*v),
+                Inner::I64(v) => (*v).try_into().ok(),
+                Inner::U64(v) => (*v).try_into().ok(),
+                Inner::I128(v) => (*v).try_into().ok(),
+                Inner::Any(v) => any_support::resolve(*v).to_u128(),
+                Inner::Redacted(_) => redacted_support::resolve().to_u128(),
+                Inner::Hinted(v, _) => hint_support::resolve(*v).to_u128(),
+                Inner::Unit(v) => v.resolve().to_u128(),
+                _ => None,
+            }
+        }
+
+        pub fn to_i128(&self) -> Option<i128> {
+            match self {
+                Inner::I128(v) => Some(*v),
+                Inner::I64(v) => (*v).try_into().ok(),
+                Inner::U64(v) => (*v).try_into().ok(),
+                Inner::U128(v) => (*v).try_into().ok(),
+                Inner::Any(v) => any_support::resolve(*v).to_i128(),
+                Inner::Redacted(_) => redacted_support::resolve().to_i128(),
+                Inner::Hinted(v, _) => hint_support::resolve(*v).to_i128(),
+                Inner::Unit(v) => v.resolve().to_i128(),
+                _ => None,
+            }
+        }
+
+        pub fn to_borrowed_str(&self) -> Option<&'v str> {
+            match self {
+                Inner::Str(v) => Some(v),
+                Inner::Any(v) => any_support::resolve(*v).to_borrowed_str(),
+                Inner::Redacted(_) => redacted_support::resolve().to_borrowed_str(),
+                Inner::Hinted(v, _) => hint_support::resolve(*v).to_borrowed_str(),
+                Inner::Unit(v) => v.resolve().to_borrowed_str(),
+                _ => None,
+            }
+        }
+
+        #[cfg(feature = "std")]
+        pub fn to_str(&self) -> Option<std::borrow::Cow<'v, str>> {
+            match self {
+                Inner::Str(v) => Some((*v).into()),
+                Inner::Char(v) => Some(v.to_string().into()),
+                Inner::Any(v) => any_support::resolve(*v).to_str(),
+                Inner::Redacted(_) => redacted_support::resolve().to_str(),
+                Inner::Hinted(v, _) => hint_support::resolve(*v).to_str(),
+                Inner::Unit(v) => v.resolve().to_str(),
+                _ => None,
+            }
+        }
+
+        pub fn to_borrowed_bytes(&self) -> Option<&'v [u8]> {
+            match self {
+                Inner::Bytes(v) => Some(v),
+                Inner::Any(v) => any_support::resolve(*v).to_borrowed_bytes(),
+                Inner::Redacted(_) => redacted_support::resolve().to_borrowed_bytes(),
+                Inner::Hinted(v, _) => hint_support::resolve(*v).to_borrowed_bytes(),
+                Inner::Unit(v) => v.resolve().to_borrowed_bytes(),
+                _ => None,
+            }
+        }
+
+        pub fn as_seq(&self) -> Option<&'v dyn Seq> {
+            match self {
+                Inner::Seq(v) => Some(*v),
+                Inner::Any(v) => any_support::resolve(*v).as_seq(),
+                Inner::Redacted(_) => redacted_support::resolve().as_seq(),
+                Inner::Hinted(v, _) => hint_support::resolve(*v).as_seq(),
+                Inner::Unit(v) => v.resolve().as_seq(),
+                _ => None,
+            }
+        }
+
+        pub fn as_map(&self) -> Option<&'v dyn Source> {
+            match self {
+                Inner::Map(v) => Some(*v),
+                Inner::Any(v) => any_support::resolve(*v).as_map(),
+                Inner::Redacted(_) => redacted_support::resolve().as_map(),
+                Inner::Hinted(v, _) => hint_support::resolve(*v).as_map(),
+                Inner::Unit(v) => v.resolve().as_map(),
+                _ => None,
+            }
+        }
+
+        pub fn kind(&self) -> ValueKind {
+            match self {
+                Inner::None => ValueKind::Null,
+                Inner::Bool(_) => ValueKind::Bool,
+                Inner::Str(_) => ValueKind::Str,
+                Inner::Char(_) => ValueKind::Char,
+                Inner::I64(_) | Inner::U64(_) | Inner::I128(_) | Inner::U128(_) => ValueKind::Int,
+                Inner::F64(_) => ValueKind::Float,
+                Inner::Bytes(_) => ValueKind::Bytes,
+                Inner::Seq(_) => ValueKind::Seq,
+                Inner::Map(_) => ValueKind::Map,
+                Inner::Any(v) => any_support::resolve(*v).kind(),
+                Inner::Redacted(_) => redacted_support::resolve().kind(),
+                Inner::Hinted(v, _) => hint_support::resolve(*v).kind(),
+                Inner::Unit(v) => v.resolve().kind(),
+                Inner::Debug(_) | Inner::Display(_) => ValueKind::Other,
+            }
+        }
+
+        pub fn downcast_ref<T: 'static>(&self) -> Option<&T> {
+            match self {
+                Inner::Any(v) => v.as_any().downcast_ref(),
+                _ => None,
+            }
+        }
+
+        pub fn to_unredacted(&self) -> Option<Value<'v>> {
+            match self {
+                Inner::Redacted(v) => Some(ToValue::to_value(*v)),
+                Inner::Hinted(v, _) => hint_support::resolve(*v).to_unredacted(),
+                _ => None,
+            }
+        }
+
+        pub fn to_unit(&self) -> Option<&'static str> {
+            match self {
+                Inner::Unit(v) => Some(v.unit()),
+                Inner::Hinted(v, _) => hint_support::resolve(*v).to_unit(),
+                _ => None,
+            }
+        }
+
+        pub fn to_format_hint(&self) -> Option<FormatHint> {
+            match self {
+                Inner::Hinted(_, hint) => Some(*hint),
+                _ => None,
+            }
+        }
+
+        #[cfg(feature = "std")]
+        pub fn to_owned(&self) -> OwnedInner {
+            // Integers are widened to match the `value_bag`-based owned implementation
+            match self {
+                Inner::None => OwnedInner::None,
+                Inner::Bool(v) => OwnedInner::Bool(*v),
+                Inner::Str(v) => OwnedInner::Str((*v).into()),
+                Inner::Char(v) => OwnedInner::Char(*v),
+                Inner::I64(v) => OwnedInner::I128(*v as i128),
+                Inner::U64(v) => OwnedInner::U128(*v as u128),
+                Inner::F64(v) => OwnedInner::F64(*v),
+                Inner::I128(v) => OwnedInner::I128(*v),
+                Inner::U128(v) => OwnedInner::U128(*v),
+                Inner::Bytes(v) => OwnedInner::Bytes((*v).into()),
+                Inner::Seq(v) => OwnedInner::Seq(structured::to_owned_seq(*v)),
+                Inner::Map(v) => OwnedInner::Map(crate::kv::OwnedSource::from_source(*v)),
+                Inner::Any(v) => any_support::resolve(*v).to_owned(),
+                Inner::Redacted(_) => redacted_support::resolve().to_owned(),
+                Inner::Hinted(v, _) => hint_support::resolve(*v).to_owned(),
+                Inner::Unit(v) => OwnedInner::Unit(*v),
+                Inner::Debug(v) => OwnedInner::Fmt(format!("{:?}", v).into()),
+                Inner::Display(v) => OwnedInner::Fmt(v.to_string().into()),
+            }
+        }
+
+        #[cfg(test)]
+        pub fn to_test_token(&self) -> Token<'v> {
+            match self {
+                Inner::None => Token::None,
+                Inner::Bool(v) => Token::Bool(*v),
+                Inner::Str(v) => Token::Str(*v),
+                Inner::Char(v) => Token::Char(*v),
+                Inner::I64(v) => Token::I64(*v),
+                Inner::U64(v) => Token::U64(*v),
+                Inner::F64(v) => Token::F64(*v),
+                Inner::I128(_) => unimplemented!(),
+                Inner::U128(_) => unimplemented!(),
+                Inner::Bytes(_) => unimplemented!(),
+                Inner::Seq(_) => unimplemented!(),
+                Inner::Map(_) => unimplemented!(),
+                Inner::Any(v) => any_support::resolve(*v).to_test_token(),
+                Inner::Redacted(_) => redacted_support::resolve().to_test_token(),
+                Inner::Hinted(v, _) => hint_support::resolve(*v).to_test_token(),
+                Inner::Unit(v) => v.resolve().to_test_token(),
+                Inner::Debug(_) => unimplemented!(),
+                Inner::Display(_) => unimplemented!(),
+            }
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl OwnedInner {
+        pub fn by_ref(&self) -> Inner<'_> {
+            match self {
+                OwnedInner::None => Inner::None,
+                OwnedInner::Bool(v) => Inner::Bool(*v),
+                OwnedInner::Str(v) => Inner::Str(v),
+                OwnedInner::Char(v) => Inner::Char(*v),
+                OwnedInner::F64(v) => Inner::F64(*v),
+                OwnedInner::I128(v) => Inner::I128(*v),
+                OwnedInner::U128(v) => Inner::U128(*v),
+                OwnedInner::Bytes(v) => Inner::Bytes(v),
+                OwnedInner::Seq(v) => Inner::Seq(v),
+                OwnedInner::Map(v) => Inner::Map(v),
+                OwnedInner::Unit(v) => Inner::Unit(*v),
+                OwnedInner::Fmt(v) => Inner::Display(v),
+            }
+        }
+    }
+
+    #[cfg(test)]
+    #[derive(Debug, PartialEq)]
+    pub enum Token<'v> {
+        None,
+        Bool(bool),
+        Char(char),
+        Str(&'v str),
+        F64(f64),
+        I64(i64),
+        U64(u64),
+    }
+
+    pub fn visit<'v>(
+        inner: &Inner<'v>,
+        mut visitor: impl VisitValue<'v>,
+    ) -> Result<(), crate::kv::Error> {
+        match inner {
+            Inner::None => visitor.visit_null(),
+            Inner::Bool(v) => visitor.visit_bool(*v),
+            Inner::Str(v) => visitor.visit_borrowed_str(*v),
+            Inner::Char(v) => visitor.visit_char(*v),
+            Inner::I64(v) => visitor.visit_i64(*v),
+            Inner::U64(v) => visitor.visit_u64(*v),
+            Inner::F64(v) => visitor.visit_f64(*v),
+            Inner::I128(v) => visitor.visit_i128(*v),
+            Inner::U128(v) => visitor.visit_u128(*v),
+            Inner::Bytes(v) => visitor.visit_bytes(v),
+            Inner::Seq(v) => visitor.visit_seq(*v),
+            Inner::Map(v) => visitor.visit_map(*v),
+            Inner::Any(v) => visit(&any_support::resolve(*v), visitor),
+            Inner::Redacted(_) => visit(&redacted_support::resolve(), visitor),
+            Inner::Hinted(v, _) => visit(&hint_support::resolve(*v), visitor),
+            Inner::Unit(v) => visit(&v.resolve(), visitor),
+            Inner::Debug(v) => visitor.visit_any(Value::from_dyn_debug(*v)),
+            Inner::Display(v) => visitor.visit_any(Value::from_dyn_display(*v)),
+        }
+    }
+}
+
+impl<'v> Value<'v> {
+    /// Get a value from a type implementing `std::fmt::Debug`.
+    #[cfg(feature = "kv_unstable")]
+    #[deprecated(note = "use `from_debug` instead")]
+    pub fn capture_debug<T>(value: &'v T) -> Self
+    where
+        T: fmt::Debug + 'static,
+    {
+        Value {
+            inner: inner::Inner::capture_debug(value),
+        }
+    }
+
+    /// Get a value from a type implementing `std::fmt::Display`.
+    #[cfg(feature = "kv_unstable")]
+    #[deprecated(note = "use `from_display` instead")]
+    pub fn capture_display<T>(value: &'v T) -> Self
+    where
+        T: fmt::Display + 'static,
+    {
+        Value {
+            inner: inner::Inner::capture_display(value),
+        }
+    }
+
+    /// Get a value from an error.
+    #[cfg(feature = "kv_unstable_std")]
+    #[deprecated(note = "use `from_dyn_error` instead")]
+    pub fn capture_error<T>(err: &'v T) -> Self
+    where
+        T: std::error::Error + 'static,
+    {
+        Value {
+            inner: inner::Inner::capture_error(err),
+        }
+    }
+
+    /// Get a value from a type implementing `serde::Serialize`.
+    #[cfg(feature = "kv_unstable_serde")]
+    #[deprecated(note = "use `from_serde` instead")]
+    pub fn capture_serde<T>(value: &'v T) -> Self
+    where
+        T: serde::Serialize + 'static,
+    {
+        Value {
+            inner: inner::Inner::capture_serde1(value),
+        }
+    }
+
+    /// Get a value from a type implementing `sval::Value`.
+    #[cfg(feature = "kv_unstable_sval")]
+    #[deprecated(note = "use `from_sval` instead")]
+    pub fn capture_sval<T>(value: &'v T) -> Self
+    where
+        T: sval::Value + 'static,
+    {
+        Value {
+            inner: inner::Inner::capture_sval2(value),
+        }
+    }
+}
+
+// NOTE: Deprecated; but aliases can't carry this attribute
+#[cfg(feature = "kv_unstable")]
+pub use VisitValue as Visit;
+
+/// Get a value from a type implementing `std::fmt::Debug`.
+#[cfg(feature = "kv_unstable")]
+#[deprecated(note = "use the `key:? = value` macro syntax instead")]
+#[macro_export]
+macro_rules! as_debug {
+    ($capture:expr) => {
+        $crate::kv::Value::from_debug(&$capture)
+    };
+}
+
+/// Get a value from a type implementing `std::fmt::Display`.
+#[cfg(feature = "kv_unstable")]
+#[deprecated(note = "use the `key:% = value` macro syntax instead")]
+#[macro_export]
+macro_rules! as_display {
+    ($capture:expr) => {
+        $crate::kv::Value::from_display(&$capture)
+    };
+}
+
+/// Get a value from an error.
+#[cfg(feature = "kv_unstable_std")]
+#[deprecated(note = "use the `key:err = value` macro syntax instead")]
+#[macro_export]
+macro_rules! as_error {
+    ($capture:expr) => {
+        $crate::kv::Value::from_dyn_error(&$capture)
+    };
+}
+
+#[cfg(feature = "kv_unstable_serde")]
+#[deprecated(note = "use the `key:serde = value` macro syntax instead")]
+/// Get a value from a type implementing `serde::Serialize`.
+#[macro_export]
+macro_rules! as_serde {
+    ($capture:expr) => {
+        $crate::kv::Value::from_serde(&$capture)
+    };
+}
+
+/// Get a value from a type implementing `sval::Value`.
+#[cfg(feature = "kv_unstable_sval")]
+#[deprecated(note = "use the `key:sval = value` macro syntax instead")]
+#[macro_export]
+macro_rules! as_sval {
+    ($capture:expr) => {
+        $crate::kv::Value::from_sval(&$capture)
+    };
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    impl<'v> Value<'v> {
+        pub(crate) fn to_token(&self) -> inner::Token {
+            self.inner.to_test_token()
+        }
+    }
+
+    fn unsigned() -> impl Iterator<Item = Value<'static>> {
+        vec![
+            Value::from(8u8),
+            Value::from(16u16),
+            Value::from(32u32),
+            Value::from(64u64),
+            Value::from(1usize),
+            Value::from(std::num::NonZeroU8::new(8).unwrap()),
+            Value::from(std::num::NonZeroU16::new(16).unwrap()),
+            Value::from(std::num::NonZeroU32::new(32).unwrap()),
+            Value::from(std::num::NonZeroU64::new(64).unwrap()),
+            Value::from(std::num::NonZeroUsize::new(1).unwrap()),
+        ]
+        .into_iter()
+    }
+
+    fn signed() -> impl Iterator<Item = Value<'static>> {
+        vec![
+            Value::from(-8i8),
+            Value::from(-16i16),
+            Value::from(-32i32),
+            Value::from(-64i64),
+            Value::from(-1isize),
+            Value::from(std::num::NonZeroI8::new(-8).unwrap()),
+            Value::from(std::num::NonZeroI16::new(-16).unwrap()),
+            Value::from(std::num::NonZeroI32::new(-32).unwrap()),
+            Value::from(std::num::NonZeroI64::new(-64).unwrap()),
+            Value::from(std::num::NonZeroIsize::new(-1).unwrap()),
+        ]
+        .into_iter()
+    }
+
+    fn float() -> impl Iterator<Item = Value<'static>> {
+        vec![Value::from(32.32f32), Value::from(64.64f64)].into_iter()
+    }
+
+    fn bool() -> impl Iterator<Item = Value<'static>> {
+        vec![Value::from(true), Value::from(false)].into_iter()
+    }
+
+    fn str() -> impl Iterator<Item = Value<'static>> {
+        vec![Value::from("a string"), Value::from("a loong string")].into_iter()
+    }
+
+    fn char() -> impl Iterator<Item = Value<'static>> {
+        vec![Value::from('a'), Value::from('⛰')].into_iter()
+    }
+
+    #[test]
+    fn test_to_value_display() {
+        assert_eq!(42u64.to_value().to_string(), "42");
+        assert_eq!(42i64.to_value().to_string(), "42");
+        assert_eq!(42.01f64.to_value().to_string(), "42.01");
+        assert_eq!(true.to_value().to_string(), "true");
+        assert_eq!('a'.to_value().to_string(), "a");
+        assert_eq!("a loong string".to_value().to_string(), "a loong string");
+        assert_eq!(Some(true).to_value().to_string(), "true");
+        assert_eq!(().to_value().to_string(), "None");
+        assert_eq!(None::<bool>.to_value().to_string(), "None");
+    }
+
+    #[test]
+    fn test_to_value_structured() {
+        assert_eq!(42u64.to_value().to_token(), inner::Token::U64(42));
+        assert_eq!(42i64.to_value().to_token(), inner::Token::I64(42));
+        assert_eq!(42.01f64.to_value().to_token(), inner::Token::F64(42.01));
+        assert_eq!(true.to_value().to_token(), inner::Token::Bool(true));
+        assert_eq!('a'.to_value().to_token(), inner::Token::Char('a'));
+        assert_eq!(
+            "a loong string".to_value().to_token(),
+            inner::Token::Str("a loong string".into())
+        );
+        assert_eq!(Some(true).to_value().to_token(), inner::Token::Bool(true));
+        assert_eq!(().to_value().to_token(), inner::Token::None);
+        assert_eq!(None::<bool>.to_value().to_token(), inner::Token::None);
+    }
+
+    #[test]
+    fn test_to_number() {
+        for v in unsigned() {
+            assert!(v.to_u64().is_some());
+            assert!(v.to_i64().is_some());
+        }
+
+        for v in signed() {
+            assert!(v.to_i64().is_some());
+        }
+
+        for v in unsigned().chain(signed()).chain(float()) {
+            assert!(v.to_f64().is_some());
+        }
+
+        for v in bool().chain(str()).chain(char()) {
+            assert!(v.to_u64().is_none());
+            assert!(v.to_i64().is_none());
+            assert!(v.to_f64().is_none());
+        }
+    }
+
+    #[test]
+    fn test_to_float() {
+        // Only integers from i32::MIN..=u32::MAX can be converted into floats
+        assert!(Value::from(i32::MIN).to_f64().is_some());
+        assert!(Value::from(u32::MAX).to_f64().is_some());
+
+        assert!(Value::from((i32::MIN as i64) - 1).to_f64().is_none());
+        assert!(Value::from((u32::MAX as u64) + 1).to_f64().is_none());
+    }
+
+    #[test]
+    fn test_atomic() {
+        use std::sync::atomic::{AtomicBool, AtomicI32, AtomicU64, AtomicUsize, Ordering};
+
+        let counter = AtomicUsize::new(1);
+        counter.fetch_add(1, Ordering::Relaxed);
+
+        assert_eq!(Some(2), counter.to_value().to_u64());
+        assert_eq!(Some(64), AtomicU64::new(64).to_value().to_u64());
+        assert_eq!(Some(-32), AtomicI32::new(-32).to_value().to_i64());
+        assert_eq!(Some(true), AtomicBool::new(true).to_value().to_bool());
+    }
+
+    #[test]
+    fn test_to_value_mut_ref() {
+        let mut v = 42;
+        let v = &mut v;
+
+        assert_eq!(Some(42), v.to_value().to_i64());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_to_value_smart_pointers() {
+        use std::borrow::Cow;
+        use std::rc::Rc;
+        use std::sync::Arc;
+
+        assert_eq!(Some(42), Box::new(42).to_value().to_i64());
+        assert_eq!(Some(42), Arc::new(42).to_value().to_i64());
+        assert_eq!(Some(42), Rc::new(42).to_value().to_i64());
+        assert_eq!(
+            Some("a"),
+            Box::<str>::from("a").to_value().to_borrowed_str()
+        );
+
+        assert_eq!(Some("a"), Cow::from("a").to_value().to_borrowed_str());
+        assert_eq!(
+            Some("a"),
+            Cow::<str>::Owned("a".into()).to_value().to_borrowed_str()
+        );
+        assert_eq!(Some(42), Cow::<i32>::Owned(42).to_value().to_i64());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_path() {
+        use std::ffi::OsStr;
+        use std::path::{Path, PathBuf};
+
+        let path = PathBuf::from("/var/log/app.log");
+
+        let v = path.to_value();
+        assert_eq!(Some("/var/log/app.log"), v.to_borrowed_str());
+        assert_eq!(Some(Path::new("/var/log/app.log")), v.to_path());
+        assert_eq!("/var/log/app.log", v.to_string());
+
+        let v = Value::from(OsStr::new("app.log"));
+        assert_eq!(Some(Path::new("app.log")), v.to_path());
+
+        assert_eq!(None, Value::from(1).to_path());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStrExt;
+
+            let v = Path::new(OsStr::from_bytes(b"a\xff")).to_value();
+            assert_eq!(None, v.to_path());
+            assert_eq!(Some(&b"a\xff"[..]), v.to_borrowed_bytes());
+        }
+    }
+
+    #[test]
+    fn test_to_cow_str() {
+        for v in str() {
+            assert!(v.to_borrowed_str().is_some());
+
+            #[cfg(feature = "std")]
+            assert!(matches!(
+                v.to_cow_str(),
+                Some(std::borrow::Cow::Borrowed(_))
+            ));
+        }
+
+        let short_lived = String::from("short lived");
+        let v = Value::from(&*short_lived);
+
+        assert!(v.to_borrowed_str().is_some());
+
+        #[cfg(feature = "std")]
+        assert!(v.to_cow_str().is_some());
+
+        #[cfg(feature = "std")]
+        for v in char() {
+            assert!(v.to_borrowed_str().is_none());
+            assert!(matches!(v.to_cow_str(), Some(std::borrow::Cow::Owned(_))));
+        }
+
+        #[cfg(feature = "std")]
+        assert_eq!(Some("a"), Value::from('a').to_cow_str().as_deref());
+
+        for v in unsigned().chain(signed()).chain(float()).chain(bool()) {
+            assert!(v.to_borrowed_str().is_none());
+
+            #[cfg(feature = "std")]
+            assert!(v.to_cow_str().is_none());
+        }
+
+        #[cfg(feature = "std")]
+        assert!(Value::from_display(&"formatted").to_cow_str().is_none());
+    }
+
+    #[test]
+    fn test_to_bool() {
+        for v in bool() {
+            assert!(v.to_bool().is_some());
+        }
+
+        for v in unsigned()
+            .chain(signed())
+            .chain(float())
+            .chain(str())
+            .chain(char())
+        {
+            assert!(v.to_bool().is_none());
+        }
+    }
+
+    #[test]
+    fn test_to_char() {
+        for v in char() {
+            assert!(v.to_char().is_some());
+        }
+
+        for v in unsigned()
+            .chain(signed())
+            .chain(float())
+            .chain(str())
+            .chain(bool())
+        {
+            assert!(v.to_char().is_none());
+        }
+    }
+
+    #[test]
+    fn test_visit_integer() {
+        struct Extract(Option<u64>);
+
+        impl<'v> VisitValue<'v> for Extract {
+            fn visit_any(&mut self, value: Value) -> Result<(), Error> {
+                unimplemented!("unexpected value: {value:?}")
+            }
+
+            fn visit_u64(&mut self, value: u64) -> Result<(), Error> {
+                self.0 = Some(value);
+
+                Ok(())
+            }
+        }
+
+        let mut extract = Extract(None);
+        Value::from(42u64).visit(&mut extract).unwrap();
+
+        assert_eq!(Some(42), extract.0);
+    }
+
+    #[test]
+    fn test_visit_borrowed_str() {
+        struct Extract<'v>(Option<&'v str>);
+
+        impl<'v> VisitValue<'v> for Extract<'v> {
+            fn visit_any(&mut self, value: Value) -> Result<(), Error> {
+                unimplemented!("unexpected value: {value:?}")
+            }
+
+            fn visit_borrowed_str(&mut self, value: &'v str) -> Result<(), Error> {
+                self.0 = Some(value);
+
+                Ok(())
+            }
+        }
+
+        let mut extract = Extract(None);
+
+        let short_lived = String::from("A short-lived string");
+        Value::from(&*short_lived).visit(&mut extract).unwrap();
+
+        assert_eq!(Some("A short-lived string"), extract.0);
+    }
+
+    #[test]
+    fn test_eq() {
+        assert_eq!(Value::from(1u8), Value::from(1i64));
+        assert_eq!(Value::from(1u128), Value::from(1i8));
+        assert_eq!(Value::from(u128::MAX), Value::from(u128::MAX));
+        assert_eq!(Value::from(1.5f64), Value::from(1.5f32));
+        assert_eq!(Value::from(true), Value::from(true));
+        assert_eq!(Value::from('a'), Value::from('a'));
+        assert_eq!(Value::from("a"), Value::from("a"));
+        assert_eq!(Value::null(), ().to_value());
+        assert_eq!(Value::from_bytes(&[1, 2]), Value::from_bytes(&[1, 2]));
+
+        assert_ne!(Value::from(1u8), Value::from(2u8));
+        assert_ne!(Value::from(-1i8), Value::from(u128::MAX));
+        assert_ne!(Value::from(1u8), Value::from(1.0f64));
+        assert_ne!(Value::from('a'), Value::from("a"));
+        assert_ne!(Value::from("a"), Value::from("b"));
+        assert_ne!(Value::from(f64::NAN), Value::from(f64::NAN));
+        assert_ne!(Value::from_debug(&1), Value::from_debug(&1));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_hash() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        fn hash(value: impl Hash) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            value.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        assert_eq!(hash(Value::from(1u8)), hash(Value::from(1i64)));
+        assert_eq!(hash(Value::from("a")), hash(Value::from("a")));
+        assert_eq!(hash(Value::from(0.0f64)), hash(Value::from(-0.0f64)));
+        assert_ne!(hash(Value::from(1u8)), hash(Value::from(1.0f64)));
+
+        let owned = Value::from("a").to_owned();
+        assert_eq!(owned, Value::from("a").to_owned());
+        assert_eq!(hash(&owned), hash(Value::from("a")));
+    }
+
+    #[test]
+    #[cfg(feature = "kv_sval")]
+    fn test_eq_computed_str() {
+        let computed = String::from("a string");
+
+        assert_eq!(Value::from("a string"), Value::from_sval(&computed));
+    }
+
+    #[test]
+    fn test_downcast_ref() {
+        #[derive(Debug, PartialEq)]
+        struct Id(u64);
+
+        impl ToValue for Id {
+            fn to_value(&self) -> Value {
+                Value::from(self.0)
+            }
+        }
+
+        let id = Id(42);
+        let v = Value::from_concrete(&id);
+
+        assert!(v.is::<Id>());
+        assert_eq!(Some(&Id(42)), v.downcast_ref::<Id>());
+        assert!(!v.is::<u64>());
+
+        assert_eq!(Some(42), v.to_u64());
+        assert_eq!("42", v.to_string());
+
+        assert!(!id.to_value().is::<Id>());
+        assert!(!Value::from(42u64).is::<u64>());
+    }
+
+    #[test]
+    fn test_bytes() {
+        struct Extract<'v>(Option<&'v [u8]>);
+
+        impl<'v> VisitValue<'v> for Extract<'v> {
+            fn visit_any(&mut self, value: Value) -> Result<(), Error> {
+                unimplemented!("unexpected value: {value:?}")
+            }
+
+            fn visit_bytes(&mut self, value: &'v [u8]) -> Result<(), Error> {
+                self.0 = Some(value);
+
+                Ok(())
+            }
+        }
+
+        let bytes = [1u8, 2, 3];
+        let v = Value::from_bytes(&bytes);
+
+        assert_eq!(Some(&bytes[..]), v.to_borrowed_bytes());
+        assert!(v.to_u64().is_none());
+        assert!(v.to_borrowed_str().is_none());
+        assert!(Value::from("a string").to_borrowed_bytes().is_none());
+
+        assert_eq!("[1, 2, 3]", v.to_string());
+        assert_eq!("[1, 2, 3]", format!("{:?}", v));
+
+        let mut extract = Extract(None);
+        v.visit(&mut extract).unwrap();
+
+        assert_eq!(Some(&bytes[..]), extract.0);
+    }
+
+    #[test]
+    #[cfg(feature = "kv_serde")]
+    fn test_bytes_serde() {
+        use serde_test::{assert_ser_tokens, Token};
+
+        assert_ser_tokens(&Value::from_bytes(&[1, 2, 3]), &[Token::Bytes(&[1, 2, 3])]);
+    }
+
+    #[test]
+    fn test_seq() {
+        struct Collect(Vec<Option<i64>>);
+
+        impl<'v> VisitSeq<'v> for Collect {
+            fn visit_value(&mut self, value: Value<'v>) -> Result<(), Error> {
+                self.0.push(value.to_i64());
+
+                Ok(())
+            }
+        }
+
+        let seq = [1, 2, 3];
+        let v = seq.to_value();
+
+        let mut collect = Collect(Vec::new());
+        v.as_seq().unwrap().visit(&mut collect).unwrap();
+
+        assert_eq!(vec![Some(1), Some(2), Some(3)], collect.0);
+        assert!(v.as_map().is_none());
+        assert!(v.to_i64().is_none());
+        assert!(Value::from(1).as_seq().is_none());
+
+        assert_eq!("[1, 2, 3]", v.to_string());
+        assert_eq!("[1, 2, 3]", format!("{:?}", v));
+        assert_eq!(
+            "[[1, 2], [3, 4]]",
+            Value::from_seq(&[[1, 2], [3, 4]]).to_string()
+        );
+    }
+
+    #[test]
+    fn test_seq_iter() {
+        let batch = vec![1, 2, 3];
+
+        let v = SeqIter::new(&batch);
+        assert_eq!("[1, 2, 3]", v.to_value().to_string());
+        assert!(v.to_value().as_seq().is_some());
+
+        let v = SeqIter::new(batch.iter().filter(|id| **id != 2));
+        assert_eq!("[1, 3]", v.to_value().to_string());
+        assert_eq!("[]", SeqIter::new(&[] as &[i32]).to_value().to_string());
+    }
+
+    #[test]
+    fn test_map() {
+        use crate::kv::{Key, VisitSource};
+
+        struct Collect<'kvs>(Vec<(Key<'kvs>, Option<i64>)>);
+
+        impl<'kvs> VisitSource<'kvs> for Collect<'kvs> {
+            fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+                self.0.push((key, value.to_i64()));
+
+                Ok(())
+            }
+        }
+
+        let map = [("a", 1), ("b", 2)];
+        let v = Value::from_map(&map);
+
+        let mut collect = Collect(Vec::new());
+        v.as_map().unwrap().visit(&mut collect).unwrap();
+
+        assert_eq!(
+            vec![(Key::from("a"), Some(1)), (Key::from("b"), Some(2))],
+            collect.0
+        );
+        assert!(v.as_seq().is_none());
+        assert!(Value::from(1).as_map().is_none());
+
+        assert_eq!("{\"a\": 1, \"b\": 2}", v.to_string());
+        assert_eq!("{\"a\": 1, \"b\": 2}", format!("{:?}", v));
+    }
+
+    #[test]
+    fn test_visit_seq_map() {
+        use crate::kv::Key;
+
+        #[derive(Default)]
+        struct Count {
+            any: usize,
+            seq: usize,
+            map: usize,
+        }
+
+        impl<'v> VisitValue<'v> for Count {
+            fn visit_any(&mut self, _: Value) -> Result<(), Error> {
+                self.any += 1;
+                Ok(())
+            }
+
+            fn visit_seq(&mut self, seq: &'v dyn Seq) -> Result<(), Error> {
+                self.seq += 1;
 
-#[cfg(feature = "kv_unstable_serde")]
-#[deprecated(note = "use the `key:serde = value` macro syntax instead")]
-/// Get a value from a type implementing `serde::Serialize`.
-#[macro_export]
-macro_rules! as_serde {
-    ($capture:expr) => {
-        $crate::kv::Value::from_serde(&$capture)
-    };
-}
+                struct Elements<'a>(&'a mut Count);
 
-/// Get a value from a type implementing `sval::Value`.
-#[cfg(feature = "kv_unstable_sval")]
-#[deprecated(note = "use the `key:sval = value` macro syntax instead")]
-#[macro_export]
-macro_rules! as_sval {
-    ($capture:expr) => {
-        $crate::kv::Value::from_sval(&$capture)
-    };
-}
+                impl<'a, 'v> VisitSeq<'v> for Elements<'a> {
+                    fn visit_value(&mut self, value: Value<'v>) -> Result<(), Error> {
+                        value.visit(&mut *self.0)
+                    }
+                }
 
-#[cfg(test)]
-pub(crate) mod tests {
-    use super::*;
+                seq.visit(&mut Elements(self))
+            }
 
-    impl<'v> Value<'v> {
-        pub(crate) fn to_token(&self) -> inner::Token {
-            self.inner.to_test_token()
+            fn visit_map(&mut self, map: &'v dyn Source) -> Result<(), Error> {
+                self.map += 1;
+
+                map.get(Key::from("a")).unwrap().visit(self)
+            }
         }
-    }
 
-    fn unsigned() -> impl Iterator<Item = Value<'static>> {
-        vec![
-            Value::from(8u8),
-            Value::from(16u16),
-            Value::from(32u32),
-            Value::from(64u64),
-            Value::from(1usize),
-            Value::from(std::num::NonZeroU8::new(8).unwrap()),
-            Value::from(std::num::NonZeroU16::new(16).unwrap()),
-            Value::from(std::num::NonZeroU32::new(32).unwrap()),
-            Value::from(std::num::NonZeroU64::new(64).unwrap()),
-            Value::from(std::num::NonZeroUsize::new(1).unwrap()),
-        ]
-        .into_iter()
-    }
+        let mut count = Count::default();
 
-    fn signed() -> impl Iterator<Item = Value<'static>> {
-        vec![
-            Value::from(-8i8),
-            Value::from(-16i16),
-            Value::from(-32i32),
-            Value::from(-64i64),
-            Value::from(-1isize),
-            Value::from(std::num::NonZeroI8::new(-8).unwrap()),
-            Value::from(std::num::NonZeroI16::new(-16).unwrap()),
-            Value::from(std::num::NonZeroI32::new(-32).unwrap()),
-            Value::from(std::num::NonZeroI64::new(-64).unwrap()),
-            Value::from(std::num::NonZeroIsize::new(-1).unwrap()),
-        ]
-        .into_iter()
-    }
+        let seq = [Value::from(1), Value::from_map(&[("a", [1, 2])])];
+        Value::from_seq(&seq).visit(&mut count).unwrap();
 
-    fn float() -> impl Iterator<Item = Value<'static>> {
-        vec![Value::from(32.32f32), Value::from(64.64f64)].into_iter()
-    }
+        assert_eq!(2, count.seq);
+        assert_eq!(1, count.map);
+        assert_eq!(3, count.any);
 
-    fn bool() -> impl Iterator<Item = Value<'static>> {
-        vec![Value::from(true), Value::from(false)].into_iter()
-    }
+        // Without overriding them, sequences and maps fallthrough to `visit_any`
+        struct Any(Option<String>);
 
-    fn str() -> impl Iterator<Item = Value<'static>> {
-        vec![Value::from("a string"), Value::from("a loong string")].into_iter()
-    }
+        impl<'v> VisitValue<'v> for Any {
+            fn visit_any(&mut self, value: Value) -> Result<(), Error> {
+                self.0 = Some(value.to_string());
+                Ok(())
+            }
+        }
 
-    fn char() -> impl Iterator<Item = Value<'static>> {
-        vec![Value::from('a'), Value::from('⛰')].into_iter()
+        let mut any = Any(None);
+        Value::from_seq(&[1, 2]).visit(&mut any).unwrap();
+
+        assert_eq!(Some("[1, 2]"), any.0.as_deref());
     }
 
     #[test]
-    fn test_to_value_display() {
-        assert_eq!(42u64.to_value().to_string(), "42");
-        assert_eq!(42i64.to_value().to_string(), "42");
-        assert_eq!(42.01f64.to_value().to_string(), "42.01");
-        assert_eq!(true.to_value().to_string(), "true");
-        assert_eq!('a'.to_value().to_string(), "a");
-        assert_eq!("a loong string".to_value().to_string(), "a loong string");
-        assert_eq!(Some(true).to_value().to_string(), "true");
-        assert_eq!(().to_value().to_string(), "None");
-        assert_eq!(None::<bool>.to_value().to_string(), "None");
+    #[cfg(feature = "kv_std")]
+    fn test_std_seq_map() {
+        use std::collections::BTreeMap;
+
+        let v = vec!["a", "b"];
+        assert_eq!("[\"a\", \"b\"]", v.to_value().to_string());
+
+        let mut map = BTreeMap::new();
+        map.insert("a", vec![1]);
+        map.insert("b", vec![2, 3]);
+
+        let v = map.to_value();
+        assert_eq!("{\"a\": [1], \"b\": [2, 3]}", v.to_string());
+
+        let owned = v.to_owned();
+        assert_eq!("{\"a\": [1], \"b\": [2, 3]}", owned.to_string());
+        assert_eq!(2, owned.by_ref().as_map().unwrap().count());
     }
 
     #[test]
-    fn test_to_value_structured() {
-        assert_eq!(42u64.to_value().to_token(), inner::Token::U64(42));
-        assert_eq!(42i64.to_value().to_token(), inner::Token::I64(42));
-        assert_eq!(42.01f64.to_value().to_token(), inner::Token::F64(42.01));
-        assert_eq!(true.to_value().to_token(), inner::Token::Bool(true));
-        assert_eq!('a'.to_value().to_token(), inner::Token::Char('a'));
-        assert_eq!(
-            "a loong string".to_value().to_token(),
-            inner::Token::Str("a loong string".into())
+    #[cfg(feature = "kv_serde")]
+    fn test_seq_map_serde() {
+        use serde_test::{assert_ser_tokens, Token};
+
+        assert_ser_tokens(
+            &Value::from_seq(&[1u8, 2]),
+            &[
+                Token::Seq { len: None },
+                Token::U64(1),
+                Token::U64(2),
+                Token::SeqEnd,
+            ],
+        );
+
+        assert_ser_tokens(
+            &Value::from_map(&[("a", 1u8)]),
+            &[
+                Token::Map { len: Some(1) },
+                Token::Str("a"),
+                Token::U64(1),
+                Token::MapEnd,
+            ],
         );
-        assert_eq!(Some(true).to_value().to_token(), inner::Token::Bool(true));
-        assert_eq!(().to_value().to_token(), inner::Token::None);
-        assert_eq!(None::<bool>.to_value().to_token(), inner::Token::None);
     }
 
     #[test]
-    fn test_to_number() {
-        for v in unsigned() {
-            assert!(v.to_u64().is_some());
-            assert!(v.to_i64().is_some());
+    fn test_format_hint() {
+        for (expected, value, hint) in [
+            ("ff", Value::from(255u8), FormatHint::LowerHex),
+            ("FF", Value::from(255u8), FormatHint::UpperHex),
+            ("ffffffffffffffff", Value::from(-1i64), FormatHint::LowerHex),
+            ("377", Value::from(255u8), FormatHint::Octal),
+            ("101", Value::from(5u8), FormatHint::Binary),
+            ("1.5e3", Value::from(1500.0f64), FormatHint::LowerExp),
+            ("1.5E3", Value::from(1500.0f64), FormatHint::UpperExp),
+            ("1.235", Value::from(1.23456f64), FormatHint::Precision(3)),
+            ("a", Value::from("a"), FormatHint::LowerHex),
+        ] {
+            let v = Value::from_hinted(&value, hint);
+
+            assert_eq!(Some(hint), v.to_format_hint());
+            assert_eq!(expected, v.to_string());
+            assert_eq!(expected, format!("{:?}", v));
         }
 
-        for v in signed() {
-            assert!(v.to_i64().is_some());
-        }
+        let v = Value::from_hinted(&255u8, FormatHint::LowerHex);
+        assert_eq!(Some(255), v.to_u64());
 
-        for v in unsigned().chain(signed()).chain(float()) {
-            assert!(v.to_f64().is_some());
-        }
+        #[cfg(feature = "kv_serde")]
+        assert_eq!("255", serde_json::to_string(&v).unwrap());
 
-        for v in bool().chain(str()).chain(char()) {
-            assert!(v.to_u64().is_none());
-            assert!(v.to_i64().is_none());
-            assert!(v.to_f64().is_none());
-        }
+        assert_eq!(None, Value::from(255u8).to_format_hint());
     }
 
     #[test]
-    fn test_to_float() {
-        // Only integers from i32::MIN..=u32::MAX can be converted into floats
-        assert!(Value::from(i32::MIN).to_f64().is_some());
-        assert!(Value::from(u32::MAX).to_f64().is_some());
+    fn test_format_hint_pretty_debug() {
+        let v = Value::from_hinted(&"a", FormatHint::PrettyDebug);
 
-        assert!(Value::from((i32::MIN as i64) - 1).to_f64().is_none());
-        assert!(Value::from((u32::MAX as u64) + 1).to_f64().is_none());
+        assert_eq!(Some(FormatHint::PrettyDebug), v.to_format_hint());
+        assert_eq!("\"a\"", v.to_string());
+        assert_eq!(Some("a"), v.to_borrowed_str());
+
+        let seq = [1, 2];
+        let v = Value::from_hinted(&seq, FormatHint::PrettyDebug);
+
+        assert_eq!("[\n    1,\n    2,\n]", v.to_string());
+        assert_eq!("[\n    1,\n    2,\n]", format!("{:?}", v));
     }
 
     #[test]
-    fn test_to_cow_str() {
-        for v in str() {
-            assert!(v.to_borrowed_str().is_some());
+    fn test_kind() {
+        for (expected, value) in [
+            (ValueKind::Null, Value::null()),
+            (ValueKind::Bool, Value::from(true)),
+            (ValueKind::Char, Value::from('a')),
+            (ValueKind::Str, Value::from("a")),
+            (ValueKind::Int, Value::from(1u8)),
+            (ValueKind::Int, Value::from(-1i64)),
+            (ValueKind::Int, Value::from(u128::MAX)),
+            (ValueKind::Float, Value::from(1.5f64)),
+            (ValueKind::Bytes, Value::from_bytes(&[1, 2])),
+            (ValueKind::Seq, Value::from_seq(&[1, 2])),
+            (ValueKind::Map, Value::from_map(&[("a", 1)])),
+            (ValueKind::Int, Value::from_concrete(&1u8)),
+            (ValueKind::Str, Value::from_redacted(&1u8)),
+            (ValueKind::Float, Value::from_f64_with_unit(1.5, "ms")),
+            (
+                ValueKind::Int,
+                Value::from_hinted(&255u8, FormatHint::LowerHex),
+            ),
+            (ValueKind::Other, Value::from_debug(&1u8)),
+            (ValueKind::Other, Value::from_display(&1u8)),
+        ] {
+            assert_eq!(expected, value.kind());
+        }
 
-            #[cfg(feature = "kv_std")]
-            assert!(v.to_cow_str().is_some());
+        assert!(Value::null().is_null());
+        assert!(Value::from(true).is_bool());
+        assert!(Value::from("a").is_str());
+        assert!(Value::from(1u8).is_number());
+        assert!(Value::from(1.5f64).is_number());
+        assert!(!Value::from("1").is_number());
+        assert!(Value::from_seq(&[1]).is_seq());
+        assert!(Value::from_map(&[("a", 1)]).is_map());
+
+        #[cfg(feature = "kv_std")]
+        {
+            let err = std::io::Error::new(std::io::ErrorKind::Other, "an error");
+            assert_eq!(ValueKind::Error, Value::from_dyn_error(&err).kind());
         }
+    }
 
-        let short_lived = String::from("short lived");
-        let v = Value::from(&*short_lived);
+    #[test]
+    fn test_unit() {
+        let v = Value::from_f64_with_unit(12.5, "ms");
 
-        assert!(v.to_borrowed_str().is_some());
+        assert_eq!(Some("ms"), v.to_unit());
+        assert_eq!(Some(12.5), v.to_f64());
+        assert_eq!("12.5", v.to_string());
 
-        #[cfg(feature = "kv_std")]
-        assert!(v.to_cow_str().is_some());
+        let v = Value::from(1024u32).with_unit("bytes");
+        assert_eq!(Some("bytes"), v.to_unit());
+        assert_eq!(Some(1024), v.to_u64());
 
-        for v in unsigned().chain(signed()).chain(float()).chain(bool()) {
-            assert!(v.to_borrowed_str().is_none());
+        let v = Value::from(-1i8).with_unit("s");
+        assert_eq!(Some("s"), v.to_unit());
+        assert_eq!(Some(-1), v.to_i64());
 
-            #[cfg(feature = "kv_std")]
-            assert!(v.to_cow_str().is_none());
+        #[cfg(feature = "std")]
+        {
+            let owned = v.to_owned();
+            assert_eq!(Some("s"), owned.by_ref().to_unit());
+            assert_eq!(Some(-1), owned.by_ref().to_i64());
         }
+
+        #[cfg(feature = "kv_serde")]
+        assert_eq!("-1", serde_json::to_string(&v).unwrap());
+
+        assert_eq!(None, Value::from("a").with_unit("ms").to_unit());
+        assert_eq!(None, Value::from(1).to_unit());
     }
 
     #[test]
-    fn test_to_bool() {
-        for v in bool() {
-            assert!(v.to_bool().is_some());
-        }
+    fn test_lazy() {
+        use std::cell::Cell;
 
-        for v in unsigned()
-            .chain(signed())
-            .chain(float())
-            .chain(str())
-            .chain(char())
+        let calls = Cell::new(0);
+        let lazy = Lazy::new(|| {
+            calls.set(calls.get() + 1);
+            "a value"
+        });
+
+        let v = lazy.to_value();
+        assert_eq!(0, calls.get());
+
+        assert_eq!("a value", v.to_string());
+        assert_eq!("\"a value\"", format!("{:?}", lazy));
+        assert_eq!(2, calls.get());
+    }
+
+    #[test]
+    fn test_redacted() {
+        let secret = Redacted::new(42);
+        let v = secret.to_value();
+
+        assert!(v.is_redacted());
+        assert_eq!("[REDACTED]", format!("{}", v));
+        assert_eq!("\"[REDACTED]\"", format!("{:?}", v));
+        assert_eq!("[REDACTED]", format!("{:?}", secret));
+        assert_eq!(Some("[REDACTED]"), v.to_borrowed_str());
+        assert_eq!(None, v.to_u64());
+        assert_eq!(Some(42), v.to_unredacted().and_then(|v| v.to_u64()));
+
+        #[cfg(feature = "std")]
         {
-            assert!(v.to_bool().is_none());
+            let owned = v.to_owned();
+            assert!(!owned.by_ref().is_redacted());
+            assert_eq!("[REDACTED]", owned.by_ref().to_string());
         }
+
+        #[cfg(feature = "kv_serde")]
+        assert_eq!(r#""[REDACTED]""#, serde_json::to_string(&v).unwrap());
+
+        assert!(!Value::from(42).is_redacted());
+        assert!(Value::from(42).to_unredacted().is_none());
     }
 
     #[test]
-    fn test_to_char() {
-        for v in char() {
-            assert!(v.to_char().is_some());
+    #[cfg(feature = "kv_std")]
+    fn test_result() {
+        let ok: Result<i32, std::io::Error> = Ok(1);
+        let v = Value::from_result(&ok);
+
+        assert_eq!(Some(1), v.to_i64());
+        assert!(v.to_borrowed_error().is_none());
+
+        let err: Result<i32, std::io::Error> =
+            Err(std::io::Error::new(std::io::ErrorKind::Other, "an error"));
+        let v = Value::from_result(&err);
+
+        assert_eq!(None, v.to_i64());
+        assert_eq!("an error", v.to_borrowed_error().unwrap().to_string());
+    }
+
+    #[test]
+    #[cfg(feature = "kv_std")]
+    fn test_error_chain() {
+        #[derive(Debug)]
+        struct Outer(std::io::Error);
+
+        impl fmt::Display for Outer {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("outer")
+            }
         }
 
-        for v in unsigned()
-            .chain(signed())
-            .chain(float())
-            .chain(str())
-            .chain(bool())
-        {
-            assert!(v.to_char().is_none());
+        impl std::error::Error for Outer {
+            fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+                Some(&self.0)
+            }
         }
+
+        let err = Outer(std::io::Error::new(std::io::ErrorKind::Other, "inner"));
+        let v = Value::from_dyn_error(&err);
+
+        let chain = v.to_error_chain().expect("invalid value");
+        assert_eq!(
+            vec!["outer".to_owned(), "inner".to_owned()],
+            chain.clone().map(|err| err.to_string()).collect::<Vec<_>>()
+        );
+        #[cfg(feature = "kv_serde")]
+        assert_eq!(
+            r#"["outer","inner"]"#,
+            serde_json::to_string(&Value::from_seq(&chain)).unwrap()
+        );
+
+        assert!(Value::from("outer").to_error_chain().is_none());
     }
 
     #[test]
-    fn test_visit_integer() {
-        struct Extract(Option<u64>);
+    #[cfg(feature = "kv_std")]
+    fn test_duration() {
+        use std::time::Duration;
+
+        struct Extract(Option<Duration>);
 
         impl<'v> VisitValue<'v> for Extract {
             fn visit_any(&mut self, value: Value) -> Result<(), Error> {
                 unimplemented!("unexpected value: {value:?}")
             }
 
-            fn visit_u64(&mut self, value: u64) -> Result<(), Error> {
+            fn visit_duration(&mut self, value: Duration) -> Result<(), Error> {
                 self.0 = Some(value);
 
                 Ok(())
             }
         }
 
+        let duration = Duration::from_micros(1_234_500);
+        let v = duration.to_value();
+
+        assert_eq!(Some(duration), v.to_duration());
+        assert!(v.to_u64().is_none());
+        assert!(Value::from(1u64).to_duration().is_none());
+
+        assert_eq!("1.2345s", v.to_string());
+        assert_eq!("1.2345s", format!("{:?}", v));
+        assert_eq!("1.2345s", v.to_owned().to_string());
+
         let mut extract = Extract(None);
-        Value::from(42u64).visit(&mut extract).unwrap();
+        v.visit(&mut extract).unwrap();
 
-        assert_eq!(Some(42), extract.0);
+        assert_eq!(Some(duration), extract.0);
     }
 
     #[test]
-    fn test_visit_borrowed_str() {
-        struct Extract<'v>(Option<&'v str>);
+    #[cfg(feature = "kv_std")]
+    fn test_system_time() {
+        use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+        for (expected, since_epoch) in [
+            ("1970-01-01T00:00:00Z", Duration::from_secs(0)),
+            ("2000-02-29T00:00:00Z", Duration::from_secs(951_782_400)),
+            (
+                "2023-11-14T22:13:20.5Z",
+                Duration::from_millis(1_700_000_000_500),
+            ),
+            (
+                "2023-11-14T22:13:20.000000001Z",
+                Duration::new(1_700_000_000, 1),
+            ),
+        ] {
+            let time = UNIX_EPOCH + since_epoch;
+            let v = time.to_value();
+
+            assert_eq!(Some(time), v.to_system_time());
+            assert_eq!(expected, v.to_string());
+            assert_eq!(expected, format!("{:?}", v));
+            assert_eq!(Some(time), v.to_owned().by_ref().to_system_time());
+        }
 
-        impl<'v> VisitValue<'v> for Extract<'v> {
-            fn visit_any(&mut self, value: Value) -> Result<(), Error> {
-                unimplemented!("unexpected value: {value:?}")
-            }
+        assert!(Value::from("2000-02-29T00:00:00Z")
+            .to_system_time()
+            .is_none());
+        assert!(Value::from(SystemTime::now()).to_duration().is_none());
+    }
 
-            fn visit_borrowed_str(&mut self, value: &'v str) -> Result<(), Error> {
-                self.0 = Some(value);
+    #[test]
+    #[cfg(feature = "kv_std")]
+    fn test_addr() {
+        use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV6};
+
+        let ipv4 = Ipv4Addr::new(127, 0, 0, 1);
+        let ipv6 = Ipv6Addr::LOCALHOST;
+
+        for (expected, v) in [
+            ("127.0.0.1", ipv4.to_value()),
+            ("::1", ipv6.to_value()),
+            ("127.0.0.1", IpAddr::V4(ipv4).to_value()),
+        ] {
+            assert_eq!(expected, v.to_string());
+            assert_eq!(expected, format!("{:?}", v));
+            assert_eq!(Some(expected.into()), v.to_cow_str());
+            assert_eq!(Some(expected.parse().unwrap()), v.to_ip_addr());
+            assert!(v.to_socket_addr().is_none());
+        }
 
-                Ok(())
-            }
+        for (expected, v) in [
+            ("127.0.0.1:8080", SocketAddr::from((ipv4, 8080)).to_value()),
+            ("[::1]:8080", SocketAddrV6::new(ipv6, 8080, 0, 0).to_value()),
+        ] {
+            assert_eq!(expected, v.to_string());
+            assert_eq!(Some(expected.into()), v.to_cow_str());
+            assert_eq!(Some(expected.parse().unwrap()), v.to_socket_addr());
+            assert_eq!(
+                Some(expected.parse().unwrap()),
+                v.to_owned().by_ref().to_socket_addr()
+            );
+            assert!(v.to_ip_addr().is_none());
         }
 
-        let mut extract = Extract(None);
+        assert!(Value::from("127.0.0.1").to_ip_addr().is_none());
+    }
 
-        let short_lived = String::from("A short-lived string");
-        Value::from(&*short_lived).visit(&mut extract).unwrap();
+    #[test]
+    #[cfg(feature = "kv_serde")]
+    fn test_addr_serde() {
+        use serde_test::{assert_ser_tokens, Token};
+        use std::net::{Ipv6Addr, SocketAddr};
+
+        assert_ser_tokens(&Value::from(&Ipv6Addr::LOCALHOST), &[Token::Str("::1")]);
+        assert_ser_tokens(
+            &Value::from(&SocketAddr::from(([127, 0, 0, 1], 8080))),
+            &[Token::Str("127.0.0.1:8080")],
+        );
+    }
 
-        assert_eq!(Some("A short-lived string"), extract.0);
+    #[test]
+    #[cfg(feature = "kv_serde")]
+    fn test_time_serde() {
+        use serde_test::{assert_ser_tokens, Token};
+        use std::time::{Duration, UNIX_EPOCH};
+
+        assert_ser_tokens(
+            &Value::from_duration(Duration::new(1, 2)),
+            &[
+                Token::Struct {
+                    name: "Duration",
+                    len: 2,
+                },
+                Token::Str("secs"),
+                Token::U64(1),
+                Token::Str("nanos"),
+                Token::U32(2),
+                Token::StructEnd,
+            ],
+        );
+
+        assert_ser_tokens(
+            &Value::from_system_time(UNIX_EPOCH + Duration::new(1, 2)),
+            &[
+                Token::Struct {
+                    name: "SystemTime",
+                    len: 2,
+                },
+                Token::Str("secs_since_epoch"),
+                Token::U64(1),
+                Token::Str("nanos_since_epoch"),
+                Token::U32(2),
+                Token::StructEnd,
+            ],
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_to_owned() {
+        for v in unsigned().chain(signed()) {
+            let owned = v.to_owned();
+
+            assert_eq!(v.to_i128(), owned.by_ref().to_i128());
+            assert_eq!(v.to_string(), owned.to_string());
+        }
+
+        for v in float().chain(bool()).chain(str()).chain(char()) {
+            let owned = v.to_owned();
+
+            assert_eq!(v.to_token(), owned.by_ref().to_token());
+            assert_eq!(v.to_string(), owned.to_string());
+        }
+
+        let owned = {
+            let short_lived = String::from("a short-lived string");
+            Value::from(&*short_lived).to_owned()
+        };
+        assert_eq!(
+            Some("a short-lived string"),
+            owned.by_ref().to_borrowed_str()
+        );
+
+        let owned = {
+            let short_lived = vec![1, 2, 3];
+            Value::from_debug(&short_lived).to_owned()
+        };
+        assert_eq!("[1, 2, 3]", owned.to_string());
+        assert_eq!("[1, 2, 3]", format!("{:?}", owned));
+
+        let owned = {
+            let short_lived = vec![1u8, 2, 3];
+            Value::from_bytes(&short_lived).to_owned()
+        };
+        assert_eq!(Some(&[1u8, 2, 3][..]), owned.by_ref().to_borrowed_bytes());
     }
 }
@@ -21,7 +21,8 @@
 //!
 //! A log request consists of a _target_, a _level_, and a _body_. A target is a
 //! string which defaults to the module path of the location of the log request,
-//! though that default may be overridden. Logger implementations typically use
+//! though that default may be overridden, or extended with a suffix like
+//! `info!(target+: "db", ...)`. Logger implementations typically use
 //! the target to filter requests based on some user configuration.
 //!
 //! # Usage
@@ -124,7 +125,43 @@
 //! # fn main() {}
 //! ```
 //!
-//! See the [`kv`] module documentation for more details.
+//! See the [`kv`](mod@kv) module documentation for more details.
+//!
+//! ## Instrumenting functions
+//!
+//! If you enable the `instrument` feature you can log when a function is entered and exited
+//! using the `#[log::instrument]` attribute, instead of writing the logging statements yourself:
+//!
+//! ```
+//! # #[derive(Debug)] pub struct Yak(String);
+//! # impl Yak { fn shave(&mut self, _: u32) {} }
+//! # #[cfg(feature = "instrument")]
+//! # fn main() {
+//! #[log::instrument(level = "debug", target = "yak_events", skip(yak))]
+//! pub fn shave_the_yak(yak: &mut Yak, razor: u32) {
+//!     yak.shave(razor);
+//! }
+//! # }
+//! # #[cfg(not(feature = "instrument"))]
+//! # fn main() {}
+//! ```
+//!
+//! On entry, a record with the message `enter shave_the_yak` is logged, with each argument
+//! captured as a key-value using its `Debug` implementation. On exit, including early returns
+//! and panics, a record with the message `exit shave_the_yak` is logged, with a `duration_ms`
+//! key-value holding the time spent in the function. The attribute accepts:
+//!
+//! * `level`: the level to log at, either as a string like `"debug"` or an expression like
+//!   `log::Level::Debug`. The default is `info`.
+//! * `target`: the target to log with. The default is the module path.
+//! * `name`: the name to use in messages. The default is the function's name.
+//! * `skip(..)`: arguments that shouldn't be captured, like ones that don't implement `Debug`.
+//! * `crate`: the path to `log` as a string, like `crate = "my_log"`, for crates that rename
+//!   or re-export it. The default is `::log`.
+//!
+//! `self` and arguments that are destructured by patterns aren't captured. For `async`
+//! functions, the time is measured from when the returned future is first polled until it
+//! completes or is dropped.
 //!
 //! # Available logging implementations
 //!
@@ -275,6 +312,37 @@
 //! Libraries should avoid using the max level features because they're global and can't be changed
 //! once they're set.
 //!
+//! Instead, a crate can lower the max level for its own log invocations by setting the
+//! `LOG_STATIC_MAX_LEVEL` environment variable while it's compiled, to one of `off`, `error`,
+//! `warn`, `info`, `debug`, or `trace`. The logging macros read this variable using `option_env!`
+//! when they're expanded, so it only affects the crate they're called from. It can be set in the
+//! crate's build script:
+//!
+//! ```no_run
+//! // In build.rs
+//! println!("cargo:rustc-env=LOG_STATIC_MAX_LEVEL=debug");
+//! ```
+//!
+//! This level can only lower the max level set by the features above, not raise it.
+//!
+//! Logging for whole subsystems can also be compiled out by setting the `LOG_STRIP_TARGETS`
+//! environment variable while `log` is compiled, to a comma-separated list of target prefixes.
+//! A prefix matches the target with the same name and any targets nested under it, so
+//! `my_crate::internal` strips `my_crate::internal` and `my_crate::internal::cache`, but not
+//! `my_crate::internals`. Log invocations in modules that match are removed from the binary
+//! entirely, and records for other explicit targets that match are dropped before they reach
+//! the logger. Since this applies to all crates, it's usually set by the final binary in its
+//! `.cargo/config.toml`:
+//!
+//! ```toml
+//! [env]
+//! LOG_STRIP_TARGETS = "my_crate::internal,noisy_dependency"
+//! ```
+//!
+//! With the `std` feature, the max level for a target and any targets nested under it can also
+//! be lowered at runtime using [`set_target_max_level`]. This is checked by the logging macros
+//! before a record reaches the logger, so it works the same way whichever logger is installed.
+//!
 //! For example, a crate can disable trace level logs in debug builds and trace, debug, and info
 //! level logs in release builds with the following configuration:
 //!
@@ -282,6 +350,13 @@
 //! [dependencies]
 //! log = { version = "0.4", features = ["max_level_debug", "release_max_level_warn"] }
 //! ```
+//!
+//! The `release_strip_location` feature stops the logging macros from embedding the source
+//! file, line, and module path of log invocations in release builds, which can noticeably shrink
+//! binaries for embedded targets. Records are still logged with their level, target, and message,
+//! but [`Record::module_path`], [`Record::file`], and [`Record::line`] return `None`. Module paths
+//! are still embedded when they're used as the default target.
+//!
 //! # Crate Feature Flags
 //!
 //! The following crate feature flags are available in addition to the filters. They are
@@ -289,7 +364,34 @@
 //!
 //! * `std` allows use of `std` crate instead of the default `core`. Enables using `std::error` and
 //! `set_boxed_logger` functionality.
-//! * `serde` enables support for serialization and deserialization of `Level` and `LevelFilter`.
+//! * `serde` enables support for serialization and deserialization of `Level` and `LevelFilter`,
+//!   and serialization of `Metadata` and `Record`. Records are serialized as a single object
+//!   with their message rendered, and their key-values as a nested map with `kv_serde`.
+//! * `timestamp` adds a [`Record::timestamp`], which the logging macros set to the current time
+//!   when the `std` feature is also enabled.
+//! * `seq` adds a [`Record::seq`], which the logging macros set to a sequence number that
+//!   increases with each record logged on any thread.
+//! * `thread` adds a [`Record::thread_id`] and [`Record::thread_name`], which the logging
+//!   macros set to the thread the record is logged on. It requires `std`.
+//! * `task_local` adds [`scope_async`], for overriding the logger for a single future. It
+//!   requires `std`.
+//! * `crate_info` adds [`Metadata::crate_name`] and [`Metadata::crate_version`], which the
+//!   logging macros set to the `CARGO_PKG_NAME` and `CARGO_PKG_VERSION` of the crate they're
+//!   called from.
+//! * `early_buffer` buffers records logged before the global logger is set, up to 1024 of
+//!   them, and replays them into the logger once it's set, so diagnostics from early in
+//!   startup aren't lost. Records are only filtered by the logger's [`Log::enabled`] when
+//!   they're replayed. The max level still starts at `Off`, so nothing is buffered until
+//!   it's raised, using [`set_max_level`] or [`set_early_buffer_level`]. It requires `std`.
+//! * `callsite_cache` makes the logging macros cache each logger's [`Log::interest`] in the
+//!   invocations that don't specify a target, so invocations the logger isn't interested in
+//!   are skipped cheaply. Invocations with a `target:` are never cached, because the target
+//!   may change between calls, and neither are the `*_if!`, rate-limited, `log_time!`, and
+//!   `log_writer!` macros.
+//! * `unsafe_replace_logger` adds [`replace_boxed_logger`] and [`unset_logger`], for swapping
+//!   the global logger after it's been set. They're `unsafe`, because the previous logger may
+//!   still be in use when it's returned. It requires `std`.
+//! * `instrument` enables the `#[log::instrument]` attribute for logging function entry and exit.
 //!
 //! ```toml
 //! [dependencies]
@@ -388,17 +490,59 @@ use std::cfg;
 #[cfg(feature = "std")]
 use std::error;
 use std::str::FromStr;
-use std::{cmp, fmt, mem};
+use std::{cmp, fmt, mem, ptr};
 
 #[macro_use]
 mod macros;
+#[cfg(feature = "std")]
+mod dispatch;
+#[cfg(feature = "early_buffer")]
+mod early;
+#[cfg(feature = "std")]
+mod owned;
+#[cfg(feature = "std")]
+mod scoped;
 mod serde;
+#[cfg(feature = "std")]
+mod targets;
+#[cfg(feature = "std")]
+mod writer;
+
+#[cfg(feature = "std")]
+pub use self::dispatch::Dispatch;
+#[cfg(feature = "early_buffer")]
+pub use self::early::set_early_buffer_level;
+#[cfg(all(feature = "kv", feature = "std"))]
+pub use self::kv::global::set_global_kv;
+#[cfg(all(feature = "kv", feature = "std"))]
+pub use self::kv::scope::Context;
+#[cfg(all(feature = "kv", feature = "task_local"))]
+pub use self::kv::scope::WithContext;
+#[cfg(feature = "std")]
+pub use self::owned::OwnedRecord;
+#[cfg(feature = "std")]
+pub use self::scoped::with_scoped_logger;
+#[cfg(feature = "task_local")]
+pub use self::scoped::{scope_async, ScopedFuture};
+#[cfg(feature = "std")]
+pub use self::targets::{clear_target_max_level, set_target_max_level, target_max_level};
+#[cfg(feature = "std")]
+pub use self::writer::LogWriter;
+
+#[cfg(feature = "instrument")]
+pub use log_derive::instrument;
 
 #[cfg(feature = "kv")]
 pub mod kv;
 
+#[cfg(feature = "std")]
+pub mod filter;
+
+#[cfg(all(feature = "kv", feature = "std"))]
+pub mod mdc;
+
 #[cfg(target_has_atomic = "ptr")]
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
 
 #[cfg(not(target_has_atomic = "ptr"))]
 use std::cell::Cell;
@@ -424,7 +568,12 @@ impl AtomicUsize {
         self.v.set(val)
     }
 
-    #[cfg(target_has_atomic = "ptr")]
+    fn fetch_add(&self, val: usize, _order: Ordering) -> usize {
+        let prev = self.v.get();
+        self.v.set(prev.wrapping_add(val));
+        prev
+    }
+
     fn compare_exchange(
         &self,
         current: usize,
@@ -435,8 +584,30 @@ impl AtomicUsize {
         let prev = self.v.get();
         if current == prev {
             self.v.set(new);
+            Ok(prev)
+        } else {
+            Err(prev)
         }
-        Ok(prev)
+    }
+}
+
+#[cfg(not(target_has_atomic = "ptr"))]
+struct AtomicPtr<T> {
+    p: Cell<*mut T>,
+}
+
+#[cfg(not(target_has_atomic = "ptr"))]
+impl<T> AtomicPtr<T> {
+    const fn new(p: *mut T) -> AtomicPtr<T> {
+        AtomicPtr { p: Cell::new(p) }
+    }
+
+    fn load(&self, _order: Ordering) -> *mut T {
+        self.p.get()
+    }
+
+    fn store(&self, p: *mut T, _order: Ordering) {
+        self.p.set(p)
     }
 }
 
@@ -444,13 +615,26 @@ impl AtomicUsize {
 // writing via Cell will not be a race condition.
 #[cfg(not(target_has_atomic = "ptr"))]
 unsafe impl Sync for AtomicUsize {}
+#[cfg(not(target_has_atomic = "ptr"))]
+unsafe impl<T> Sync for AtomicPtr<T> {}
 
 // The LOGGER static holds a pointer to the global logger. It is protected by
 // the STATE static which determines whether LOGGER has been initialized yet.
+#[cfg(not(all(feature = "unsafe_replace_logger", target_has_atomic = "ptr")))]
 static mut LOGGER: &dyn Log = &NopLogger;
 
+// With `unsafe_replace_logger` the logger can be written while other threads are
+// reading it, so LOGGER points to a leaked cell holding it that's swapped atomically.
+// Cells are never freed, because another thread may still be reading an old one.
+#[cfg(all(feature = "unsafe_replace_logger", target_has_atomic = "ptr"))]
+static LOGGER: std::sync::atomic::AtomicPtr<&'static dyn Log> =
+    std::sync::atomic::AtomicPtr::new(std::ptr::null_mut());
+
 static STATE: AtomicUsize = AtomicUsize::new(0);
 
+// Bumped whenever the interest cached by callsites may be out of date.
+static INTEREST_EPOCH: AtomicUsize = AtomicUsize::new(0);
+
 // There are three different states that we care about: the logger's
 // uninitialized, the logger's initializing (set_logger's been called but
 // LOGGER hasn't actually been set yet), or the logger's active.
@@ -460,8 +644,25 @@ const INITIALIZED: usize = 2;
 
 static MAX_LOG_LEVEL_FILTER: AtomicUsize = AtomicUsize::new(0);
 
+// The handler passed to `set_error_handler`, as a `fn(LogError)`, or null if there isn't one.
+static ERROR_HANDLER: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
 static LOG_LEVEL_NAMES: [&str; 6] = ["OFF", "ERROR", "WARN", "INFO", "DEBUG", "TRACE"];
 
+// Syslog severity names that are also accepted when parsing levels
+static LOG_LEVEL_ALIASES: [(&str, Level); 3] = [
+    ("NOTICE", Level::Info),
+    ("CRITICAL", Level::Error),
+    ("ALERT", Level::Error),
+];
+
+fn level_from_alias(level: &str) -> Option<Level> {
+    LOG_LEVEL_ALIASES
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(level))
+        .map(|(_, level)| *level)
+}
+
 static SET_LOGGER_ERROR: &str = "attempted to set a logger after the logging system \
                                  was already initialized";
 static LEVEL_PARSE_ERROR: &str =
@@ -525,6 +726,7 @@ impl FromStr for Level {
             .filter(|&idx| idx != 0)
             .map(|idx| Level::from_usize(idx).unwrap())
             .next()
+            .or_else(|| level_from_alias(level))
             .ok_or(ParseLevelError(()))
     }
 }
@@ -583,6 +785,81 @@ impl Level {
     pub fn iter() -> impl Iterator<Item = Self> {
         (1..6).map(|i| Self::from_usize(i).unwrap())
     }
+
+    /// Returns the numeric verbosity of the `Level`.
+    ///
+    /// Verbosity starts at `1` for `Error` and goes up to `5` for `Trace`.
+    #[inline]
+    pub fn verbosity(&self) -> u32 {
+        *self as u32
+    }
+
+    /// Converts a numeric verbosity to the `Level` that carries it.
+    ///
+    /// Verbosities from `1` to `5` are the named levels, from `Error` to `Trace`. Anything
+    /// more verbose than that is `Trace`, with the finer verbosity carried separately in
+    /// [`Metadata::verbosity`]. Returns `None` for a verbosity of `0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use log::Level;
+    ///
+    /// assert_eq!(Some(Level::Info), Level::from_verbosity(3));
+    /// assert_eq!(Some(Level::Trace), Level::from_verbosity(7));
+    /// assert_eq!(None, Level::from_verbosity(0));
+    /// ```
+    #[inline]
+    pub fn from_verbosity(verbosity: u32) -> Option<Level> {
+        match verbosity {
+            0 => None,
+            v => Level::from_usize(cmp::min(v, 5) as usize),
+        }
+    }
+
+    /// Converts the `Level` to a syslog severity, as defined by [RFC 5424].
+    ///
+    /// `Error` is `3` (error), `Warn` is `4` (warning), `Info` is `6` (informational), and
+    /// both `Debug` and `Trace` are `7` (debug).
+    ///
+    /// [RFC 5424]: https://datatracker.ietf.org/doc/html/rfc5424#section-6.2.1
+    #[inline]
+    pub fn to_syslog_severity(&self) -> u8 {
+        match self {
+            Level::Error => 3,
+            Level::Warn => 4,
+            Level::Info => 6,
+            Level::Debug | Level::Trace => 7,
+        }
+    }
+
+    /// Converts a syslog severity, as defined by [RFC 5424], to the closest `Level`.
+    ///
+    /// Severities from `0` (emergency) to `3` (error) are `Error`, `4` (warning) is `Warn`,
+    /// `5` (notice) and `6` (informational) are `Info`, and `7` (debug) is `Debug`. Returns
+    /// `None` for anything else.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use log::Level;
+    ///
+    /// assert_eq!(Some(Level::Error), Level::from_syslog_severity(2));
+    /// assert_eq!(Some(Level::Info), Level::from_syslog_severity(5));
+    /// assert_eq!(None, Level::from_syslog_severity(8));
+    /// ```
+    ///
+    /// [RFC 5424]: https://datatracker.ietf.org/doc/html/rfc5424#section-6.2.1
+    #[inline]
+    pub fn from_syslog_severity(severity: u8) -> Option<Level> {
+        match severity {
+            0..=3 => Some(Level::Error),
+            4 => Some(Level::Warn),
+            5 | 6 => Some(Level::Info),
+            7 => Some(Level::Debug),
+            _ => None,
+        }
+    }
 }
 
 /// An enum representing the available verbosity level filters of the logger.
@@ -590,6 +867,13 @@ impl Level {
 /// A `LevelFilter` may be compared directly to a [`Level`]. Use this type
 /// to get and set the maximum log level with [`max_level()`] and [`set_max_level`].
 ///
+/// `LevelFilter` implements `FromStr`, so command line parsers that accept `FromStr` types,
+/// like clap's `value_parser!`, can parse it directly. `log` doesn't depend on clap, so there's
+/// no `clap::ValueEnum` implementation; the possible values can be listed using
+/// [`iter`](LevelFilter::iter) instead. Flags like `-v` and `-q` can be mapped onto it using
+/// [`increase`](LevelFilter::increase) and [`decrease`](LevelFilter::decrease), or
+/// [`from_verbosity`](LevelFilter::from_verbosity).
+///
 /// [`Level`]: enum.Level.html
 /// [`max_level()`]: fn.max_level.html
 /// [`set_max_level`]: fn.set_max_level.html
@@ -631,6 +915,7 @@ impl FromStr for LevelFilter {
             .iter()
             .position(|&name| name.eq_ignore_ascii_case(level))
             .map(|p| LevelFilter::from_usize(p).unwrap())
+            .or_else(|| level_from_alias(level).map(|level| level.to_level_filter()))
             .ok_or(ParseLevelError(()))
     }
 }
@@ -692,6 +977,57 @@ impl LevelFilter {
     pub fn iter() -> impl Iterator<Item = Self> {
         (0..6).map(|i| Self::from_usize(i).unwrap())
     }
+
+    /// Converts a numeric verbosity, like the number of `-v` flags given to a command,
+    /// to a `LevelFilter`.
+    ///
+    /// A verbosity of `0` is `Off`, and anything more verbose than `5` is `Trace`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use log::LevelFilter;
+    ///
+    /// assert_eq!(LevelFilter::Off, LevelFilter::from_verbosity(0));
+    /// assert_eq!(LevelFilter::Warn, LevelFilter::from_verbosity(2));
+    /// assert_eq!(LevelFilter::Trace, LevelFilter::from_verbosity(7));
+    /// ```
+    #[inline]
+    pub fn from_verbosity(verbosity: u32) -> LevelFilter {
+        LevelFilter::from_usize(cmp::min(verbosity, 5) as usize).unwrap()
+    }
+
+    /// Returns the next more verbose `LevelFilter`, or `Trace` if `self` is already `Trace`.
+    ///
+    /// Together with [`decrease`](LevelFilter::decrease), this maps `-v` and `-q` command line
+    /// flags onto a default filter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use log::LevelFilter;
+    ///
+    /// # let (verbose, quiet) = (2, 0);
+    /// let mut filter = LevelFilter::Warn;
+    /// for _ in 0..verbose {
+    ///     filter = filter.increase();
+    /// }
+    /// for _ in 0..quiet {
+    ///     filter = filter.decrease();
+    /// }
+    ///
+    /// assert_eq!(LevelFilter::Debug, filter);
+    /// ```
+    #[inline]
+    pub fn increase(&self) -> LevelFilter {
+        LevelFilter::from_usize(cmp::min(*self as usize + 1, 5)).unwrap()
+    }
+
+    /// Returns the next less verbose `LevelFilter`, or `Off` if `self` is already `Off`.
+    #[inline]
+    pub fn decrease(&self) -> LevelFilter {
+        LevelFilter::from_usize((*self as usize).saturating_sub(1)).unwrap()
+    }
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
@@ -764,8 +1100,40 @@ pub struct Record<'a> {
     line: Option<u32>,
     #[cfg(feature = "kv")]
     key_values: KeyValues<'a>,
+    #[cfg(feature = "kv")]
+    template: Option<&'a str>,
+    #[cfg(feature = "kv")]
+    message_value: Option<&'a kv::Value<'a>>,
+    #[cfg(feature = "timestamp")]
+    timestamp: Option<Timestamp>,
+    #[cfg(feature = "seq")]
+    seq: Option<u64>,
+    #[cfg(feature = "thread")]
+    thread_id: Option<std::thread::ThreadId>,
+    #[cfg(feature = "thread")]
+    thread_name: Option<&'a str>,
+    #[cfg(feature = "std")]
+    error: Option<&'a dyn error::Error>,
 }
 
+/// The time a [`Record`] was created.
+///
+/// With the `std` feature, this is a [`SystemTime`](std::time::SystemTime), and records
+/// created by the logging macros are timestamped when they're logged. Without `std` there's
+/// no clock to read, so timestamps are a `u64` set using [`RecordBuilder::timestamp`], in
+/// whatever unit the application's loggers agree on.
+#[cfg(all(feature = "timestamp", feature = "std"))]
+pub type Timestamp = std::time::SystemTime;
+
+/// The time a [`Record`] was created.
+///
+/// With the `std` feature, this is a [`SystemTime`](std::time::SystemTime), and records
+/// created by the logging macros are timestamped when they're logged. Without `std` there's
+/// no clock to read, so timestamps are a `u64` set using [`RecordBuilder::timestamp`], in
+/// whatever unit the application's loggers agree on.
+#[cfg(all(feature = "timestamp", not(feature = "std")))]
+pub type Timestamp = u64;
+
 // This wrapper type is only needed so we can
 // `#[derive(Debug)]` on `Record`. It also
 // provides a useful `Debug` implementation for
@@ -857,21 +1225,110 @@ impl<'a> Record<'a> {
         self.key_values.0
     }
 
+    /// The message template the message was rendered from, if there is one.
+    ///
+    /// Records logged with `template:` before their message, like
+    /// `info!(user = name; template: "User {user} logged in")`, keep the raw template
+    /// here, so backends can group records by their template rather than their
+    /// rendered message.
+    #[cfg(feature = "kv")]
+    #[inline]
+    pub fn template(&self) -> Option<&'a str> {
+        self.template
+    }
+
+    /// The message as a structured value, if there is one.
+    ///
+    /// Event-style records can carry a machine-readable payload as their primary body,
+    /// set using [`RecordBuilder::message_value`], so backends can forward it without
+    /// stringifying it first. The record's [`args`](Record::args) are still its message
+    /// rendered as text, for backends that don't look for a value.
+    #[cfg(feature = "kv")]
+    #[inline]
+    pub fn message_value(&self) -> Option<kv::Value<'a>> {
+        self.message_value.map(kv::ToValue::to_value)
+    }
+
+    /// The time the record was created, if it's known.
+    ///
+    /// Backends that process records after they're logged, like ones that
+    /// buffer them or send them to another thread, should use this instead of
+    /// the current time.
+    #[cfg(feature = "timestamp")]
+    #[inline]
+    pub fn timestamp(&self) -> Option<Timestamp> {
+        self.timestamp
+    }
+
+    /// The sequence number of the record, if it has one.
+    ///
+    /// The logging macros number records from a counter shared by every thread, so
+    /// backends that buffer records on multiple threads, or receive them out of order, can
+    /// restore the order they were logged in. Sequence numbers always increase, but may
+    /// skip values for records that were filtered out. On targets without 64-bit atomics,
+    /// the counter is pointer-sized, so it wraps after `usize::MAX` records.
+    ///
+    /// Sequence numbers are assigned when the logging macros log a record, not when a
+    /// record is built, so records built using [`Record::builder`] don't have one unless
+    /// it's set using [`RecordBuilder::seq`].
+    #[cfg(feature = "seq")]
+    #[inline]
+    pub fn seq(&self) -> Option<u64> {
+        self.seq
+    }
+
+    /// The id of the thread that logged the record.
+    ///
+    /// Records created by the logging macros have the id of the thread they were logged on.
+    /// Backends should use this instead of [`std::thread::current`], which returns the wrong
+    /// thread for records that have been forwarded from another one.
+    #[cfg(feature = "thread")]
+    #[inline]
+    pub fn thread_id(&self) -> Option<std::thread::ThreadId> {
+        self.thread_id
+    }
+
+    /// The name of the thread that logged the record, if it has one.
+    #[cfg(feature = "thread")]
+    #[inline]
+    pub fn thread_name(&self) -> Option<&'a str> {
+        self.thread_name
+    }
+
+    /// The error attached to the record, if there is one.
+    ///
+    /// The error is independent of the record's key-values, so backends can report it
+    /// consistently, including walking its chain of [`source`](std::error::Error::source)s.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn error(&self) -> Option<&'a dyn error::Error> {
+        self.error
+    }
+
     /// Create a new [`RecordBuilder`](struct.RecordBuilder.html) based on this record.
     #[cfg(feature = "kv")]
     #[inline]
     pub fn to_builder(&self) -> RecordBuilder {
         RecordBuilder {
             record: Record {
-                metadata: Metadata {
-                    level: self.metadata.level,
-                    target: self.metadata.target,
-                },
+                metadata: self.metadata.clone(),
                 args: self.args,
                 module_path: self.module_path,
                 file: self.file,
                 line: self.line,
                 key_values: self.key_values.clone(),
+                template: self.template,
+                message_value: self.message_value,
+                #[cfg(feature = "timestamp")]
+                timestamp: self.timestamp,
+                #[cfg(feature = "seq")]
+                seq: self.seq,
+                #[cfg(feature = "thread")]
+                thread_id: self.thread_id,
+                #[cfg(feature = "thread")]
+                thread_name: self.thread_name,
+                #[cfg(feature = "std")]
+                error: self.error,
             },
         }
     }
@@ -945,6 +1402,20 @@ impl<'a> RecordBuilder<'a> {
                 line: None,
                 #[cfg(feature = "kv")]
                 key_values: KeyValues(&None::<(kv::Key, kv::Value)>),
+                #[cfg(feature = "kv")]
+                template: None,
+                #[cfg(feature = "kv")]
+                message_value: None,
+                #[cfg(feature = "timestamp")]
+                timestamp: None,
+                #[cfg(feature = "seq")]
+                seq: None,
+                #[cfg(feature = "thread")]
+                thread_id: None,
+                #[cfg(feature = "thread")]
+                thread_name: None,
+                #[cfg(feature = "std")]
+                error: None,
             },
         }
     }
@@ -977,6 +1448,47 @@ impl<'a> RecordBuilder<'a> {
         self
     }
 
+    /// Set [`Metadata::process`](struct.Metadata.html#method.process)
+    #[inline]
+    pub fn process(&mut self, process: Option<&'a ProcessInfo>) -> &mut RecordBuilder<'a> {
+        self.record.metadata.process = process;
+        self
+    }
+
+    /// Set [`Metadata::verbosity`](struct.Metadata.html#method.verbosity)
+    #[inline]
+    pub fn verbosity(&mut self, verbosity: u32) -> &mut RecordBuilder<'a> {
+        self.record.metadata.verbosity = Some(verbosity);
+        self
+    }
+
+    /// Set [`Metadata::crate_name`](struct.Metadata.html#method.crate_name)
+    #[cfg(feature = "crate_info")]
+    #[inline]
+    pub fn crate_name(&mut self, crate_name: Option<&'a str>) -> &mut RecordBuilder<'a> {
+        self.record.metadata.crate_name = crate_name;
+        self
+    }
+
+    /// Set [`Metadata::crate_version`](struct.Metadata.html#method.crate_version)
+    #[cfg(feature = "crate_info")]
+    #[inline]
+    pub fn crate_version(&mut self, crate_version: Option<&'a str>) -> &mut RecordBuilder<'a> {
+        self.record.metadata.crate_version = crate_version;
+        self
+    }
+
+    /// Set [`Metadata::static_key_values`](struct.Metadata.html#method.static_key_values)
+    #[cfg(feature = "kv")]
+    #[inline]
+    pub fn static_key_values(
+        &mut self,
+        static_key_values: &'a [(&'a str, &'a str)],
+    ) -> &mut RecordBuilder<'a> {
+        self.record.metadata.static_key_values = static_key_values;
+        self
+    }
+
     /// Set [`module_path`](struct.Record.html#method.module_path)
     #[inline]
     pub fn module_path(&mut self, path: Option<&'a str>) -> &mut RecordBuilder<'a> {
@@ -1020,6 +1532,68 @@ impl<'a> RecordBuilder<'a> {
         self
     }
 
+    /// Set [`template`](struct.Record.html#method.template)
+    #[cfg(feature = "kv")]
+    #[inline]
+    pub fn template(&mut self, template: Option<&'a str>) -> &mut RecordBuilder<'a> {
+        self.record.template = template;
+        self
+    }
+
+    /// Set [`message_value`](struct.Record.html#method.message_value)
+    ///
+    /// The [`args`](RecordBuilder::args) should still be set to the value rendered as text,
+    /// like `format_args!("{}", value)`.
+    #[cfg(feature = "kv")]
+    #[inline]
+    pub fn message_value(&mut self, value: Option<&'a kv::Value<'a>>) -> &mut RecordBuilder<'a> {
+        self.record.message_value = value;
+        self
+    }
+
+    /// Set [`timestamp`](struct.Record.html#method.timestamp)
+    #[cfg(feature = "timestamp")]
+    #[inline]
+    pub fn timestamp(&mut self, timestamp: Option<Timestamp>) -> &mut RecordBuilder<'a> {
+        self.record.timestamp = timestamp;
+        self
+    }
+
+    /// Set [`seq`](struct.Record.html#method.seq)
+    #[cfg(feature = "seq")]
+    #[inline]
+    pub fn seq(&mut self, seq: Option<u64>) -> &mut RecordBuilder<'a> {
+        self.record.seq = seq;
+        self
+    }
+
+    /// Set [`thread_id`](struct.Record.html#method.thread_id)
+    #[cfg(feature = "thread")]
+    #[inline]
+    pub fn thread_id(
+        &mut self,
+        thread_id: Option<std::thread::ThreadId>,
+    ) -> &mut RecordBuilder<'a> {
+        self.record.thread_id = thread_id;
+        self
+    }
+
+    /// Set [`thread_name`](struct.Record.html#method.thread_name)
+    #[cfg(feature = "thread")]
+    #[inline]
+    pub fn thread_name(&mut self, thread_name: Option<&'a str>) -> &mut RecordBuilder<'a> {
+        self.record.thread_name = thread_name;
+        self
+    }
+
+    /// Set [`error`](struct.Record.html#method.error)
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn error(&mut self, error: &'a dyn error::Error) -> &mut RecordBuilder<'a> {
+        self.record.error = Some(error);
+        self
+    }
+
     /// Invoke the builder and return a `Record`
     #[inline]
     pub fn build(&self) -> Record<'a> {
@@ -1075,6 +1649,16 @@ impl<'a> Default for RecordBuilder<'a> {
 pub struct Metadata<'a> {
     level: Level,
     target: &'a str,
+    #[cfg(feature = "kv")]
+    keys: &'a [&'a str],
+    #[cfg(feature = "kv")]
+    static_key_values: &'a [(&'a str, &'a str)],
+    process: Option<&'a ProcessInfo>,
+    verbosity: Option<u32>,
+    #[cfg(feature = "crate_info")]
+    crate_name: Option<&'a str>,
+    #[cfg(feature = "crate_info")]
+    crate_version: Option<&'a str>,
 }
 
 impl<'a> Metadata<'a> {
@@ -1095,47 +1679,210 @@ impl<'a> Metadata<'a> {
     pub fn target(&self) -> &'a str {
         self.target
     }
-}
-
-/// Builder for [`Metadata`](struct.Metadata.html).
-///
-/// Typically should only be used by log library creators or for testing and "shim loggers".
-/// The `MetadataBuilder` can set the different parameters of a `Metadata` object, and returns
-/// the created object when `build` is called.
-///
-/// # Example
-///
-/// ```
-/// let target = "myApp";
-/// use log::{Level, MetadataBuilder};
-/// let metadata = MetadataBuilder::new()
-///                     .level(Level::Debug)
-///                     .target(target)
-///                     .build();
-/// ```
-#[derive(Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
-pub struct MetadataBuilder<'a> {
-    metadata: Metadata<'a>,
-}
 
-impl<'a> MetadataBuilder<'a> {
-    /// Construct a new `MetadataBuilder`.
-    ///
-    /// The default options are:
+    /// The keys of the key-values a record would be logged with.
     ///
-    /// - `level`: `Level::Info`
-    /// - `target`: `""`
+    /// Keys are given when asking whether a record would be logged using
+    /// `log_enabled!(Level::Debug, user_id, "http.method")`, so loggers that filter on
+    /// key-values can say so before they're computed. Keys are empty otherwise,
+    /// including for the metadata of a [`Record`]; use [`Record::key_values`] instead.
+    #[cfg(feature = "kv")]
     #[inline]
-    pub fn new() -> MetadataBuilder<'a> {
-        MetadataBuilder {
-            metadata: Metadata {
-                level: Level::Info,
-                target: "",
-            },
-        }
+    pub fn keys(&self) -> &'a [&'a str] {
+        self.keys
     }
 
-    /// Setter for [`level`](struct.Metadata.html#method.level).
+    /// The static key-values declared at the callsite.
+    ///
+    /// Static key-values, like a component or subsystem name, are declared using
+    /// `log!(static: [component = "db"], ..)`. Unlike a record's key-values, they're part
+    /// of its metadata, so loggers can filter on them in [`Log::enabled`] before any
+    /// dynamic values are computed. They aren't included in [`Record::key_values`].
+    #[cfg(feature = "kv")]
+    #[inline]
+    pub fn static_key_values(&self) -> &'a [(&'a str, &'a str)] {
+        self.static_key_values
+    }
+
+    /// The process that logged the message, if it was set using
+    /// [`MetadataBuilder::process`].
+    #[inline]
+    pub fn process(&self) -> Option<&'a ProcessInfo> {
+        self.process
+    }
+
+    /// The id of the process that logged the message.
+    ///
+    /// If a pid wasn't set using [`MetadataBuilder::process`] then, with the `std` feature,
+    /// it's the id of the current process, fetched when this method is called. Records
+    /// forwarded from another process should set the pid of the process they came from.
+    #[inline]
+    pub fn pid(&self) -> Option<u32> {
+        self.process
+            .and_then(ProcessInfo::pid)
+            .or_else(process::current_pid)
+    }
+
+    /// The id of the parent of the process that logged the message.
+    ///
+    /// If a parent pid wasn't set using [`MetadataBuilder::process`] then, with the `std`
+    /// feature on Unix platforms, it's the id of the current process's parent, fetched when
+    /// this method is called.
+    #[inline]
+    pub fn parent_pid(&self) -> Option<u32> {
+        self.process
+            .and_then(ProcessInfo::parent_pid)
+            .or_else(process::current_parent_pid)
+    }
+
+    /// The numeric verbosity of the message.
+    ///
+    /// This is the [verbosity of its level](Level::verbosity) unless a finer one was set
+    /// using [`MetadataBuilder::verbosity`], like the `vlog!(7, ..)` macro does. Verbosities
+    /// past `5` are all logged at the `Trace` level, so loggers can use them to tell the
+    /// most detailed records apart without abusing targets.
+    #[inline]
+    pub fn verbosity(&self) -> u32 {
+        self.verbosity.unwrap_or_else(|| self.level.verbosity())
+    }
+
+    /// The name of the crate that logged the message.
+    ///
+    /// The logging macros set this to the `CARGO_PKG_NAME` of the crate they're called
+    /// from, so records can be attributed to a component even when their target is
+    /// overridden.
+    #[cfg(feature = "crate_info")]
+    #[inline]
+    pub fn crate_name(&self) -> Option<&'a str> {
+        self.crate_name
+    }
+
+    /// The version of the crate that logged the message.
+    ///
+    /// The logging macros set this to the `CARGO_PKG_VERSION` of the crate they're called from.
+    #[cfg(feature = "crate_info")]
+    #[inline]
+    pub fn crate_version(&self) -> Option<&'a str> {
+        self.crate_version
+    }
+}
+
+/// The process a record was logged from.
+///
+/// Records logged by the current process don't need this, since [`Metadata::pid`] and
+/// [`Metadata::parent_pid`] default to the current process. Records forwarded from another
+/// process can set the process they came from using [`MetadataBuilder::process`]. Metadata
+/// only holds a reference to it, since it's usually the same for every record from a process.
+///
+/// # Examples
+///
+/// ```
+/// use log::{Metadata, ProcessInfo};
+///
+/// let process = ProcessInfo::new(Some(42), Some(1));
+///
+/// let metadata = Metadata::builder().process(Some(&process)).build();
+///
+/// assert_eq!(Some(42), metadata.pid());
+/// assert_eq!(Some(1), metadata.parent_pid());
+/// ```
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct ProcessInfo {
+    pid: Option<u32>,
+    parent_pid: Option<u32>,
+}
+
+impl ProcessInfo {
+    /// Describe a process by its id and the id of its parent.
+    #[inline]
+    pub const fn new(pid: Option<u32>, parent_pid: Option<u32>) -> Self {
+        ProcessInfo { pid, parent_pid }
+    }
+
+    /// The id of the process.
+    #[inline]
+    pub fn pid(&self) -> Option<u32> {
+        self.pid
+    }
+
+    /// The id of the parent of the process.
+    #[inline]
+    pub fn parent_pid(&self) -> Option<u32> {
+        self.parent_pid
+    }
+}
+
+mod process {
+    #[cfg(feature = "std")]
+    pub(crate) fn current_pid() -> Option<u32> {
+        Some(std::process::id())
+    }
+
+    #[cfg(not(feature = "std"))]
+    pub(crate) fn current_pid() -> Option<u32> {
+        None
+    }
+
+    #[cfg(all(feature = "std", unix))]
+    pub(crate) fn current_parent_pid() -> Option<u32> {
+        Some(std::os::unix::process::parent_id())
+    }
+
+    #[cfg(not(all(feature = "std", unix)))]
+    pub(crate) fn current_parent_pid() -> Option<u32> {
+        None
+    }
+}
+
+/// Builder for [`Metadata`](struct.Metadata.html).
+///
+/// Typically should only be used by log library creators or for testing and "shim loggers".
+/// The `MetadataBuilder` can set the different parameters of a `Metadata` object, and returns
+/// the created object when `build` is called.
+///
+/// # Example
+///
+/// ```
+/// let target = "myApp";
+/// use log::{Level, MetadataBuilder};
+/// let metadata = MetadataBuilder::new()
+///                     .level(Level::Debug)
+///                     .target(target)
+///                     .build();
+/// ```
+#[derive(Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct MetadataBuilder<'a> {
+    metadata: Metadata<'a>,
+}
+
+impl<'a> MetadataBuilder<'a> {
+    /// Construct a new `MetadataBuilder`.
+    ///
+    /// The default options are:
+    ///
+    /// - `level`: `Level::Info`
+    /// - `target`: `""`
+    #[inline]
+    pub fn new() -> MetadataBuilder<'a> {
+        MetadataBuilder {
+            metadata: Metadata {
+                level: Level::Info,
+                target: "",
+                #[cfg(feature = "kv")]
+                keys: &[],
+                #[cfg(feature = "kv")]
+                static_key_values: &[],
+                process: None,
+                verbosity: None,
+                #[cfg(feature = "crate_info")]
+                crate_name: None,
+                #[cfg(feature = "crate_info")]
+                crate_version: None,
+            },
+        }
+    }
+
+    /// Setter for [`level`](struct.Metadata.html#method.level).
     #[inline]
     pub fn level(&mut self, arg: Level) -> &mut MetadataBuilder<'a> {
         self.metadata.level = arg;
@@ -1149,6 +1896,55 @@ impl<'a> MetadataBuilder<'a> {
         self
     }
 
+    /// Setter for [`keys`](struct.Metadata.html#method.keys).
+    #[cfg(feature = "kv")]
+    #[inline]
+    pub fn keys(&mut self, keys: &'a [&'a str]) -> &mut MetadataBuilder<'a> {
+        self.metadata.keys = keys;
+        self
+    }
+
+    /// Setter for [`static_key_values`](struct.Metadata.html#method.static_key_values).
+    #[cfg(feature = "kv")]
+    #[inline]
+    pub fn static_key_values(
+        &mut self,
+        static_key_values: &'a [(&'a str, &'a str)],
+    ) -> &mut MetadataBuilder<'a> {
+        self.metadata.static_key_values = static_key_values;
+        self
+    }
+
+    /// Setter for [`process`](struct.Metadata.html#method.process).
+    #[inline]
+    pub fn process(&mut self, process: Option<&'a ProcessInfo>) -> &mut MetadataBuilder<'a> {
+        self.metadata.process = process;
+        self
+    }
+
+    /// Setter for [`verbosity`](struct.Metadata.html#method.verbosity).
+    #[inline]
+    pub fn verbosity(&mut self, verbosity: u32) -> &mut MetadataBuilder<'a> {
+        self.metadata.verbosity = Some(verbosity);
+        self
+    }
+
+    /// Setter for [`crate_name`](struct.Metadata.html#method.crate_name).
+    #[cfg(feature = "crate_info")]
+    #[inline]
+    pub fn crate_name(&mut self, crate_name: Option<&'a str>) -> &mut MetadataBuilder<'a> {
+        self.metadata.crate_name = crate_name;
+        self
+    }
+
+    /// Setter for [`crate_version`](struct.Metadata.html#method.crate_version).
+    #[cfg(feature = "crate_info")]
+    #[inline]
+    pub fn crate_version(&mut self, crate_version: Option<&'a str>) -> &mut MetadataBuilder<'a> {
+        self.metadata.crate_version = crate_version;
+        self
+    }
+
     /// Returns a `Metadata` object.
     #[inline]
     pub fn build(&self) -> Metadata<'a> {
@@ -1194,6 +1990,78 @@ pub trait Log: Sync + Send {
     /// This method isn't called automatically by the `log!` macros.
     /// It can be called manually on shut-down to ensure any in-flight records are flushed.
     fn flush(&self);
+
+    /// Flushes any buffered records, returning an error if they couldn't be flushed.
+    ///
+    /// This lets applications detect records that may have been lost, like when the
+    /// destination they're written to has gone away.
+    ///
+    /// # For implementors
+    ///
+    /// The default implementation calls [`Log::flush`] and returns `Ok`. Loggers that
+    /// can fail to flush should override this method, and have `flush` call it.
+    fn try_flush(&self) -> Result<(), FlushError> {
+        self.flush();
+        Ok(())
+    }
+
+    /// Shuts down the logger.
+    ///
+    /// This is called by applications at process exit, using `log::logger().shutdown()`,
+    /// and gives loggers a point to flush any buffered records and stop any background
+    /// work, like joining a thread that writes records from a channel. Records logged
+    /// after the logger is shut down may be dropped.
+    ///
+    /// # For implementors
+    ///
+    /// The default implementation calls [`Log::try_flush`], ignoring any error.
+    fn shutdown(&self) {
+        let _ = self.try_flush();
+    }
+
+    /// Determines whether records from a callsite with the specified metadata are ever,
+    /// sometimes, or always logged.
+    ///
+    /// With the `callsite_cache` feature, the `log!` macros call this method once for each
+    /// invocation that doesn't specify a target, and cache the result until
+    /// [`rebuild_interest_cache`] is called. Invocations the logger has no interest in are
+    /// skipped without building a record.
+    ///
+    /// Invocations that specify a `target:` never call this method, because their target may
+    /// change between calls. Neither do the `*_if!`, rate-limited, `log_time!`, and
+    /// `log_writer!` macros, even without a target.
+    ///
+    /// # For implementors
+    ///
+    /// The default implementation returns [`Interest::Sometimes`], so records are always
+    /// passed to [`Log::log`]. Loggers whose `enabled` method only depends on the metadata can
+    /// return `Interest::from(self.enabled(metadata))`, and must call
+    /// [`rebuild_interest_cache`] whenever their filters change.
+    fn interest(&self, metadata: &Metadata) -> Interest {
+        let _ = metadata;
+        Interest::Sometimes
+    }
+}
+
+/// A logger's interest in the records from a callsite, returned by [`Log::interest`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Interest {
+    /// Records from the callsite are never logged, so it can be skipped.
+    Never,
+    /// Records from the callsite may be logged, depending on more than their metadata.
+    Sometimes,
+    /// Records from the callsite are always logged.
+    Always,
+}
+
+impl From<bool> for Interest {
+    fn from(enabled: bool) -> Self {
+        if enabled {
+            Interest::Always
+        } else {
+            Interest::Never
+        }
+    }
 }
 
 // Just used as a dummy initial value for LOGGER
@@ -1222,6 +2090,18 @@ where
     fn flush(&self) {
         (**self).flush();
     }
+
+    fn try_flush(&self) -> Result<(), FlushError> {
+        (**self).try_flush()
+    }
+
+    fn shutdown(&self) {
+        (**self).shutdown();
+    }
+
+    fn interest(&self, metadata: &Metadata) -> Interest {
+        (**self).interest(metadata)
+    }
 }
 
 #[cfg(feature = "std")]
@@ -1239,6 +2119,18 @@ where
     fn flush(&self) {
         self.as_ref().flush();
     }
+
+    fn try_flush(&self) -> Result<(), FlushError> {
+        self.as_ref().try_flush()
+    }
+
+    fn shutdown(&self) {
+        self.as_ref().shutdown();
+    }
+
+    fn interest(&self, metadata: &Metadata) -> Interest {
+        self.as_ref().interest(metadata)
+    }
 }
 
 #[cfg(feature = "std")]
@@ -1256,6 +2148,18 @@ where
     fn flush(&self) {
         self.as_ref().flush();
     }
+
+    fn try_flush(&self) -> Result<(), FlushError> {
+        self.as_ref().try_flush()
+    }
+
+    fn shutdown(&self) {
+        self.as_ref().shutdown();
+    }
+
+    fn interest(&self, metadata: &Metadata) -> Interest {
+        self.as_ref().interest(metadata)
+    }
 }
 
 /// Sets the global maximum log level.
@@ -1335,7 +2239,7 @@ pub fn max_level() -> LevelFilter {
 /// [`set_logger`]: fn.set_logger.html
 #[cfg(all(feature = "std", target_has_atomic = "ptr"))]
 pub fn set_boxed_logger(logger: Box<dyn Log>) -> Result<(), SetLoggerError> {
-    set_logger_inner(|| Box::leak(logger))
+    set_logger_inner(|| Box::leak(logger), true)
 }
 
 /// Sets the global logger to a `&'static Log`.
@@ -1393,14 +2297,16 @@ pub fn set_boxed_logger(logger: Box<dyn Log>) -> Result<(), SetLoggerError> {
 /// [`set_logger_racy`]: fn.set_logger_racy.html
 #[cfg(target_has_atomic = "ptr")]
 pub fn set_logger(logger: &'static dyn Log) -> Result<(), SetLoggerError> {
-    set_logger_inner(|| logger)
+    set_logger_inner(|| logger, false)
 }
 
 #[cfg(target_has_atomic = "ptr")]
-fn set_logger_inner<F>(make_logger: F) -> Result<(), SetLoggerError>
+fn set_logger_inner<F>(make_logger: F, boxed: bool) -> Result<(), SetLoggerError>
 where
     F: FnOnce() -> &'static dyn Log,
 {
+    let _ = boxed;
+
     match STATE.compare_exchange(
         UNINITIALIZED,
         INITIALIZING,
@@ -1408,10 +2314,14 @@ where
         Ordering::Relaxed,
     ) {
         Ok(UNINITIALIZED) => {
+            let logger = make_logger();
             unsafe {
-                LOGGER = make_logger();
+                store_logger(logger);
             }
-            STATE.store(INITIALIZED, Ordering::Release);
+            #[cfg(feature = "unsafe_replace_logger")]
+            LOGGER_IS_BOXED.store(boxed, Ordering::Relaxed);
+            initialize(logger);
+            rebuild_interest_cache();
             Ok(())
         }
         Err(INITIALIZING) => {
@@ -1424,6 +2334,114 @@ where
     }
 }
 
+/// Replace the global logger with a `Box<Log>`, returning the previous one.
+///
+/// This is for long-running hosts, test suites, and dynamically-loaded plugins that need to
+/// swap the logger after it's been set. If no logger has been set yet then this behaves like
+/// [`set_boxed_logger`]. The previous logger is only returned if it was set using
+/// [`set_boxed_logger`] or this function. Loggers set using [`set_logger`] are `'static`, so
+/// there's nothing to return.
+///
+/// Records logged while the logger is being replaced may be dropped. The max level isn't
+/// changed. Each replacement leaks a pointer-sized allocation, because other threads may still
+/// be reading the previous logger through it.
+///
+/// Requires the `unsafe_replace_logger` feature.
+///
+/// # Safety
+///
+/// [`logger`] hands out `&'static` references to the global logger, and the log macros hold
+/// on to one while a record is logged. Dropping the returned logger while another thread
+/// may still be using it is undefined behavior. Callers must make sure no other thread can
+/// be logging through the previous logger before dropping it, or leak it instead.
+///
+/// # Examples
+///
+/// ```
+/// # use log::{Log, Metadata, Record};
+/// # struct MyLogger;
+/// # impl Log for MyLogger {
+/// #     fn enabled(&self, _: &Metadata) -> bool { true }
+/// #     fn log(&self, _: &Record) {}
+/// #     fn flush(&self) {}
+/// # }
+/// // SAFETY: No other threads are logging
+/// let previous = unsafe { log::replace_boxed_logger(Box::new(MyLogger)) };
+///
+/// if let Some(previous) = previous {
+///     previous.flush();
+/// }
+/// ```
+#[cfg(all(feature = "unsafe_replace_logger", target_has_atomic = "ptr"))]
+pub unsafe fn replace_boxed_logger(logger: Box<dyn Log>) -> Option<Box<dyn Log>> {
+    replace_logger_inner(Some(logger))
+}
+
+/// Remove the global logger, returning it.
+///
+/// After the logger is removed, records are dropped until another one is set using any
+/// of the `set_logger` functions, which can be called again. The previous logger is only
+/// returned if it was set using [`set_boxed_logger`] or [`replace_boxed_logger`]. The max
+/// level isn't changed.
+///
+/// Requires the `unsafe_replace_logger` feature.
+///
+/// # Safety
+///
+/// The same caveats as [`replace_boxed_logger`] apply. Dropping the returned logger while
+/// another thread may still be using it is undefined behavior.
+#[cfg(all(feature = "unsafe_replace_logger", target_has_atomic = "ptr"))]
+pub unsafe fn unset_logger() -> Option<Box<dyn Log>> {
+    replace_logger_inner(None)
+}
+
+// Whether `LOGGER` was leaked from a box, so it can be given back by `replace_logger_inner`
+#[cfg(all(feature = "unsafe_replace_logger", target_has_atomic = "ptr"))]
+static LOGGER_IS_BOXED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+#[cfg(all(feature = "unsafe_replace_logger", target_has_atomic = "ptr"))]
+unsafe fn replace_logger_inner(logger: Option<Box<dyn Log>>) -> Option<Box<dyn Log>> {
+    static NOP: NopLogger = NopLogger;
+
+    // Take the logger, waiting for any other thread that's setting it
+    let prev_state = loop {
+        match STATE.load(Ordering::Relaxed) {
+            INITIALIZING => std::hint::spin_loop(),
+            state => {
+                if STATE
+                    .compare_exchange(state, INITIALIZING, Ordering::Acquire, Ordering::Relaxed)
+                    .is_ok()
+                {
+                    break state;
+                }
+            }
+        }
+    };
+
+    let prev = load_logger();
+    let prev_boxed = LOGGER_IS_BOXED.swap(logger.is_some(), Ordering::Relaxed);
+
+    match logger {
+        Some(logger) => {
+            let logger: &'static dyn Log = Box::leak(logger);
+            store_logger(logger);
+            initialize(logger);
+        }
+        // Removing the logger makes it possible to set another one
+        None => {
+            store_logger(&NOP);
+            STATE.store(UNINITIALIZED, Ordering::Release);
+        }
+    }
+    rebuild_interest_cache();
+
+    if prev_state == INITIALIZED && prev_boxed {
+        Some(Box::from_raw(prev as *const dyn Log as *mut dyn Log))
+    } else {
+        None
+    }
+}
+
 /// A thread-unsafe version of [`set_logger`].
 ///
 /// This function is available on all platforms, even those that do not have
@@ -1446,8 +2464,9 @@ where
 pub unsafe fn set_logger_racy(logger: &'static dyn Log) -> Result<(), SetLoggerError> {
     match STATE.load(Ordering::Acquire) {
         UNINITIALIZED => {
-            LOGGER = logger;
-            STATE.store(INITIALIZED, Ordering::Release);
+            store_logger(logger);
+            initialize(logger);
+            rebuild_interest_cache();
             Ok(())
         }
         INITIALIZING => {
@@ -1458,6 +2477,21 @@ pub unsafe fn set_logger_racy(logger: &'static dyn Log) -> Result<(), SetLoggerE
     }
 }
 
+// Publish the logger in `LOGGER` by moving `STATE` to `INITIALIZED`.
+//
+// With `early_buffer`, records buffered before the logger was set are replayed into it
+// first, so they aren't overtaken by records logged once it's published.
+fn initialize(logger: &'static dyn Log) {
+    #[cfg(feature = "early_buffer")]
+    early::replay(logger, || STATE.store(INITIALIZED, Ordering::Release));
+
+    #[cfg(not(feature = "early_buffer"))]
+    {
+        let _ = logger;
+        STATE.store(INITIALIZED, Ordering::Release);
+    }
+}
+
 /// The type returned by [`set_logger`] if [`set_logger`] has already been called.
 ///
 /// [`set_logger`]: fn.set_logger.html
@@ -1475,6 +2509,193 @@ impl fmt::Display for SetLoggerError {
 #[cfg(feature = "std")]
 impl error::Error for SetLoggerError {}
 
+/// The type returned by [`Log::try_flush`] when buffered records couldn't be flushed.
+#[derive(Debug)]
+pub struct FlushError(FlushErrorInner);
+
+#[derive(Debug)]
+enum FlushErrorInner {
+    Msg(&'static str),
+    #[cfg(feature = "std")]
+    Boxed(Box<dyn error::Error + Send + Sync>),
+}
+
+impl FlushError {
+    /// Create an error from a message.
+    pub fn msg(msg: &'static str) -> Self {
+        FlushError(FlushErrorInner::Msg(msg))
+    }
+
+    /// Create an error from a standard error type.
+    #[cfg(feature = "std")]
+    pub fn boxed<E>(err: E) -> Self
+    where
+        E: Into<Box<dyn error::Error + Send + Sync>>,
+    {
+        FlushError(FlushErrorInner::Boxed(err.into()))
+    }
+}
+
+impl fmt::Display for FlushError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match &self.0 {
+            FlushErrorInner::Msg(msg) => fmt.write_str(msg),
+            #[cfg(feature = "std")]
+            FlushErrorInner::Boxed(err) => write!(fmt, "failed to flush records: {}", err),
+        }
+    }
+}
+
+// The Error trait is not available in libcore
+#[cfg(feature = "std")]
+impl error::Error for FlushError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match &self.0 {
+            FlushErrorInner::Msg(_) => None,
+            FlushErrorInner::Boxed(err) => Some(&**err),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for FlushError {
+    fn from(err: std::io::Error) -> Self {
+        FlushError::boxed(err)
+    }
+}
+
+/// The type passed to the [error handler](set_error_handler) when a logger fails to write
+/// a record.
+#[derive(Debug)]
+pub struct LogError(LogErrorInner);
+
+#[derive(Debug)]
+enum LogErrorInner {
+    Msg(&'static str),
+    #[cfg(feature = "std")]
+    Boxed(Box<dyn error::Error + Send + Sync>),
+}
+
+impl LogError {
+    /// Create an error from a message.
+    pub fn msg(msg: &'static str) -> Self {
+        LogError(LogErrorInner::Msg(msg))
+    }
+
+    /// Create an error from a standard error type.
+    #[cfg(feature = "std")]
+    pub fn boxed<E>(err: E) -> Self
+    where
+        E: Into<Box<dyn error::Error + Send + Sync>>,
+    {
+        LogError(LogErrorInner::Boxed(err.into()))
+    }
+}
+
+impl fmt::Display for LogError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match &self.0 {
+            LogErrorInner::Msg(msg) => fmt.write_str(msg),
+            #[cfg(feature = "std")]
+            LogErrorInner::Boxed(err) => write!(fmt, "failed to log a record: {}", err),
+        }
+    }
+}
+
+// The Error trait is not available in libcore
+#[cfg(feature = "std")]
+impl error::Error for LogError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match &self.0 {
+            LogErrorInner::Msg(_) => None,
+            LogErrorInner::Boxed(err) => Some(&**err),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for LogError {
+    fn from(err: std::io::Error) -> Self {
+        LogError::boxed(err)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<FlushError> for LogError {
+    fn from(err: FlushError) -> Self {
+        match err.0 {
+            FlushErrorInner::Msg(msg) => LogError::msg(msg),
+            FlushErrorInner::Boxed(err) => LogError(LogErrorInner::Boxed(err)),
+        }
+    }
+}
+
+/// Set the handler that loggers report failures to using [`report_error`].
+///
+/// [`Log::log`] can't return an error, so loggers that fail to write a record, because a
+/// disk is full, a pipe is broken, or a value couldn't be serialized, report the failure
+/// here instead of dropping it silently. This gives applications one place to observe
+/// logging failures and react to them, whichever logger is installed.
+///
+/// Each call replaces the previous handler. Errors reported before a handler is set are
+/// ignored.
+///
+/// The handler may be called from any thread, and from within a logger, so it shouldn't
+/// log records itself.
+///
+/// # Examples
+///
+/// ```
+/// use log::LogError;
+///
+/// fn on_log_error(err: LogError) {
+///     eprintln!("logging failed: {}", err);
+/// }
+///
+/// log::set_error_handler(on_log_error);
+/// ```
+pub fn set_error_handler(handler: fn(LogError)) {
+    ERROR_HANDLER.store(handler as *mut (), Ordering::Release);
+}
+
+/// Report a failure to write a record to the handler set using [`set_error_handler`].
+///
+/// This is for implementors of [`Log`]. If no handler has been set then the error is
+/// ignored.
+///
+/// # Examples
+///
+/// ```
+/// use log::{LogError, Metadata, Record};
+/// use std::io::Write;
+///
+/// struct StderrLogger;
+///
+/// impl log::Log for StderrLogger {
+///     fn enabled(&self, _: &Metadata) -> bool {
+///         true
+///     }
+///
+///     fn log(&self, record: &Record) {
+///         if writeln!(std::io::stderr(), "{}", record.args()).is_err() {
+///             log::report_error(LogError::msg("failed to write to stderr"));
+///         }
+///     }
+///
+///     fn flush(&self) {}
+/// }
+/// ```
+pub fn report_error(err: LogError) {
+    let handler = ERROR_HANDLER.load(Ordering::Acquire);
+
+    if !handler.is_null() {
+        // SAFETY: Non-null pointers in `ERROR_HANDLER` are only ever `fn(LogError)`s
+        let handler = unsafe { mem::transmute::<*mut (), fn(LogError)>(handler) };
+
+        handler(err);
+    }
+}
+
 /// The type returned by [`from_str`] when the string doesn't match any of the log levels.
 ///
 /// [`from_str`]: https://doc.rust-lang.org/std/str/trait.FromStr.html#tymethod.from_str
@@ -1494,7 +2715,9 @@ impl error::Error for ParseLevelError {}
 
 /// Returns a reference to the logger.
 ///
-/// If a logger has not been set, a no-op implementation is returned.
+/// If a logger has not been set, a no-op implementation is returned. With the
+/// `early_buffer` feature, a logger that buffers records until one is set is returned
+/// instead.
 pub fn logger() -> &'static dyn Log {
     // Acquire memory ordering guarantees that current thread would see any
     // memory writes that happened before store of the value
@@ -1505,13 +2728,91 @@ pub fn logger() -> &'static dyn Log {
     // write to the `LOGGER` static and initialization of the logger
     // internal state synchronized with current thread.
     if STATE.load(Ordering::Acquire) != INITIALIZED {
+        #[cfg(feature = "early_buffer")]
+        if early::is_open() {
+            return &early::EARLY;
+        }
+
         static NOP: NopLogger = NopLogger;
         &NOP
     } else {
-        unsafe { LOGGER }
+        unsafe { load_logger() }
     }
 }
 
+/// Returns a reference to the logger, if one has been set.
+///
+/// Unlike [`logger`], this returns `None` instead of a no-op implementation, so libraries
+/// can tell when records would be dropped, and fall back to printing fatal errors to
+/// standard error, or skip capturing expensive diagnostics entirely. With the
+/// `early_buffer` feature, it also returns `None` while records are being buffered.
+///
+/// # Examples
+///
+/// ```
+/// if log::try_logger().is_none() {
+///     eprintln!("no logger is installed");
+/// }
+/// ```
+pub fn try_logger() -> Option<&'static dyn Log> {
+    // See `logger` for the memory ordering
+    if STATE.load(Ordering::Acquire) != INITIALIZED {
+        None
+    } else {
+        Some(unsafe { load_logger() })
+    }
+}
+
+/// Whether a logger has been set.
+///
+/// This is the same as `try_logger().is_some()`.
+pub fn logger_installed() -> bool {
+    try_logger().is_some()
+}
+
+// Read the logger in `LOGGER`.
+//
+// Callers must have observed `INITIALIZED` in `STATE`, or be the only thread that can write it.
+#[cfg(not(all(feature = "unsafe_replace_logger", target_has_atomic = "ptr")))]
+unsafe fn load_logger() -> &'static dyn Log {
+    LOGGER
+}
+
+#[cfg(all(feature = "unsafe_replace_logger", target_has_atomic = "ptr"))]
+unsafe fn load_logger() -> &'static dyn Log {
+    match LOGGER.load(Ordering::Acquire).as_ref() {
+        Some(logger) => *logger,
+        None => &NopLogger,
+    }
+}
+
+// Write the logger in `LOGGER`.
+//
+// Callers must have moved `STATE` to `INITIALIZING`, or be the only thread that can write it.
+#[cfg(not(all(feature = "unsafe_replace_logger", target_has_atomic = "ptr")))]
+unsafe fn store_logger(logger: &'static dyn Log) {
+    LOGGER = logger;
+}
+
+#[cfg(all(feature = "unsafe_replace_logger", target_has_atomic = "ptr"))]
+unsafe fn store_logger(logger: &'static dyn Log) {
+    LOGGER.store(Box::into_raw(Box::new(logger)), Ordering::Release);
+}
+
+/// Invalidate the interest each callsite has cached with [`Log::interest`].
+///
+/// Loggers that return [`Interest::Never`] or [`Interest::Always`] must call this whenever
+/// their filters change. It's called automatically when the global logger is set.
+pub fn rebuild_interest_cache() {
+    INTEREST_EPOCH.fetch_add(1, Ordering::Release);
+}
+
+/// The current interest epoch, for callsites to check their cached interest against.
+#[cfg(feature = "callsite_cache")]
+fn interest_epoch() -> usize {
+    INTEREST_EPOCH.load(Ordering::Acquire)
+}
+
 // WARNING: this is not part of the crate's public API and is subject to change at any time
 #[doc(hidden)]
 pub mod __private_api;
@@ -1559,6 +2860,9 @@ mod tests {
             ("INFO", Ok(LevelFilter::Info)),
             ("DEBUG", Ok(LevelFilter::Debug)),
             ("TRACE", Ok(LevelFilter::Trace)),
+            ("notice", Ok(LevelFilter::Info)),
+            ("Critical", Ok(LevelFilter::Error)),
+            ("ALERT", Ok(LevelFilter::Error)),
             ("asdf", Err(ParseLevelError(()))),
         ];
         for &(s, ref expected) in &tests {
@@ -1580,6 +2884,9 @@ mod tests {
             ("INFO", Ok(Level::Info)),
             ("DEBUG", Ok(Level::Debug)),
             ("TRACE", Ok(Level::Trace)),
+            ("notice", Ok(Level::Info)),
+            ("Critical", Ok(Level::Error)),
+            ("ALERT", Ok(Level::Error)),
             ("asdf", Err(ParseLevelError(()))),
         ];
         for &(s, ref expected) in &tests {
@@ -1634,6 +2941,50 @@ mod tests {
         assert_eq!(Some(Level::Debug), LevelFilter::Debug.to_level());
     }
 
+    #[test]
+    fn test_syslog_severity() {
+        assert_eq!(3, Level::Error.to_syslog_severity());
+        assert_eq!(4, Level::Warn.to_syslog_severity());
+        assert_eq!(6, Level::Info.to_syslog_severity());
+        assert_eq!(7, Level::Trace.to_syslog_severity());
+
+        // Levels survive a round trip, except `Trace`, which syslog can't tell from `Debug`
+        for level in Level::iter().filter(|&level| level != Level::Trace) {
+            assert_eq!(
+                Some(level),
+                Level::from_syslog_severity(level.to_syslog_severity())
+            );
+        }
+
+        assert_eq!(Some(Level::Error), Level::from_syslog_severity(0));
+        assert_eq!(Some(Level::Info), Level::from_syslog_severity(5));
+        assert_eq!(None, Level::from_syslog_severity(8));
+    }
+
+    #[test]
+    fn test_level_filter_increase_decrease() {
+        assert_eq!(LevelFilter::Error, LevelFilter::Off.increase());
+        assert_eq!(LevelFilter::Trace, LevelFilter::Debug.increase());
+        assert_eq!(LevelFilter::Trace, LevelFilter::Trace.increase());
+
+        assert_eq!(LevelFilter::Debug, LevelFilter::Trace.decrease());
+        assert_eq!(LevelFilter::Off, LevelFilter::Error.decrease());
+        assert_eq!(LevelFilter::Off, LevelFilter::Off.decrease());
+
+        for filter in LevelFilter::iter().skip(1) {
+            assert_eq!(filter, filter.decrease().increase());
+        }
+    }
+
+    #[test]
+    fn test_level_filter_command_line_values() {
+        // Command line parsers list lowercase names as possible values
+        for filter in LevelFilter::iter() {
+            let value = filter.as_str().to_lowercase();
+            assert_eq!(Ok(filter), value.parse::<LevelFilter>());
+        }
+    }
+
     #[test]
     fn test_to_level_filter() {
         assert_eq!(LevelFilter::Error, Level::Error.to_level_filter());
@@ -1655,6 +3006,47 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_crate_static_max_level() {
+        use crate::__private_api::static_max_level;
+
+        assert_eq!(STATIC_MAX_LEVEL, static_max_level(None, "app"));
+        assert_eq!(STATIC_MAX_LEVEL, static_max_level(Some(""), "app"));
+        assert_eq!(STATIC_MAX_LEVEL, static_max_level(Some("trace"), "app"));
+        assert_eq!(LevelFilter::Off, static_max_level(Some("off"), "app"));
+        assert_eq!(
+            STATIC_MAX_LEVEL.min(LevelFilter::Warn),
+            static_max_level(Some("WARN"), "app")
+        );
+        assert_eq!(
+            STATIC_MAX_LEVEL.min(LevelFilter::Debug),
+            static_max_level(Some("Debug"), "app")
+        );
+    }
+
+    #[test]
+    fn test_strip_targets() {
+        use crate::__private_api::is_stripped_by;
+
+        assert!(!is_stripped_by(None, "app"));
+        assert!(!is_stripped_by(Some(""), "app"));
+
+        let prefixes = Some("app::internal, app::db ,,");
+        assert!(is_stripped_by(prefixes, "app::internal"));
+        assert!(is_stripped_by(prefixes, "app::internal::cache"));
+        assert!(is_stripped_by(prefixes, "app::db"));
+        assert!(!is_stripped_by(prefixes, "app"));
+        assert!(!is_stripped_by(prefixes, "app::internals"));
+        assert!(!is_stripped_by(prefixes, "app::internal:"));
+        assert!(!is_stripped_by(prefixes, "other::app::db"));
+    }
+
+    #[test]
+    #[should_panic(expected = "LOG_STATIC_MAX_LEVEL")]
+    fn test_crate_static_max_level_invalid() {
+        crate::__private_api::static_max_level(Some("verbose"), "app");
+    }
+
     #[test]
     #[cfg_attr(not(debug_assertions), ignore)]
     fn test_static_max_level_debug() {
@@ -1715,6 +3107,112 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_try_flush() {
+        use super::{FlushError, Log, Metadata, Record};
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        struct FailingLogger {
+            flushed: AtomicBool,
+        }
+
+        impl Log for FailingLogger {
+            fn enabled(&self, _: &Metadata) -> bool {
+                true
+            }
+
+            fn log(&self, _: &Record) {}
+
+            fn flush(&self) {
+                let _ = self.try_flush();
+            }
+
+            fn try_flush(&self) -> Result<(), FlushError> {
+                self.flushed.store(true, Ordering::Relaxed);
+                Err(FlushError::msg("the destination was closed"))
+            }
+        }
+
+        struct DefaultLogger;
+
+        impl Log for DefaultLogger {
+            fn enabled(&self, _: &Metadata) -> bool {
+                true
+            }
+
+            fn log(&self, _: &Record) {}
+
+            fn flush(&self) {}
+        }
+
+        assert!(DefaultLogger.try_flush().is_ok());
+        DefaultLogger.shutdown();
+
+        let logger = FailingLogger {
+            flushed: AtomicBool::new(false),
+        };
+
+        // Flushing is forwarded through references
+        fn try_flush(logger: impl Log) -> Result<(), FlushError> {
+            logger.try_flush()
+        }
+
+        let err = try_flush(&logger).unwrap_err();
+        assert_eq!("the destination was closed", err.to_string());
+
+        logger.flushed.store(false, Ordering::Relaxed);
+        (&logger as &dyn Log).shutdown();
+        assert!(logger.flushed.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_flush_error_source() {
+        use super::FlushError;
+        use std::error::Error;
+        use std::io;
+
+        let err = FlushError::from(io::Error::new(io::ErrorKind::BrokenPipe, "broken pipe"));
+        assert_eq!("failed to flush records: broken pipe", err.to_string());
+        assert_eq!("broken pipe", err.source().unwrap().to_string());
+        assert!(FlushError::msg("closed").source().is_none());
+    }
+
+    #[test]
+    fn test_error_handler() {
+        use super::{report_error, set_error_handler, LogError};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static REPORTED: AtomicUsize = AtomicUsize::new(0);
+
+        fn handler(err: LogError) {
+            assert_eq!("disk full", err.to_string());
+            REPORTED.fetch_add(1, Ordering::Relaxed);
+        }
+
+        set_error_handler(handler);
+        report_error(LogError::msg("disk full"));
+        report_error(LogError::msg("disk full"));
+
+        assert_eq!(2, REPORTED.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_log_error_source() {
+        use super::{FlushError, LogError};
+        use std::error::Error;
+        use std::io;
+
+        let err = LogError::from(io::Error::new(io::ErrorKind::BrokenPipe, "broken pipe"));
+        assert_eq!("failed to log a record: broken pipe", err.to_string());
+        assert_eq!("broken pipe", err.source().unwrap().to_string());
+
+        let err = LogError::from(FlushError::msg("closed"));
+        assert_eq!("closed", err.to_string());
+        assert!(err.source().is_none());
+    }
+
     #[test]
     fn test_metadata_builder() {
         use super::MetadataBuilder;
@@ -1727,6 +3225,103 @@ mod tests {
         assert_eq!(metadata_test.target(), "myApp");
     }
 
+    #[test]
+    #[cfg(feature = "kv")]
+    fn test_metadata_keys() {
+        use super::MetadataBuilder;
+        let metadata_test = MetadataBuilder::new().build();
+        assert!(metadata_test.keys().is_empty());
+
+        let metadata_test = MetadataBuilder::new().keys(&["a", "b"]).build();
+        assert_eq!(metadata_test.keys(), &["a", "b"]);
+    }
+
+    #[test]
+    #[cfg(feature = "kv")]
+    fn test_metadata_static_key_values() {
+        use super::{MetadataBuilder, Record};
+
+        let metadata = MetadataBuilder::new().build();
+        assert!(metadata.static_key_values().is_empty());
+
+        let metadata = MetadataBuilder::new()
+            .static_key_values(&[("component", "db")])
+            .build();
+        assert_eq!(&[("component", "db")], metadata.static_key_values());
+
+        let record = Record::builder()
+            .static_key_values(&[("component", "db")])
+            .key_values(&[("user", "Nori")])
+            .build();
+        assert_eq!(
+            &[("component", "db")],
+            record.metadata().static_key_values()
+        );
+        assert_eq!(1, record.key_values().count());
+    }
+
+    #[test]
+    fn test_metadata_pid() {
+        use super::{MetadataBuilder, ProcessInfo};
+
+        let metadata = MetadataBuilder::new().build();
+        #[cfg(feature = "std")]
+        assert_eq!(Some(std::process::id()), metadata.pid());
+        #[cfg(not(feature = "std"))]
+        assert_eq!(None, metadata.pid());
+        #[cfg(all(feature = "std", unix))]
+        assert_eq!(
+            Some(std::os::unix::process::parent_id()),
+            metadata.parent_pid()
+        );
+
+        let process = ProcessInfo::new(Some(42), Some(1));
+        let metadata = MetadataBuilder::new().process(Some(&process)).build();
+        assert_eq!(Some(&process), metadata.process());
+        assert_eq!(Some(42), metadata.pid());
+        assert_eq!(Some(1), metadata.parent_pid());
+    }
+
+    #[test]
+    fn test_verbosity() {
+        use super::{LevelFilter, Metadata};
+
+        for level in Level::iter() {
+            assert_eq!(Some(level), Level::from_verbosity(level.verbosity()));
+            assert_eq!(
+                level.to_level_filter(),
+                LevelFilter::from_verbosity(level.verbosity())
+            );
+        }
+        assert_eq!(None, Level::from_verbosity(0));
+        assert_eq!(Some(Level::Trace), Level::from_verbosity(u32::MAX));
+        assert_eq!(LevelFilter::Off, LevelFilter::from_verbosity(0));
+        assert_eq!(LevelFilter::Trace, LevelFilter::from_verbosity(u32::MAX));
+
+        let metadata = Metadata::builder().level(Level::Debug).build();
+        assert_eq!(4, metadata.verbosity());
+
+        let metadata = Metadata::builder().level(Level::Trace).verbosity(7).build();
+        assert_eq!(7, metadata.verbosity());
+    }
+
+    #[test]
+    #[cfg(feature = "crate_info")]
+    fn test_metadata_crate_info() {
+        use super::Metadata;
+
+        let metadata = Metadata::builder().build();
+        assert_eq!(None, metadata.crate_name());
+        assert_eq!(None, metadata.crate_version());
+
+        let metadata = Metadata::builder()
+            .crate_name(Some("my_crate"))
+            .crate_version(Some("1.2.3"))
+            .build();
+        assert_eq!(Some("my_crate"), metadata.crate_name());
+        assert_eq!(Some("1.2.3"), metadata.crate_version());
+    }
+
     #[test]
     fn test_metadata_convenience_builder() {
         use super::Metadata;
@@ -1758,6 +3353,88 @@ mod tests {
         assert_eq!(record_test.line(), Some(30));
     }
 
+    #[test]
+    #[cfg(feature = "timestamp")]
+    fn test_record_timestamp() {
+        use super::{Record, Timestamp};
+
+        #[cfg(feature = "std")]
+        let timestamp: Timestamp = std::time::SystemTime::UNIX_EPOCH;
+        #[cfg(not(feature = "std"))]
+        let timestamp: Timestamp = 42;
+
+        assert_eq!(None, Record::builder().build().timestamp());
+        assert_eq!(
+            Some(timestamp),
+            Record::builder()
+                .timestamp(Some(timestamp))
+                .build()
+                .timestamp()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "seq")]
+    fn test_record_seq() {
+        use super::Record;
+
+        assert_eq!(None, Record::builder().build().seq());
+        assert_eq!(Some(42), Record::builder().seq(Some(42)).build().seq());
+    }
+
+    #[test]
+    #[cfg(feature = "thread")]
+    fn test_record_thread() {
+        use super::Record;
+
+        let thread = std::thread::current();
+
+        let record = Record::builder().build();
+        assert_eq!(None, record.thread_id());
+        assert_eq!(None, record.thread_name());
+
+        let record = Record::builder()
+            .thread_id(Some(thread.id()))
+            .thread_name(Some("worker"))
+            .build();
+        assert_eq!(Some(thread.id()), record.thread_id());
+        assert_eq!(Some("worker"), record.thread_name());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_record_error() {
+        use super::Record;
+        use std::{error, fmt, io};
+
+        #[derive(Debug)]
+        struct ConnectError(io::Error);
+
+        impl fmt::Display for ConnectError {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("connection failed")
+            }
+        }
+
+        impl error::Error for ConnectError {
+            fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+                Some(&self.0)
+            }
+        }
+
+        assert!(Record::builder().build().error().is_none());
+
+        let err = ConnectError(io::Error::new(io::ErrorKind::TimedOut, "timed out"));
+        let record = Record::builder()
+            .args(format_args!("request failed"))
+            .error(&err)
+            .build();
+
+        let error = record.error().unwrap();
+        assert_eq!("connection failed", error.to_string());
+        assert_eq!("timed out", error.source().unwrap().to_string());
+    }
+
     #[test]
     fn test_record_convenience_builder() {
         use super::{Metadata, Record};
@@ -1845,6 +3522,28 @@ mod tests {
         );
     }
 
+    #[test]
+    #[cfg(feature = "kv")]
+    fn test_record_message_value() {
+        use super::Record;
+        use crate::kv::Value;
+
+        let record = Record::builder().args(format_args!("42")).build();
+        assert!(record.message_value().is_none());
+
+        let value = Value::from(42);
+        let record = Record::builder()
+            .args(format_args!("42"))
+            .message_value(Some(&value))
+            .build();
+
+        assert_eq!(Some(42), record.message_value().and_then(|v| v.to_i64()));
+        assert_eq!("42", record.args().to_string());
+
+        let record = record.to_builder().build();
+        assert_eq!(Some(42), record.message_value().and_then(|v| v.to_i64()));
+    }
+
     // Test that the `impl Log for Foo` blocks work
     // This test mostly operates on a type level, so failures will be compile errors
     #[test]
@@ -27,36 +27,123 @@
 ///     data.0, data.1, private_data);
 /// # }
 /// ```
+///
+/// A target can also be given as a suffix to the module path using `target+:`.
+/// Here, the target is `concat!(module_path!(), "::db")`:
+///
+/// ```
+/// use log::{log, Level};
+///
+/// # fn main() {
+/// log!(target+: "db", Level::Info, "Connected");
+/// # }
+/// ```
+///
+/// The module path, file, and line of the record can be overridden using `module_path:`,
+/// `file:`, and `line:`, in any order, after the target. This lets wrapper macros, FFI shims,
+/// and generated code report the true origin of the record instead of their own. If the
+/// module path is overridden and no target is given, the target is the overridden module path:
+///
+/// ```
+/// use log::{log, Level};
+///
+/// # fn main() {
+/// # let (file, line) = ("src/bridge.c", 42);
+/// log!(module_path: "bridge", file: file, line: line, Level::Warn, "Buffer full");
+/// log!(target: "ffi", file: file, line: line, Level::Warn, "Buffer full");
+/// # }
+/// ```
+///
+/// With the `kv` feature, static key-values like a component or subsystem name can be
+/// declared using `static:` in the same position. They're attached to the record's
+/// [`Metadata`](struct.Metadata.html#method.static_key_values) rather than its key-values,
+/// so loggers can filter on them before any other values are computed. Their values
+/// are strings:
+///
+/// ```
+/// # #[cfg(feature = "kv")]
+/// # fn main() {
+/// use log::{log, log_enabled, Level};
+///
+/// # let pool_size = 8;
+/// log!(static: [component = "db"], Level::Info, pool_size; "Pool started");
+/// log!(target: "app_events", static: [component = "db"], Level::Info, "Pool started");
+///
+/// if log_enabled!(static: [component = "db"], Level::Debug) {
+///     log!(static: [component = "db"], Level::Debug, "Pool stats");
+/// }
+/// # }
+/// # #[cfg(not(feature = "kv"))]
+/// # fn main() {}
+/// ```
 #[macro_export]
 macro_rules! log {
+    // log!(target: "my_target", file: "src/ffi.c", line: 42, Level::Info, "a log event")
+    (target: $target:expr, $key:ident: $($arg:tt)+) => (
+        $crate::__log_origin!({$target} {} {} {} {} $key: $($arg)+)
+    );
+
+    // log!(target: "my_target", Level::Info, user = "Nori"; template: "User {user} logged in");
+    (target: $target:expr, $lvl:expr, $($key:tt $($name:ident)? $(:$capture:tt $(? $($pretty:ident)?)?)? $(= $value:expr)?),+; template: $template:expr) => ({
+        let lvl = $lvl;
+        if lvl <= $crate::__static_max_level!() && lvl <= $crate::max_level() {
+            $crate::__private_api::log_template(
+                $template,
+                lvl,
+                &($crate::__log_crate!($target), $crate::__log_module_path!(), $crate::__private_api::loc()),
+                &[$(($crate::__log_key!($key $($name)*), $crate::__log_value!($key $($name)* $(:$capture $(? $($pretty)?)?)* = $($value)*))),+]
+            );
+        }
+    });
+
     // log!(target: "my_target", Level::Info, key1:? = 42, key2 = true; "a {} event", "log");
-    (target: $target:expr, $lvl:expr, $($key:tt $(:$capture:tt)? $(= $value:expr)?),+; $($arg:tt)+) => ({
+    // log!(target: "my_target", Level::Info, ?key1, %key2; "a {} event", "log");
+    (target: $target:expr, $lvl:expr, $($key:tt $($name:ident)? $(:$capture:tt $(? $($pretty:ident)?)?)? $(= $value:expr)?),+; $($arg:tt)+) => ({
         let lvl = $lvl;
-        if lvl <= $crate::STATIC_MAX_LEVEL && lvl <= $crate::max_level() {
-            $crate::__private_api::log::<&_>(
+        if lvl <= $crate::__static_max_level!() && lvl <= $crate::max_level() {
+            $crate::__private_api::log::<_, &_>(
                 $crate::__private_api::format_args!($($arg)+),
                 lvl,
-                &($target, $crate::__private_api::module_path!(), $crate::__private_api::loc()),
-                &[$(($crate::__log_key!($key), $crate::__log_value!($key $(:$capture)* = $($value)*))),+]
+                &($crate::__log_crate!($target), $crate::__log_module_path!(), $crate::__private_api::loc()),
+                $crate::__log_kvs!(
+                    [$(($crate::__log_key!($key $($name)*), $crate::__log_value!($key $($name)* $(:$capture $(? $($pretty)?)?)* = $($value)*))),+]
+                    $($arg)+
+                )
             );
         }
     });
 
+    // log!(target: "my_target", Level::Info, key1:? = 42, key2 = true;);
+    (target: $target:expr, $lvl:expr, $($key:tt $($name:ident)? $(:$capture:tt $(? $($pretty:ident)?)?)? $(= $value:expr)?),+;) => (
+        $crate::log!(target: $target, $lvl, $($key $($name)* $(:$capture $(? $($pretty)?)?)* $(= $value)*),+; "")
+    );
+
     // log!(target: "my_target", Level::Info, "a {} event", "log");
     (target: $target:expr, $lvl:expr, $($arg:tt)+) => ({
         let lvl = $lvl;
-        if lvl <= $crate::STATIC_MAX_LEVEL && lvl <= $crate::max_level() {
+        if lvl <= $crate::__static_max_level!() && lvl <= $crate::max_level() {
             $crate::__private_api::log(
                 $crate::__private_api::format_args!($($arg)+),
                 lvl,
-                &($target, $crate::__private_api::module_path!(), $crate::__private_api::loc()),
-                (),
+                &($crate::__log_crate!($target), $crate::__log_module_path!(), $crate::__private_api::loc()),
+                $crate::__log_kvs!([] $($arg)+),
             );
         }
     });
 
+    // log!(target+: "db", Level::Info, "a log event")
+    (target+: $suffix:literal, $lvl:expr, $($arg:tt)+) => ($crate::log!(target: $crate::__log_target!($suffix), $lvl, $($arg)+));
+
+    // log!(file: "src/ffi.c", line: 42, Level::Info, "a log event")
+    (module_path: $($arg:tt)+) => ($crate::__log_origin!({} {} {} {} {} module_path: $($arg)+));
+    (file: $($arg:tt)+) => ($crate::__log_origin!({} {} {} {} {} file: $($arg)+));
+    (line: $($arg:tt)+) => ($crate::__log_origin!({} {} {} {} {} line: $($arg)+));
+
+    // log!(static: [component = "db"], Level::Info, "a log event")
+    (static: $($arg:tt)+) => ($crate::__log_origin!({} {} {} {} {} static: $($arg)+));
+
     // log!(Level::Info, "a log event")
-    ($lvl:expr, $($arg:tt)+) => ($crate::log!(target: $crate::__private_api::module_path!(), $lvl, $($arg)+));
+    ($lvl:expr, $($arg:tt)+) => ($crate::__log_callsite!($lvl, $($arg)+));
 }
 
 /// Logs a message at the error level.
@@ -79,6 +166,9 @@ macro_rules! error {
     // error!(target: "my_target", "a {} event", "log")
     (target: $target:expr, $($arg:tt)+) => ($crate::log!(target: $target, $crate::Level::Error, $($arg)+));
 
+    // error!(target+: "db", "a {} event", "log")
+    (target+: $suffix:literal, $($arg:tt)+) => ($crate::log!(target+: $suffix, $crate::Level::Error, $($arg)+));
+
     // error!("a {} event", "log")
     ($($arg:tt)+) => ($crate::log!($crate::Level::Error, $($arg)+))
 }
@@ -103,6 +193,9 @@ macro_rules! warn {
     // warn!(target: "my_target", "a {} event", "log")
     (target: $target:expr, $($arg:tt)+) => ($crate::log!(target: $target, $crate::Level::Warn, $($arg)+));
 
+    // warn!(target+: "db", "a {} event", "log")
+    (target+: $suffix:literal, $($arg:tt)+) => ($crate::log!(target+: $suffix, $crate::Level::Warn, $($arg)+));
+
     // warn!("a {} event", "log")
     ($($arg:tt)+) => ($crate::log!($crate::Level::Warn, $($arg)+))
 }
@@ -129,6 +222,9 @@ macro_rules! info {
     // info!(target: "my_target", "a {} event", "log")
     (target: $target:expr, $($arg:tt)+) => ($crate::log!(target: $target, $crate::Level::Info, $($arg)+));
 
+    // info!(target+: "db", "a {} event", "log")
+    (target+: $suffix:literal, $($arg:tt)+) => ($crate::log!(target+: $suffix, $crate::Level::Info, $($arg)+));
+
     // info!("a {} event", "log")
     ($($arg:tt)+) => ($crate::log!($crate::Level::Info, $($arg)+))
 }
@@ -154,6 +250,9 @@ macro_rules! debug {
     // debug!(target: "my_target", "a {} event", "log")
     (target: $target:expr, $($arg:tt)+) => ($crate::log!(target: $target, $crate::Level::Debug, $($arg)+));
 
+    // debug!(target+: "db", "a {} event", "log")
+    (target+: $suffix:literal, $($arg:tt)+) => ($crate::log!(target+: $suffix, $crate::Level::Debug, $($arg)+));
+
     // debug!("a {} event", "log")
     ($($arg:tt)+) => ($crate::log!($crate::Level::Debug, $($arg)+))
 }
@@ -181,10 +280,59 @@ macro_rules! trace {
     // trace!(target: "my_target", "a {} event", "log")
     (target: $target:expr, $($arg:tt)+) => ($crate::log!(target: $target, $crate::Level::Trace, $($arg)+));
 
+    // trace!(target+: "db", "a {} event", "log")
+    (target+: $suffix:literal, $($arg:tt)+) => ($crate::log!(target+: $suffix, $crate::Level::Trace, $($arg)+));
+
     // trace!("a {} event", "log")
     ($($arg:tt)+) => ($crate::log!($crate::Level::Trace, $($arg)+))
 }
 
+/// Logs a message at a numeric verbosity.
+///
+/// This is for projects used to glog's `VLOG(n)` or `-vvv`-style command lines, where
+/// verbosity goes past the five named levels. Verbosities from `1` to `5` are logged at the
+/// levels from `Error` to `Trace`, and anything more verbose is logged at `Trace`, so it's
+/// filtered by the usual max levels. The verbosity itself is carried in the record's
+/// [`Metadata::verbosity`](struct.Metadata.html#method.verbosity), so loggers can tell
+/// `vlog!(6, ..)` and `vlog!(7, ..)` apart. A verbosity of `0` is logged at `Error`.
+///
+/// This macro accepts the same arguments as [`log!`](macro.log.html), with the verbosity
+/// in place of the level.
+///
+/// # Examples
+///
+/// ```
+/// use log::vlog;
+///
+/// # fn main() {
+/// # let (frame, bytes) = (3, [0u8; 4]);
+/// vlog!(3, "Decoding frame {}", frame);
+/// vlog!(target: "codec", 7, "Frame bytes: {:?}", bytes);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! vlog {
+    // vlog!(target: "my_target", 7, key1 = 42, key2 = true; "a {} event", "log")
+    // vlog!(target: "my_target", 7, "a {} event", "log")
+    (target: $target:expr, $verbosity:expr, $($arg:tt)+) => ({
+        let verbosity: u32 = $verbosity;
+        let lvl = $crate::Level::from_verbosity(verbosity).unwrap_or($crate::Level::Error);
+
+        $crate::log!(
+            target: $crate::__private_api::Origin::new($crate::__private_api::module_path!())
+                .target($target)
+                .verbosity(verbosity),
+            lvl,
+            $($arg)+
+        )
+    });
+
+    // vlog!(7, "a {} event", "log")
+    ($verbosity:expr, $($arg:tt)+) => (
+        $crate::vlog!(target: $crate::__private_api::module_path!(), $verbosity, $($arg)+)
+    );
+}
+
 /// Determines if a message logged at the specified level in that module will
 /// be logged.
 ///
@@ -211,157 +359,1536 @@ macro_rules! trace {
 /// # fn expensive_call() -> Data { Data { x: 0, y: 0 } }
 /// # fn main() {}
 /// ```
+///
+/// With the `kv` feature, the keys a record would be logged with can also be given after
+/// the level, so loggers that filter on key-values can be asked before those values are
+/// computed. The keys are available from [`Metadata::keys`](struct.Metadata.html#method.keys):
+///
+/// ```
+/// # #[cfg(feature = "kv")]
+/// # fn main() {
+/// use log::Level::Debug;
+/// use log::{debug, log_enabled};
+///
+/// if log_enabled!(Debug, user_id, "http.method") {
+///     let (user_id, method) = expensive_call();
+///     debug!(user_id, "http.method" = method; "handled request");
+/// }
+/// # }
+/// # fn expensive_call() -> (u32, &'static str) { (42, "GET") }
+/// # #[cfg(not(feature = "kv"))]
+/// # fn main() {}
+/// ```
 #[macro_export]
 macro_rules! log_enabled {
+    (target: $target:expr, $lvl:expr, $($key:tt),+ $(,)?) => {{
+        let lvl = $lvl;
+        lvl <= $crate::__static_max_level!()
+            && lvl <= $crate::max_level()
+            && $crate::__private_api::enabled_keys(lvl, $target, &[$($crate::__log_key!($key)),+])
+    }};
     (target: $target:expr, $lvl:expr) => {{
         let lvl = $lvl;
-        lvl <= $crate::STATIC_MAX_LEVEL
+        lvl <= $crate::__static_max_level!()
             && lvl <= $crate::max_level()
             && $crate::__private_api::enabled(lvl, $target)
     }};
-    ($lvl:expr) => {
-        $crate::log_enabled!(target: $crate::__private_api::module_path!(), $lvl)
+    (target+: $suffix:literal, $lvl:expr $(, $key:tt)* $(,)?) => {
+        $crate::log_enabled!(target: $crate::__log_target!($suffix), $lvl $(, $key)*)
+    };
+    (static: $static:tt, target: $target:expr, $lvl:expr $(, $key:tt)* $(,)?) => {{
+        let lvl = $lvl;
+        lvl <= $crate::__static_max_level!()
+            && lvl <= $crate::max_level()
+            && $crate::__private_api::enabled_static(
+                lvl,
+                $target,
+                &[$($crate::__log_key!($key)),*],
+                $crate::__log_static_kvs!($static),
+            )
+    }};
+    (static: $static:tt, $lvl:expr $(, $key:tt)* $(,)?) => {
+        $crate::log_enabled!(static: $static, target: $crate::__private_api::module_path!(), $lvl $(, $key)*)
+    };
+    ($lvl:expr $(, $key:tt)* $(,)?) => {
+        $crate::log_enabled!(target: $crate::__private_api::module_path!(), $lvl $(, $key)*)
     };
 }
 
-// These macros use a pattern of #[cfg]s to produce nicer error
-// messages when log features aren't available
+/// Logs a message only the first time this callsite is reached.
+///
+/// This macro accepts the same arguments as [`log!`](macro.log.html). Later calls from
+/// the same callsite are ignored, even if they'd be logged otherwise. Calls made while
+/// the level is disabled don't count.
+///
+/// # Examples
+///
+/// ```
+/// use log::{log_once, Level};
+///
+/// # fn main() {
+/// for attempt in 0..3 {
+///     log_once!(Level::Warn, "retrying a request (attempt {attempt})");
+/// }
+/// # }
+/// ```
+#[macro_export]
+macro_rules! log_once {
+    // log_once!(target: "my_target", Level::Info, key1:? = 42, key2 = true; "a {} event", "log");
+    // log_once!(target: "my_target", Level::Info, "a {} event", "log");
+    (target: $target:expr, $lvl:expr, $($arg:tt)+) => (
+        $crate::__log_rate_limited!(once(), target: $target, $lvl, $($arg)+)
+    );
 
-#[doc(hidden)]
+    // log_once!(Level::Info, "a log event")
+    ($lvl:expr, $($arg:tt)+) => (
+        $crate::log_once!(target: $crate::__private_api::module_path!(), $lvl, $($arg)+)
+    );
+}
+
+/// Logs a message the first time, and every `n`th time after, this callsite is reached.
+///
+/// This macro accepts the same arguments as [`log!`](macro.log.html), after `n`.
+/// Calls made while the level is disabled don't count.
+///
+/// # Examples
+///
+/// ```
+/// use log::{log_every_n, Level};
+///
+/// # fn main() {
+/// for item in 0..10_000 {
+///     // Logs for items 0, 1000, 2000, and so on
+///     log_every_n!(1000, Level::Info, "processing item {item}");
+/// }
+/// # }
+/// ```
 #[macro_export]
-#[cfg(feature = "kv")]
-macro_rules! __log_key {
-    // key1 = 42
-    ($($args:ident)*) => {
-        $crate::__private_api::stringify!($($args)*)
-    };
-    // "key1" = 42
-    ($($args:expr)*) => {
-        $($args)*
-    };
+macro_rules! log_every_n {
+    // log_every_n!(1000, target: "my_target", Level::Info, key1:? = 42, key2 = true; "a {} event", "log");
+    // log_every_n!(1000, target: "my_target", Level::Info, "a {} event", "log");
+    ($n:expr, target: $target:expr, $lvl:expr, $($arg:tt)+) => (
+        $crate::__log_rate_limited!(every_n($n), target: $target, $lvl, $($arg)+)
+    );
+
+    // log_every_n!(1000, Level::Info, "a log event")
+    ($n:expr, $lvl:expr, $($arg:tt)+) => (
+        $crate::log_every_n!($n, target: $crate::__private_api::module_path!(), $lvl, $($arg)+)
+    );
 }
 
-#[doc(hidden)]
+/// Logs a message at most once every `secs` seconds from this callsite.
+///
+/// This macro accepts the same arguments as [`log!`](macro.log.html), after `secs`.
+/// The first call is always logged. Calls made while the level is disabled don't count.
+///
+/// This macro requires the `std` feature.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "std")]
+/// # fn main() {
+/// use log::{log_every_secs, Level};
+///
+/// for attempt in 0..3 {
+///     log_every_secs!(30, Level::Warn, "the connection was refused (attempt {attempt})");
+/// }
+/// # }
+/// # #[cfg(not(feature = "std"))]
+/// # fn main() {}
+/// ```
 #[macro_export]
-#[cfg(not(feature = "kv"))]
-macro_rules! __log_key {
-    ($($args:tt)*) => {
-        compile_error!("key value support requires the `kv` feature of `log`")
-    };
+macro_rules! log_every_secs {
+    // log_every_secs!(30, target: "my_target", Level::Info, key1:? = 42, key2 = true; "a {} event", "log");
+    // log_every_secs!(30, target: "my_target", Level::Info, "a {} event", "log");
+    ($secs:expr, target: $target:expr, $lvl:expr, $($arg:tt)+) => (
+        $crate::__log_every_secs!($secs, target: $target, $lvl, $($arg)+)
+    );
+
+    // log_every_secs!(30, Level::Info, "a log event")
+    ($secs:expr, $lvl:expr, $($arg:tt)+) => (
+        $crate::log_every_secs!($secs, target: $crate::__private_api::module_path!(), $lvl, $($arg)+)
+    );
 }
 
-#[doc(hidden)]
+/// Logs a message at the error level only the first time this callsite is reached.
+///
+/// See [`log_once!`](macro.log_once.html) for details.
 #[macro_export]
-#[cfg(feature = "kv")]
-macro_rules! __log_value {
-    // Entrypoint
-    ($key:tt = $args:expr) => {
-        $crate::__log_value!(($args):value)
-    };
-    ($key:tt :$capture:tt = $args:expr) => {
-        $crate::__log_value!(($args):$capture)
-    };
-    ($key:ident =) => {
-        $crate::__log_value!(($key):value)
-    };
-    ($key:ident :$capture:tt =) => {
-        $crate::__log_value!(($key):$capture)
-    };
-    // ToValue
-    (($args:expr):value) => {
-        $crate::__private_api::capture_to_value(&&$args)
-    };
-    // Debug
-    (($args:expr):?) => {
-        $crate::__private_api::capture_debug(&&$args)
-    };
-    (($args:expr):debug) => {
-        $crate::__private_api::capture_debug(&&$args)
-    };
-    // Display
-    (($args:expr):%) => {
-        $crate::__private_api::capture_display(&&$args)
-    };
-    (($args:expr):display) => {
-        $crate::__private_api::capture_display(&&$args)
-    };
-    //Error
-    (($args:expr):err) => {
-        $crate::__log_value_error!($args)
-    };
-    // sval::Value
-    (($args:expr):sval) => {
-        $crate::__log_value_sval!($args)
-    };
-    // serde::Serialize
-    (($args:expr):serde) => {
-        $crate::__log_value_serde!($args)
-    };
+macro_rules! error_once {
+    (target: $target:expr, $($arg:tt)+) => ($crate::log_once!(target: $target, $crate::Level::Error, $($arg)+));
+    ($($arg:tt)+) => ($crate::log_once!($crate::Level::Error, $($arg)+))
 }
 
-#[doc(hidden)]
+/// Logs a message at the error level the first time, and every `n`th time after, this callsite is reached.
+///
+/// See [`log_every_n!`](macro.log_every_n.html) for details.
 #[macro_export]
-#[cfg(not(feature = "kv"))]
-macro_rules! __log_value {
-    ($($args:tt)*) => {
-        compile_error!("key value support requires the `kv` feature of `log`")
-    };
+macro_rules! error_every_n {
+    ($n:expr, target: $target:expr, $($arg:tt)+) => ($crate::log_every_n!($n, target: $target, $crate::Level::Error, $($arg)+));
+    ($n:expr, $($arg:tt)+) => ($crate::log_every_n!($n, $crate::Level::Error, $($arg)+))
 }
 
-#[doc(hidden)]
+/// Logs a message at the error level at most once every `secs` seconds from this callsite.
+///
+/// See [`log_every_secs!`](macro.log_every_secs.html) for details.
 #[macro_export]
-#[cfg(feature = "kv_sval")]
-macro_rules! __log_value_sval {
-    ($args:expr) => {
-        $crate::__private_api::capture_sval(&&$args)
-    };
+macro_rules! error_every_secs {
+    ($secs:expr, target: $target:expr, $($arg:tt)+) => ($crate::log_every_secs!($secs, target: $target, $crate::Level::Error, $($arg)+));
+    ($secs:expr, $($arg:tt)+) => ($crate::log_every_secs!($secs, $crate::Level::Error, $($arg)+))
 }
 
-#[doc(hidden)]
+/// Logs a message at the warn level only the first time this callsite is reached.
+///
+/// See [`log_once!`](macro.log_once.html) for details.
 #[macro_export]
-#[cfg(not(feature = "kv_sval"))]
-macro_rules! __log_value_sval {
-    ($args:expr) => {
-        compile_error!("capturing values as `sval::Value` requites the `kv_sval` feature of `log`")
-    };
+macro_rules! warn_once {
+    (target: $target:expr, $($arg:tt)+) => ($crate::log_once!(target: $target, $crate::Level::Warn, $($arg)+));
+    ($($arg:tt)+) => ($crate::log_once!($crate::Level::Warn, $($arg)+))
 }
 
-#[doc(hidden)]
+/// Logs a message at the warn level the first time, and every `n`th time after, this callsite is reached.
+///
+/// See [`log_every_n!`](macro.log_every_n.html) for details.
 #[macro_export]
-#[cfg(feature = "kv_serde")]
-macro_rules! __log_value_serde {
-    ($args:expr) => {
-        $crate::__private_api::capture_serde(&&$args)
-    };
+macro_rules! warn_every_n {
+    ($n:expr, target: $target:expr, $($arg:tt)+) => ($crate::log_every_n!($n, target: $target, $crate::Level::Warn, $($arg)+));
+    ($n:expr, $($arg:tt)+) => ($crate::log_every_n!($n, $crate::Level::Warn, $($arg)+))
 }
 
-#[doc(hidden)]
+/// Logs a message at the warn level at most once every `secs` seconds from this callsite.
+///
+/// See [`log_every_secs!`](macro.log_every_secs.html) for details.
 #[macro_export]
-#[cfg(not(feature = "kv_serde"))]
-macro_rules! __log_value_serde {
-    ($args:expr) => {
-        compile_error!(
-            "capturing values as `serde::Serialize` requites the `kv_serde` feature of `log`"
-        )
-    };
+macro_rules! warn_every_secs {
+    ($secs:expr, target: $target:expr, $($arg:tt)+) => ($crate::log_every_secs!($secs, target: $target, $crate::Level::Warn, $($arg)+));
+    ($secs:expr, $($arg:tt)+) => ($crate::log_every_secs!($secs, $crate::Level::Warn, $($arg)+))
 }
 
-#[doc(hidden)]
+/// Logs a message at the info level only the first time this callsite is reached.
+///
+/// See [`log_once!`](macro.log_once.html) for details.
 #[macro_export]
-#[cfg(feature = "kv_std")]
-macro_rules! __log_value_error {
-    ($args:expr) => {
-        $crate::__private_api::capture_error(&$args)
-    };
+macro_rules! info_once {
+    (target: $target:expr, $($arg:tt)+) => ($crate::log_once!(target: $target, $crate::Level::Info, $($arg)+));
+    ($($arg:tt)+) => ($crate::log_once!($crate::Level::Info, $($arg)+))
+}
+
+/// Logs a message at the info level the first time, and every `n`th time after, this callsite is reached.
+///
+/// See [`log_every_n!`](macro.log_every_n.html) for details.
+#[macro_export]
+macro_rules! info_every_n {
+    ($n:expr, target: $target:expr, $($arg:tt)+) => ($crate::log_every_n!($n, target: $target, $crate::Level::Info, $($arg)+));
+    ($n:expr, $($arg:tt)+) => ($crate::log_every_n!($n, $crate::Level::Info, $($arg)+))
+}
+
+/// Logs a message at the info level at most once every `secs` seconds from this callsite.
+///
+/// See [`log_every_secs!`](macro.log_every_secs.html) for details.
+#[macro_export]
+macro_rules! info_every_secs {
+    ($secs:expr, target: $target:expr, $($arg:tt)+) => ($crate::log_every_secs!($secs, target: $target, $crate::Level::Info, $($arg)+));
+    ($secs:expr, $($arg:tt)+) => ($crate::log_every_secs!($secs, $crate::Level::Info, $($arg)+))
+}
+
+/// Logs a message at the debug level only the first time this callsite is reached.
+///
+/// See [`log_once!`](macro.log_once.html) for details.
+#[macro_export]
+macro_rules! debug_once {
+    (target: $target:expr, $($arg:tt)+) => ($crate::log_once!(target: $target, $crate::Level::Debug, $($arg)+));
+    ($($arg:tt)+) => ($crate::log_once!($crate::Level::Debug, $($arg)+))
+}
+
+/// Logs a message at the debug level the first time, and every `n`th time after, this callsite is reached.
+///
+/// See [`log_every_n!`](macro.log_every_n.html) for details.
+#[macro_export]
+macro_rules! debug_every_n {
+    ($n:expr, target: $target:expr, $($arg:tt)+) => ($crate::log_every_n!($n, target: $target, $crate::Level::Debug, $($arg)+));
+    ($n:expr, $($arg:tt)+) => ($crate::log_every_n!($n, $crate::Level::Debug, $($arg)+))
+}
+
+/// Logs a message at the debug level at most once every `secs` seconds from this callsite.
+///
+/// See [`log_every_secs!`](macro.log_every_secs.html) for details.
+#[macro_export]
+macro_rules! debug_every_secs {
+    ($secs:expr, target: $target:expr, $($arg:tt)+) => ($crate::log_every_secs!($secs, target: $target, $crate::Level::Debug, $($arg)+));
+    ($secs:expr, $($arg:tt)+) => ($crate::log_every_secs!($secs, $crate::Level::Debug, $($arg)+))
+}
+
+/// Logs a message at the trace level only the first time this callsite is reached.
+///
+/// See [`log_once!`](macro.log_once.html) for details.
+#[macro_export]
+macro_rules! trace_once {
+    (target: $target:expr, $($arg:tt)+) => ($crate::log_once!(target: $target, $crate::Level::Trace, $($arg)+));
+    ($($arg:tt)+) => ($crate::log_once!($crate::Level::Trace, $($arg)+))
+}
+
+/// Logs a message at the trace level the first time, and every `n`th time after, this callsite is reached.
+///
+/// See [`log_every_n!`](macro.log_every_n.html) for details.
+#[macro_export]
+macro_rules! trace_every_n {
+    ($n:expr, target: $target:expr, $($arg:tt)+) => ($crate::log_every_n!($n, target: $target, $crate::Level::Trace, $($arg)+));
+    ($n:expr, $($arg:tt)+) => ($crate::log_every_n!($n, $crate::Level::Trace, $($arg)+))
+}
+
+/// Logs a message at the trace level at most once every `secs` seconds from this callsite.
+///
+/// See [`log_every_secs!`](macro.log_every_secs.html) for details.
+#[macro_export]
+macro_rules! trace_every_secs {
+    ($secs:expr, target: $target:expr, $($arg:tt)+) => ($crate::log_every_secs!($secs, target: $target, $crate::Level::Trace, $($arg)+));
+    ($secs:expr, $($arg:tt)+) => ($crate::log_every_secs!($secs, $crate::Level::Trace, $($arg)+))
 }
 
 #[doc(hidden)]
 #[macro_export]
-#[cfg(not(feature = "kv_std"))]
-macro_rules! __log_value_error {
-    ($args:expr) => {
-        compile_error!(
-            "capturing values as `std::error::Error` requites the `kv_std` feature of `log`"
-        )
+macro_rules! __log_rate_limited {
+    ($check:ident($($limit:expr)?), target: $target:expr, $lvl:expr, $($arg:tt)+) => ({
+        let lvl = $lvl;
+        if lvl <= $crate::__static_max_level!() && lvl <= $crate::max_level() {
+            static LIMIT: $crate::__private_api::RateLimit = $crate::__private_api::RateLimit::new();
+
+            if LIMIT.$check($($limit)?) {
+                $crate::log!(target: $target, lvl, $($arg)+);
+            }
+        }
+    });
+}
+
+/// Logs a message if a condition is true.
+///
+/// This macro accepts the same arguments as [`log!`](macro.log.html), after the condition.
+/// The condition is only evaluated if the level is enabled, and the key-values and format
+/// arguments are only evaluated if the condition is also true.
+///
+/// # Examples
+///
+/// ```
+/// use log::{log_if, warn_if, Level};
+///
+/// # fn main() {
+/// # let (elapsed_ms, retries) = (1200, 3);
+/// log_if!(elapsed_ms > 1000, Level::Warn, "a request took {elapsed_ms}ms");
+/// warn_if!(retries > 0, target: "app_events", "a request was retried {retries} times");
+/// # }
+/// ```
+#[macro_export]
+macro_rules! log_if {
+    // log_if!(cond, target: "my_target", Level::Info, key1:? = 42, key2 = true; "a {} event", "log");
+    // log_if!(cond, target: "my_target", Level::Info, "a {} event", "log");
+    ($cond:expr, target: $target:expr, $lvl:expr, $($arg:tt)+) => ({
+        let lvl = $lvl;
+        if lvl <= $crate::__static_max_level!() && lvl <= $crate::max_level() && $cond {
+            $crate::log!(target: $target, lvl, $($arg)+);
+        }
+    });
+
+    // log_if!(cond, Level::Info, "a log event")
+    ($cond:expr, $lvl:expr, $($arg:tt)+) => (
+        $crate::log_if!($cond, target: $crate::__private_api::module_path!(), $lvl, $($arg)+)
+    );
+}
+
+/// Logs a message at the error level if a condition is true.
+///
+/// See [`log_if!`](macro.log_if.html) for details.
+#[macro_export]
+macro_rules! error_if {
+    ($cond:expr, target: $target:expr, $($arg:tt)+) => ($crate::log_if!($cond, target: $target, $crate::Level::Error, $($arg)+));
+    ($cond:expr, $($arg:tt)+) => ($crate::log_if!($cond, $crate::Level::Error, $($arg)+))
+}
+
+/// Logs a message at the warn level if a condition is true.
+///
+/// See [`log_if!`](macro.log_if.html) for details.
+#[macro_export]
+macro_rules! warn_if {
+    ($cond:expr, target: $target:expr, $($arg:tt)+) => ($crate::log_if!($cond, target: $target, $crate::Level::Warn, $($arg)+));
+    ($cond:expr, $($arg:tt)+) => ($crate::log_if!($cond, $crate::Level::Warn, $($arg)+))
+}
+
+/// Logs a message at the info level if a condition is true.
+///
+/// See [`log_if!`](macro.log_if.html) for details.
+#[macro_export]
+macro_rules! info_if {
+    ($cond:expr, target: $target:expr, $($arg:tt)+) => ($crate::log_if!($cond, target: $target, $crate::Level::Info, $($arg)+));
+    ($cond:expr, $($arg:tt)+) => ($crate::log_if!($cond, $crate::Level::Info, $($arg)+))
+}
+
+/// Logs a message at the debug level if a condition is true.
+///
+/// See [`log_if!`](macro.log_if.html) for details.
+#[macro_export]
+macro_rules! debug_if {
+    ($cond:expr, target: $target:expr, $($arg:tt)+) => ($crate::log_if!($cond, target: $target, $crate::Level::Debug, $($arg)+));
+    ($cond:expr, $($arg:tt)+) => ($crate::log_if!($cond, $crate::Level::Debug, $($arg)+))
+}
+
+/// Logs a message at the trace level if a condition is true.
+///
+/// See [`log_if!`](macro.log_if.html) for details.
+#[macro_export]
+macro_rules! trace_if {
+    ($cond:expr, target: $target:expr, $($arg:tt)+) => ($crate::log_if!($cond, target: $target, $crate::Level::Trace, $($arg)+));
+    ($cond:expr, $($arg:tt)+) => ($crate::log_if!($cond, $crate::Level::Trace, $($arg)+))
+}
+
+/// Attaches key-values to every record logged on the current thread while a block runs.
+///
+/// Key-values use the same syntax and capturing modifiers as [`kv!`](macro.kv.html),
+/// followed by `=>` and a block. The block's result is returned. Scopes can be nested;
+/// see [`kv::push_context`](kv/fn.push_context.html) for how their key-values are
+/// combined with the record's own.
+///
+/// This macro requires the `kv` and `std` features.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(all(feature = "kv", feature = "std"))]
+/// # fn main() {
+/// use log::{info, kv_scope};
+///
+/// # let (id, user) = (1, "Nori");
+/// let handled = kv_scope!(request_id = id, user:% = user => {
+///     // These records include `request_id` and `user`
+///     info!("Handling a request");
+///     info!(status = 200; "Handled a request");
+///
+///     true
+/// });
+/// # let _ = handled;
+/// # }
+/// # #[cfg(not(all(feature = "kv", feature = "std")))]
+/// # fn main() {}
+/// ```
+#[macro_export]
+macro_rules! kv_scope {
+    ($($key:tt $(:$capture:tt $(? $($pretty:ident)?)?)? $(= $value:expr)?),+ => $body:block) => {
+        $crate::__kv_scope!($($key $(:$capture $(? $($pretty)?)?)? $(= $value)?),+ => $body)
+    };
+}
+
+/// Logs the error in a `Result`, then returns the `Result` unchanged.
+///
+/// The error is logged along with its chain of sources, like `connection failed: timed out`,
+/// at the error level unless another level is given. The record's file and line are
+/// those of the `log_err!` call. `Ok` values are returned without logging anything.
+///
+/// This macro requires the `std` feature.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "std")]
+/// # fn main() -> Result<(), std::io::Error> {
+/// use log::{log_err, Level};
+///
+/// # fn connect() -> Result<(), std::io::Error> { Ok(()) }
+/// log_err!(connect())?;
+/// log_err!(Level::Warn, connect())?;
+/// log_err!(target: "app_events", Level::Warn, connect())?;
+/// # Ok(())
+/// # }
+/// # #[cfg(not(feature = "std"))]
+/// # fn main() {}
+/// ```
+#[macro_export]
+macro_rules! log_err {
+    // log_err!(target: "my_target", Level::Warn, result)
+    (target: $target:expr, $lvl:expr, $result:expr $(,)?) => (
+        $crate::__log_err!(target: $target, $lvl, $result)
+    );
+
+    // log_err!(Level::Warn, result)
+    ($lvl:expr, $result:expr $(,)?) => (
+        $crate::log_err!(target: $crate::__private_api::module_path!(), $lvl, $result)
+    );
+
+    // log_err!(result)
+    ($result:expr $(,)?) => (
+        $crate::log_err!(target: $crate::__private_api::module_path!(), $crate::Level::Error, $result)
+    );
+}
+
+/// Logs an expression and its `Debug` value, then returns the value.
+///
+/// This macro works like the standard library's `dbg!`, but logs a record like
+/// `items.len() = 3` instead of unconditionally writing to stderr, so it can be dropped
+/// into the middle of an expression without restructuring it. The expression is always
+/// evaluated, but it's only formatted if the level is enabled. The record's file and line
+/// are those of the macro call.
+///
+/// The [`trace_dbg!`](macro.trace_dbg.html) and [`debug_dbg!`](macro.debug_dbg.html) macros
+/// log at a fixed level, and can also take multiple expressions, returning them as a tuple.
+///
+/// # Examples
+///
+/// ```
+/// use log::{debug_dbg, log_dbg, trace_dbg, Level};
+///
+/// # fn main() {
+/// let items = vec![1, 2, 3];
+///
+/// let total: i32 = trace_dbg!(items.iter().sum());
+/// let (len, first) = debug_dbg!(items.len(), items[0]);
+/// let last = log_dbg!(target: "app_events", Level::Info, items[len - 1]);
+/// # let _ = (total, first, last);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! log_dbg {
+    // log_dbg!(target: "my_target", Level::Trace, expr)
+    (target: $target:expr, $lvl:expr, $val:expr $(,)?) => (
+        match $val {
+            val => {
+                $crate::log!(
+                    target: $target,
+                    $lvl,
+                    "{} = {:?}",
+                    $crate::__private_api::stringify!($val),
+                    &val
+                );
+                val
+            }
+        }
+    );
+
+    // log_dbg!(Level::Trace, expr)
+    ($lvl:expr, $val:expr $(,)?) => (
+        $crate::log_dbg!(target: $crate::__private_api::module_path!(), $lvl, $val)
+    );
+}
+
+/// Logs an expression and its `Debug` value at the debug level, then returns the value.
+///
+/// See [`log_dbg!`](macro.log_dbg.html) for details.
+#[macro_export]
+macro_rules! debug_dbg {
+    // debug_dbg!(target: "my_target", expr)
+    (target: $target:expr, $val:expr $(,)?) => (
+        $crate::log_dbg!(target: $target, $crate::Level::Debug, $val)
+    );
+    (target: $target:expr, $($val:expr),+ $(,)?) => (
+        ($($crate::log_dbg!(target: $target, $crate::Level::Debug, $val)),+,)
+    );
+
+    // debug_dbg!(expr)
+    ($val:expr $(,)?) => (
+        $crate::log_dbg!($crate::Level::Debug, $val)
+    );
+    ($($val:expr),+ $(,)?) => (
+        ($($crate::log_dbg!($crate::Level::Debug, $val)),+,)
+    );
+}
+
+/// Logs an expression and its `Debug` value at the trace level, then returns the value.
+///
+/// See [`log_dbg!`](macro.log_dbg.html) for details.
+#[macro_export]
+macro_rules! trace_dbg {
+    // trace_dbg!(target: "my_target", expr)
+    (target: $target:expr, $val:expr $(,)?) => (
+        $crate::log_dbg!(target: $target, $crate::Level::Trace, $val)
+    );
+    (target: $target:expr, $($val:expr),+ $(,)?) => (
+        ($($crate::log_dbg!(target: $target, $crate::Level::Trace, $val)),+,)
+    );
+
+    // trace_dbg!(expr)
+    ($val:expr $(,)?) => (
+        $crate::log_dbg!($crate::Level::Trace, $val)
+    );
+    ($($val:expr),+ $(,)?) => (
+        ($($crate::log_dbg!($crate::Level::Trace, $val)),+,)
+    );
+}
+
+/// Times a section of code, logging the elapsed wall time when it ends.
+///
+/// This macro returns a guard that logs a record with the given message when it's dropped,
+/// with the time since the guard was created, in milliseconds, as a `duration_ms` key-value.
+/// Since the record is logged from the guard's destructor, it's also logged on early returns
+/// and `?`. The record's file and line are those of the `log_time!` call, and the level is
+/// checked when the guard is dropped.
+///
+/// This macro requires the `kv` and `std` features.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(all(feature = "kv", feature = "std"))]
+/// # fn main() -> Result<(), std::io::Error> {
+/// use log::{log_time, Level};
+///
+/// # fn read_config() -> Result<String, std::io::Error> { Ok(String::new()) }
+/// fn load_config() -> Result<String, std::io::Error> {
+///     let _timer = log_time!(Level::Debug, "load config");
+///
+///     // Logs `load config` with a `duration_ms` key-value, even if reading fails
+///     let config = read_config()?;
+///
+///     Ok(config)
+/// }
+///
+/// let _timer = log_time!(target: "app_events", Level::Info, "startup");
+///
+/// load_config()?;
+/// # Ok(())
+/// # }
+/// # #[cfg(not(all(feature = "kv", feature = "std")))]
+/// # fn main() {}
+/// ```
+#[macro_export]
+macro_rules! log_time {
+    // log_time!(target: "my_target", Level::Debug, "a message")
+    (target: $target:expr, $lvl:expr, $label:expr $(,)?) => (
+        $crate::__log_time!(target: $target, $lvl, $label)
+    );
+
+    // log_time!(Level::Debug, "a message")
+    ($lvl:expr, $label:expr $(,)?) => (
+        $crate::log_time!(target: $crate::__private_api::module_path!(), $lvl, $label)
+    );
+}
+
+/// Creates a [`LogWriter`](struct.LogWriter.html) that builds up a message and emits it as a
+/// single record when it's dropped.
+///
+/// The writer implements [`std::fmt::Write`], so the message can be written across multiple
+/// statements using `write!`. Whether the level is enabled is checked when the writer is
+/// created, so writes to a disabled writer are cheap and nothing is emitted.
+///
+/// This macro requires the `std` feature.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "std")]
+/// # fn main() -> std::fmt::Result {
+/// use log::{log_writer, Level};
+/// use std::fmt::Write;
+///
+/// let failed = ["db", "cache"];
+///
+/// let mut writer = log_writer!(target: "app_events", Level::Warn);
+/// write!(writer, "{} checks failed:", failed.len())?;
+/// for check in &failed {
+///     write!(writer, " {}", check)?;
+/// }
+///
+/// // Logs `2 checks failed: db cache`
+/// drop(writer);
+/// # Ok(())
+/// # }
+/// # #[cfg(not(feature = "std"))]
+/// # fn main() {}
+/// ```
+#[macro_export]
+macro_rules! log_writer {
+    // log_writer!(target: "my_target", Level::Info)
+    (target: $target:expr, $lvl:expr $(,)?) => (
+        $crate::__log_writer!(target: $target, $lvl)
+    );
+
+    // log_writer!(Level::Info)
+    ($lvl:expr $(,)?) => (
+        $crate::log_writer!(target: $crate::__private_api::module_path!(), $lvl)
+    );
+}
+
+/// Logs a message at the error level, then panics with the same message.
+///
+/// This macro accepts the same arguments as [`error!`](macro.error.html), including key-values.
+/// The message is formatted once and used for both the record and the panic, and the logger
+/// is flushed before panicking, so the record isn't lost if the panic takes the process down.
+///
+/// # Examples
+///
+/// ```should_panic
+/// use log::panic_log;
+///
+/// # fn main() {
+/// let port = 22;
+///
+/// panic_log!(target: "app_events", "Unable to bind to port {}", port);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! panic_log {
+    // panic_log!(target: "my_target", key1 = 42, key2 = true; "a {} event", "log")
+    (target: $target:expr, $($key:tt $($name:ident)? $(:$capture:tt $(? $($pretty:ident)?)?)? $(= $value:expr)?),+; $($arg:tt)+) => (
+        match $crate::__private_api::format_args!($($arg)+) {
+            args => {
+                $crate::log!(
+                    target: $target,
+                    $crate::Level::Error,
+                    $($key $($name)* $(:$capture $(? $($pretty)?)?)* $(= $value)*),+;
+                    "{}",
+                    args
+                );
+                $crate::logger().flush();
+                $crate::__private_api::panic!("{}", args)
+            }
+        }
+    );
+
+    // panic_log!(target: "my_target", "a {} event", "log")
+    (target: $target:expr, $($arg:tt)+) => (
+        match $crate::__private_api::format_args!($($arg)+) {
+            args => {
+                $crate::log!(target: $target, $crate::Level::Error, "{}", args);
+                $crate::logger().flush();
+                $crate::__private_api::panic!("{}", args)
+            }
+        }
+    );
+
+    // panic_log!("a {} event", "log")
+    ($($arg:tt)+) => ($crate::panic_log!(target: $crate::__private_api::module_path!(), $($arg)+));
+}
+
+/// Asserts that a boolean expression is `true`, logging an error before panicking if it isn't.
+///
+/// This macro works like the standard library's `assert!`, but the failure is also logged
+/// as an error-level record through the logger before panicking, so it isn't only visible
+/// on the panicking thread's stderr. The record's message is the same as the panic's, and
+/// with the `kv` feature, the failed expression is captured as an `expr` key-value.
+///
+/// The [`log_assert_eq!`](macro.log_assert_eq.html) and [`log_assert_ne!`](macro.log_assert_ne.html)
+/// macros also capture their operands as `left` and `right` key-values, and
+/// [`log_debug_assert!`](macro.log_debug_assert.html) is only checked when debug assertions
+/// are enabled.
+///
+/// # Examples
+///
+/// ```
+/// use log::{log_assert, log_assert_eq, log_assert_ne};
+///
+/// # fn main() {
+/// let pool_size = 4;
+///
+/// log_assert!(pool_size > 0);
+/// log_assert!(pool_size > 0, "the pool must have at least one connection");
+/// log_assert_eq!(pool_size % 2, 0, "the pool size must be even, got {}", pool_size);
+/// log_assert_ne!(pool_size, 3);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! log_assert {
+    // log_assert!(cond)
+    ($cond:expr $(,)?) => (
+        if !$cond {
+            $crate::__log_assert_failed!(
+                expr = $crate::__private_api::stringify!($cond);
+                "assertion failed: {}",
+                $crate::__private_api::stringify!($cond)
+            );
+        }
+    );
+
+    // log_assert!(cond, "a {} message", "formatted")
+    ($cond:expr, $($arg:tt)+) => (
+        if !$cond {
+            $crate::__log_assert_failed!(expr = $crate::__private_api::stringify!($cond); $($arg)+);
+        }
+    );
+}
+
+/// Asserts that two expressions are equal, logging an error before panicking if they aren't.
+///
+/// See [`log_assert!`](macro.log_assert.html) for details.
+#[macro_export]
+macro_rules! log_assert_eq {
+    // log_assert_eq!(left, right)
+    // log_assert_eq!(left, right, "a {} message", "formatted")
+    ($left:expr, $right:expr $(, $($arg:tt)+)?) => (
+        $crate::__log_assert_cmp!(==, $left, $right $(, $($arg)+)?)
+    );
+}
+
+/// Asserts that two expressions aren't equal, logging an error before panicking if they are.
+///
+/// See [`log_assert!`](macro.log_assert.html) for details.
+#[macro_export]
+macro_rules! log_assert_ne {
+    // log_assert_ne!(left, right)
+    // log_assert_ne!(left, right, "a {} message", "formatted")
+    ($left:expr, $right:expr $(, $($arg:tt)+)?) => (
+        $crate::__log_assert_cmp!(!=, $left, $right $(, $($arg)+)?)
+    );
+}
+
+/// Asserts that a boolean expression is `true` when debug assertions are enabled,
+/// logging an error before panicking if it isn't.
+///
+/// See [`log_assert!`](macro.log_assert.html) for details.
+#[macro_export]
+macro_rules! log_debug_assert {
+    ($($arg:tt)*) => (
+        if $crate::__private_api::cfg!(debug_assertions) {
+            $crate::log_assert!($($arg)*);
+        }
+    );
+}
+
+/// Asserts that two expressions are equal when debug assertions are enabled,
+/// logging an error before panicking if they aren't.
+///
+/// See [`log_assert!`](macro.log_assert.html) for details.
+#[macro_export]
+macro_rules! log_debug_assert_eq {
+    ($($arg:tt)*) => (
+        if $crate::__private_api::cfg!(debug_assertions) {
+            $crate::log_assert_eq!($($arg)*);
+        }
+    );
+}
+
+/// Asserts that two expressions aren't equal when debug assertions are enabled,
+/// logging an error before panicking if they are.
+///
+/// See [`log_assert!`](macro.log_assert.html) for details.
+#[macro_export]
+macro_rules! log_debug_assert_ne {
+    ($($arg:tt)*) => (
+        if $crate::__private_api::cfg!(debug_assertions) {
+            $crate::log_assert_ne!($($arg)*);
+        }
+    );
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __log_origin {
+    ({$($target:tt)*} {$($module_path:tt)*} {$($file:tt)*} {$($line:tt)*} {$($static:tt)*} module_path: $value:expr, $($arg:tt)+) => (
+        $crate::__log_origin!({$($target)*} {$value} {$($file)*} {$($line)*} {$($static)*} $($arg)+)
+    );
+    ({$($target:tt)*} {$($module_path:tt)*} {$($file:tt)*} {$($line:tt)*} {$($static:tt)*} file: $value:expr, $($arg:tt)+) => (
+        $crate::__log_origin!({$($target)*} {$($module_path)*} {$value} {$($line)*} {$($static)*} $($arg)+)
+    );
+    ({$($target:tt)*} {$($module_path:tt)*} {$($file:tt)*} {$($line:tt)*} {$($static:tt)*} line: $value:expr, $($arg:tt)+) => (
+        $crate::__log_origin!({$($target)*} {$($module_path)*} {$($file)*} {$value} {$($static)*} $($arg)+)
+    );
+    ({$($target:tt)*} {$($module_path:tt)*} {$($file:tt)*} {$($line:tt)*} {$($static:tt)*} static: $value:tt, $($arg:tt)+) => (
+        $crate::__log_origin!({$($target)*} {$($module_path)*} {$($file)*} {$($line)*} {$value} $($arg)+)
+    );
+    ({$($target:expr)?} {$($module_path:expr)?} {$($file:expr)?} {$($line:expr)?} {$($static:tt)?} $lvl:expr, $($arg:tt)+) => (
+        $crate::log!(
+            target: $crate::__private_api::Origin::new($crate::__private_api::module_path!())
+                $(.target($target))?
+                $(.module_path($module_path))?
+                $(.file($file))?
+                $(.line($line))?
+                $(.static_key_values($crate::__log_static_kvs!($static)))?,
+            $lvl,
+            $($arg)+
+        )
+    );
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "kv")]
+macro_rules! __log_static_kvs {
+    ([$($key:tt = $value:expr),* $(,)?]) => {
+        &[$(($crate::__log_key!($key), $value)),*]
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "kv"))]
+macro_rules! __log_static_kvs {
+    ($($args:tt)*) => {
+        compile_error!("static key-values require the `kv` feature of `log`")
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __log_assert_cmp {
+    ($op:tt, $left:expr, $right:expr $(,)?) => (
+        match (&$left, &$right) {
+            (left, right) => {
+                if !(*left $op *right) {
+                    $crate::__log_assert_failed!(
+                        expr = $crate::__private_api::stringify!($left $op $right),
+                        left = left,
+                        right = right;
+                        "assertion `left {} right` failed\n  left: {:?}\n right: {:?}",
+                        $crate::__private_api::stringify!($op),
+                        left,
+                        right
+                    );
+                }
+            }
+        }
+    );
+    ($op:tt, $left:expr, $right:expr, $($arg:tt)+) => (
+        match (&$left, &$right) {
+            (left, right) => {
+                if !(*left $op *right) {
+                    $crate::__log_assert_failed!(
+                        expr = $crate::__private_api::stringify!($left $op $right),
+                        left = left,
+                        right = right;
+                        "assertion `left {} right` failed: {}\n  left: {:?}\n right: {:?}",
+                        $crate::__private_api::stringify!($op),
+                        $crate::__private_api::format_args!($($arg)+),
+                        left,
+                        right
+                    );
+                }
+            }
+        }
+    );
+}
+
+/// Builds a source of key-values outside of a log statement.
+///
+/// Key-values use the same syntax and capturing modifiers as the logging macros.
+/// The result implements [`kv::Source`](kv/trait.Source.html), so it can be stored,
+/// passed to other functions, and attached to any number of records.
+///
+/// Values are borrowed rather than moved into the source.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "kv")]
+/// # fn main() {
+/// use log::kv::{self, Key, Source};
+///
+/// #[derive(Debug)]
+/// struct User {
+///     id: u32,
+/// }
+///
+/// let user = User { id: 42 };
+/// let ctx = log::kv!(request_id = 1, user:? = user, "user.name" = "Nori");
+///
+/// fn log_with(ctx: &dyn kv::Source) {
+///     log::logger().log(
+///         &log::Record::builder()
+///             .args(format_args!("request handled"))
+///             .key_values(ctx)
+///             .build(),
+///     );
+/// }
+///
+/// log_with(&ctx);
+///
+/// assert_eq!(3, ctx.count());
+/// assert_eq!(Some(1), ctx.get_i64(Key::from_str("request_id")));
+/// # }
+/// # #[cfg(not(feature = "kv"))]
+/// # fn main() {}
+/// ```
+#[macro_export]
+macro_rules! kv {
+    ($($args:tt)*) => {
+        $crate::__kv_source!($($args)*)
+    };
+}
+
+// These macros use a pattern of #[cfg]s to produce nicer error
+// messages when log features aren't available
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "kv")]
+macro_rules! __kv_source {
+    () => {
+        $crate::__private_api::KvEnd
+    };
+    (? $key:ident $(, $($rest:tt)*)?) => {
+        $crate::__kv_source!($key:? $(, $($rest)*)?)
+    };
+    (% $key:ident $(, $($rest:tt)*)?) => {
+        $crate::__kv_source!($key:% $(, $($rest)*)?)
+    };
+    ($key:tt :#? $(= $value:expr)? $(, $($rest:tt)*)?) => {
+        $crate::__private_api::KvPair {
+            key: $crate::__log_key!($key),
+            value: $crate::__kv_value!($key $(= $value)?),
+            capture: |v| $crate::__private_api::capture_debug_pretty(v),
+            rest: $crate::__kv_source!($($($rest)*)?),
+        }
+    };
+    ($key:tt $(:$capture:tt)? $(= $value:expr)? $(, $($rest:tt)*)?) => {
+        $crate::__private_api::KvPair {
+            key: $crate::__log_key!($key),
+            value: $crate::__kv_value!($key $(:$capture)? $(= $value)?),
+            capture: |v| $crate::__log_capture!((v) $(:$capture)?),
+            rest: $crate::__kv_source!($($($rest)*)?),
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "kv"))]
+macro_rules! __kv_source {
+    ($($args:tt)*) => {
+        compile_error!("key value support requires the `kv` feature of `log`")
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __kv_value {
+    ($key:ident :lazy) => {
+        &$crate::kv::Lazy::new($key)
+    };
+    ($key:ident :seq) => {
+        &$crate::kv::SeqIter::new(&$key)
+    };
+    ($key:tt :seq = $value:expr) => {
+        &$crate::kv::SeqIter::new($value)
+    };
+    ($key:tt :lazy = $value:expr) => {
+        &$crate::kv::Lazy::new($value)
+    };
+    ($key:ident $(:$capture:tt)?) => {
+        &$key
+    };
+    ($key:tt $(:$capture:tt)? = $value:expr) => {
+        &$value
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "kv")]
+macro_rules! __log_key {
+    // ?key1
+    (? $key:ident) => {
+        $crate::__private_api::stringify!($key)
+    };
+    // %key1
+    (% $key:ident) => {
+        $crate::__private_api::stringify!($key)
+    };
+    // key1 = 42
+    ($($args:ident)*) => {
+        $crate::__private_api::stringify!($($args)*)
+    };
+    // "key1" = 42
+    ($($args:expr)*) => {
+        $($args)*
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "kv"))]
+macro_rules! __log_key {
+    ($($args:tt)*) => {
+        compile_error!("key value support requires the `kv` feature of `log`")
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "kv")]
+macro_rules! __log_value {
+    // Entrypoint
+    (? $key:ident =) => {
+        $crate::__log_value!(($key):?)
+    };
+    (% $key:ident =) => {
+        $crate::__log_value!(($key):%)
+    };
+    ($key:tt :#? = $args:expr) => {
+        $crate::__private_api::capture_debug_pretty(&&$args)
+    };
+    ($key:ident :#? =) => {
+        $crate::__private_api::capture_debug_pretty(&&$key)
+    };
+    ($key:ident :seq =) => {
+        $crate::__log_value!((&$key):seq)
+    };
+    ($key:tt = $args:expr) => {
+        $crate::__log_value!(($args):value)
+    };
+    ($key:tt :$capture:tt = $args:expr) => {
+        $crate::__log_value!(($args):$capture)
+    };
+    ($key:ident =) => {
+        $crate::__log_value!(($key):value)
+    };
+    ($key:ident :$capture:tt =) => {
+        $crate::__log_value!(($key):$capture)
+    };
+    // ToValue
+    (($args:expr):value) => {
+        $crate::__log_capture!((&&$args))
+    };
+    // Lazy
+    (($args:expr):lazy) => {
+        $crate::__private_api::capture_to_value(&&$crate::kv::Lazy::new($args))
+    };
+    // Sequences
+    (($args:expr):seq) => {
+        $crate::__private_api::capture_to_value(&&$crate::kv::SeqIter::new($args))
+    };
+    (($args:expr):$capture:tt) => {
+        $crate::__log_capture!((&&$args):$capture)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "kv"))]
+macro_rules! __log_value {
+    ($($args:tt)*) => {
+        compile_error!("key value support requires the `kv` feature of `log`")
+    };
+}
+
+// Capture a value through a double reference using its capturing modifier
+// This is shared by the logging macros and `kv!`, which captures values
+// when its source is visited rather than when it's built
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __log_capture {
+    // ToValue
+    (($v:expr)) => {
+        $crate::__private_api::capture_to_value($v)
+    };
+    // Lazy values and sequences are wrapped before they're captured
+    (($v:expr):lazy) => {
+        $crate::__private_api::capture_to_value($v)
+    };
+    (($v:expr):seq) => {
+        $crate::__private_api::capture_to_value($v)
+    };
+    // Redacted
+    (($v:expr):redact) => {
+        $crate::__private_api::capture_redacted($v)
+    };
+    // Units
+    (($v:expr):ns) => {
+        $crate::__private_api::capture_unit($v, "ns")
+    };
+    (($v:expr):us) => {
+        $crate::__private_api::capture_unit($v, "us")
+    };
+    (($v:expr):ms) => {
+        $crate::__private_api::capture_unit($v, "ms")
+    };
+    (($v:expr):s) => {
+        $crate::__private_api::capture_unit($v, "s")
+    };
+    (($v:expr):bytes) => {
+        $crate::__private_api::capture_unit($v, "bytes")
+    };
+    // Formatting hints
+    (($v:expr):x) => {
+        $crate::__private_api::capture_hint($v, $crate::kv::FormatHint::LowerHex)
+    };
+    (($v:expr):X) => {
+        $crate::__private_api::capture_hint($v, $crate::kv::FormatHint::UpperHex)
+    };
+    (($v:expr):o) => {
+        $crate::__private_api::capture_hint($v, $crate::kv::FormatHint::Octal)
+    };
+    (($v:expr):b) => {
+        $crate::__private_api::capture_hint($v, $crate::kv::FormatHint::Binary)
+    };
+    (($v:expr):e) => {
+        $crate::__private_api::capture_hint($v, $crate::kv::FormatHint::LowerExp)
+    };
+    (($v:expr):E) => {
+        $crate::__private_api::capture_hint($v, $crate::kv::FormatHint::UpperExp)
+    };
+    (($v:expr):{. $precision:literal}) => {
+        $crate::__private_api::capture_hint($v, $crate::kv::FormatHint::Precision($precision))
+    };
+    // Debug
+    (($v:expr):?) => {
+        $crate::__private_api::capture_debug($v)
+    };
+    (($v:expr):debug) => {
+        $crate::__private_api::capture_debug($v)
+    };
+    // Display
+    (($v:expr):%) => {
+        $crate::__private_api::capture_display($v)
+    };
+    (($v:expr):display) => {
+        $crate::__private_api::capture_display($v)
+    };
+    //Error
+    (($v:expr):err) => {
+        $crate::__log_value_error!($v)
+    };
+    // Result
+    (($v:expr):result) => {
+        $crate::__log_value_result!($v)
+    };
+    // sval::Value
+    (($v:expr):sval) => {
+        $crate::__log_value_sval!($v)
+    };
+    // serde::Serialize
+    (($v:expr):serde) => {
+        $crate::__log_value_serde!($v)
+    };
+    // Raw JSON
+    (($v:expr):json) => {
+        $crate::__log_value_json!($v)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "kv_sval")]
+macro_rules! __log_value_sval {
+    ($v:expr) => {
+        $crate::__private_api::capture_sval($v)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "kv_sval"))]
+macro_rules! __log_value_sval {
+    ($v:expr) => {
+        compile_error!("capturing values as `sval::Value` requites the `kv_sval` feature of `log`")
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "kv_serde")]
+macro_rules! __log_value_serde {
+    ($v:expr) => {
+        $crate::__private_api::capture_serde($v)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "kv_serde"))]
+macro_rules! __log_value_serde {
+    ($v:expr) => {
+        compile_error!(
+            "capturing values as `serde::Serialize` requites the `kv_serde` feature of `log`"
+        )
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "kv_json")]
+macro_rules! __log_value_json {
+    ($v:expr) => {
+        $crate::__private_api::capture_json($v)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "kv_json"))]
+macro_rules! __log_value_json {
+    ($v:expr) => {
+        compile_error!("capturing values as raw JSON requires the `kv_json` feature of `log`")
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "kv_std")]
+macro_rules! __log_value_error {
+    ($v:expr) => {
+        $crate::__private_api::capture_error(*$v)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "kv_std"))]
+macro_rules! __log_value_error {
+    ($v:expr) => {
+        compile_error!(
+            "capturing values as `std::error::Error` requites the `kv_std` feature of `log`"
+        )
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "kv_std")]
+macro_rules! __log_value_result {
+    ($v:expr) => {
+        $crate::__private_api::capture_result(*$v)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "kv_std"))]
+macro_rules! __log_value_result {
+    ($v:expr) => {
+        compile_error!("capturing values as `Result` requires the `kv_std` feature of `log`")
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __static_max_level {
+    () => {{
+        const STATIC_MAX_LEVEL: $crate::LevelFilter = $crate::__private_api::static_max_level(
+            $crate::__private_api::option_env!("LOG_STATIC_MAX_LEVEL"),
+            $crate::__private_api::module_path!(),
+        );
+        STATIC_MAX_LEVEL
+    }};
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "crate_info")]
+macro_rules! __log_crate {
+    ($target:expr) => {
+        $crate::__private_api::Crate::new(
+            $target,
+            $crate::__private_api::option_env!("CARGO_PKG_NAME"),
+            $crate::__private_api::option_env!("CARGO_PKG_VERSION"),
+        )
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "crate_info"))]
+macro_rules! __log_crate {
+    ($target:expr) => {
+        $target
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(all(feature = "release_strip_location", not(debug_assertions))))]
+macro_rules! __log_module_path {
+    () => {
+        $crate::__private_api::module_path!()
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(all(feature = "release_strip_location", not(debug_assertions)))]
+macro_rules! __log_module_path {
+    () => {
+        ""
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "std")]
+macro_rules! __log_every_secs {
+    ($secs:expr, target: $target:expr, $lvl:expr, $($arg:tt)+) => (
+        $crate::__log_rate_limited!(every_secs($secs), target: $target, $lvl, $($arg)+)
+    );
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "std"))]
+macro_rules! __log_every_secs {
+    ($($args:tt)*) => {
+        compile_error!("rate limiting by time requires the `std` feature of `log`")
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "std")]
+macro_rules! __log_err {
+    (target: $target:expr, $lvl:expr, $result:expr) => {
+        match $result {
+            $crate::__private_api::Ok(ok) => $crate::__private_api::Ok(ok),
+            $crate::__private_api::Err(err) => {
+                $crate::log!(target: $target, $lvl, "{}", $crate::__private_api::ErrorChainDisplay(&err));
+                $crate::__private_api::Err(err)
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "std"))]
+macro_rules! __log_err {
+    ($($args:tt)*) => {
+        compile_error!("logging errors requires the `std` feature of `log`")
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(all(feature = "kv", feature = "std"))]
+macro_rules! __log_time {
+    (target: $target:expr, $lvl:expr, $label:expr) => {
+        $crate::__private_api::Timer::new(
+            $label,
+            $lvl,
+            $crate::__static_max_level!(),
+            (
+                $crate::__log_crate!($target),
+                $crate::__log_module_path!(),
+                $crate::__private_api::loc(),
+            ),
+        )
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(all(feature = "kv", feature = "std")))]
+macro_rules! __log_time {
+    ($($args:tt)*) => {
+        compile_error!("timing requires the `kv` and `std` features of `log`")
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "callsite_cache")]
+macro_rules! __log_callsite {
+    ($lvl:expr, $($arg:tt)+) => ({
+        static CALLSITE: $crate::__private_api::Callsite = $crate::__private_api::Callsite::new();
+
+        let lvl = $lvl;
+        if lvl <= $crate::__static_max_level!()
+            && lvl <= $crate::max_level()
+            && CALLSITE.is_enabled(lvl, $crate::__private_api::module_path!())
+        {
+            $crate::log!(target: $crate::__private_api::module_path!(), lvl, $($arg)+);
+        }
+    });
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "callsite_cache"))]
+macro_rules! __log_callsite {
+    ($lvl:expr, $($arg:tt)+) => ($crate::log!(target: $crate::__private_api::module_path!(), $lvl, $($arg)+));
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "std")]
+macro_rules! __log_writer {
+    (target: $target:expr, $lvl:expr) => {
+        $crate::__private_api::log_writer(
+            $lvl,
+            $crate::__static_max_level!(),
+            (
+                $crate::__log_crate!($target),
+                $crate::__log_module_path!(),
+                $crate::__private_api::loc(),
+            ),
+        )
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "std"))]
+macro_rules! __log_writer {
+    ($($args:tt)*) => {
+        compile_error!("log writers require the `std` feature of `log`")
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "kv")]
+macro_rules! __log_assert_failed {
+    (expr = $expr:expr $(, $key:ident = $value:expr)*; $($arg:tt)+) => {
+        match $crate::__private_api::format_args!($($arg)+) {
+            args => {
+                $crate::error!(expr = $expr $(, $key:? = $value)*; "{}", args);
+                $crate::__private_api::panic!("{}", args)
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "kv"))]
+macro_rules! __log_assert_failed {
+    (expr = $expr:expr $(, $key:ident = $value:expr)*; $($arg:tt)+) => {
+        match $crate::__private_api::format_args!($($arg)+) {
+            args => {
+                $crate::error!("{}", args);
+                $crate::__private_api::panic!("{}", args)
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(all(feature = "kv", feature = "std"))]
+macro_rules! __kv_scope {
+    ($($key:tt $(:$capture:tt $(? $($pretty:ident)?)?)? $(= $value:expr)?),+ => $body:block) => {{
+        let _guard = $crate::kv::push_context(&$crate::kv!($($key $(:$capture $(? $($pretty)?)?)? $(= $value)?),+));
+
+        $body
+    }};
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(all(feature = "kv", feature = "std")))]
+macro_rules! __kv_scope {
+    ($($args:tt)*) => {
+        compile_error!("scoped key-values require the `kv` and `std` features of `log`")
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "kv_format_args")]
+macro_rules! __log_kvs {
+    ($kvs:tt $($arg:tt)+) => {
+        $crate::__private_api::format_kvs!($crate $kvs $($arg)+)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "kv_format_args"))]
+macro_rules! __log_kvs {
+    ([] $($arg:tt)+) => {
+        ()
+    };
+    ([$($kvs:tt)+] $($arg:tt)+) => {
+        &[$($kvs)+]
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __log_target {
+    ($suffix:literal) => {
+        $crate::__private_api::concat!($crate::__private_api::module_path!(), "::", $suffix)
     };
 }
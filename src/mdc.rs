@@ -0,0 +1,271 @@
+//! A mapped diagnostic context (MDC) for the current thread.
+//!
+//! The MDC is a map of key-values that's added to every record logged on the current
+//! thread using the macros, until the keys are removed. It's like
+//! [`push_context`](crate::kv::push_context), except keys can be inserted and removed
+//! individually instead of in nested scopes, which suits code that sets a field like a
+//! request id in one place and clears it somewhere else.
+//!
+//! The MDC is added to records after the [global key-values](crate::set_global_kv) and
+//! before any scoped ones, so scoped key-values and the record's own take precedence over
+//! the same key in the MDC when using [`Source::get`].
+//!
+//! # Examples
+//!
+//! ```
+//! use log::{info, mdc};
+//!
+//! mdc::insert("request_id", 42);
+//!
+//! // This record includes `request_id = 42`
+//! info!("handling a request");
+//!
+//! mdc::remove("request_id");
+//!
+//! {
+//!     let _guard = mdc::insert_scoped("user", "Nori");
+//!
+//!     // This record includes `user = "Nori"`
+//!     info!("authenticated");
+//! }
+//! ```
+
+use std::cell::RefCell;
+use std::fmt;
+use std::marker::PhantomData;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use crate::kv::{Error, Key, OwnedValue, Source, ToValue, Value, VisitSource};
+
+pub(crate) type Entries = Vec<(Box<str>, OwnedValue)>;
+
+thread_local! {
+    static CURRENT: RefCell<Option<Rc<Entries>>> = const { RefCell::new(None) };
+}
+
+/// Insert a key-value into the MDC for the current thread.
+///
+/// If the key is already present then its value is replaced, and the previous value
+/// is returned.
+pub fn insert(key: &str, value: impl ToValue) -> Option<OwnedValue> {
+    let value = value.to_value().to_owned();
+
+    with_entries(
+        |entries| match entries.iter_mut().find(|(k, _)| **k == *key) {
+            Some((_, existing)) => Some(std::mem::replace(existing, value)),
+            None => {
+                entries.push((key.into(), value));
+                None
+            }
+        },
+    )
+    .flatten()
+}
+
+/// Remove a key from the MDC for the current thread, returning its value if it was present.
+pub fn remove(key: &str) -> Option<OwnedValue> {
+    with_entries(|entries| {
+        let index = entries.iter().position(|(k, _)| **k == *key)?;
+
+        Some(entries.remove(index).1)
+    })
+    .flatten()
+}
+
+/// Get the value of a key in the MDC for the current thread.
+pub fn get(key: &str) -> Option<OwnedValue> {
+    current().and_then(|mdc| mdc.get(Key::from_str(key)).map(|value| value.to_owned()))
+}
+
+/// Remove all key-values from the MDC for the current thread.
+pub fn clear() {
+    let _ = CURRENT.try_with(|current| current.borrow_mut().take());
+}
+
+/// Insert a key-value into the MDC for the current thread until the returned guard
+/// is dropped.
+///
+/// Dropping the guard restores the value the key had before it was inserted, or removes
+/// the key if it wasn't present.
+pub fn insert_scoped(key: &str, value: impl ToValue) -> Guard {
+    let prev = insert(key, value);
+
+    Guard {
+        key: key.into(),
+        prev,
+        _not_send: PhantomData,
+    }
+}
+
+/// A guard returned by [`insert_scoped`].
+#[must_use = "the key is removed as soon as the guard is dropped"]
+#[derive(Debug)]
+pub struct Guard {
+    key: Box<str>,
+    prev: Option<OwnedValue>,
+    // The MDC belongs to the thread the key was inserted on
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        match self.prev.take() {
+            Some(prev) => {
+                insert(&self.key, prev);
+            }
+            None => {
+                remove(&self.key);
+            }
+        }
+    }
+}
+
+fn with_entries<R>(f: impl FnOnce(&mut Entries) -> R) -> Option<R> {
+    CURRENT
+        .try_with(|current| {
+            let mut current = current.borrow_mut();
+
+            // Records that are being logged may be holding on to the previous entries
+            let entries = Rc::make_mut(current.get_or_insert_with(Default::default));
+            let result = f(entries);
+
+            if entries.is_empty() {
+                *current = None;
+            }
+
+            result
+        })
+        .ok()
+}
+
+/// The key-values in the MDC for the current thread, as a source.
+#[derive(Clone)]
+pub(crate) struct Mdc(Rc<Entries>);
+
+/// Get the MDC for the current thread, if it's not empty.
+pub(crate) fn current() -> Option<Mdc> {
+    CURRENT
+        .try_with(|current| current.borrow().clone().map(Mdc))
+        .ok()
+        .flatten()
+}
+
+/// Copy the MDC for the current thread, so it can be sent to another thread.
+pub(crate) fn capture() -> Option<Arc<Entries>> {
+    current().map(|mdc| Arc::new((*mdc.0).clone()))
+}
+
+/// Replace the MDC for the current thread, returning the previous one.
+pub(crate) fn replace(entries: Option<Rc<Entries>>) -> Option<Rc<Entries>> {
+    CURRENT
+        .try_with(|current| std::mem::replace(&mut *current.borrow_mut(), entries))
+        .ok()
+        .flatten()
+}
+
+impl Source for Mdc {
+    fn visit<'kvs>(&'kvs self, visitor: &mut dyn VisitSource<'kvs>) -> Result<(), Error> {
+        for (key, value) in &*self.0 {
+            visitor.visit_pair(Key::from_str(key), value.by_ref())?;
+        }
+
+        Ok(())
+    }
+
+    fn get(&self, key: Key) -> Option<Value<'_>> {
+        self.0
+            .iter()
+            .find(|(k, _)| **k == *key.as_str())
+            .map(|(_, value)| value.by_ref())
+    }
+
+    fn count(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl fmt::Debug for Mdc {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut map = f.debug_map();
+        for (key, value) in &*self.0 {
+            map.entry(key, value);
+        }
+        map.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn current_string() -> Option<String> {
+        current().map(|mdc| {
+            let mut logfmt = String::new();
+            crate::kv::to_logfmt(&mut logfmt, &mdc).unwrap();
+            logfmt
+        })
+    }
+
+    #[test]
+    fn insert_remove() {
+        assert!(current().is_none());
+
+        assert!(insert("a", 1).is_none());
+        assert!(insert("b", 2).is_none());
+        assert_eq!(Some("a=1 b=2"), current_string().as_deref());
+
+        assert_eq!(
+            Some(1),
+            insert("a", 3).and_then(|prev| prev.by_ref().to_i64())
+        );
+        assert_eq!(Some("a=3 b=2"), current_string().as_deref());
+        assert_eq!(Some(2), get("b").and_then(|value| value.by_ref().to_i64()));
+
+        assert!(remove("c").is_none());
+        assert_eq!(Some(3), remove("a").and_then(|prev| prev.by_ref().to_i64()));
+        assert_eq!(Some("b=2"), current_string().as_deref());
+
+        clear();
+        assert!(current().is_none());
+    }
+
+    #[test]
+    fn insert_scoped() {
+        insert("a", 1);
+
+        {
+            let _a = super::insert_scoped("a", 2);
+            let _b = super::insert_scoped("b", 3);
+            assert_eq!(Some("a=2 b=3"), current_string().as_deref());
+        }
+
+        assert_eq!(Some("a=1"), current_string().as_deref());
+
+        remove("a");
+        assert!(current().is_none());
+    }
+
+    #[test]
+    fn snapshot_outlives_changes() {
+        insert("a", 1);
+        let mdc = current().unwrap();
+
+        remove("a");
+
+        assert!(current().is_none());
+        assert_eq!(Some(1), mdc.get_i64(Key::from_str("a")));
+    }
+
+    #[test]
+    fn per_thread() {
+        insert("a", 1);
+
+        std::thread::spawn(|| assert!(current().is_none()))
+            .join()
+            .unwrap();
+
+        assert!(current().is_some());
+        clear();
+    }
+}
@@ -0,0 +1,493 @@
+//! Records that own their data.
+
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+
+use crate::{Level, Metadata, ProcessInfo, Record};
+
+#[cfg(feature = "timestamp")]
+use crate::Timestamp;
+
+#[cfg(feature = "kv")]
+use crate::kv::{self, OwnedSource, OwnedValue};
+
+/// An owned version of a [`Record`].
+///
+/// An owned record has its message rendered into a string, and its key-values and metadata
+/// copied. Its error, if it has one, is rendered into a chain of messages, so it keeps its
+/// [`source`](Error::source)s but not its type. It's `'static` and `Send`, so it can be moved into a channel or a background
+/// thread after the logging call it came from has returned. Owned records are created using
+/// [`Record::to_owned`].
+///
+/// # Examples
+///
+/// ```
+/// use log::{Level, Record};
+///
+/// let owned = Record::builder()
+///     .level(Level::Info)
+///     .target("app")
+///     .args(format_args!("hello {}", "world"))
+///     .build()
+///     .to_owned();
+///
+/// std::thread::spawn(move || {
+///     assert_eq!("hello world", owned.message());
+///
+///     // Owned records can be turned back into records to pass on to another logger
+///     owned.with_record(|record| {
+///         assert_eq!("app", record.target());
+///         assert_eq!("hello world", record.args().to_string());
+///     });
+/// })
+/// .join()
+/// .unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct OwnedRecord {
+    level: Level,
+    target: String,
+    process: ProcessInfo,
+    verbosity: u32,
+    #[cfg(feature = "crate_info")]
+    crate_name: Option<String>,
+    #[cfg(feature = "crate_info")]
+    crate_version: Option<String>,
+    message: String,
+    module_path: Option<Cow<'static, str>>,
+    file: Option<Cow<'static, str>>,
+    line: Option<u32>,
+    #[cfg(feature = "kv")]
+    static_key_values: Vec<(String, String)>,
+    #[cfg(feature = "kv")]
+    key_values: OwnedSource,
+    #[cfg(feature = "kv")]
+    template: Option<String>,
+    #[cfg(feature = "kv")]
+    message_value: Option<OwnedValue>,
+    #[cfg(feature = "timestamp")]
+    timestamp: Option<Timestamp>,
+    #[cfg(feature = "seq")]
+    seq: Option<u64>,
+    #[cfg(feature = "thread")]
+    thread_id: Option<std::thread::ThreadId>,
+    #[cfg(feature = "thread")]
+    thread_name: Option<String>,
+    error: Option<OwnedError>,
+}
+
+impl<'a> Record<'a> {
+    /// Buffer this record into an [`OwnedRecord`].
+    ///
+    /// The message is formatted, and the key-values are copied. The module path and file
+    /// are only copied if they're not `'static` strings.
+    pub fn to_owned(&self) -> OwnedRecord {
+        fn to_owned(s: Option<&str>, s_static: Option<&'static str>) -> Option<Cow<'static, str>> {
+            match s_static {
+                Some(s) => Some(Cow::Borrowed(s)),
+                None => s.map(|s| Cow::Owned(s.to_owned())),
+            }
+        }
+
+        OwnedRecord {
+            level: self.level(),
+            target: self.target().to_owned(),
+            process: ProcessInfo::new(self.metadata().pid(), self.metadata().parent_pid()),
+            verbosity: self.metadata().verbosity(),
+            #[cfg(feature = "crate_info")]
+            crate_name: self.metadata().crate_name().map(ToOwned::to_owned),
+            #[cfg(feature = "crate_info")]
+            crate_version: self.metadata().crate_version().map(ToOwned::to_owned),
+            message: self.args().to_string(),
+            module_path: to_owned(self.module_path(), self.module_path_static()),
+            file: to_owned(self.file(), self.file_static()),
+            line: self.line(),
+            #[cfg(feature = "kv")]
+            static_key_values: self
+                .metadata()
+                .static_key_values()
+                .iter()
+                .map(|(k, v)| ((*k).to_owned(), (*v).to_owned()))
+                .collect(),
+            #[cfg(feature = "kv")]
+            key_values: OwnedSource::from_source(self.key_values()),
+            #[cfg(feature = "kv")]
+            template: self.template().map(ToOwned::to_owned),
+            #[cfg(feature = "kv")]
+            message_value: self.message_value().map(|value| value.to_owned()),
+            #[cfg(feature = "timestamp")]
+            timestamp: self.timestamp(),
+            #[cfg(feature = "seq")]
+            seq: self.seq(),
+            #[cfg(feature = "thread")]
+            thread_id: self.thread_id(),
+            #[cfg(feature = "thread")]
+            thread_name: self.thread_name().map(ToOwned::to_owned),
+            error: self.error().map(OwnedError::new),
+        }
+    }
+}
+
+impl OwnedRecord {
+    /// The rendered message.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Metadata about the log directive.
+    ///
+    /// The metadata doesn't include the static key-values, since they can't be borrowed as
+    /// a slice. They're available from [`static_key_values`](OwnedRecord::static_key_values),
+    /// and are included in the metadata of records passed to
+    /// [`with_record`](OwnedRecord::with_record).
+    pub fn metadata(&self) -> Metadata<'_> {
+        let mut builder = Metadata::builder();
+        builder
+            .level(self.level)
+            .target(&self.target)
+            .process(Some(&self.process))
+            .verbosity(self.verbosity);
+
+        #[cfg(feature = "crate_info")]
+        builder
+            .crate_name(self.crate_name.as_deref())
+            .crate_version(self.crate_version.as_deref());
+
+        builder.build()
+    }
+
+    /// The verbosity level of the message.
+    pub fn level(&self) -> Level {
+        self.level
+    }
+
+    /// The name of the target of the directive.
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    /// The module path of the message.
+    pub fn module_path(&self) -> Option<&str> {
+        self.module_path.as_deref()
+    }
+
+    /// The source file containing the message.
+    pub fn file(&self) -> Option<&str> {
+        self.file.as_deref()
+    }
+
+    /// The line containing the message.
+    pub fn line(&self) -> Option<u32> {
+        self.line
+    }
+
+    /// The static key-values declared at the callsite.
+    #[cfg(feature = "kv")]
+    pub fn static_key_values(&self) -> &[(String, String)] {
+        &self.static_key_values
+    }
+
+    /// The structured key-value pairs associated with the message.
+    #[cfg(feature = "kv")]
+    pub fn key_values(&self) -> &dyn kv::Source {
+        &self.key_values
+    }
+
+    /// The message template the message was rendered from, if there is one.
+    #[cfg(feature = "kv")]
+    pub fn template(&self) -> Option<&str> {
+        self.template.as_deref()
+    }
+
+    /// The message as a structured value, if there is one.
+    #[cfg(feature = "kv")]
+    pub fn message_value(&self) -> Option<kv::Value<'_>> {
+        self.message_value.as_ref().map(OwnedValue::by_ref)
+    }
+
+    /// The time the record was created, if it's known.
+    #[cfg(feature = "timestamp")]
+    pub fn timestamp(&self) -> Option<Timestamp> {
+        self.timestamp
+    }
+
+    /// The sequence number of the record, if it has one.
+    #[cfg(feature = "seq")]
+    pub fn seq(&self) -> Option<u64> {
+        self.seq
+    }
+
+    /// The id of the thread that logged the record.
+    #[cfg(feature = "thread")]
+    pub fn thread_id(&self) -> Option<std::thread::ThreadId> {
+        self.thread_id
+    }
+
+    /// The name of the thread that logged the record, if it has one.
+    #[cfg(feature = "thread")]
+    pub fn thread_name(&self) -> Option<&str> {
+        self.thread_name.as_deref()
+    }
+
+    /// The error attached to the record, if there is one.
+    ///
+    /// This is the original error's chain of messages, rather than the original error.
+    pub fn error(&self) -> Option<&(dyn Error + 'static)> {
+        self.error
+            .as_ref()
+            .map(|error| error as &(dyn Error + 'static))
+    }
+
+    /// Borrow this owned record as a [`Record`].
+    ///
+    /// The record is only available within the closure, because its arguments borrow
+    /// from the rendered message.
+    pub fn with_record<R>(&self, f: impl FnOnce(&Record) -> R) -> R {
+        // The arguments can't outlive the expression they're created in
+        self.with_args(format_args!("{}", self.message), f)
+    }
+
+    fn with_args<R>(&self, args: fmt::Arguments, f: impl FnOnce(&Record) -> R) -> R {
+        let mut builder = Record::builder();
+
+        builder.metadata(self.metadata()).args(args).line(self.line);
+
+        #[cfg(feature = "timestamp")]
+        builder.timestamp(self.timestamp);
+
+        #[cfg(feature = "seq")]
+        builder.seq(self.seq);
+
+        #[cfg(feature = "thread")]
+        builder
+            .thread_id(self.thread_id)
+            .thread_name(self.thread_name.as_deref());
+
+        match &self.module_path {
+            Some(Cow::Borrowed(module_path)) => builder.module_path_static(Some(module_path)),
+            module_path => builder.module_path(module_path.as_deref()),
+        };
+        match &self.file {
+            Some(Cow::Borrowed(file)) => builder.file_static(Some(file)),
+            file => builder.file(file.as_deref()),
+        };
+
+        if let Some(error) = &self.error {
+            builder.error(error);
+        }
+
+        #[cfg(feature = "kv")]
+        let static_key_values = self
+            .static_key_values
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect::<Vec<_>>();
+        #[cfg(feature = "kv")]
+        let message_value = self.message_value();
+        #[cfg(feature = "kv")]
+        builder
+            .static_key_values(&static_key_values)
+            .key_values(&self.key_values)
+            .template(self.template.as_deref())
+            .message_value(message_value.as_ref());
+
+        f(&builder.build())
+    }
+}
+
+impl fmt::Display for OwnedRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// An error rendered into its chain of messages.
+#[derive(Clone, Debug)]
+struct OwnedError {
+    message: String,
+    source: Option<Box<OwnedError>>,
+}
+
+impl OwnedError {
+    fn new(error: &dyn Error) -> Self {
+        OwnedError {
+            message: error.to_string(),
+            source: error
+                .source()
+                .map(|source| Box::new(OwnedError::new(source))),
+        }
+    }
+}
+
+impl fmt::Display for OwnedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for OwnedError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source
+            .as_deref()
+            .map(|source| source as &(dyn Error + 'static))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_send<T: Send + 'static>(_: &T) {}
+
+    #[test]
+    fn to_owned() {
+        let file = String::from("main.rs");
+
+        let owned = {
+            let message = String::from("message");
+
+            Record::builder()
+                .level(Level::Warn)
+                .target("app")
+                .args(format_args!("a {}", message))
+                .module_path_static(Some("app::main"))
+                .file(Some(&file))
+                .line(Some(42))
+                .build()
+                .to_owned()
+        };
+        assert_send(&owned);
+
+        assert_eq!(Level::Warn, owned.level());
+        assert_eq!("app", owned.target());
+        assert_eq!("a message", owned.message());
+        assert_eq!(Some("app::main"), owned.module_path());
+        assert_eq!(Some("main.rs"), owned.file());
+        assert_eq!(Some(42), owned.line());
+        assert_eq!(Some(std::process::id()), owned.metadata().pid());
+
+        owned.with_record(|record| {
+            assert_eq!(Level::Warn, record.level());
+            assert_eq!("a message", record.args().to_string());
+            assert_eq!(Some("app::main"), record.module_path_static());
+            assert_eq!(Some("main.rs"), record.file());
+            assert_eq!(None, record.file_static());
+            assert_eq!(Some(42), record.line());
+        });
+    }
+
+    #[test]
+    #[cfg(feature = "kv")]
+    fn to_owned_kv() {
+        use crate::kv::Key;
+
+        let owned = {
+            let value = String::from("value");
+            let kvs = [("a", value.as_str())];
+
+            Record::builder()
+                .args(format_args!("a {}", "message"))
+                .static_key_values(&[("component", "db")])
+                .key_values(&kvs)
+                .template(Some("a {a}"))
+                .build()
+                .to_owned()
+        };
+
+        assert_eq!(
+            &[("component".to_owned(), "db".to_owned())],
+            owned.static_key_values()
+        );
+        assert_eq!(
+            "value",
+            owned
+                .key_values()
+                .get(Key::from_str("a"))
+                .unwrap()
+                .to_string()
+        );
+        assert_eq!(Some("a {a}"), owned.template());
+
+        owned.with_record(|record| {
+            assert_eq!(
+                &[("component", "db")],
+                record.metadata().static_key_values()
+            );
+            assert_eq!(1, record.key_values().count());
+            assert_eq!(Some("a {a}"), record.template());
+        });
+    }
+
+    #[test]
+    fn to_owned_error() {
+        #[derive(Debug)]
+        struct ConnectError(std::io::Error);
+
+        impl fmt::Display for ConnectError {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("failed to connect")
+            }
+        }
+
+        impl Error for ConnectError {
+            fn source(&self) -> Option<&(dyn Error + 'static)> {
+                Some(&self.0)
+            }
+        }
+
+        let owned = {
+            let error = ConnectError(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                "connection refused",
+            ));
+
+            Record::builder()
+                .args(format_args!("a message"))
+                .error(&error)
+                .build()
+                .to_owned()
+        };
+
+        let error = owned.error().unwrap();
+        assert_eq!("failed to connect", error.to_string());
+        assert_eq!(
+            Some("connection refused".to_owned()),
+            error.source().map(|source| source.to_string())
+        );
+
+        owned.with_record(|record| {
+            let error = record.error().unwrap();
+            assert_eq!("failed to connect", error.to_string());
+            assert!(error.source().unwrap().source().is_none());
+        });
+
+        assert!(Record::builder().build().to_owned().error().is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "kv")]
+    fn to_owned_message_value() {
+        let owned = {
+            let value = kv::Value::from("payload");
+
+            Record::builder()
+                .args(format_args!("{}", value))
+                .message_value(Some(&value))
+                .build()
+                .to_owned()
+        };
+
+        assert_eq!(
+            Some("payload".to_owned()),
+            owned.message_value().map(|value| value.to_string())
+        );
+
+        owned.with_record(|record| {
+            assert_eq!(
+                Some("payload".to_owned()),
+                record.message_value().map(|value| value.to_string())
+            );
+        });
+    }
+}
@@ -0,0 +1,288 @@
+//! Overriding the logger for the current thread or future.
+
+use std::cell::Cell;
+use std::mem;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[cfg(feature = "task_local")]
+use std::fmt;
+#[cfg(feature = "task_local")]
+use std::future::Future;
+#[cfg(feature = "task_local")]
+use std::pin::Pin;
+#[cfg(feature = "task_local")]
+use std::task::{Context, Poll};
+
+use crate::Log;
+
+thread_local! {
+    static CURRENT: Cell<Option<&'static dyn Log>> = const { Cell::new(None) };
+}
+
+// The number of scoped loggers on any thread, so callsites know not to trust the
+// interest they've cached from the global logger
+static ACTIVE: AtomicUsize = AtomicUsize::new(0);
+
+/// Send records logged on the current thread to `logger` while `f` runs.
+///
+/// Records logged on other threads still go to the global logger, as do records logged
+/// on this thread once `f` returns or panics. Scoped loggers can be nested, in which
+/// case the innermost one is used. The global logger doesn't need to be set for a scoped
+/// logger to receive records, but the global [`max_level`](crate::max_level) still
+/// applies to the log macros.
+///
+/// The [`logger`](crate::logger) function always returns the global logger.
+///
+/// # Examples
+///
+/// ```
+/// use log::{info, LevelFilter, Log, Metadata, Record};
+/// use std::sync::Mutex;
+///
+/// struct Capture(Mutex<Vec<String>>);
+///
+/// impl Log for Capture {
+///     fn enabled(&self, _: &Metadata) -> bool {
+///         true
+///     }
+///
+///     fn log(&self, record: &Record) {
+///         self.0.lock().unwrap().push(record.args().to_string());
+///     }
+///
+///     fn flush(&self) {}
+/// }
+///
+/// log::set_max_level(LevelFilter::Info);
+///
+/// let capture = Capture(Mutex::new(Vec::new()));
+/// log::with_scoped_logger(&capture, || {
+///     info!("captured");
+/// });
+///
+/// assert_eq!(vec!["captured".to_owned()], *capture.0.lock().unwrap());
+/// ```
+pub fn with_scoped_logger<R>(logger: &dyn Log, f: impl FnOnce() -> R) -> R {
+    // Restores the previous logger even if `f` panics
+    struct Guard(Option<&'static dyn Log>);
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            let _ = CURRENT.try_with(|current| current.set(self.0));
+            ACTIVE.fetch_sub(1, Ordering::Release);
+        }
+    }
+
+    // SAFETY: The logger is only reachable through `CURRENT` until the guard
+    // is dropped, which happens before this function returns
+    let logger = unsafe { mem::transmute::<&dyn Log, &'static dyn Log>(logger) };
+
+    ACTIVE.fetch_add(1, Ordering::Acquire);
+    let _guard = Guard(
+        CURRENT
+            .try_with(|current| current.replace(Some(logger)))
+            .ok()
+            .flatten(),
+    );
+
+    f()
+}
+
+/// Send records logged by `future` to `logger`.
+///
+/// The logger is scoped to the thread with [`with_scoped_logger`] each time the future is
+/// polled, so it follows the future across `.await` points and between threads on a
+/// multi-threaded executor. Unlike a thread-local override, it doesn't leak into other
+/// tasks that run on the same thread while the future is waiting.
+///
+/// # Examples
+///
+/// ```
+/// use log::{info, Log, Metadata, Record};
+///
+/// struct RequestLogger {
+///     request_id: u64,
+/// }
+///
+/// impl Log for RequestLogger {
+///     fn enabled(&self, _: &Metadata) -> bool {
+///         true
+///     }
+///
+///     fn log(&self, record: &Record) {
+///         println!("[{}] {}", self.request_id, record.args());
+///     }
+///
+///     fn flush(&self) {}
+/// }
+///
+/// async fn handle_request() {
+///     info!("handling a request");
+/// }
+///
+/// let future = log::scope_async(RequestLogger { request_id: 42 }, handle_request());
+///
+/// // The future can be spawned on any executor
+/// # drop(future);
+/// ```
+#[cfg(feature = "task_local")]
+pub fn scope_async<L: Log, F: Future>(logger: L, future: F) -> ScopedFuture<L, F> {
+    ScopedFuture { logger, future }
+}
+
+/// A future returned by [`scope_async`].
+#[cfg(feature = "task_local")]
+#[must_use = "futures do nothing unless polled"]
+pub struct ScopedFuture<L, F> {
+    logger: L,
+    future: F,
+}
+
+#[cfg(feature = "task_local")]
+impl<L: Log, F: Future> Future for ScopedFuture<L, F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        // SAFETY: The future is never moved out of `self`, and the logger isn't pinned
+        let this = unsafe { self.get_unchecked_mut() };
+        let future = unsafe { Pin::new_unchecked(&mut this.future) };
+
+        with_scoped_logger(&this.logger, || future.poll(cx))
+    }
+}
+
+#[cfg(feature = "task_local")]
+impl<L, F> fmt::Debug for ScopedFuture<L, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ScopedFuture").finish_non_exhaustive()
+    }
+}
+
+/// Whether there are scoped loggers on any thread.
+#[cfg(feature = "callsite_cache")]
+pub(crate) fn any_active() -> bool {
+    ACTIVE.load(Ordering::Acquire) != 0
+}
+
+/// Call `f` with the scoped logger for the current thread, or the global logger if
+/// there isn't one.
+pub(crate) fn with_current<R>(f: impl FnOnce(&dyn Log) -> R) -> R {
+    let current = CURRENT.try_with(Cell::get).ok().flatten();
+
+    f(current.unwrap_or_else(crate::logger))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Level, Metadata, Record};
+    use std::sync::Mutex;
+
+    struct Capture(Mutex<Vec<Level>>);
+
+    impl Log for Capture {
+        fn enabled(&self, _: &Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &Record) {
+            self.0.lock().unwrap().push(record.level());
+        }
+
+        fn flush(&self) {}
+    }
+
+    fn log(level: Level) {
+        with_current(|logger| logger.log(&Record::builder().level(level).build()));
+    }
+
+    #[test]
+    fn scoped_logger() {
+        let (outer, inner) = (
+            Capture(Mutex::new(Vec::new())),
+            Capture(Mutex::new(Vec::new())),
+        );
+
+        with_scoped_logger(&outer, || {
+            log(Level::Error);
+
+            with_scoped_logger(&inner, || log(Level::Warn));
+
+            log(Level::Info);
+
+            std::thread::spawn(|| log(Level::Debug)).join().unwrap();
+        });
+
+        // The global logger isn't set, so this is dropped
+        log(Level::Trace);
+
+        assert_eq!(vec![Level::Error, Level::Info], *outer.0.lock().unwrap());
+        assert_eq!(vec![Level::Warn], *inner.0.lock().unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "task_local")]
+    fn scope_async() {
+        use std::sync::Arc;
+        use std::task::{RawWaker, RawWakerVTable, Waker};
+
+        // Returns `Pending` the first time it's polled
+        struct YieldOnce(bool);
+
+        impl Future for YieldOnce {
+            type Output = ();
+
+            fn poll(mut self: Pin<&mut Self>, _: &mut Context) -> Poll<()> {
+                if self.0 {
+                    Poll::Ready(())
+                } else {
+                    self.0 = true;
+                    Poll::Pending
+                }
+            }
+        }
+
+        fn noop_waker() -> Waker {
+            fn clone(_: *const ()) -> RawWaker {
+                RawWaker::new(std::ptr::null(), &VTABLE)
+            }
+            fn noop(_: *const ()) {}
+
+            static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+
+            unsafe { Waker::from_raw(clone(std::ptr::null())) }
+        }
+
+        let capture = Arc::new(Capture(Mutex::new(Vec::new())));
+
+        let mut future = Box::pin(super::scope_async(capture.clone(), async {
+            log(Level::Error);
+            YieldOnce(false).await;
+            log(Level::Warn);
+        }));
+
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        assert!(future.as_mut().poll(&mut cx).is_pending());
+
+        // Records logged while the future is waiting aren't captured
+        log(Level::Info);
+
+        assert!(future.as_mut().poll(&mut cx).is_ready());
+
+        assert_eq!(vec![Level::Error, Level::Warn], *capture.0.lock().unwrap());
+    }
+
+    #[test]
+    fn scoped_logger_panic() {
+        let capture = Capture(Mutex::new(Vec::new()));
+
+        let _ = std::panic::catch_unwind(|| {
+            with_scoped_logger(&capture, || panic!("scoped"));
+        });
+
+        log(Level::Error);
+        assert!(capture.0.lock().unwrap().is_empty());
+    }
+}
@@ -4,9 +4,9 @@ use serde::de::{
     Deserialize, DeserializeSeed, Deserializer, EnumAccess, Error, Unexpected, VariantAccess,
     Visitor,
 };
-use serde::ser::{Serialize, Serializer};
+use serde::ser::{Serialize, SerializeStruct, Serializer};
 
-use crate::{Level, LevelFilter, LOG_LEVEL_NAMES};
+use crate::{Level, LevelFilter, Metadata, Record, LOG_LEVEL_NAMES};
 
 use std::fmt;
 use std::str::{self, FromStr};
@@ -202,10 +202,61 @@ impl<'de> Deserialize<'de> for LevelFilter {
     }
 }
 
+impl<'a> Serialize for Metadata<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut serializer = serializer.serialize_struct("Metadata", 2)?;
+
+        serializer.serialize_field("level", &self.level())?;
+        serializer.serialize_field("target", self.target())?;
+
+        serializer.end()
+    }
+}
+
+// Records are serialized with their message rendered. Key-values are only serialized
+// with the `kv_serde` feature, since that's what lets values be serialized.
+impl<'a> Serialize for Record<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        #[cfg(feature = "kv_serde")]
+        let len = 8;
+        #[cfg(not(feature = "kv_serde"))]
+        let len = 6;
+
+        let mut serializer = serializer.serialize_struct("Record", len)?;
+
+        serializer.serialize_field("level", &self.level())?;
+        serializer.serialize_field("target", self.target())?;
+        serializer.serialize_field("message", self.args())?;
+        serializer.serialize_field("module_path", &self.module_path())?;
+        serializer.serialize_field("file", &self.file())?;
+        serializer.serialize_field("line", &self.line())?;
+
+        #[cfg(feature = "kv_serde")]
+        {
+            serializer.serialize_field("key_values", self.key_values())?;
+
+            match self.template() {
+                Some(template) => serializer.serialize_field("template", template)?,
+                None => serializer.skip_field("template")?,
+            }
+        }
+
+        serializer.end()
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{Level, LevelFilter};
-    use serde_test::{assert_de_tokens, assert_de_tokens_error, assert_tokens, Token};
+    use crate::{Level, LevelFilter, Metadata, Record};
+    use serde_test::{
+        assert_de_tokens, assert_de_tokens_error, assert_ser_tokens, assert_tokens, Token,
+    };
 
     fn level_token(variant: &'static str) -> Token {
         Token::UnitVariant {
@@ -388,6 +439,86 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_metadata_ser() {
+        let metadata = Metadata::builder().level(Level::Warn).target("app").build();
+
+        assert_ser_tokens(
+            &metadata,
+            &[
+                Token::Struct {
+                    name: "Metadata",
+                    len: 2,
+                },
+                Token::Str("level"),
+                level_token("WARN"),
+                Token::Str("target"),
+                Token::Str("app"),
+                Token::StructEnd,
+            ],
+        );
+    }
+
+    #[test]
+    #[cfg(not(feature = "kv_serde"))]
+    fn test_record_ser() {
+        let record = Record::builder()
+            .level(Level::Info)
+            .target("app")
+            .args(format_args!("hello {}", "world"))
+            .module_path_static(Some("app::main"))
+            .line(Some(42))
+            .build();
+
+        assert_ser_tokens(
+            &record,
+            &[
+                Token::Struct {
+                    name: "Record",
+                    len: 6,
+                },
+                Token::Str("level"),
+                level_token("INFO"),
+                Token::Str("target"),
+                Token::Str("app"),
+                Token::Str("message"),
+                Token::Str("hello world"),
+                Token::Str("module_path"),
+                Token::Some,
+                Token::Str("app::main"),
+                Token::Str("file"),
+                Token::None,
+                Token::Str("line"),
+                Token::Some,
+                Token::U32(42),
+                Token::StructEnd,
+            ],
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "kv_serde")]
+    fn test_record_ser_json() {
+        let record = Record::builder()
+            .level(Level::Info)
+            .target("app")
+            .args(format_args!("hello {}", "world"))
+            .line(Some(42))
+            .key_values(&[("user", "Nori")])
+            .build();
+
+        assert_eq!(
+            r#"{"level":"INFO","target":"app","message":"hello world","module_path":null,"file":null,"line":42,"key_values":{"user":"Nori"}}"#,
+            serde_json::to_string(&record).unwrap()
+        );
+
+        let record = record.to_builder().template(Some("hello {user}")).build();
+
+        assert!(serde_json::to_string(&record)
+            .unwrap()
+            .ends_with(r#","template":"hello {user}"}"#));
+    }
+
     #[test]
     fn test_level_filter_de_error() {
         let msg = "unknown variant `errorx`, expected one of \
@@ -0,0 +1,159 @@
+//! Maximum levels for individual targets.
+
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+use crate::{Level, LevelFilter};
+
+// Entries are sorted with the longest targets first, so the most specific one matches
+type Table = Vec<(Box<str>, LevelFilter)>;
+
+static TABLE: AtomicPtr<Table> = AtomicPtr::new(ptr::null_mut());
+
+/// Set the maximum log level for a target, and any targets nested under it.
+///
+/// A target matches itself and any targets nested under it, so `hyper` matches `hyper` and
+/// `hyper::proto`, but not `hyperlocal`. When multiple targets match, the most specific one
+/// is used. This lets noisy dependencies be silenced regardless of which logger is installed.
+///
+/// Target levels are checked by the log macros after the global [`max_level`](crate::max_level),
+/// so they can only lower the level for a target, not raise it above the global one.
+///
+/// Each call replaces the table of target levels. The previous table is leaked, because records
+/// may still be checking against it, so target levels are meant to be set a handful of times,
+/// while the application starts up or its configuration is reloaded.
+///
+/// Requires the `std` feature.
+///
+/// # Examples
+///
+/// ```
+/// use log::{debug, warn, LevelFilter};
+///
+/// log::set_max_level(LevelFilter::Debug);
+/// log::set_target_max_level("hyper", LevelFilter::Warn);
+///
+/// // This record is ignored
+/// debug!(target: "hyper::proto", "parsed a header");
+///
+/// // This record is logged
+/// warn!(target: "hyper::proto", "connection reset");
+/// ```
+pub fn set_target_max_level(target: &str, level: LevelFilter) {
+    update(
+        |table| match table.iter_mut().find(|(t, _)| **t == *target) {
+            Some((_, existing)) => *existing = level,
+            None => {
+                let index = table.partition_point(|(t, _)| t.len() >= target.len());
+                table.insert(index, (target.into(), level));
+            }
+        },
+    );
+}
+
+/// Remove the maximum log level set for a target using [`set_target_max_level`].
+///
+/// Levels set for targets it's nested under, or targets nested under it, aren't removed.
+pub fn clear_target_max_level(target: &str) {
+    update(|table| table.retain(|(t, _)| **t != *target));
+}
+
+/// The maximum log level for a target.
+///
+/// This is the lower of the global [`max_level`](crate::max_level) and the level set for
+/// the most specific matching target using [`set_target_max_level`], if there is one.
+pub fn target_max_level(target: &str) -> LevelFilter {
+    let max_level = crate::max_level();
+
+    match level_for(target) {
+        Some(level) => max_level.min(level),
+        None => max_level,
+    }
+}
+
+/// Whether records at a level are filtered out by the level set for their target.
+pub(crate) fn is_target_disabled(level: Level, target: &str) -> bool {
+    level_for(target).map_or(false, |max_level| level > max_level)
+}
+
+fn level_for(target: &str) -> Option<LevelFilter> {
+    // SAFETY: Tables stored in `TABLE` are never freed
+    let table = unsafe { TABLE.load(Ordering::Acquire).as_ref() }?;
+
+    table
+        .iter()
+        .find(|(prefix, _)| matches(prefix, target))
+        .map(|(_, level)| *level)
+}
+
+/// Whether a target is the same as a prefix, or nested under it.
+pub(crate) fn matches(prefix: &str, target: &str) -> bool {
+    match target.strip_prefix(prefix) {
+        Some(rest) => rest.is_empty() || rest.starts_with("::"),
+        None => false,
+    }
+}
+
+fn update(f: impl Fn(&mut Table)) {
+    let mut current = TABLE.load(Ordering::Acquire);
+    loop {
+        // SAFETY: Tables stored in `TABLE` are never freed
+        let mut table = unsafe { current.as_ref() }.cloned().unwrap_or_default();
+        f(&mut table);
+
+        let table = Box::into_raw(Box::new(table));
+        match TABLE.compare_exchange(current, table, Ordering::AcqRel, Ordering::Acquire) {
+            Ok(_) => return,
+            Err(actual) => {
+                // SAFETY: The table was never shared
+                drop(unsafe { Box::from_raw(table) });
+                current = actual;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matching() {
+        assert!(matches("a", "a"));
+        assert!(matches("a", "a::b"));
+        assert!(matches("a::b", "a::b::c"));
+        assert!(!matches("a", "ab"));
+        assert!(!matches("a::b", "a"));
+    }
+
+    #[test]
+    fn target_levels() {
+        set_target_max_level("targets_test", LevelFilter::Warn);
+        set_target_max_level("targets_test::inner", LevelFilter::Debug);
+        set_target_max_level("targets_test::inner::quiet", LevelFilter::Off);
+
+        assert!(is_target_disabled(Level::Info, "targets_test"));
+        assert!(!is_target_disabled(Level::Warn, "targets_test::other"));
+        assert!(!is_target_disabled(Level::Debug, "targets_test::inner"));
+        assert!(is_target_disabled(Level::Trace, "targets_test::inner::a"));
+        assert!(is_target_disabled(
+            Level::Error,
+            "targets_test::inner::quiet"
+        ));
+        assert!(!is_target_disabled(Level::Trace, "targets_tests"));
+
+        // Setting a target again replaces its level
+        set_target_max_level("targets_test", LevelFilter::Info);
+        assert!(!is_target_disabled(Level::Info, "targets_test"));
+
+        clear_target_max_level("targets_test::inner");
+        assert!(is_target_disabled(Level::Debug, "targets_test::inner"));
+
+        clear_target_max_level("targets_test");
+        clear_target_max_level("targets_test::inner::quiet");
+        assert!(!is_target_disabled(
+            Level::Error,
+            "targets_test::inner::quiet"
+        ));
+    }
+}
@@ -0,0 +1,181 @@
+//! Building a record's message across multiple statements.
+
+use std::fmt;
+use std::panic::Location;
+
+use crate::__private_api::CrateTarget;
+use crate::Level;
+
+#[cfg(feature = "kv")]
+use crate::kv::{OwnedValue, ToValue};
+
+/// A handle that accumulates a message, and emits it as a single record when it's dropped.
+///
+/// Log writers are created using the [`log_writer!`](macro.log_writer.html) macro. They
+/// implement [`fmt::Write`], so a message can be built up using `write!` across multiple
+/// statements, instead of concatenating strings before logging them. With the `kv` feature,
+/// key-values can also be added to the record using [`LogWriter::key_value`].
+///
+/// Whether the record's level is enabled is checked when the writer is created. If it's
+/// disabled then writes are ignored and nothing is emitted.
+///
+/// # Examples
+///
+/// ```
+/// use log::{log_writer, Level};
+/// use std::fmt::Write;
+///
+/// # fn main() -> std::fmt::Result {
+/// let items = ["a", "b", "c"];
+///
+/// let mut writer = log_writer!(Level::Info);
+/// write!(writer, "processed")?;
+/// for item in &items {
+///     write!(writer, " {}", item)?;
+/// }
+///
+/// // The record `processed a b c` is emitted here
+/// drop(writer);
+/// # Ok(())
+/// # }
+/// ```
+#[must_use = "the record is emitted when the writer is dropped"]
+pub struct LogWriter<'a> {
+    enabled: bool,
+    level: Level,
+    target_module_path_and_loc: (
+        CrateTarget<'a>,
+        &'static str,
+        Option<&'static Location<'static>>,
+    ),
+    message: String,
+    #[cfg(feature = "kv")]
+    key_values: Vec<(String, OwnedValue)>,
+}
+
+impl<'a> LogWriter<'a> {
+    pub(crate) fn new(
+        enabled: bool,
+        level: Level,
+        target_module_path_and_loc: (
+            CrateTarget<'a>,
+            &'static str,
+            Option<&'static Location<'static>>,
+        ),
+    ) -> Self {
+        LogWriter {
+            enabled,
+            level,
+            target_module_path_and_loc,
+            message: String::new(),
+            #[cfg(feature = "kv")]
+            key_values: Vec::new(),
+        }
+    }
+
+    /// Whether the record will be emitted.
+    ///
+    /// This can be used to avoid building an expensive message that would be ignored anyway.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// The message written so far.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Add a key-value to the record.
+    ///
+    /// The value is buffered, so it doesn't need to outlive the writer.
+    #[cfg(feature = "kv")]
+    pub fn key_value(&mut self, key: &str, value: impl ToValue) -> &mut Self {
+        if self.enabled {
+            self.key_values
+                .push((key.to_owned(), value.to_value().to_owned()));
+        }
+
+        self
+    }
+}
+
+impl<'a> fmt::Write for LogWriter<'a> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.enabled {
+            self.message.push_str(s);
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Drop for LogWriter<'a> {
+    fn drop(&mut self) {
+        if !self.enabled {
+            return;
+        }
+
+        let (target, module_path, loc) = self.target_module_path_and_loc;
+
+        #[cfg(feature = "kv")]
+        let key_values = self
+            .key_values
+            .iter()
+            .map(|(key, value)| (&**key, value.by_ref()))
+            .collect::<Vec<_>>();
+        #[cfg(feature = "kv")]
+        let key_values = Some(&*key_values).filter(|key_values| !key_values.is_empty());
+        #[cfg(not(feature = "kv"))]
+        let key_values = None;
+
+        crate::__private_api::log_impl(
+            format_args!("{}", self.message),
+            self.level,
+            (&target, module_path, loc),
+            key_values,
+            None,
+        );
+    }
+}
+
+impl<'a> fmt::Debug for LogWriter<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("LogWriter")
+            .field("level", &self.level)
+            .field("target", &self.target_module_path_and_loc.0)
+            .field("message", &self.message)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fmt::Write;
+
+    #[test]
+    fn disabled_writes_are_ignored() {
+        let mut writer = LogWriter::new(
+            false,
+            Level::Info,
+            (crate::__log_crate!("test"), "test", None),
+        );
+
+        write!(writer, "a {}", 1).unwrap();
+        assert!(!writer.is_enabled());
+        assert_eq!("", writer.message());
+    }
+
+    #[test]
+    fn enabled_writes_are_buffered() {
+        let mut writer = LogWriter::new(
+            true,
+            Level::Info,
+            (crate::__log_crate!("test"), "test", None),
+        );
+
+        write!(writer, "a {}", 1).unwrap();
+        writer.write_char('!').unwrap();
+        assert_eq!("a 1!", writer.message());
+    }
+}
@@ -0,0 +1,74 @@
+#![cfg(feature = "callsite_cache")]
+
+use log::{debug, Interest, LevelFilter, Log, Metadata, Record};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+struct Logger {
+    debug: AtomicBool,
+    interest_calls: AtomicUsize,
+    logged: AtomicUsize,
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        let filter = if self.debug.load(Ordering::Relaxed) {
+            LevelFilter::Debug
+        } else {
+            LevelFilter::Info
+        };
+
+        metadata.level() <= filter
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            self.logged.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn flush(&self) {}
+
+    fn interest(&self, metadata: &Metadata) -> Interest {
+        self.interest_calls.fetch_add(1, Ordering::Relaxed);
+
+        Interest::from(self.enabled(metadata))
+    }
+}
+
+static LOGGER: Logger = Logger {
+    debug: AtomicBool::new(false),
+    interest_calls: AtomicUsize::new(0),
+    logged: AtomicUsize::new(0),
+};
+
+fn log_debug() {
+    for _ in 0..3 {
+        debug!("a debug record");
+    }
+}
+
+#[test]
+fn callsite_cache() {
+    log::set_logger(&LOGGER).unwrap();
+    log::set_max_level(LevelFilter::Trace);
+
+    log_debug();
+    assert_eq!(1, LOGGER.interest_calls.load(Ordering::Relaxed));
+    assert_eq!(0, LOGGER.logged.load(Ordering::Relaxed));
+
+    // The callsite keeps its cached interest until the cache is rebuilt
+    LOGGER.debug.store(true, Ordering::Relaxed);
+    log_debug();
+    assert_eq!(1, LOGGER.interest_calls.load(Ordering::Relaxed));
+    assert_eq!(0, LOGGER.logged.load(Ordering::Relaxed));
+
+    log::rebuild_interest_cache();
+    log_debug();
+    assert_eq!(2, LOGGER.interest_calls.load(Ordering::Relaxed));
+    assert_eq!(3, LOGGER.logged.load(Ordering::Relaxed));
+
+    // Invocations with a target aren't cached
+    debug!(target: "app", "a debug record");
+    assert_eq!(2, LOGGER.interest_calls.load(Ordering::Relaxed));
+    assert_eq!(4, LOGGER.logged.load(Ordering::Relaxed));
+}
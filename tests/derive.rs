@@ -0,0 +1,199 @@
+#![cfg(feature = "kv_derive")]
+
+use log::kv::{Key, Source, ToValue};
+
+#[derive(ToValue)]
+struct User {
+    id: u32,
+    name: &'static str,
+}
+
+#[derive(ToValue)]
+#[kv(rename = "Request", rename_all = "camelCase")]
+struct RequestContext {
+    request_id: u64,
+    #[kv(rename = "path")]
+    route: &'static str,
+    #[kv(skip)]
+    #[allow(dead_code)]
+    secret: &'static str,
+    #[kv(debug)]
+    tags: Vec<&'static str>,
+    user: User,
+}
+
+#[derive(ToValue)]
+struct Id(u64);
+
+#[derive(ToValue)]
+struct Unit;
+
+#[derive(ToValue)]
+#[kv(rename_all = "snake_case")]
+enum Method {
+    Get,
+    #[kv(rename = "POST")]
+    Post,
+    DeleteAll,
+}
+
+#[test]
+#[cfg(not(any(feature = "kv_sval", feature = "kv_serde")))]
+fn derive_struct() {
+    let user = User {
+        id: 1,
+        name: "Nori",
+    };
+
+    assert_eq!(
+        r#"User { id: 1, name: "Nori" }"#,
+        format!("{:?}", user.to_value())
+    );
+}
+
+#[test]
+#[cfg(not(any(feature = "kv_sval", feature = "kv_serde")))]
+fn derive_struct_attributes() {
+    let ctx = RequestContext {
+        request_id: 42,
+        route: "/",
+        secret: "hunter2",
+        tags: vec!["a", "b"],
+        user: User {
+            id: 1,
+            name: "Nori",
+        },
+    };
+
+    assert_eq!(
+        r#"Request { requestId: 42, path: "/", tags: ["a", "b"], user: User { id: 1, name: "Nori" } }"#,
+        format!("{:?}", ctx.to_value())
+    );
+}
+
+#[test]
+fn derive_struct_is_structured() {
+    let ctx = RequestContext {
+        request_id: 42,
+        route: "/",
+        secret: "hunter2",
+        tags: Vec::new(),
+        user: User {
+            id: 1,
+            name: "Nori",
+        },
+    };
+
+    assert!(ctx.to_value().to_u64().is_none());
+    assert!(ctx.to_value().to_borrowed_str().is_none());
+}
+
+#[test]
+fn derive_newtype() {
+    assert_eq!(Some(42), Id(42).to_value().to_u64());
+}
+
+#[test]
+fn derive_unit() {
+    assert_eq!(Some("Unit"), Unit.to_value().to_borrowed_str());
+}
+
+#[test]
+fn derive_enum() {
+    assert_eq!(Some("get"), Method::Get.to_value().to_borrowed_str());
+    assert_eq!(Some("POST"), Method::Post.to_value().to_borrowed_str());
+    assert_eq!(
+        Some("delete_all"),
+        Method::DeleteAll.to_value().to_borrowed_str()
+    );
+}
+
+#[test]
+#[cfg(feature = "kv_serde")]
+fn derive_serde() {
+    let ctx = RequestContext {
+        request_id: 42,
+        route: "/",
+        secret: "hunter2",
+        tags: vec!["a", "b"],
+        user: User {
+            id: 1,
+            name: "Nori",
+        },
+    };
+
+    assert_eq!(
+        r#"{"requestId":42,"path":"/","tags":"[\"a\", \"b\"]","user":{"id":1,"name":"Nori"}}"#,
+        serde_json::to_string(&ctx.to_value()).unwrap()
+    );
+}
+
+#[derive(Source)]
+#[kv(rename_all = "kebab-case")]
+struct Context {
+    request_id: u64,
+    #[kv(rename = "user")]
+    user_name: &'static str,
+    #[kv(skip)]
+    #[allow(dead_code)]
+    secret: &'static str,
+    #[kv(display)]
+    addr: std::net::Ipv4Addr,
+}
+
+#[derive(Source)]
+struct Pair(u32, &'static str);
+
+#[test]
+fn derive_source() {
+    let ctx = Context {
+        request_id: 42,
+        user_name: "Nori",
+        secret: "hunter2",
+        addr: std::net::Ipv4Addr::LOCALHOST,
+    };
+
+    assert_eq!(3, ctx.count());
+    assert_eq!(Some(42), ctx.get_u64(Key::from_str("request-id")));
+    assert_eq!(Some("Nori"), ctx.get_str(Key::from_str("user")));
+    assert_eq!(
+        "127.0.0.1",
+        ctx.get(Key::from_str("addr")).unwrap().to_string()
+    );
+    assert!(ctx.get(Key::from_str("secret")).is_none());
+}
+
+#[test]
+fn derive_source_tuple() {
+    let pair = Pair(1, "a");
+
+    assert_eq!(2, pair.count());
+    assert_eq!(Some(1), pair.get_u64(Key::from_str("0")));
+    assert_eq!(Some("a"), pair.get_str(Key::from_str("1")));
+}
+
+mod reexport {
+    pub use log as my_log;
+}
+
+#[derive(ToValue)]
+#[kv(crate = "reexport::my_log")]
+struct Renamed {
+    id: u32,
+}
+
+#[derive(Source)]
+#[kv(crate = "reexport::my_log")]
+struct RenamedSource {
+    #[kv(display)]
+    id: u32,
+    count: u32,
+}
+
+#[test]
+fn derive_crate() {
+    assert!(Renamed { id: 1 }.to_value().to_u64().is_none());
+    let source = RenamedSource { id: 1, count: 2 };
+    assert_eq!("1", source.get(Key::from_str("id")).unwrap().to_string());
+    assert_eq!(Some(2), source.get_u64(Key::from_str("count")));
+}
@@ -0,0 +1,65 @@
+#![cfg(feature = "early_buffer")]
+
+use log::{debug, info, trace, warn, Level, LevelFilter, Log, Metadata, Record};
+use std::sync::{Arc, Mutex};
+
+struct Logger(Arc<Mutex<Vec<String>>>);
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= Level::Info
+    }
+
+    fn log(&self, record: &Record) {
+        self.0.lock().unwrap().push(record.args().to_string());
+    }
+
+    fn flush(&self) {}
+}
+
+#[test]
+fn early_buffer() {
+    let records = Arc::new(Mutex::new(Vec::new()));
+
+    // Nothing is buffered until the max level is raised
+    info!("dropped before the buffer level is set");
+
+    log::set_early_buffer_level(LevelFilter::Debug);
+    assert_eq!(LevelFilter::Debug, log::max_level());
+
+    // These records are logged before the logger is set
+    info!("starting {}", 1);
+    trace!("filtered by the buffer level");
+    debug!("filtered when replayed");
+    warn!("config missing");
+
+    // Records past the capacity of the buffer are dropped
+    for i in 0..1024 {
+        info!("filler {}", i);
+    }
+
+    log::set_boxed_logger(Box::new(Logger(records.clone()))).unwrap();
+
+    // The max level is put back once the buffer has been replayed
+    assert_eq!(LevelFilter::Off, log::max_level());
+
+    {
+        let records = records.lock().unwrap();
+
+        assert_eq!(["starting 1", "config missing", "filler 0"], records[..3]);
+        assert_eq!("filler 1020", records[records.len() - 1]);
+        assert_eq!(1023, records.len());
+    }
+
+    // Records logged once the buffer has been replayed go straight to the logger
+    log::set_max_level(LevelFilter::Info);
+    info!("running");
+
+    // Buffering can't be turned back on once the logger is set
+    log::set_early_buffer_level(LevelFilter::Trace);
+    assert_eq!(LevelFilter::Info, log::max_level());
+
+    let records = records.lock().unwrap();
+    assert_eq!(1024, records.len());
+    assert_eq!("running", records[records.len() - 1]);
+}
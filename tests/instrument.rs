@@ -0,0 +1,154 @@
+#![cfg(feature = "instrument")]
+
+use log::{kv, Level, LevelFilter, Log, Metadata, Record};
+use std::sync::Mutex;
+
+type Records = Vec<(Level, String, String, String)>;
+
+struct Logger(Mutex<Records>);
+
+impl Log for Logger {
+    fn enabled(&self, _: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        let mut kvs = String::new();
+        kv::to_logfmt(&mut kvs, record.key_values()).unwrap();
+
+        self.0.lock().unwrap().push((
+            record.level(),
+            record.target().to_owned(),
+            record.args().to_string(),
+            kvs,
+        ));
+    }
+
+    fn flush(&self) {}
+}
+
+#[derive(Debug)]
+struct Request {
+    id: u32,
+}
+
+struct Connection;
+
+#[log::instrument]
+fn handle(request: &Request, attempt: u32) -> u32 {
+    request.id + attempt
+}
+
+#[log::instrument(level = "debug", target = "db", name = "query", skip(conn))]
+fn run_query(conn: &Connection, sql: &str) -> Result<(), String> {
+    let _ = conn;
+
+    if sql.is_empty() {
+        return Err("empty query".into());
+    }
+
+    Ok(())
+}
+
+#[log::instrument(level = Level::Trace)]
+fn no_args() {}
+
+mod reexport {
+    pub use log as my_log;
+}
+
+#[log::instrument(level = "error", crate = "reexport::my_log")]
+fn renamed(n: u32) {}
+
+struct Service;
+
+impl Service {
+    #[log::instrument(level = "warn")]
+    fn call(&self, mut retries: u8) -> u8 {
+        retries += 1;
+        retries
+    }
+}
+
+fn take(logger: &Logger) -> Records {
+    std::mem::take(&mut *logger.0.lock().unwrap())
+}
+
+fn assert_exit(record: &(Level, String, String, String), level: Level, target: &str, msg: &str) {
+    assert_eq!(level, record.0);
+    assert_eq!(target, record.1);
+    assert_eq!(msg, record.2);
+    assert!(record.3.starts_with("duration_ms="), "{}", record.3);
+}
+
+#[test]
+fn instrument() {
+    let logger = Box::leak(Box::new(Logger(Mutex::new(Vec::new()))));
+    log::set_logger(logger).unwrap();
+    log::set_max_level(LevelFilter::Trace);
+
+    assert_eq!(43, handle(&Request { id: 42 }, 1));
+    let records = take(logger);
+    assert_eq!(2, records.len());
+    assert_eq!(
+        (
+            Level::Info,
+            "instrument".to_owned(),
+            "enter handle".to_owned(),
+            r#"request="Request { id: 42 }" attempt=1"#.to_owned()
+        ),
+        records[0]
+    );
+    assert_exit(&records[1], Level::Info, "instrument", "exit handle");
+
+    assert!(run_query(&Connection, "").is_err());
+    let records = take(logger);
+    assert_eq!(2, records.len());
+    assert_eq!(
+        (
+            Level::Debug,
+            "db".to_owned(),
+            "enter query".to_owned(),
+            r#"sql="\"\"""#.to_owned()
+        ),
+        records[0]
+    );
+    assert_exit(&records[1], Level::Debug, "db", "exit query");
+
+    no_args();
+    let records = take(logger);
+    assert_eq!(
+        (
+            Level::Trace,
+            "instrument".to_owned(),
+            "enter no_args".to_owned(),
+            String::new()
+        ),
+        records[0]
+    );
+    assert_exit(&records[1], Level::Trace, "instrument", "exit no_args");
+
+    renamed(1);
+    let records = take(logger);
+    assert_eq!(
+        (
+            Level::Error,
+            "instrument".to_owned(),
+            "enter renamed".to_owned(),
+            "n=1".to_owned()
+        ),
+        records[0]
+    );
+    assert_exit(&records[1], Level::Error, "instrument", "exit renamed");
+
+    assert_eq!(2, Service.call(1));
+    let records = take(logger);
+    assert_eq!("enter call", records[0].2);
+    assert_eq!("retries=1", records[0].3);
+    assert_exit(&records[1], Level::Warn, "instrument", "exit call");
+
+    log::set_max_level(LevelFilter::Info);
+
+    run_query(&Connection, "select 1").unwrap();
+    assert!(take(logger).is_empty());
+}
@@ -14,18 +14,93 @@ fn set_boxed_logger(logger: Box<dyn Log>) -> Result<(), log::SetLoggerError> {
 struct State {
     last_log_level: Mutex<Option<Level>>,
     last_log_location: Mutex<Option<u32>>,
+    last_log_target: Mutex<Option<String>>,
+    last_log_message: Mutex<Option<String>>,
+    last_log_verbosity: Mutex<Option<u32>>,
+    #[cfg(feature = "kv")]
+    last_log_kvs: Mutex<Option<String>>,
+    #[cfg(feature = "kv")]
+    last_log_template: Mutex<Option<String>>,
+    #[cfg(feature = "kv")]
+    last_enabled_keys: Mutex<Option<Vec<String>>>,
+    #[cfg(feature = "kv")]
+    last_static_kvs: Mutex<Option<Vec<(String, String)>>>,
+    #[cfg(feature = "timestamp")]
+    last_log_timestamp: Mutex<Option<log::Timestamp>>,
+    #[cfg(feature = "seq")]
+    last_log_seq: Mutex<Option<u64>>,
+    #[cfg(feature = "thread")]
+    last_log_thread: Mutex<Option<(std::thread::ThreadId, Option<String>)>>,
+    #[cfg(feature = "crate_info")]
+    last_log_crate: Mutex<Option<(Option<String>, Option<String>)>>,
 }
 
 struct Logger(Arc<State>);
 
+#[cfg(feature = "kv")]
+fn static_kvs(metadata: &Metadata) -> Vec<(String, String)> {
+    metadata
+        .static_key_values()
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
 impl Log for Logger {
-    fn enabled(&self, _: &Metadata) -> bool {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        #[cfg(feature = "kv")]
+        if metadata.target() == "enabled_keys" {
+            *self.0.last_enabled_keys.lock().unwrap() =
+                Some(metadata.keys().iter().map(|key| key.to_string()).collect());
+            *self.0.last_static_kvs.lock().unwrap() = Some(static_kvs(metadata));
+        }
+
+        let _ = metadata;
         true
     }
 
     fn log(&self, record: &Record) {
+        // Ignore records from other tests that may be running alongside these ones
+        // Module paths may have been stripped with the `release_strip_location` feature
+        if record.module_path().map_or(false, |m| m != module_path!()) {
+            return;
+        }
+
         *self.0.last_log_level.lock().unwrap() = Some(record.level());
         *self.0.last_log_location.lock().unwrap() = record.line();
+        *self.0.last_log_target.lock().unwrap() = Some(record.target().to_owned());
+        *self.0.last_log_message.lock().unwrap() = Some(record.args().to_string());
+        *self.0.last_log_verbosity.lock().unwrap() = Some(record.metadata().verbosity());
+        #[cfg(feature = "timestamp")]
+        {
+            *self.0.last_log_timestamp.lock().unwrap() = record.timestamp();
+        }
+        #[cfg(feature = "seq")]
+        {
+            *self.0.last_log_seq.lock().unwrap() = record.seq();
+        }
+        #[cfg(feature = "thread")]
+        {
+            *self.0.last_log_thread.lock().unwrap() = record
+                .thread_id()
+                .map(|id| (id, record.thread_name().map(str::to_owned)));
+        }
+        #[cfg(feature = "crate_info")]
+        {
+            *self.0.last_log_crate.lock().unwrap() = Some((
+                record.metadata().crate_name().map(str::to_owned),
+                record.metadata().crate_version().map(str::to_owned),
+            ));
+        }
+
+        #[cfg(feature = "kv")]
+        {
+            let mut kvs = String::new();
+            log::kv::to_logfmt(&mut kvs, record.key_values()).unwrap();
+            *self.0.last_log_kvs.lock().unwrap() = Some(kvs);
+            *self.0.last_log_template.lock().unwrap() = record.template().map(str::to_owned);
+            *self.0.last_static_kvs.lock().unwrap() = Some(static_kvs(record.metadata()));
+        }
     }
     fn flush(&self) {}
 }
@@ -51,9 +126,33 @@ fn main() {
         let me = Arc::new(State {
             last_log_level: Mutex::new(None),
             last_log_location: Mutex::new(None),
+            last_log_target: Mutex::new(None),
+            last_log_message: Mutex::new(None),
+            last_log_verbosity: Mutex::new(None),
+            #[cfg(feature = "kv")]
+            last_log_kvs: Mutex::new(None),
+            #[cfg(feature = "kv")]
+            last_log_template: Mutex::new(None),
+            #[cfg(feature = "kv")]
+            last_enabled_keys: Mutex::new(None),
+            #[cfg(feature = "kv")]
+            last_static_kvs: Mutex::new(None),
+            #[cfg(feature = "timestamp")]
+            last_log_timestamp: Mutex::new(None),
+            #[cfg(feature = "seq")]
+            last_log_seq: Mutex::new(None),
+            #[cfg(feature = "thread")]
+            last_log_thread: Mutex::new(None),
+            #[cfg(feature = "crate_info")]
+            last_log_crate: Mutex::new(None),
         });
         let a = me.clone();
+        assert!(!log::logger_installed());
+        assert!(log::try_logger().is_none());
+
         set_boxed_logger(Box::new(Logger(me))).unwrap();
+        assert!(log::logger_installed());
+        assert!(log::try_logger().is_some());
 
         test_filter(&a, LevelFilter::Off);
         test_filter(&a, LevelFilter::Error);
@@ -63,6 +162,54 @@ fn main() {
         test_filter(&a, LevelFilter::Trace);
 
         test_line_numbers(&a);
+        test_rate_limited(&a);
+        test_target_suffix(&a);
+        test_location_override(&a);
+        test_vlog(&a);
+        #[cfg(feature = "std")]
+        test_log_err(&a);
+        test_log_dbg(&a);
+        test_log_if(&a);
+        test_log_assert(&a);
+        test_panic_log(&a);
+        #[cfg(feature = "kv")]
+        test_kv_lazy(&a);
+        #[cfg(feature = "kv")]
+        test_kv_seq(&a);
+        #[cfg(feature = "kv")]
+        test_kv_debug_pretty(&a);
+        #[cfg(feature = "kv")]
+        test_kv_no_message(&a);
+        #[cfg(feature = "kv")]
+        test_kv_template(&a);
+        #[cfg(feature = "kv")]
+        test_enabled_keys(&a);
+        #[cfg(feature = "kv")]
+        test_static_key_values(&a);
+        #[cfg(feature = "kv_format_args")]
+        test_kv_format_args(&a);
+        #[cfg(all(feature = "kv", feature = "std"))]
+        test_kv_scope(&a);
+        #[cfg(all(feature = "kv", feature = "std"))]
+        test_global_kv(&a);
+        #[cfg(all(feature = "kv", feature = "std"))]
+        test_mdc(&a);
+        #[cfg(all(feature = "kv", feature = "std"))]
+        test_log_time(&a);
+        #[cfg(feature = "std")]
+        test_log_writer(&a);
+        #[cfg(feature = "std")]
+        test_scoped_logger(&a);
+        #[cfg(feature = "std")]
+        test_target_max_level(&a);
+        #[cfg(all(feature = "timestamp", feature = "std"))]
+        test_timestamp(&a);
+        #[cfg(feature = "seq")]
+        test_seq(&a);
+        #[cfg(feature = "thread")]
+        test_thread(&a);
+        #[cfg(feature = "crate_info")]
+        test_crate_info(&a);
     }
 }
 
@@ -102,7 +249,872 @@ fn test_line_numbers(state: &State) {
     #[track_caller]
     fn check_log_location(state: &State) {
         let location = std::panic::Location::caller().line(); // get function calling location
-        let line_number = state.last_log_location.lock().unwrap().take().unwrap(); // get location of most recent log
-        assert_eq!(line_number, location - 1);
+        let line_number = state.last_log_location.lock().unwrap().take(); // get location of most recent log
+        assert_eq!(line_number, expected_line(location - 1));
+    }
+}
+
+fn test_location_override(state: &State) {
+    fn last(state: &State) -> (Option<u32>, Option<String>) {
+        (
+            state.last_log_location.lock().unwrap().take(),
+            state.last_log_target.lock().unwrap().take(),
+        )
+    }
+
+    log::set_max_level(LevelFilter::Trace);
+
+    log::log!(target: "ffi", file: "bridge.c", line: 42, Level::Info, "");
+    assert_eq!((Some(42), Some("ffi".to_owned())), last(state));
+
+    log::log!(line: 7, file: "bridge.c", Level::Info, "");
+    assert_eq!((Some(7), Some(module_path!().to_owned())), last(state));
+
+    // The module path is used as the target when no target is given
+    log::log!(module_path: module_path!(), Level::Info, "");
+    assert_eq!(
+        (expected_line(line!() - 2), Some(module_path!().to_owned())),
+        last(state)
+    );
+}
+
+#[cfg(feature = "crate_info")]
+fn test_crate_info(state: &State) {
+    fn last(state: &State) -> Option<(Option<String>, Option<String>)> {
+        state.last_log_crate.lock().unwrap().take()
+    }
+
+    let expected = Some((
+        Some(env!("CARGO_PKG_NAME").to_owned()),
+        Some(env!("CARGO_PKG_VERSION").to_owned()),
+    ));
+
+    log::set_max_level(LevelFilter::Trace);
+
+    info!("");
+    assert_eq!(expected, last(state));
+
+    // The crate is still known when the target is overridden
+    warn!(target: "app_events", "");
+    assert_eq!(expected, last(state));
+
+    log::log!(target: "ffi", file: "bridge.c", Level::Info, "");
+    assert_eq!(expected, last(state));
+
+    // Records from writers and timers are logged after the macro returns
+    #[cfg(feature = "std")]
+    {
+        let target = String::from("app_events");
+
+        drop(log::log_writer!(target: &target, Level::Info));
+        assert_eq!(expected, last(state));
+    }
+    #[cfg(all(feature = "kv", feature = "std"))]
+    {
+        drop(log::log_time!(Level::Info, "timed"));
+        assert_eq!(expected, last(state));
+    }
+}
+
+fn test_vlog(state: &State) {
+    fn last(state: &State) -> (Option<Level>, Option<u32>, Option<String>) {
+        (
+            state.last_log_level.lock().unwrap().take(),
+            state.last_log_verbosity.lock().unwrap().take(),
+            state.last_log_target.lock().unwrap().take(),
+        )
+    }
+
+    log::set_max_level(LevelFilter::Trace);
+    last(state);
+
+    log::vlog!(3, "");
+    assert_eq!(
+        (Some(Level::Info), Some(3), Some(module_path!().to_owned())),
+        last(state)
+    );
+
+    log::vlog!(target: "codec", 7, "");
+    assert_eq!(
+        (Some(Level::Trace), Some(7), Some("codec".to_owned())),
+        last(state)
+    );
+
+    // Records logged at a named level have its verbosity
+    debug!("");
+    assert_eq!(
+        (Some(Level::Debug), Some(4), Some(module_path!().to_owned())),
+        last(state)
+    );
+
+    // Verbosities past `Trace` are filtered like `Trace`
+    log::set_max_level(LevelFilter::Debug);
+    log::vlog!(6, "");
+    assert_eq!((None, None, None), last(state));
+
+    log::set_max_level(LevelFilter::Trace);
+}
+
+fn expected_line(line: u32) -> Option<u32> {
+    if cfg!(all(
+        feature = "release_strip_location",
+        not(debug_assertions)
+    )) {
+        None
+    } else {
+        Some(line)
+    }
+}
+
+fn test_target_suffix(state: &State) {
+    fn last_target(state: &State) -> Option<String> {
+        state.last_log_target.lock().unwrap().take()
+    }
+
+    log::set_max_level(LevelFilter::Trace);
+
+    info!(target+: "db", "");
+    assert_eq!(
+        Some(concat!(module_path!(), "::db")),
+        last_target(state).as_deref()
+    );
+
+    log::log!(target+: "db::pool", Level::Warn, "");
+    assert_eq!(
+        Some(concat!(module_path!(), "::db::pool")),
+        last_target(state).as_deref()
+    );
+
+    info!("");
+    assert_eq!(Some(module_path!()), last_target(state).as_deref());
+}
+
+#[cfg(feature = "std")]
+fn test_log_err(state: &State) {
+    use std::{error, fmt};
+
+    #[derive(Debug)]
+    struct Error(&'static str, Option<Box<Error>>);
+
+    impl fmt::Display for Error {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str(self.0)
+        }
+    }
+
+    impl error::Error for Error {
+        fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+            self.1
+                .as_ref()
+                .map(|err| &**err as &(dyn error::Error + 'static))
+        }
+    }
+
+    fn last_message(state: &State) -> Option<String> {
+        state.last_log_level.lock().unwrap().take();
+        state.last_log_message.lock().unwrap().take()
+    }
+
+    log::set_max_level(LevelFilter::Trace);
+    last_message(state);
+
+    let ok: Result<i32, Error> = log::log_err!(Ok(1));
+    assert_eq!(1, ok.unwrap());
+    assert_eq!(None, last_message(state));
+
+    let err: Result<i32, Error> = log::log_err!(
+        Level::Warn,
+        Err(Error("outer", Some(Box::new(Error("inner", None)))))
+    );
+    assert_eq!("outer", err.unwrap_err().0);
+    assert_eq!(Some("outer: inner"), last_message(state).as_deref());
+
+    let err: Result<(), Error> = log::log_err!(Err(Error("outer", None)));
+    assert!(err.is_err());
+    assert_eq!(Some("outer"), last_message(state).as_deref());
+}
+
+fn test_log_dbg(state: &State) {
+    fn last(state: &State) -> (Option<Level>, Option<String>) {
+        (
+            state.last_log_level.lock().unwrap().take(),
+            state.last_log_message.lock().unwrap().take(),
+        )
+    }
+
+    log::set_max_level(LevelFilter::Trace);
+    last(state);
+
+    let items = [1, 2, 3];
+
+    assert_eq!(3, log::trace_dbg!(items.len()));
+    assert_eq!(
+        (Some(Level::Trace), Some("items.len() = 3".to_owned())),
+        last(state)
+    );
+
+    assert_eq!("a", log::log_dbg!(Level::Info, "a"));
+    assert_eq!(
+        (Some(Level::Info), Some("\"a\" = \"a\"".to_owned())),
+        last(state)
+    );
+
+    log::set_max_level(LevelFilter::Debug);
+
+    assert_eq!(1, log::trace_dbg!(items[0]));
+    assert_eq!((None, None), last(state));
+}
+
+fn test_log_if(state: &State) {
+    fn logged(state: &State) -> bool {
+        state.last_log_level.lock().unwrap().take().is_some()
+    }
+
+    logged(state);
+    log::set_max_level(LevelFilter::Info);
+
+    log::info_if!(true, "");
+    assert!(logged(state));
+
+    log::info_if!(false, "");
+    assert!(!logged(state));
+
+    log::log_if!(true, target: "log_if", Level::Warn, "");
+    assert!(logged(state));
+
+    let mut evaluated = false;
+    log::debug_if!(
+        {
+            evaluated = true;
+            true
+        },
+        ""
+    );
+    assert!(!evaluated);
+    assert!(!logged(state));
+}
+
+fn test_panic_log(state: &State) {
+    use std::panic;
+
+    log::set_max_level(LevelFilter::Trace);
+
+    let port = 22;
+    let err =
+        panic::catch_unwind(|| log::panic_log!("unable to bind to port {}", port)).unwrap_err();
+    assert_eq!(
+        Some(&"unable to bind to port 22".to_owned()),
+        err.downcast_ref::<String>()
+    );
+    assert_eq!(
+        Some(Level::Error),
+        state.last_log_level.lock().unwrap().take()
+    );
+    assert_eq!(
+        Some("unable to bind to port 22"),
+        state.last_log_message.lock().unwrap().take().as_deref()
+    );
+
+    #[cfg(feature = "kv")]
+    {
+        panic::catch_unwind(|| log::panic_log!(target: "panic_log", port; "unable to bind"))
+            .unwrap_err();
+        assert_eq!(
+            Some("panic_log"),
+            state.last_log_target.lock().unwrap().take().as_deref()
+        );
+        assert_eq!(
+            Some("port=22"),
+            state.last_log_kvs.lock().unwrap().take().as_deref()
+        );
+    }
+}
+
+fn test_log_assert(state: &State) {
+    use std::panic::{self, AssertUnwindSafe};
+
+    log::set_max_level(LevelFilter::Trace);
+
+    let a = 1;
+    let err = panic::catch_unwind(|| log::log_assert!(a > 1)).unwrap_err();
+    assert_eq!(
+        Some(&"assertion failed: a > 1".to_owned()),
+        err.downcast_ref::<String>()
+    );
+    assert_eq!(
+        Some(Level::Error),
+        state.last_log_level.lock().unwrap().take()
+    );
+    assert_eq!(
+        Some("assertion failed: a > 1"),
+        state.last_log_message.lock().unwrap().take().as_deref()
+    );
+    #[cfg(feature = "kv")]
+    assert_eq!(
+        Some(r#"expr="a > 1""#),
+        state.last_log_kvs.lock().unwrap().take().as_deref()
+    );
+
+    panic::catch_unwind(AssertUnwindSafe(|| log::log_assert_ne!(a, 1, "a is {}", a))).unwrap_err();
+    assert_eq!(
+        Some("assertion `left != right` failed: a is 1\n  left: 1\n right: 1"),
+        state.last_log_message.lock().unwrap().take().as_deref()
+    );
+    #[cfg(feature = "kv")]
+    assert_eq!(
+        Some(r#"expr="a != 1" left=1 right=1"#),
+        state.last_log_kvs.lock().unwrap().take().as_deref()
+    );
+    state.last_log_level.lock().unwrap().take();
+
+    log::log_assert_eq!(a, 1);
+    assert_eq!(None, state.last_log_level.lock().unwrap().take());
+}
+
+#[cfg(feature = "kv")]
+fn test_kv_lazy(state: &State) {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    fn dump() -> &'static str {
+        CALLS.fetch_add(1, Ordering::Relaxed);
+        "state"
+    }
+
+    log::set_max_level(LevelFilter::Info);
+
+    debug!(dump:lazy = dump; "");
+    assert_eq!(0, CALLS.load(Ordering::Relaxed));
+
+    info!(dump:lazy = dump; "");
+    assert_ne!(0, CALLS.load(Ordering::Relaxed));
+    assert_eq!(
+        Some("dump=state"),
+        state.last_log_kvs.lock().unwrap().take().as_deref()
+    );
+}
+
+#[cfg(feature = "kv")]
+fn test_kv_seq(state: &State) {
+    log::set_max_level(LevelFilter::Trace);
+
+    let batch = vec![1, 2, 3];
+    info!(ids:seq = &batch, odd:seq = batch.iter().filter(|id| **id % 2 == 1); "");
+    assert_eq!(
+        Some(r#"ids="[1, 2, 3]" odd="[1, 3]""#),
+        state.last_log_kvs.lock().unwrap().take().as_deref()
+    );
+}
+
+#[cfg(feature = "kv_format_args")]
+fn test_kv_format_args(state: &State) {
+    fn last(state: &State) -> (Option<String>, Option<String>) {
+        (
+            state.last_log_message.lock().unwrap().take(),
+            state.last_log_kvs.lock().unwrap().take(),
+        )
+    }
+
+    log::set_max_level(LevelFilter::Trace);
+
+    let (name, roles) = ("Nori", ["admin"]);
+
+    info!(
+        "user {name} logged in with {roles:?} {{escaped}} {0} {name}",
+        1
+    );
+    assert_eq!(
+        (
+            Some(r#"user Nori logged in with ["admin"] {escaped} 1 Nori"#.to_owned()),
+            Some(r#"name=Nori roles="[\"admin\"]""#.to_owned())
+        ),
+        last(state)
+    );
+
+    info!(a = 1; "user {name:>6} logged in as {role}", role = roles[0]);
+    assert_eq!(
+        (
+            Some("user   Nori logged in as admin".to_owned()),
+            Some("a=1 name=Nori".to_owned())
+        ),
+        last(state)
+    );
+
+    // Holes that are already keys aren't captured again
+    let user = 1;
+    info!(user = 2, ?roles; "user {user} {roles:?}");
+    assert_eq!(
+        (
+            Some(r#"user 1 ["admin"]"#.to_owned()),
+            Some(r#"user=2 roles="[\"admin\"]""#.to_owned())
+        ),
+        last(state)
+    );
+
+    info!("no arguments");
+    assert_eq!(
+        (Some("no arguments".to_owned()), Some("".to_owned())),
+        last(state)
+    );
+}
+
+#[cfg(feature = "kv")]
+fn test_kv_no_message(state: &State) {
+    log::set_max_level(LevelFilter::Trace);
+
+    let key = 1;
+    info!(event = "cache_miss", key;);
+    assert_eq!(
+        Some(""),
+        state.last_log_message.lock().unwrap().take().as_deref()
+    );
+    assert_eq!(
+        Some("event=cache_miss key=1"),
+        state.last_log_kvs.lock().unwrap().take().as_deref()
+    );
+}
+
+#[cfg(feature = "kv")]
+fn test_enabled_keys(state: &State) {
+    log::set_max_level(LevelFilter::Trace);
+
+    assert!(log::log_enabled!(target: "enabled_keys", Level::Debug, user_id, "http.method"));
+    assert_eq!(
+        Some(vec!["user_id".to_owned(), "http.method".to_owned()]),
+        state.last_enabled_keys.lock().unwrap().take()
+    );
+
+    assert!(log::log_enabled!(target: "enabled_keys", Level::Debug));
+    assert_eq!(
+        Some(Vec::<String>::new()),
+        state.last_enabled_keys.lock().unwrap().take()
+    );
+
+    // The logger isn't asked if the level is disabled
+    log::set_max_level(LevelFilter::Info);
+    assert!(!log::log_enabled!(target: "enabled_keys", Level::Debug, user_id));
+    assert_eq!(None, state.last_enabled_keys.lock().unwrap().take());
+}
+
+#[cfg(feature = "kv")]
+fn test_static_key_values(state: &State) {
+    log::set_max_level(LevelFilter::Trace);
+
+    let expected = Some(vec![("component".to_owned(), "db".to_owned())]);
+
+    let pool_size = 8;
+    log::log!(static: [component = "db"], Level::Info, pool_size; "pool started");
+    assert_eq!(expected, state.last_static_kvs.lock().unwrap().take());
+    assert_eq!(
+        Some("pool_size=8"),
+        state.last_log_kvs.lock().unwrap().take().as_deref()
+    );
+
+    assert!(
+        log::log_enabled!(static: [component = "db"], target: "enabled_keys", Level::Debug, user_id)
+    );
+    assert_eq!(expected, state.last_static_kvs.lock().unwrap().take());
+    assert_eq!(
+        Some(vec!["user_id".to_owned()]),
+        state.last_enabled_keys.lock().unwrap().take()
+    );
+
+    info!("");
+    assert_eq!(Some(vec![]), state.last_static_kvs.lock().unwrap().take());
+}
+
+#[cfg(feature = "kv")]
+fn test_kv_template(state: &State) {
+    log::set_max_level(LevelFilter::Trace);
+
+    let name = "Nori";
+    info!(user = name, attempts:? = "3"; template: "User {user} logged in after {attempts:?} attempts {{{missing}}}");
+    assert_eq!(
+        Some(r#"User Nori logged in after "3" attempts {{missing}}"#),
+        state.last_log_message.lock().unwrap().take().as_deref()
+    );
+    assert_eq!(
+        Some(r#"user=Nori attempts="\"3\"""#),
+        state.last_log_kvs.lock().unwrap().take().as_deref()
+    );
+    assert_eq!(
+        Some("User {user} logged in after {attempts:?} attempts {{{missing}}}"),
+        state.last_log_template.lock().unwrap().take().as_deref()
+    );
+
+    info!(user = name; "User {} logged in", name);
+    assert_eq!(
+        None,
+        state.last_log_template.lock().unwrap().take().as_deref()
+    );
+}
+
+#[cfg(feature = "kv")]
+fn test_kv_debug_pretty(state: &State) {
+    log::set_max_level(LevelFilter::Trace);
+
+    info!(a:#? = [1], b:? = [1]; "");
+    assert_eq!(
+        Some(r#"a="[\n    1,\n]" b=[1]"#),
+        state.last_log_kvs.lock().unwrap().take().as_deref()
+    );
+}
+
+#[cfg(all(feature = "kv", feature = "std"))]
+fn test_log_time(state: &State) {
+    fn load(fail: bool) -> Result<(), ()> {
+        let _timer = log::log_time!(target: "timing", Level::Debug, "load config");
+
+        if fail {
+            return Err(());
+        }
+
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        Ok(())
+    }
+
+    log::set_max_level(LevelFilter::Trace);
+
+    for fail in [false, true] {
+        let _ = load(fail);
+
+        assert_eq!(
+            Some(Level::Debug),
+            state.last_log_level.lock().unwrap().take()
+        );
+        assert_eq!(
+            Some("timing"),
+            state.last_log_target.lock().unwrap().take().as_deref()
+        );
+        assert_eq!(
+            Some("load config"),
+            state.last_log_message.lock().unwrap().take().as_deref()
+        );
+
+        let kvs = state.last_log_kvs.lock().unwrap().take().unwrap();
+        let duration_ms: u64 = kvs.strip_prefix("duration_ms=").unwrap().parse().unwrap();
+        assert!(fail || duration_ms >= 5, "{}", kvs);
+    }
+
+    log::set_max_level(LevelFilter::Info);
+
+    let _ = load(false);
+    assert_eq!(None, state.last_log_level.lock().unwrap().take());
+}
+
+#[cfg(all(feature = "timestamp", feature = "std"))]
+fn test_timestamp(state: &State) {
+    use std::time::SystemTime;
+
+    log::set_max_level(LevelFilter::Trace);
+
+    let before = SystemTime::now();
+    info!("");
+    let after = SystemTime::now();
+
+    let timestamp = state.last_log_timestamp.lock().unwrap().take().unwrap();
+    assert!(before <= timestamp && timestamp <= after);
+}
+
+#[cfg(feature = "seq")]
+fn test_seq(state: &State) {
+    fn last_seq(state: &State) -> u64 {
+        state.last_log_seq.lock().unwrap().take().unwrap()
+    }
+
+    log::set_max_level(LevelFilter::Trace);
+
+    info!("");
+    let first = last_seq(state);
+
+    warn!("");
+    let second = last_seq(state);
+
+    debug!(target: "other", "");
+    let third = last_seq(state);
+
+    assert!(first < second && second < third);
+
+    // Records for disabled targets don't use up a number
+    #[cfg(feature = "std")]
+    {
+        log::set_target_max_level("seq_quiet", LevelFilter::Off);
+
+        info!(target: "seq_quiet", "");
+        info!("");
+        assert_eq!(third + 1, last_seq(state));
+
+        log::clear_target_max_level("seq_quiet");
+    }
+}
+
+#[cfg(feature = "thread")]
+fn test_thread(state: &State) {
+    use std::thread;
+
+    log::set_max_level(LevelFilter::Trace);
+
+    let id = thread::Builder::new()
+        .name("worker".into())
+        .spawn(|| {
+            info!("");
+            thread::current().id()
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+
+    assert_eq!(
+        Some((id, Some("worker".to_owned()))),
+        state.last_log_thread.lock().unwrap().take()
+    );
+}
+
+#[cfg(feature = "std")]
+fn test_scoped_logger(state: &State) {
+    struct Capture(Mutex<Vec<String>>);
+
+    impl Log for Capture {
+        fn enabled(&self, metadata: &Metadata) -> bool {
+            metadata.target() != "disabled"
+        }
+
+        fn log(&self, record: &Record) {
+            self.0.lock().unwrap().push(record.args().to_string());
+        }
+
+        fn flush(&self) {}
+    }
+
+    log::set_max_level(LevelFilter::Trace);
+
+    let capture = Capture(Mutex::new(Vec::new()));
+    log::with_scoped_logger(&capture, || {
+        info!("scoped");
+        assert!(!log::log_enabled!(target: "disabled", Level::Info));
+
+        // Other threads still log to the global logger
+        std::thread::spawn(|| info!("global")).join().unwrap();
+    });
+
+    assert_eq!(vec!["scoped".to_owned()], *capture.0.lock().unwrap());
+    assert_eq!(
+        Some("global".to_owned()),
+        state.last_log_message.lock().unwrap().take()
+    );
+
+    info!("global again");
+    assert_eq!(1, capture.0.lock().unwrap().len());
+    assert_eq!(
+        Some("global again".to_owned()),
+        state.last_log_message.lock().unwrap().take()
+    );
+}
+
+#[cfg(feature = "std")]
+fn test_target_max_level(state: &State) {
+    fn logged(state: &State) -> bool {
+        state.last_log_level.lock().unwrap().take().is_some()
+    }
+
+    log::set_max_level(LevelFilter::Debug);
+    log::set_target_max_level("noisy", LevelFilter::Warn);
+    logged(state);
+
+    assert_eq!(LevelFilter::Warn, log::target_max_level("noisy::proto"));
+    assert_eq!(LevelFilter::Debug, log::target_max_level("noisy_neighbour"));
+
+    info!(target: "noisy::proto", "");
+    assert!(!logged(state));
+    assert!(!log::log_enabled!(target: "noisy", Level::Info));
+
+    warn!(target: "noisy::proto", "");
+    assert!(logged(state));
+
+    info!(target: "noisy_neighbour", "");
+    assert!(logged(state));
+
+    log::clear_target_max_level("noisy");
+
+    info!(target: "noisy::proto", "");
+    assert!(logged(state));
+}
+
+#[cfg(feature = "std")]
+fn test_log_writer(state: &State) {
+    use std::fmt::Write;
+
+    log::set_max_level(LevelFilter::Trace);
+
+    let mut writer = log::log_writer!(target: "writer", Level::Warn);
+    assert!(writer.is_enabled());
+    write!(writer, "{} checks failed:", 2).unwrap();
+    for check in ["db", "cache"] {
+        write!(writer, " {}", check).unwrap();
+    }
+    #[cfg(feature = "kv")]
+    writer
+        .key_value("failed", 2)
+        .key_value("check", String::from("cache"));
+
+    // Nothing is logged until the writer is dropped
+    assert_eq!(None, state.last_log_level.lock().unwrap().take());
+    drop(writer);
+
+    assert_eq!(
+        Some(Level::Warn),
+        state.last_log_level.lock().unwrap().take()
+    );
+    assert_eq!(
+        Some("writer"),
+        state.last_log_target.lock().unwrap().take().as_deref()
+    );
+    assert_eq!(
+        Some("2 checks failed: db cache"),
+        state.last_log_message.lock().unwrap().take().as_deref()
+    );
+    #[cfg(feature = "kv")]
+    assert_eq!(
+        Some("failed=2 check=cache"),
+        state.last_log_kvs.lock().unwrap().take().as_deref()
+    );
+
+    log::set_max_level(LevelFilter::Info);
+
+    let mut writer = log::log_writer!(Level::Debug);
+    assert!(!writer.is_enabled());
+    write!(writer, "ignored").unwrap();
+    drop(writer);
+    assert_eq!(None, state.last_log_level.lock().unwrap().take());
+}
+
+#[cfg(all(feature = "kv", feature = "std"))]
+fn test_kv_scope(state: &State) {
+    fn last_kvs(state: &State) -> Option<String> {
+        state.last_log_level.lock().unwrap().take();
+        state.last_log_kvs.lock().unwrap().take()
+    }
+
+    log::set_max_level(LevelFilter::Trace);
+
+    let id = 42;
+    let status = log::kv_scope!(request_id = id, user:? = "Nori" => {
+        info!("");
+        assert_eq!(Some(r#"request_id=42 user="\"Nori\"""#), last_kvs(state).as_deref());
+
+        let _guard = log::kv::push_context(&("attempt", 2));
+        info!(status = 200; "");
+        assert_eq!(
+            Some(r#"request_id=42 user="\"Nori\"" attempt=2 status=200"#),
+            last_kvs(state).as_deref()
+        );
+
+        200
+    });
+    assert_eq!(200, status);
+
+    info!("");
+    assert_eq!(Some(""), last_kvs(state).as_deref());
+}
+
+#[cfg(all(feature = "kv", feature = "std"))]
+fn test_global_kv(state: &State) {
+    fn last_kvs(state: &State) -> Option<String> {
+        state.last_log_level.lock().unwrap().take();
+        state.last_log_kvs.lock().unwrap().take()
+    }
+
+    log::set_max_level(LevelFilter::Trace);
+
+    log::set_global_kv(&[("service", "checkout")]);
+
+    info!(status = 200; "");
+    assert_eq!(
+        Some("service=checkout status=200"),
+        last_kvs(state).as_deref()
+    );
+
+    log::kv_scope!(request_id = 42 => {
+        std::thread::spawn(|| info!(""))
+            .join()
+            .unwrap();
+        assert_eq!(Some("service=checkout"), last_kvs(state).as_deref());
+
+        info!("");
+        assert_eq!(Some("service=checkout request_id=42"), last_kvs(state).as_deref());
+    });
+
+    log::set_global_kv(&[] as &[(&str, &str)]);
+
+    info!("");
+    assert_eq!(Some(""), last_kvs(state).as_deref());
+}
+
+#[cfg(all(feature = "kv", feature = "std"))]
+fn test_mdc(state: &State) {
+    use log::mdc;
+
+    fn last_kvs(state: &State) -> Option<String> {
+        state.last_log_level.lock().unwrap().take();
+        state.last_log_kvs.lock().unwrap().take()
+    }
+
+    log::set_max_level(LevelFilter::Trace);
+
+    mdc::insert("request_id", 42);
+
+    log::kv_scope!(attempt = 2 => {
+        info!(status = 200; "");
+        assert_eq!(
+            Some("request_id=42 attempt=2 status=200"),
+            last_kvs(state).as_deref()
+        );
+    });
+
+    {
+        let _guard = mdc::insert_scoped("request_id", 43);
+        info!("");
+        assert_eq!(Some("request_id=43"), last_kvs(state).as_deref());
+    }
+
+    info!("");
+    assert_eq!(Some("request_id=42"), last_kvs(state).as_deref());
+
+    mdc::remove("request_id");
+
+    info!("");
+    assert_eq!(Some(""), last_kvs(state).as_deref());
+}
+
+fn test_rate_limited(state: &State) {
+    fn logged(state: &State) -> bool {
+        state.last_log_level.lock().unwrap().take().is_some()
+    }
+
+    logged(state);
+    log::set_max_level(LevelFilter::Warn);
+
+    for _ in 0..2 {
+        log::info_once!("");
+        assert!(!logged(state));
+    }
+
+    log::set_max_level(LevelFilter::Trace);
+
+    for i in 0..3 {
+        log::info_once!("");
+        assert_eq!(i == 0, logged(state));
+    }
+
+    for i in 0..7 {
+        log::warn_every_n!(3, target: "rate_limited", "");
+        assert_eq!(i % 3 == 0, logged(state));
+    }
+
+    #[cfg(feature = "std")]
+    for i in 0..3 {
+        log::log_every_secs!(3600, Level::Error, "");
+        assert_eq!(i == 0, logged(state));
     }
 }
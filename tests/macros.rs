@@ -92,6 +92,202 @@ fn inlined_args() {
     all_log_macros!(target: "my_target", "hello {world}",);
 }
 
+#[test]
+fn target_suffix() {
+    for lvl in log::Level::iter() {
+        log!(target+: "db", lvl, "hello {}", "world");
+
+        let _ = log_enabled!(target+: "db", lvl);
+    }
+
+    all_log_macros!(target+: "db", "hello {}", "world");
+
+    #[cfg(feature = "kv")]
+    all_log_macros!(target+: "db", a = 1; "hello {}", "world");
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn log_err() {
+    fn fallible(ok: bool) -> Result<u8, std::io::Error> {
+        if ok {
+            Ok(1)
+        } else {
+            Err(std::io::Error::new(std::io::ErrorKind::Other, "failed"))
+        }
+    }
+
+    assert_eq!(1, log::log_err!(fallible(true)).unwrap());
+    assert!(log::log_err!(fallible(false)).is_err());
+    assert!(log::log_err!(log::Level::Warn, fallible(false)).is_err());
+    assert!(log::log_err!(target: "my_target", log::Level::Warn, fallible(false),).is_err());
+}
+
+#[test]
+fn log_dbg() {
+    let items = vec![1, 2, 3];
+
+    assert_eq!(6, log::trace_dbg!(items.iter().sum::<i32>()));
+    assert_eq!((3, 1), log::debug_dbg!(items.len(), items[0],));
+    assert_eq!(3, log::trace_dbg!(target: "my_target", items.len(),));
+    assert_eq!(
+        (3, 1),
+        log::debug_dbg!(target: "my_target", items.len(), items[0])
+    );
+    assert_eq!(2, log::log_dbg!(log::Level::Info, items[1]));
+    assert_eq!(
+        vec![1, 2, 3],
+        log::log_dbg!(target: "my_target", log::Level::Warn, items)
+    );
+}
+
+#[test]
+fn log_if() {
+    for lvl in log::Level::iter() {
+        log::log_if!(true, lvl, "hello {}", "world");
+        log::log_if!(false, target: "my_target", lvl, "hello {}", "world");
+    }
+
+    log::error_if!(true, "hello");
+    log::warn_if!(false, target: "my_target", "hello {}", "world");
+    log::info_if!(1 > 0, "hello");
+    log::debug_if!(true, "hello {}", "world");
+    log::trace_if!(true, target: "my_target", "hello");
+
+    #[cfg(feature = "kv")]
+    log::info_if!(true, a = 1; "hello world");
+}
+
+#[test]
+#[cfg(all(feature = "kv", feature = "std"))]
+fn log_time() {
+    for lvl in log::Level::iter() {
+        let _timer = log::log_time!(lvl, "load config");
+        let _timer = log::log_time!(target: "my_target", lvl, "load config",);
+    }
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn log_writer() {
+    use std::fmt::Write;
+
+    for lvl in log::Level::iter() {
+        let mut writer = log::log_writer!(lvl);
+        write!(writer, "hello {}", lvl).unwrap();
+
+        let mut writer = log::log_writer!(target: "my_target", lvl,);
+        writer.write_str("hello").unwrap();
+        #[cfg(feature = "kv")]
+        writer.key_value("a", 1);
+    }
+}
+
+#[test]
+fn vlog() {
+    for verbosity in 0..8 {
+        log::vlog!(verbosity, "hello {}", "world");
+        log::vlog!(target: "my_target", verbosity, "hello");
+    }
+
+    #[cfg(feature = "kv")]
+    log::vlog!(7, a = 1; "hello world");
+}
+
+#[test]
+fn location_override() {
+    let file = String::from("bridge.c");
+
+    for lvl in log::Level::iter() {
+        log!(file: "bridge.c", line: 42, lvl, "hello {}", "world");
+        log!(target: "my_target", module_path: "bridge", file: &file, lvl, "hello");
+        log!(line: 42, module_path: "bridge", lvl, "hello");
+        log!(target: "my_target", line: 42, lvl, "hello {}", "world");
+    }
+
+    #[cfg(feature = "kv")]
+    log!(target: "my_target", file: &file, line: 42, log::Level::Info, a = 1; "hello");
+}
+
+#[test]
+#[should_panic(expected = "unable to bind to port 22")]
+fn panic_log() {
+    log::panic_log!("unable to bind to port {}", 22);
+}
+
+#[test]
+#[should_panic(expected = "unable to bind to port 22")]
+fn panic_log_target() {
+    log::panic_log!(target: "my_target", "unable to bind to port {}", 22);
+}
+
+#[test]
+#[cfg(feature = "kv")]
+#[should_panic(expected = "unable to bind")]
+fn panic_log_kv() {
+    let port = 22;
+
+    log::panic_log!(port, addr:? = "0.0.0.0"; "unable to bind");
+}
+
+#[test]
+fn log_assert() {
+    let a = 1;
+
+    log::log_assert!(a == 1);
+    log::log_assert!(a == 1, "a is {}", a);
+    log::log_assert_eq!(a, 1);
+    log::log_assert_eq!(a, 1, "a is {}", a);
+    log::log_assert_ne!(a, 2);
+    log::log_assert_ne!(a, 2, "a is {}", a);
+
+    log::log_debug_assert!(a == 1);
+    log::log_debug_assert_eq!(a, 1, "a is {}", a);
+    log::log_debug_assert_ne!(a, 2);
+}
+
+#[test]
+#[should_panic(expected = "assertion `left == right` failed: a is 1\n  left: 1\n right: 2")]
+fn log_assert_eq_panics() {
+    let a = 1;
+
+    log::log_assert_eq!(a, 2, "a is {}", a);
+}
+
+#[test]
+fn rate_limited() {
+    for lvl in log::Level::iter() {
+        log::log_once!(lvl, "hello {}", "world");
+        log::log_once!(target: "my_target", lvl, "hello {}", "world");
+        log::log_every_n!(10, lvl, "hello {}", "world");
+        log::log_every_n!(10, target: "my_target", lvl, "hello {}", "world");
+
+        #[cfg(feature = "std")]
+        {
+            log::log_every_secs!(10, lvl, "hello {}", "world");
+            log::log_every_secs!(10, target: "my_target", lvl, "hello {}", "world");
+        }
+    }
+
+    log::error_once!("hello");
+    log::warn_once!(target: "my_target", "hello {}", "world");
+    log::info_every_n!(10, "hello");
+    log::debug_every_n!(10, target: "my_target", "hello {}", "world");
+
+    #[cfg(feature = "std")]
+    {
+        log::trace_every_secs!(10, "hello");
+        log::info_every_secs!(30, target: "my_target", "hello {}", "world");
+    }
+}
+
+#[test]
+#[cfg(feature = "kv")]
+fn rate_limited_kv() {
+    log::log_once!(log::Level::Info, a = 1; "hello");
+    log::warn_every_n!(10, a = 1, b:? = 2; "hello");
+}
+
 #[test]
 fn enabled() {
     for lvl in log::Level::iter() {
@@ -99,6 +295,31 @@ fn enabled() {
     }
 }
 
+#[test]
+#[cfg(feature = "kv")]
+fn enabled_keys() {
+    for lvl in log::Level::iter() {
+        let _enabled = log_enabled!(lvl, a, "b.c");
+        let _enabled = log_enabled!(target: "my_target", lvl, a,);
+        let _enabled = log_enabled!(target+: "db", lvl, a, b);
+    }
+}
+
+#[test]
+#[cfg(feature = "kv")]
+fn static_key_values() {
+    let pool_size = 8;
+
+    for lvl in log::Level::iter() {
+        log!(static: [component = "db"], lvl, "hello");
+        log!(static: [component = "db", "sub.system" = "pool",], lvl, pool_size; "hello");
+        log!(target: "my_target", line: 42, static: [component = "db"], lvl, "hello");
+
+        let _enabled = log_enabled!(static: [component = "db"], lvl);
+        let _enabled = log_enabled!(static: [component = "db"], target: "my_target", lvl, a);
+    }
+}
+
 #[test]
 fn expr() {
     for lvl in log::Level::iter() {
@@ -279,6 +500,22 @@ fn kv_display() {
     );
 }
 
+#[test]
+#[cfg(feature = "kv")]
+fn kv_option() {
+    let a: Option<i32> = None;
+    all_log_macros!(a, b = Some(1); "hello world");
+
+    let source = log::kv!(a, b = Some(1));
+    assert!(log::kv::Source::get(&source, log::kv::Key::from("a"))
+        .unwrap()
+        .is_null());
+
+    let mut json = String::new();
+    log::kv::to_json_writer(&mut json, &source).unwrap();
+    assert_eq!(r#"{"a":null,"b":1}"#, json);
+}
+
 #[test]
 #[cfg(feature = "kv_std")]
 fn kv_error() {
@@ -288,6 +525,35 @@ fn kv_error() {
     );
 }
 
+#[test]
+#[cfg(feature = "kv_std")]
+fn kv_result() {
+    let ok: Result<i32, std::io::Error> = Ok(1);
+    all_log_macros!(
+        a:result = ok;
+        "hello world"
+    );
+
+    let err: Result<i32, std::io::Error> =
+        Err(std::io::Error::new(std::io::ErrorKind::Other, "an error"));
+    all_log_macros!(
+        err:result;
+        "hello world"
+    );
+
+    let source = log::kv!(a:result = ok, b:result = err);
+    assert_eq!(
+        Some(1),
+        log::kv::Source::get(&source, log::kv::Key::from("a"))
+            .unwrap()
+            .to_i64()
+    );
+    assert!(log::kv::Source::get(&source, log::kv::Key::from("b"))
+        .unwrap()
+        .to_borrowed_error()
+        .is_some());
+}
+
 #[test]
 #[cfg(feature = "kv_sval")]
 fn kv_sval() {
@@ -306,6 +572,292 @@ fn kv_serde() {
     );
 }
 
+#[test]
+#[cfg(feature = "kv")]
+fn kv_redact() {
+    all_log_macros!(
+        password:redact = "hunter2";
+        "hello world"
+    );
+
+    let password = "hunter2";
+    all_log_macros!(
+        password:redact;
+        "hello world"
+    );
+
+    let source = log::kv!(password:redact = "hunter2");
+    let value = log::kv::Source::get(&source, log::kv::Key::from("password")).unwrap();
+    assert_eq!("[REDACTED]", value.to_string());
+    assert_eq!("hunter2", value.to_unredacted().unwrap().to_string());
+}
+
+#[test]
+#[cfg(feature = "kv")]
+fn kv_shorthand_sigils() {
+    let user_id = 1;
+    let path = "/";
+
+    all_log_macros!(user_id, path; "hello world");
+    all_log_macros!(?user_id, %path; "hello world");
+    all_log_macros!(target: "my_target", ?user_id, path:?, %path; "hello world");
+
+    let source = log::kv!(%user_id, ?path, a = 1);
+    assert_eq!(3, log::kv::Source::count(&source));
+    assert_eq!(
+        "\"/\"",
+        log::kv::Source::get(&source, log::kv::Key::from("path"))
+            .unwrap()
+            .to_string()
+    );
+    assert_eq!(
+        "1",
+        log::kv::Source::get(&source, log::kv::Key::from("user_id"))
+            .unwrap()
+            .to_string()
+    );
+}
+
+#[test]
+#[cfg(feature = "kv_format_args")]
+fn kv_format_args() {
+    let (name, roles) = ("Nori", ["admin"]);
+
+    all_log_macros!("user {name} logged in with {roles:?}");
+    all_log_macros!(target: "my_target", a = 1; "user {name} {}", 1);
+    all_log_macros!("user {name} {role}", role = roles[0]);
+    all_log_macros!(concat!("user ", "{}"), name);
+
+    // Holes with other format traits aren't captured, so their values don't need `Display`
+    struct NoDisplay;
+
+    impl std::fmt::LowerHex for NoDisplay {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            f.write_str("ff")
+        }
+    }
+
+    let (p, h) = (&NoDisplay, NoDisplay);
+    all_log_macros!("ptr {p:p} hex {h:x}");
+    all_log_macros!(a = 1; "user {name} ptr {p:p} hex {h:#x}");
+}
+
+#[test]
+#[cfg(feature = "kv")]
+fn kv_no_message() {
+    let key = "a";
+
+    all_log_macros!(event = "cache_miss", key;);
+    all_log_macros!(target: "my_target", event = "cache_miss", key:?, ?key;);
+
+    for lvl in log::Level::iter() {
+        log!(lvl, event = "cache_miss";);
+        log!(target: "my_target", lvl, event = "cache_miss", key:#?;);
+    }
+}
+
+#[test]
+#[cfg(feature = "kv")]
+fn kv_template() {
+    let name = "Nori";
+
+    all_log_macros!(user = name; template: "User {user} logged in");
+    all_log_macros!(target: "my_target", user = name, ?name; template: "User {user} ({name:?})");
+
+    for lvl in log::Level::iter() {
+        log!(lvl, user = name; template: "User {user} logged in");
+        log!(target: "my_target", lvl, user:? = name; template: "User {user}");
+    }
+}
+
+#[test]
+#[cfg(feature = "kv")]
+fn kv_debug_pretty() {
+    #[derive(Debug)]
+    #[allow(dead_code)]
+    struct Point {
+        x: i32,
+    }
+
+    let point = Point { x: 1 };
+
+    all_log_macros!(point:#? = Point { x: 1 }, a:? = 1; "hello world");
+    all_log_macros!(point:#?; "hello world");
+    all_log_macros!(a = 1, point:#?, b:? = 2; "hello world");
+
+    let source = log::kv!(point:#?, a:? = 1);
+    let value = log::kv::Source::get(&source, log::kv::Key::from("point")).unwrap();
+    assert_eq!("Point {\n    x: 1,\n}", value.to_string());
+    assert_eq!(
+        Some(log::kv::FormatHint::PrettyDebug),
+        value.to_format_hint()
+    );
+
+    let value = log::kv::Source::get(&source, log::kv::Key::from("a")).unwrap();
+    assert_eq!(None, value.to_format_hint());
+}
+
+#[test]
+#[cfg(feature = "kv")]
+fn kv_seq() {
+    let batch = [1, 2, 3];
+    let ids = [4, 5];
+
+    all_log_macros!(ids:seq = &batch; "hello world");
+    all_log_macros!(ids:seq, evens:seq = batch.iter().filter(|id| **id % 2 == 0); "hello world");
+
+    let source = log::kv!(ids:seq, batch:seq = &batch);
+    assert_eq!(2, log::kv::Source::count(&source));
+}
+
+#[test]
+#[cfg(feature = "kv")]
+fn kv_lazy() {
+    use std::cell::Cell;
+
+    let calls = Cell::new(0);
+    let dump = || {
+        calls.set(calls.get() + 1);
+        "state"
+    };
+
+    all_log_macros!(
+        dump:lazy = dump;
+        "hello world"
+    );
+    all_log_macros!(
+        dump:lazy;
+        "hello world"
+    );
+    calls.set(0);
+
+    let source = log::kv!(dump:lazy = dump);
+    assert_eq!(0, calls.get());
+
+    let value = log::kv::Source::get(&source, log::kv::Key::from("dump")).unwrap();
+    assert_eq!("state", value.to_string());
+    assert_eq!(1, calls.get());
+}
+
+#[test]
+#[cfg(feature = "kv")]
+fn kv_unit() {
+    all_log_macros!(
+        latency:ms = 12.5,
+        size:bytes = 1024;
+        "hello world"
+    );
+
+    let timeout = 30;
+    all_log_macros!(
+        timeout:s;
+        "hello world"
+    );
+
+    let source = log::kv!(latency:ms = 12.5, size:bytes = 1024);
+    let latency = log::kv::Source::get(&source, log::kv::Key::from("latency")).unwrap();
+    assert_eq!(Some("ms"), latency.to_unit());
+    assert_eq!(Some(12.5), latency.to_f64());
+
+    let size = log::kv::Source::get(&source, log::kv::Key::from("size")).unwrap();
+    assert_eq!(Some("bytes"), size.to_unit());
+}
+
+#[test]
+#[cfg(feature = "kv")]
+fn kv_format_hint() {
+    all_log_macros!(
+        addr:x = 0xdead_beefu32,
+        latency:{.3} = 1.23456;
+        "hello world"
+    );
+
+    let flags = 5u8;
+    all_log_macros!(
+        flags:b;
+        "hello world"
+    );
+
+    let source = log::kv!(addr:X = 255, latency:{.3} = 1.23456, flags:o = 8);
+    assert_eq!(
+        "FF",
+        log::kv::Source::get(&source, log::kv::Key::from("addr"))
+            .unwrap()
+            .to_string()
+    );
+    assert_eq!(
+        "1.235",
+        log::kv::Source::get(&source, log::kv::Key::from("latency"))
+            .unwrap()
+            .to_string()
+    );
+    assert_eq!(
+        "10",
+        log::kv::Source::get(&source, log::kv::Key::from("flags"))
+            .unwrap()
+            .to_string()
+    );
+}
+
+#[test]
+#[cfg(feature = "kv_json")]
+fn kv_json() {
+    all_log_macros!(
+        a:json = r#"{"b":1}"#;
+        "hello world"
+    );
+
+    let payload = String::from(r#"[1,2]"#);
+    all_log_macros!(
+        payload:json;
+        "hello world"
+    );
+
+    let source = log::kv!(a:json = r#"{"b":1}"#);
+    assert_eq!(
+        r#"{"a":{"b":1}}"#,
+        serde_json::to_string(&log::kv::Value::from_map(&source)).unwrap()
+    );
+}
+
+#[test]
+#[cfg(feature = "kv")]
+fn kv_source() {
+    use log::kv::{Key, Source};
+
+    let b = "b";
+    let source = log::kv!(a = 1, b, "c":? = vec![1, 2], d:% = 4.5);
+
+    assert_eq!(4, source.count());
+    assert_eq!(Some(1), source.get_i64(Key::from_str("a")));
+    assert_eq!(Some("b"), source.get_str(Key::from_str("b")));
+    assert_eq!(
+        "[1, 2]",
+        source.get(Key::from_str("c")).unwrap().to_string()
+    );
+    assert_eq!(
+        r#"{"a": 1, "b": "b", "c": [1, 2], "d": 4.5}"#,
+        format!("{:?}", source)
+    );
+
+    assert_eq!(0, log::kv!().count());
+    assert_eq!(1, log::kv!(a = 1,).count());
+}
+
+#[test]
+#[cfg(feature = "kv_std")]
+fn kv_source_error() {
+    use log::kv::{Key, Source};
+
+    let source = log::kv!(a:err = std::io::Error::new(std::io::ErrorKind::Other, "an error"));
+
+    assert!(source
+        .get(Key::from_str("a"))
+        .unwrap()
+        .to_borrowed_error()
+        .is_some());
+}
+
 /// Some and None (from Option) are used in the macros.
 #[derive(Debug)]
 enum Type {
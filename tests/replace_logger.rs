@@ -0,0 +1,69 @@
+#![cfg(feature = "unsafe_replace_logger")]
+
+use log::{info, LevelFilter, Log, Metadata, Record};
+use std::sync::{Arc, Mutex};
+
+struct Logger {
+    name: &'static str,
+    records: Arc<Mutex<Vec<String>>>,
+}
+
+impl Log for Logger {
+    fn enabled(&self, _: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        self.records
+            .lock()
+            .unwrap()
+            .push(format!("{}: {}", self.name, record.args()));
+    }
+
+    fn flush(&self) {}
+}
+
+#[test]
+fn replace_logger() {
+    let records = Arc::new(Mutex::new(Vec::new()));
+    let logger = |name| {
+        Box::new(Logger {
+            name,
+            records: records.clone(),
+        })
+    };
+
+    log::set_max_level(LevelFilter::Info);
+
+    // SAFETY: This is the only test in this binary, so nothing else is logging
+    unsafe {
+        assert!(log::unset_logger().is_none());
+        assert!(log::replace_boxed_logger(logger("first")).is_none());
+        info!("a");
+
+        let first = log::replace_boxed_logger(logger("second")).unwrap();
+        info!("b");
+        first.log(&Record::builder().args(format_args!("c")).build());
+
+        assert!(log::set_boxed_logger(logger("third")).is_err());
+
+        assert!(log::unset_logger().is_some());
+        info!("dropped");
+        assert!(log::unset_logger().is_none());
+    }
+
+    log::set_boxed_logger(logger("third")).unwrap();
+    info!("d");
+
+    // Loggers that were set with `set_logger` aren't returned
+    unsafe {
+        log::unset_logger().unwrap();
+        log::set_logger(Box::leak(logger("fourth"))).unwrap();
+        assert!(log::unset_logger().is_none());
+    }
+
+    assert_eq!(
+        vec!["first: a", "second: b", "first: c", "third: d"],
+        *records.lock().unwrap()
+    );
+}